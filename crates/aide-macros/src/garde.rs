@@ -0,0 +1,204 @@
+//! Translation of `garde` rules into `schemars` validation attributes.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    punctuated::Punctuated, Attribute, Data, DeriveInput, Expr, ExprLit, Fields, Lit, Meta, Token,
+};
+
+type MetaList = Punctuated<Meta, Token![,]>;
+
+pub(crate) fn expand(mut input: DeriveInput) -> syn::Result<TokenStream> {
+    match &mut input.data {
+        Data::Struct(data) => add_field_attrs(&mut data.fields)?,
+        Data::Enum(data) => {
+            for variant in &mut data.variants {
+                add_field_attrs(&mut variant.fields)?;
+            }
+        }
+        Data::Union(data) => {
+            return Err(syn::Error::new_spanned(
+                data.union_token,
+                "garde_schema does not support unions",
+            ))
+        }
+    }
+
+    Ok(quote!(#input))
+}
+
+fn add_field_attrs(fields: &mut Fields) -> syn::Result<()> {
+    for field in fields.iter_mut() {
+        let mut items = MetaList::new();
+
+        for attr in field.attrs.iter().filter(|a| a.path().is_ident("garde")) {
+            let rules = attr.parse_args_with(MetaList::parse_terminated)?;
+            items.extend(rules.iter().filter_map(translate));
+        }
+
+        // `schemars` does not allow both, and the pattern is the stricter rule.
+        if items.iter().any(|item| item.path().is_ident("regex")) {
+            items = items
+                .into_iter()
+                .filter(|item| !item.path().is_ident("contains"))
+                .collect();
+        }
+
+        if !items.is_empty() {
+            field.attrs.push(Attribute {
+                pound_token: Default::default(),
+                style: syn::AttrStyle::Outer,
+                bracket_token: Default::default(),
+                meta: syn::parse_quote!(schemars(#items)),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Translate a single `garde` rule, rules without
+/// a JSON Schema equivalent are skipped.
+fn translate(rule: &Meta) -> Option<Meta> {
+    let path = rule.path().get_ident()?.to_string();
+
+    match (path.as_str(), rule) {
+        ("email" | "url" | "required", Meta::Path(p)) => Some(Meta::Path(p.clone())),
+        ("phone_number", Meta::Path(_)) => Some(syn::parse_quote!(phone)),
+        ("length", Meta::List(list)) => {
+            let args = list.parse_args_with(MetaList::parse_terminated).ok()?;
+
+            // Only the default mode and `chars` count the same
+            // units as `minLength` and `maxLength`.
+            if args.iter().any(|arg| {
+                matches!(arg, Meta::Path(mode) if !mode.is_ident("simple") && !mode.is_ident("chars"))
+            }) {
+                return None;
+            }
+
+            bounds("length", &args, false)
+        }
+        ("range", Meta::List(list)) => {
+            let args = list.parse_args_with(MetaList::parse_terminated).ok()?;
+            bounds("range", &args, true)
+        }
+        ("pattern", Meta::List(list)) => match list.parse_args::<Expr>().ok()? {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(pattern),
+                ..
+            }) => Some(syn::parse_quote!(regex(pattern = #pattern))),
+            Expr::Path(path) => {
+                let path = quote!(#path).to_string().replace(' ', "");
+                Some(syn::parse_quote!(regex = #path))
+            }
+            _ => None,
+        },
+        ("contains", Meta::List(list)) => match list.parse_args::<Expr>().ok()? {
+            Expr::Lit(ExprLit {
+                lit: Lit::Str(value),
+                ..
+            }) => Some(syn::parse_quote!(contains = #value)),
+            _ => None,
+        },
+        ("inner", Meta::List(list)) => {
+            let rules = list.parse_args_with(MetaList::parse_terminated).ok()?;
+            let rules = rules.iter().filter_map(translate).collect::<MetaList>();
+            (!rules.is_empty()).then(|| syn::parse_quote!(inner(#rules)))
+        }
+        _ => None,
+    }
+}
+
+/// The `min`, `max` and `equal` bounds of `length` and `range`,
+/// `equal` is written as both bounds for ranges as `schemars`
+/// only supports it for lengths.
+fn bounds(name: &str, args: &MetaList, split_equal: bool) -> Option<Meta> {
+    let mut bounds = MetaList::new();
+
+    for arg in args {
+        let Meta::NameValue(nv) = arg else {
+            continue;
+        };
+        let value = &nv.value;
+
+        if nv.path.is_ident("equal") && split_equal {
+            bounds.push(syn::parse_quote!(min = #value));
+            bounds.push(syn::parse_quote!(max = #value));
+        } else if ["min", "max", "equal"].iter().any(|b| nv.path.is_ident(b)) {
+            bounds.push(Meta::NameValue(nv.clone()));
+        }
+    }
+
+    let name = syn::Ident::new(name, proc_macro2::Span::call_site());
+    (!bounds.is_empty()).then(|| syn::parse_quote!(#name(#bounds)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schemars_attrs(input: TokenStream) -> Vec<String> {
+        let output: DeriveInput =
+            syn::parse2(expand(syn::parse2(input).unwrap()).unwrap()).unwrap();
+        let Data::Struct(data) = output.data else {
+            unreachable!()
+        };
+
+        data.fields
+            .iter()
+            .flat_map(|f| &f.attrs)
+            .filter(|a| a.path().is_ident("schemars"))
+            .map(|a| quote!(#a).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_garde_rules() {
+        let attrs = schemars_attrs(quote! {
+            struct User {
+                #[garde(length(min = 3, max = 32), pattern(r"^[a-z]+$"))]
+                name: String,
+                #[garde(email)]
+                email: String,
+                #[garde(range(min = 18), custom(check_age))]
+                age: u8,
+                #[garde(length(bytes, max = 64), ascii)]
+                bio: String,
+                #[garde(inner(url))]
+                links: Vec<String>,
+                #[garde(skip)]
+                admin: bool,
+            }
+        });
+
+        assert_eq!(
+            attrs,
+            [
+                r#"# [schemars (length (min = 3 , max = 32) , regex (pattern = r"^[a-z]+$"))]"#,
+                "# [schemars (email)]",
+                "# [schemars (range (min = 18))]",
+                "# [schemars (inner (url))]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_garde_bounds() {
+        let attrs = schemars_attrs(quote! {
+            struct Code(
+                #[garde(length(chars, equal = 6), pattern(CODE_REGEX), contains("-"))] String,
+                #[garde(contains("-"))] String,
+                #[garde(range(equal = 1))] u8,
+            );
+        });
+
+        assert_eq!(
+            attrs,
+            [
+                r#"# [schemars (length (equal = 6) , regex = "CODE_REGEX")]"#,
+                r#"# [schemars (contains = "-")]"#,
+                "# [schemars (range (min = 1 , max = 1))]",
+            ]
+        );
+    }
+}
@@ -5,6 +5,10 @@ use syn::{parse_macro_input, parse_quote, DeriveInput, Type};
 
 extern crate proc_macro;
 
+#[cfg(feature = "garde")]
+mod garde;
+mod routes;
+
 #[derive(Default, FromDeriveInput)]
 #[darling(default, attributes(aide))]
 struct OperationIoOpts {
@@ -166,3 +170,93 @@ pub fn derive_operation_io(ts: TokenStream) -> TokenStream {
 
     ts.into()
 }
+
+/// Document the [`garde`] rules of a type in its [`JsonSchema`] implementation.
+///
+/// The rules with a JSON Schema equivalent are added to the fields
+/// as `schemars` validation attributes:
+///
+/// - `length` as `minLength`/`maxLength` or `minItems`/`maxItems`,
+///   lengths in `bytes`, `graphemes` or `utf16` are skipped,
+/// - `range` as `minimum` and `maximum`,
+/// - `pattern` and `contains` as `pattern`,
+/// - `email`, `url` and `phone_number` as `format`,
+/// - `required` and `inner` like their `schemars` counterparts.
+///
+/// The attribute must be placed before the derives.
+///
+/// [`validator`] rules need no translation, as `schemars`
+/// reads the `#[validate(...)]` attributes by itself.
+///
+/// # Examples
+///
+/// ```ignore
+/// #[aide::garde_schema]
+/// #[derive(Deserialize, JsonSchema, Validate)]
+/// struct NewUser {
+///     #[garde(length(min = 3, max = 32), pattern(r"^[a-z0-9_]+$"))]
+///     name: String,
+///     #[garde(email)]
+///     email: String,
+///     #[garde(range(min = 13))]
+///     age: u8,
+/// }
+/// ```
+///
+/// [`garde`]: https://docs.rs/garde
+/// [`validator`]: https://docs.rs/validator
+/// [`JsonSchema`]: https://docs.rs/schemars/latest/schemars/trait.JsonSchema.html
+#[cfg(feature = "garde")]
+#[proc_macro_attribute]
+pub fn garde_schema(args: TokenStream, ts: TokenStream) -> TokenStream {
+    if !args.is_empty() {
+        return syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "garde_schema does not take arguments",
+        )
+        .into_compile_error()
+        .into();
+    }
+
+    garde::expand(parse_macro_input!(ts as DeriveInput))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Build an `ApiRouter` from a route table that is checked
+/// when the code is compiled.
+///
+/// Every entry routes a method and a path to a handler, optionally
+/// with a transform of the operation like the `*_with` functions of
+/// `aide::axum::routing`. Routes with the same path are combined into
+/// a single method router.
+///
+/// The following mistakes are reported as compile errors instead of
+/// surfacing when the router is built:
+///
+/// - the same method and path routed more than once,
+/// - parameters at the same position of overlapping paths with
+///   different names, e.g. `/users/:id` and `/users/:user_id/posts`,
+/// - malformed paths, e.g. invalid or repeated parameter names.
+///
+/// # Examples
+///
+/// ```ignore
+/// use aide::axum::{api_routes, ApiRouter};
+///
+/// let app: ApiRouter = api_routes! {
+///     GET "/users" => list_users,
+///     POST "/users" => create_user, |op| op.description("Create a user."),
+///     GET "/users/:id" => get_user,
+/// };
+/// ```
+#[proc_macro]
+pub fn api_routes(ts: TokenStream) -> TokenStream {
+    routes::expand(parse_macro_input!(ts as routes::RouteTable))
+        .unwrap_or_else(|error| {
+            // All errors are reported in expression position.
+            let errors = error.into_compile_error();
+            quote!({ #errors unreachable!() })
+        })
+        .into()
+}
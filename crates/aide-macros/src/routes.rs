@@ -0,0 +1,338 @@
+//! A route table with compile-time checks.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+    parse::{Parse, ParseStream},
+    Expr, Ident, LitStr, Token,
+};
+
+/// The methods that can be routed, in the notation of the table.
+const METHODS: [&str; 8] = [
+    "GET", "PUT", "POST", "DELETE", "OPTIONS", "HEAD", "PATCH", "TRACE",
+];
+
+pub(crate) struct RouteTable {
+    routes: Vec<Route>,
+}
+
+/// A `METHOD "/path" => handler[, transform]` entry.
+struct Route {
+    method: Ident,
+    path: LitStr,
+    handler: Expr,
+    transform: Option<Expr>,
+}
+
+/// A segment of a path, parameters are in either
+/// the axum (`:id`, `*rest`) or the template (`{id}`, `{*rest}`)
+/// notation.
+enum Segment<'a> {
+    Static(&'a str),
+    Param(&'a str),
+    Wildcard(&'a str),
+}
+
+impl<'a> Segment<'a> {
+    fn parse(segment: &'a str) -> Self {
+        let param = segment
+            .strip_prefix(':')
+            .or_else(|| segment.strip_prefix('{')?.strip_suffix('}'));
+
+        match param {
+            Some(name) => match name.strip_prefix('*') {
+                Some(name) => Self::Wildcard(name),
+                None => Self::Param(name),
+            },
+            None => match segment.strip_prefix('*') {
+                Some(name) => Self::Wildcard(name),
+                None => Self::Static(segment),
+            },
+        }
+    }
+
+    fn name(&self) -> Option<&'a str> {
+        match *self {
+            Self::Static(_) => None,
+            Self::Param(name) | Self::Wildcard(name) => Some(name),
+        }
+    }
+
+    /// Whether the segments match the same requests.
+    fn same_as(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Static(a), Self::Static(b)) => a == b,
+            (Self::Param(_), Self::Param(_)) | (Self::Wildcard(_), Self::Wildcard(_)) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Parse for RouteTable {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut routes = Vec::new();
+
+        while !input.is_empty() {
+            routes.push(input.parse()?);
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+
+        Ok(Self { routes })
+    }
+}
+
+impl Parse for Route {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let method: Ident = input.parse()?;
+        let path = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let handler = input.parse()?;
+
+        let transform = if peek_transform(input) {
+            input.parse::<Token![,]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+
+        if !METHODS.contains(&method.to_string().as_str()) {
+            return Err(syn::Error::new(
+                method.span(),
+                format!("unknown method, expected one of {}", METHODS.join(", ")),
+            ));
+        }
+
+        Ok(Self {
+            method,
+            path,
+            handler,
+            transform,
+        })
+    }
+}
+
+/// Whether a comma is followed by a transform, rather than
+/// by the next entry that starts with a method and a path.
+fn peek_transform(input: ParseStream) -> bool {
+    let fork = input.fork();
+    if fork.parse::<Token![,]>().is_err() || fork.is_empty() {
+        return false;
+    }
+
+    !(fork.parse::<Ident>().is_ok() && fork.peek(LitStr))
+}
+
+pub(crate) fn expand(table: RouteTable) -> syn::Result<TokenStream> {
+    check(&table.routes)?;
+
+    let mut paths: Vec<(String, Vec<&Route>)> = Vec::new();
+    for route in &table.routes {
+        let path = route.path.value();
+        match paths.iter_mut().find(|(p, _)| *p == path) {
+            Some((_, routes)) => routes.push(route),
+            None => paths.push((path, vec![route])),
+        }
+    }
+
+    let api_routes = paths.iter().map(|(_, routes)| {
+        let path = &routes[0].path;
+        let method_routers = routes.iter().enumerate().map(|(i, route)| {
+            let handler = &route.handler;
+            let method = route.method.to_string().to_lowercase();
+            let call = match &route.transform {
+                Some(transform) => {
+                    let method = Ident::new(&format!("{method}_with"), route.method.span());
+                    quote!(#method(#handler, #transform))
+                }
+                None => {
+                    let method = Ident::new(&method, route.method.span());
+                    quote!(#method(#handler))
+                }
+            };
+
+            if i == 0 {
+                quote!(::aide::axum::routing::#call)
+            } else {
+                quote!(.#call)
+            }
+        });
+
+        quote!(.api_route(#path, #(#method_routers)*))
+    });
+
+    Ok(quote! {
+        ::aide::axum::ApiRouter::new() #(#api_routes)*
+    })
+}
+
+/// Report malformed paths, duplicate routes and parameters with
+/// different names at the same position of overlapping paths.
+fn check(routes: &[Route]) -> syn::Result<()> {
+    let mut errors: Option<syn::Error> = None;
+    let mut report = |error: syn::Error| match &mut errors {
+        Some(errors) => errors.combine(error),
+        None => errors = Some(error),
+    };
+
+    let paths = routes.iter().map(|r| r.path.value()).collect::<Vec<_>>();
+    let segments = paths
+        .iter()
+        .map(|path| path.split('/').skip(1).map(Segment::parse).collect())
+        .collect::<Vec<Vec<_>>>();
+
+    for (i, route) in routes.iter().enumerate() {
+        let span = route.path.span();
+
+        if let Some(error) = check_path(&paths[i], &segments[i]) {
+            report(syn::Error::new(span, error));
+            continue;
+        }
+
+        for j in 0..i {
+            let same_path = segments[i].len() == segments[j].len()
+                && segments[i]
+                    .iter()
+                    .zip(&segments[j])
+                    .all(|(a, b)| a.same_as(b));
+
+            if same_path && routes[j].method == route.method {
+                report(syn::Error::new(
+                    span,
+                    format!(
+                        r#"the route {} "{}" is already defined as "{}""#,
+                        route.method, paths[i], paths[j]
+                    ),
+                ));
+                break;
+            }
+
+            let conflict = segments[i]
+                .iter()
+                .zip(&segments[j])
+                .take_while(|(a, b)| a.same_as(b))
+                .filter_map(|(a, b)| Some((a.name()?, b.name()?)))
+                .find(|(a, b)| a != b);
+
+            if let Some((name, other)) = conflict {
+                report(syn::Error::new(
+                    span,
+                    format!(
+                        r#"the parameter "{name}" is named "{other}" in "{}", parameters at the same position must have the same name"#,
+                        paths[j]
+                    ),
+                ));
+                break;
+            }
+        }
+    }
+
+    errors.map_or(Ok(()), Err)
+}
+
+/// The reason why the path is malformed, if it is.
+fn check_path(path: &str, segments: &[Segment]) -> Option<String> {
+    if !path.starts_with('/') {
+        return Some("paths must start with a `/`".into());
+    }
+
+    for (i, segment) in segments.iter().enumerate() {
+        let Some(name) = segment.name() else {
+            continue;
+        };
+
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Some(format!(r#"the parameter name "{name}" is not valid"#));
+        }
+        if matches!(segment, Segment::Wildcard(_)) && i + 1 != segments.len() {
+            return Some(format!(r#"the wildcard "{name}" must be the last segment"#));
+        }
+        if segments[..i].iter().any(|s| s.name() == Some(name)) {
+            return Some(format!(r#"the parameter "{name}" appears more than once"#));
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check, RouteTable};
+
+    fn errors(table: &str) -> Vec<String> {
+        let table: RouteTable = syn::parse_str(table).unwrap();
+        match check(&table.routes) {
+            Ok(()) => Vec::new(),
+            Err(errors) => errors.into_iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_check_valid() {
+        assert!(errors(
+            r#"
+            GET "/users" => list_users,
+            POST "/users" => create_user, |op| op,
+            GET "/users/:id" => get_user,
+            PUT "/users/{id}" => update_user,
+            GET "/users/:id/posts/{*rest}" => get_posts,
+            "#
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_check_duplicate() {
+        assert_eq!(
+            errors(
+                r#"
+                GET "/users/:id" => get_user,
+                GET "/users/{id}" => get_user_v2,
+                POST "/users/:id" => update_user,
+                "#
+            ),
+            [r#"the route GET "/users/{id}" is already defined as "/users/:id""#]
+        );
+    }
+
+    #[test]
+    fn test_check_parameter_names() {
+        assert_eq!(
+            errors(
+                r#"
+                GET "/users/:id" => get_user,
+                GET "/users/:user_id/posts" => list_posts,
+                GET "/teams/:id" => get_team,
+                "#
+            ),
+            [
+                r#"the parameter "user_id" is named "id" in "/users/:id", parameters at the same position must have the same name"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_malformed() {
+        assert_eq!(
+            errors(
+                r#"
+                GET "users" => list_users,
+                GET "/users/:" => get_user,
+                GET "/users/:user-id" => get_user,
+                GET "/files/*rest/meta" => get_meta,
+                GET "/users/:id/friends/:id" => get_friend,
+                "#
+            ),
+            [
+                "paths must start with a `/`",
+                r#"the parameter name "" is not valid"#,
+                r#"the parameter name "user-id" is not valid"#,
+                r#"the wildcard "rest" must be the last segment"#,
+                r#"the parameter "id" appears more than once"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,81 @@
+//! Measures the time to build a router with many routes, with
+//! the documentation enabled or compiled out.
+//!
+//! Run with `cargo bench -p aide --all-features --bench docs_disabled`,
+//! and with `RUSTFLAGS="--cfg aide_docs_disabled"` to compare.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use aide::{
+    axum::{routing::get_with, ApiRouter},
+    openapi::OpenApi,
+};
+use axum::extract::{Path, Query};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+const ROUTES: usize = 500;
+const RUNS: u32 = 10;
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Filter {
+    /// The text to search for.
+    search: Option<String>,
+    /// The page to return.
+    page: Option<u32>,
+    /// The tags to filter by.
+    tags: Vec<String>,
+}
+
+async fn handler(Path(_id): Path<u32>, Query(_filter): Query<Filter>) -> String {
+    String::new()
+}
+
+fn build() -> (axum::Router, OpenApi) {
+    let mut api = OpenApi::default();
+
+    let router = (0..ROUTES)
+        .fold(ApiRouter::<()>::new(), |router, i| {
+            router.api_route(
+                &format!("/resources{i}/:id"),
+                get_with(handler, |op| op.summary("Get a resource").tag("resources")),
+            )
+        })
+        .finish_api_with(&mut api, |api| api.title("Benchmark"));
+
+    (router, api)
+}
+
+fn average(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        run();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let (_, api) = build();
+    let documented = api.paths.map_or(0, |paths| paths.paths.len());
+    if cfg!(aide_docs_disabled) {
+        assert_eq!(documented, 0, "the documentation is compiled out");
+    } else {
+        assert_eq!(documented, ROUTES);
+    }
+
+    let time = average(|| {
+        let _ = black_box(build());
+    });
+
+    let docs = if cfg!(aide_docs_disabled) {
+        "disabled"
+    } else {
+        "enabled"
+    };
+    println!("{ROUTES} routes, average of {RUNS} runs, documentation {docs}:");
+    println!("  router build time: {time:?}");
+}
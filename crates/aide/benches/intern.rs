@@ -0,0 +1,125 @@
+//! Compares the retained memory and the serialization time of a large
+//! document with its interned copy.
+//!
+//! Run with `cargo bench -p aide --all-features --bench intern`.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    hint::black_box,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use aide::{
+    axum::{routing::get_with, ApiRouter},
+    intern::InternedOpenApi,
+    openapi::OpenApi,
+};
+use axum::extract::{Path, Query};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+const ROUTES: usize = 500;
+const RUNS: u32 = 20;
+
+/// Counts the bytes currently allocated.
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Filter {
+    /// The text to search for.
+    search: Option<String>,
+    /// The page to return.
+    page: Option<u32>,
+    /// The tags to filter by.
+    tags: Vec<String>,
+}
+
+async fn handler(Path(_id): Path<u32>, Query(_filter): Query<Filter>) -> String {
+    String::new()
+}
+
+fn build() -> OpenApi {
+    let mut api = OpenApi::default();
+
+    let _ = (0..ROUTES)
+        .fold(ApiRouter::<()>::new(), |router, i| {
+            router.api_route(
+                &format!("/resources{i}/:id"),
+                get_with(handler, |op| op.summary("Get a resource").tag("resources")),
+            )
+        })
+        .finish_api_with(&mut api, |api| api.title("Benchmark"));
+
+    api
+}
+
+/// The bytes still allocated after `make` returns, while its result is held.
+fn retained<T>(make: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    let value = make();
+    let after = ALLOCATED.load(Ordering::Relaxed);
+    (value, after.saturating_sub(before))
+}
+
+fn average(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        run();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let built = build();
+    // A clone only allocates the document itself, not the generation caches.
+    let (api, api_bytes) = retained(|| built.clone());
+    drop(built);
+    let (interned, interned_bytes) = retained(|| InternedOpenApi::new(&api).unwrap());
+
+    let json = serde_json::to_vec(&api).unwrap();
+    assert_eq!(serde_json::to_vec(&interned).unwrap(), json);
+
+    let api_time = average(|| {
+        black_box(serde_json::to_vec(black_box(&api)).unwrap());
+    });
+    let interned_time = average(|| {
+        black_box(serde_json::to_vec(black_box(&interned)).unwrap());
+    });
+    let intern_time = average(|| {
+        black_box(InternedOpenApi::new(black_box(&api)).unwrap());
+    });
+
+    println!(
+        "{ROUTES} routes, {} bytes of JSON, average of {RUNS} runs:",
+        json.len()
+    );
+    println!("  retained memory:");
+    println!("    document: {api_bytes} bytes");
+    println!(
+        "    interned: {interned_bytes} bytes, {} unique strings",
+        interned.unique_strings()
+    );
+    println!("  serialization time:");
+    println!("    document: {api_time:?}");
+    println!("    interned: {interned_time:?}");
+    println!("  interning time: {intern_time:?}");
+}
@@ -0,0 +1,86 @@
+//! Compares the startup time with eager and lazy generation
+//! of the documentation of an API with many routes.
+//!
+//! Run with `cargo bench -p aide --features axum --bench lazy`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use aide::{
+    axum::{routing::get_with, ApiRouter},
+    lazy::LazyOpenApi,
+    openapi::OpenApi,
+};
+use axum::extract::{Path, Query};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+const ROUTES: usize = 500;
+const RUNS: u32 = 10;
+
+#[derive(Deserialize, JsonSchema)]
+#[allow(dead_code)]
+struct Filter {
+    /// The text to search for.
+    search: Option<String>,
+    /// The page to return.
+    page: Option<u32>,
+    /// The tags to filter by.
+    tags: Vec<String>,
+}
+
+async fn handler(Path(_id): Path<u32>, Query(_filter): Query<Filter>) -> String {
+    String::new()
+}
+
+fn generate() -> OpenApi {
+    let mut api = OpenApi::default();
+
+    let _ = (0..ROUTES)
+        .fold(ApiRouter::<()>::new(), |router, i| {
+            router.api_route(
+                &format!("/resources{i}/:id"),
+                get_with(handler, |op| op.summary("Get a resource").tag("resources")),
+            )
+        })
+        .finish_api_with(&mut api, |api| api.title("Benchmark"));
+
+    api
+}
+
+fn average(mut run: impl FnMut()) -> Duration {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        run();
+    }
+    start.elapsed() / RUNS
+}
+
+fn main() {
+    let eager = average(|| {
+        black_box(generate());
+    });
+
+    let startup = average(|| {
+        black_box(LazyOpenApi::new(generate));
+    });
+
+    let first_request = average(|| {
+        let api = LazyOpenApi::new(generate);
+        black_box(api.get());
+    });
+
+    let api = LazyOpenApi::new(generate);
+    let _ = api.get();
+    let cached = average(|| {
+        black_box(api.get());
+    });
+
+    println!("{ROUTES} routes, average of {RUNS} runs:");
+    println!("  eager generation at startup: {eager:?}");
+    println!("  lazy startup:                {startup:?}");
+    println!("  lazy first request:          {first_request:?}");
+    println!("  lazy cached request:         {cached:?}");
+}
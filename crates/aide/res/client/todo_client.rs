@@ -0,0 +1,92 @@
+// Generated by aide from the API documentation, do not edit.
+
+use super::Todo;
+
+/// A client of the API.
+#[derive(Debug, Clone)]
+pub struct TodoClient {
+    client: reqwest::Client,
+    base_url: reqwest::Url,
+}
+
+#[allow(clippy::all, unused_mut)]
+impl TodoClient {
+    /// Create a client for the API at the given base URL.
+    pub fn new(base_url: reqwest::Url) -> Self {
+        Self::with_client(reqwest::Client::new(), base_url)
+    }
+
+    /// Create a client for the API at the given base URL with an existing HTTP client.
+    pub fn with_client(client: reqwest::Client, base_url: reqwest::Url) -> Self {
+        Self { client, base_url }
+    }
+
+    fn url(&self, segments: &[&str]) -> reqwest::Url {
+        let mut url = self.base_url.clone();
+        url.path_segments_mut()
+            .expect("the base URL must be able to have a path")
+            .pop_if_empty()
+            .extend(segments);
+        url
+    }
+
+    /// `GET /todos`
+    pub async fn list_todos(&self, params: &ListTodosParams) -> Result<ListTodosResponse, reqwest::Error> {
+        let url = self.url(&["todos"]);
+        let mut req = self.client.request(reqwest::Method::GET, url);
+        let mut query = Vec::<(&str, String)>::new();
+        if let Some(value) = &params.page { query.push(("page", value.to_string())); }
+        req = req.query(&query);
+        let res = req.send().await?;
+        Ok(match res.status().as_u16() {
+            200 => ListTodosResponse::Ok(res.json().await?),
+            status => ListTodosResponse::Other(status, res.text().await?),
+        })
+    }
+
+    /// Update a todo.
+    ///
+    /// `PUT /lists/{list_id}/todos/{id}`
+    pub async fn update_todo(&self, params: &UpdateTodoParams, body: &Todo) -> Result<UpdateTodoResponse, reqwest::Error> {
+        let url = self.url(&["lists", &params.list_id.to_string(), "todos", &params.id.to_string()]);
+        let mut req = self.client.request(reqwest::Method::PUT, url);
+        req = req.json(body);
+        let res = req.send().await?;
+        Ok(match res.status().as_u16() {
+            200 => UpdateTodoResponse::Ok(res.json().await?),
+            404 => UpdateTodoResponse::NotFound,
+            status => UpdateTodoResponse::Other(status, res.text().await?),
+        })
+    }
+}
+
+/// The parameters of [`TodoClient::list_todos`].
+#[derive(Debug, Clone)]
+pub struct ListTodosParams {
+    pub page: Option<u32>,
+}
+
+/// The responses of [`TodoClient::list_todos`].
+#[derive(Debug)]
+pub enum ListTodosResponse {
+    Ok(Vec<Todo>),
+    /// An undocumented response with its status code and body.
+    Other(u16, String),
+}
+
+/// The parameters of [`TodoClient::update_todo`].
+#[derive(Debug, Clone)]
+pub struct UpdateTodoParams {
+    pub id: u64,
+    pub list_id: u32,
+}
+
+/// The responses of [`TodoClient::update_todo`].
+#[derive(Debug)]
+pub enum UpdateTodoResponse {
+    Ok(Todo),
+    /// no content
+    NotFound,
+    /// An undocumented response with its status code and body.
+    Other(u16, String),
+}
@@ -168,13 +168,20 @@
 //! and the documented routes will be updated as expected.
 //!
 
-use std::{convert::Infallible, future::Future, mem, pin::Pin};
+use std::{
+    convert::Infallible,
+    future::Future,
+    num::NonZeroUsize,
+    panic,
+    pin::Pin,
+    sync::{Mutex, PoisonError},
+    thread,
+};
 
 use crate::{
-    gen::{self, in_context},
-    openapi::{OpenApi, PathItem, ReferenceOr, SchemaObject},
+    gen::{GenContext, GenStats},
+    openapi::{OpenApi, Operation, PathItem, Response},
     operation::OperationHandler,
-    util::merge_paths,
     OperationInput, OperationOutput,
 };
 use axum::{
@@ -187,14 +194,33 @@ use axum::{
 };
 #[cfg(not(feature = "axum-wasm"))]
 use axum::extract::connect_info::IntoMakeServiceWithConnectInfo;
-use indexmap::map::Entry;
 use indexmap::IndexMap;
+use schemars::{schema::Schema, Map};
 use tower_layer::Layer;
 use tower_service::Service;
 
+use crate::transform::{TransformOpenApi, TransformPathItem, TransformResponse};
+
+// Only used to generate the documentation.
+#[cfg(not(aide_docs_disabled))]
+use std::{mem, time::Instant};
+
+#[cfg(not(aide_docs_disabled))]
 use crate::{
-    transform::{TransformOpenApi, TransformPathItem},
-    util::path_colon_params,
+    example::populate_examples,
+    gen::{self, in_context},
+    helpers::add_response_if_missing,
+    impls::responses_of,
+    openapi::{ReferenceOr, SchemaObject, StatusCode},
+    transform::{allows_anonymous, TransformOperation},
+    util::{
+        add_method_not_allowed_responses, add_prefix_parameters, check_path_template,
+        derive_implicit_operations, iter_operations_mut, merge_paths, name_path_parameters,
+        nested_route, operation_mut, path_colon_params, path_wildcard, require_security_headers,
+        METHODS, WILDCARD,
+    },
+    visibility::{hide_operations, is_hidden, prune_hidden},
+    Error,
 };
 
 use self::routing::ApiMethodRouter;
@@ -203,6 +229,81 @@ mod inputs;
 mod outputs;
 
 pub mod routing;
+pub mod typed;
+
+/// Duplicate routes and misspelled parameters are compile errors:
+///
+/// ```compile_fail
+/// use aide::axum::{api_routes, ApiRouter};
+///
+/// async fn get_user() {}
+///
+/// async fn list_posts() {}
+///
+/// let app: ApiRouter = api_routes! {
+///     GET "/users/:id" => get_user,
+///     GET "/users/:user_id/posts" => list_posts,
+/// };
+/// ```
+///
+/// The same method and path can only be routed once:
+///
+/// ```compile_fail
+/// use aide::axum::{api_routes, ApiRouter};
+///
+/// async fn get_user() {}
+///
+/// async fn get_user_v2() {}
+///
+/// let app: ApiRouter = api_routes! {
+///     GET "/users/:id" => get_user,
+///     GET "/users/{id}" => get_user_v2,
+/// };
+/// ```
+///
+/// Paths must start with a `/`:
+///
+/// ```compile_fail
+/// use aide::axum::{api_routes, ApiRouter};
+///
+/// async fn list_users() {}
+///
+/// let app: ApiRouter = api_routes! {
+///     GET "users" => list_users,
+/// };
+/// ```
+///
+/// and parameter names must be valid and unique in the path:
+///
+/// ```compile_fail
+/// use aide::axum::{api_routes, ApiRouter};
+///
+/// async fn get_friend() {}
+///
+/// let app: ApiRouter = api_routes! {
+///     GET "/users/:id/friends/:id" => get_friend,
+/// };
+/// ```
+///
+/// The routes are registered with [`ApiRouter::api_route`]:
+///
+/// ```
+/// use aide::axum::{api_routes, ApiRouter};
+///
+/// async fn list_users() {}
+///
+/// async fn create_user() {}
+///
+/// async fn get_user() {}
+///
+/// let app: ApiRouter = api_routes! {
+///     GET "/users" => list_users,
+///     POST "/users" => create_user, |op| op.description("Create a user."),
+///     GET "/users/:id" => get_user,
+/// };
+/// ```
+#[cfg(feature = "macros")]
+pub use aide_macros::api_routes;
 
 /// A wrapper over [`axum::Router`] that adds
 /// API documentation-specific features.
@@ -210,14 +311,26 @@ pub mod routing;
 #[derive(Debug)]
 pub struct ApiRouter<S = ()> {
     paths: IndexMap<String, PathItem>,
+    /// The type names of the handlers of the operations by path.
+    handlers: IndexMap<String, IndexMap<&'static str, &'static str>>,
     router: Router<S>,
+    default_error_responses: Vec<fn(&mut GenContext, &mut Operation)>,
+    fallback_response: Option<Box<Response>>,
+    security: Vec<(String, Vec<String>)>,
+    /// Schemas generated on other threads, see [`ApiRouter::merge_parallel`].
+    schemas: Map<String, Schema>,
 }
 
 impl<S> Clone for ApiRouter<S> {
     fn clone(&self) -> Self {
         Self {
             paths: self.paths.clone(),
+            handlers: self.handlers.clone(),
             router: self.router.clone(),
+            default_error_responses: self.default_error_responses.clone(),
+            fallback_response: self.fallback_response.clone(),
+            security: self.security.clone(),
+            schemas: self.schemas.clone(),
         }
     }
 }
@@ -258,7 +371,12 @@ where
     pub fn new() -> Self {
         Self {
             paths: IndexMap::new(),
+            handlers: IndexMap::new(),
             router: Router::new(),
+            default_error_responses: Vec::new(),
+            fallback_response: None,
+            security: Vec::new(),
+            schemas: Map::new(),
         }
     }
 
@@ -268,17 +386,185 @@ where
     pub fn with_state<S2>(self, state: S) -> ApiRouter<S2> {
         ApiRouter {
             paths: self.paths,
+            handlers: self.handlers,
             router: self.router.with_state(state),
+            default_error_responses: self.default_error_responses,
+            fallback_response: self.fallback_response,
+            security: self.security,
+            schemas: self.schemas,
+        }
+    }
+
+    /// Document the responses of `E` for every operation of this router,
+    /// e.g. a service-wide error type.
+    ///
+    /// The responses are added when the documentation is finished,
+    /// or when this router is nested into or merged with another one,
+    /// so they apply to all routes of this router regardless of the
+    /// order of the calls. Responses that are already documented for
+    /// an operation, e.g. by its handler or transform, are kept.
+    ///
+    /// Successful responses of `E` and a response without a
+    /// status code are documented as the default response.
+    ///
+    /// ```
+    /// use aide::axum::{routing::get, ApiRouter};
+    /// # #[derive(serde::Serialize, schemars::JsonSchema)]
+    /// # struct ApiError { message: String }
+    /// # impl aide::OperationOutput for ApiError { type Inner = Self; }
+    ///
+    /// async fn list_todos() {}
+    ///
+    /// let app: ApiRouter = ApiRouter::new()
+    ///     .api_route("/todos", get(list_todos))
+    ///     .default_error_response::<axum::Json<ApiError>>();
+    /// ```
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut))]
+    pub fn default_error_response<E>(mut self) -> Self
+    where
+        E: OperationOutput,
+    {
+        #[cfg(not(aide_docs_disabled))]
+        self.default_error_responses
+            .push(add_default_error_response::<E>);
+        self
+    }
+
+    /// Require the security scheme with the given scopes for
+    /// all routes that are added to this router afterwards,
+    /// e.g. when an authentication layer is added to the router.
+    ///
+    /// Operations that [allow anonymous access](TransformOperation::allow_anonymous)
+    /// are left untouched.
+    ///
+    /// ```
+    /// use aide::axum::{routing::{get, get_with}, ApiRouter};
+    ///
+    /// async fn handler() {}
+    ///
+    /// let app: ApiRouter = ApiRouter::new()
+    ///     .api_route("/login", get(handler))
+    ///     .require_security("bearerAuth", ["todos"])
+    ///     .api_route("/todos", get(handler))
+    ///     .api_route("/todos/public", get_with(handler, |op| op.allow_anonymous()));
+    /// ```
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut, unused_variables))]
+    pub fn require_security<I, T>(mut self, security_scheme: &str, scopes: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        #[cfg(not(aide_docs_disabled))]
+        self.security.push((
+            security_scheme.into(),
+            scopes.into_iter().map(Into::into).collect(),
+        ));
+        self
+    }
+
+    /// Add the [required security](Self::require_security)
+    /// to the operations of a path item.
+    #[cfg(not(aide_docs_disabled))]
+    fn apply_security(&self, path: &mut PathItem) {
+        if self.security.is_empty() {
+            return;
+        }
+
+        for (_, operation) in iter_operations_mut(path) {
+            if allows_anonymous(operation) {
+                continue;
+            }
+
+            let mut op = TransformOperation::new(operation);
+            for (scheme, scopes) in &self.security {
+                op = op.security_requirement_scopes(scheme, scopes.iter().cloned());
+            }
+        }
+    }
+
+    /// Apply the [default error responses](Self::default_error_response)
+    /// and the [fallback response](Self::api_fallback) to the documented
+    /// operations.
+    #[cfg(not(aide_docs_disabled))]
+    fn apply_default_error_responses(&mut self) {
+        let default_error_responses = mem::take(&mut self.default_error_responses);
+        let fallback_response = self.fallback_response.take();
+        if default_error_responses.is_empty() && fallback_response.is_none() {
+            return;
+        }
+
+        in_context(|ctx| {
+            for item in self.paths.values_mut() {
+                for (_, operation) in iter_operations_mut(item) {
+                    for add_responses in &default_error_responses {
+                        add_responses(ctx, operation);
+                    }
+                    if let Some(res) = &fallback_response {
+                        add_response_if_missing(operation, 404, (**res).clone());
+                    }
+                }
+            }
+        });
+    }
+
+    /// Add the documentation of a route, operations that are
+    /// already documented for the path are reported.
+    #[cfg(not(aide_docs_disabled))]
+    fn add_path_item(
+        &mut self,
+        ctx: &mut GenContext,
+        path: &str,
+        mut path_item: PathItem,
+        handlers: IndexMap<&'static str, &'static str>,
+    ) {
+        if let Some(target) = self.paths.get_mut(path) {
+            let target_handlers = self.handlers.entry(path.into()).or_default();
+            merge_operations(
+                ctx,
+                path,
+                (target, target_handlers),
+                (&mut path_item, handlers),
+                MergePolicy::Error,
+            );
+            merge_paths(target, path_item);
+        } else {
+            self.paths.insert(path.into(), path_item);
+            self.handlers.insert(path.into(), handlers);
         }
     }
 
+    /// Add the documentation of a nested router under the prefix,
+    /// the parameters of the prefix are documented for all of
+    /// its operations.
+    #[cfg(not(aide_docs_disabled))]
+    fn nest_paths(
+        &mut self,
+        prefix: &str,
+        paths: IndexMap<String, PathItem>,
+        mut handlers: IndexMap<String, IndexMap<&'static str, &'static str>>,
+    ) {
+        in_context(|ctx| {
+            for (route, mut path_item) in paths {
+                add_prefix_parameters(ctx, prefix, &mut path_item);
+
+                let nested = nested_route(prefix, &route);
+                if let Some(handlers) = handlers.swap_remove(&route) {
+                    self.handlers.insert(nested.clone(), handlers);
+                }
+                self.paths.insert(nested, path_item);
+            }
+        });
+    }
+
     /// Transform the contained [`PathItem`]s.
     ///
     /// This method accepts a transform function to edit each [`PathItem`] provided by this router.
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut, unused_variables))]
     pub fn with_path_items(
         mut self,
         mut transform: impl FnMut(TransformPathItem) -> TransformPathItem,
     ) -> Self {
+        #[cfg(not(aide_docs_disabled))]
         for (_, item) in &mut self.paths {
             let _ = transform(TransformPathItem::new(item));
         }
@@ -292,16 +578,16 @@ where
     ///
     /// See [`axum::Router::route`] for details.
     #[tracing::instrument(skip_all, fields(% path))]
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut))]
     pub fn api_route(mut self, path: &str, mut method_router: ApiMethodRouter<S>) -> Self {
-        in_context(|ctx| {
-            let new_path_item = method_router.take_path_item();
+        #[cfg(not(aide_docs_disabled))]
+        {
+            let mut new_path_item = method_router.take_path_item();
+            self.apply_security(&mut new_path_item);
+            let handlers = mem::take(&mut method_router.handlers);
 
-            if let Some(path_item) = self.paths.get_mut(path) {
-                merge_paths(ctx, path, path_item, new_path_item);
-            } else {
-                self.paths.insert(path.into(), new_path_item);
-            }
-        });
+            in_context(|ctx| self.add_path_item(ctx, path, new_path_item, handlers));
+        }
 
         self.router = self.router.route(path, method_router.router);
         self
@@ -315,29 +601,72 @@ where
     ///
     /// See [`axum::Router::route`] or [`api_route`](crate::axum::ApiRouter::api_route) for details.
     #[tracing::instrument(skip_all, fields(%path))]
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut, unused_variables))]
     pub fn api_route_with(
         mut self,
         path: &str,
         mut method_router: ApiMethodRouter<S>,
         transform: impl FnOnce(TransformPathItem) -> TransformPathItem,
     ) -> Self {
+        #[cfg(not(aide_docs_disabled))]
         in_context(|ctx| {
             let mut p = method_router.take_path_item();
-            let t = transform(TransformPathItem::new(&mut p));
+            let _ = transform(TransformPathItem::new(&mut p));
 
-            if !t.hidden {
-                if let Some(path_item) = self.paths.get_mut(path) {
-                    merge_paths(ctx, path, path_item, p);
-                } else {
-                    self.paths.insert(path.into(), p);
-                }
-            }
+            // Only the operations are merged, the path can
+            // also have operations that are not hidden.
+            hide_operations(&mut p);
+            self.apply_security(&mut p);
+
+            let handlers = mem::take(&mut method_router.handlers);
+            self.add_path_item(ctx, path, p, handlers);
         });
 
         self.router = self.router.route(path, method_router.router);
         self
     }
 
+    /// Create a route to the given method router and include it in
+    /// the API documentation only if `enabled`.
+    ///
+    /// The route itself is always registered, only its documentation
+    /// is [hidden](TransformPathItem::hidden) otherwise, e.g. to document
+    /// experimental endpoints only in some environments:
+    ///
+    /// ```
+    /// use aide::axum::{routing::get, ApiRouter, DocsEnabled};
+    ///
+    /// async fn preview() {}
+    ///
+    /// let app: ApiRouter = ApiRouter::new().api_route_if(
+    ///     DocsEnabled::for_environments("APP_ENV", ["development", "staging"]),
+    ///     "/preview",
+    ///     get(preview),
+    ///     |p| p.summary("Experimental preview"),
+    /// );
+    /// ```
+    ///
+    /// See [`api_route_with`](crate::axum::ApiRouter::api_route_with) for details.
+    #[tracing::instrument(skip_all, fields(%path))]
+    pub fn api_route_if(
+        self,
+        enabled: impl Into<DocsEnabled>,
+        path: &str,
+        method_router: ApiMethodRouter<S>,
+        transform: impl FnOnce(TransformPathItem) -> TransformPathItem,
+    ) -> Self {
+        let enabled = enabled.into().is_enabled();
+
+        self.api_route_with(path, method_router, |p| {
+            let p = transform(p);
+            if enabled {
+                p
+            } else {
+                p.hidden(true)
+            }
+        })
+    }
+
     /// Turn this router into an [`axum::Router`] while merging
     /// generated documentation into the provided [`OpenApi`].
     #[tracing::instrument(skip_all)]
@@ -360,30 +689,175 @@ where
         self.router
     }
 
+    /// Same as [`finish_api_with`](Self::finish_api_with), but also
+    /// returns [statistics](GenStats) of the generation, e.g. the time
+    /// spent on each phase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aide::{
+    ///     axum::{routing::get, ApiRouter},
+    ///     openapi::OpenApi,
+    /// };
+    ///
+    /// async fn list_users() {}
+    ///
+    /// let mut api = OpenApi::default();
+    /// let (_app, stats): (axum::Router, _) = ApiRouter::new()
+    ///     .api_route("/users", get(list_users))
+    ///     .finish_api_with_stats(&mut api, |api| api.title("Users"));
+    ///
+    /// assert_eq!(stats.routes, 1);
+    /// println!("generated in {:?}", stats.total_time);
+    /// ```
+    #[tracing::instrument(skip_all)]
+    pub fn finish_api_with_stats<F>(
+        mut self,
+        api: &mut OpenApi,
+        transform: F,
+    ) -> (Router<S>, GenStats)
+    where
+        F: FnOnce(TransformOpenApi) -> TransformOpenApi,
+    {
+        let stats = self.merge_api_with(api, transform);
+        (self.router, stats)
+    }
+
+    /// Turn this router into an [`axum::Router`] while updating the
+    /// documentation of its routes in a document that was generated
+    /// earlier, e.g. when routes are mounted at runtime.
+    ///
+    /// The operations of the routes replace the existing ones, the
+    /// other routes are kept. The component schemas and other
+    /// components replace the existing ones with the same names.
+    ///
+    /// With [`gen::reuse_schemas`](crate::gen::reuse_schemas) the
+    /// schemas of the document are not generated again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aide::{
+    ///     axum::{routing::get, ApiRouter},
+    ///     gen,
+    ///     openapi::OpenApi,
+    /// };
+    ///
+    /// async fn list_users() {}
+    ///
+    /// async fn list_teams() {}
+    ///
+    /// let mut api = OpenApi::default();
+    /// let _app: axum::Router = ApiRouter::new()
+    ///     .api_route("/users", get(list_users))
+    ///     .finish_api(&mut api);
+    ///
+    /// gen::reuse_schemas(&api);
+    /// let _plugin: axum::Router = ApiRouter::new()
+    ///     .api_route("/teams", get(list_teams))
+    ///     .update_api(&mut api);
+    ///
+    /// assert_eq!(api.paths.unwrap().paths.len(), 2);
+    /// ```
+    #[tracing::instrument(skip_all)]
+    pub fn update_api(self, api: &mut OpenApi) -> Router<S> {
+        self.update_api_with(api, |x| x)
+    }
+
+    /// Same as [`update_api`](Self::update_api), but the transform
+    /// function is applied to the documentation of the routes of this
+    /// router before it is merged.
+    #[tracing::instrument(skip_all)]
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut, unused_variables))]
+    pub fn update_api_with<F>(mut self, api: &mut OpenApi, transform: F) -> Router<S>
+    where
+        F: FnOnce(TransformOpenApi) -> TransformOpenApi,
+    {
+        #[cfg(not(aide_docs_disabled))]
+        {
+            let mut fragment = OpenApi::default();
+            self.merge_api_with(&mut fragment, transform);
+            update_document(api, fragment);
+        }
+        self.router
+    }
+
     fn merge_api(&mut self, api: &mut OpenApi) {
-        self.merge_api_with(api, |x| x)
+        self.merge_api_with(api, |x| x);
+    }
+
+    #[cfg(aide_docs_disabled)]
+    fn merge_api_with<F>(&mut self, _api: &mut OpenApi, _transform: F) -> GenStats
+    where
+        F: FnOnce(TransformOpenApi) -> TransformOpenApi,
+    {
+        GenStats::default()
     }
-    fn merge_api_with<F>(&mut self, api: &mut OpenApi, transform: F)
+
+    #[cfg(not(aide_docs_disabled))]
+    fn merge_api_with<F>(&mut self, api: &mut OpenApi, transform: F) -> GenStats
     where
         F: FnOnce(TransformOpenApi) -> TransformOpenApi,
     {
+        let start = Instant::now();
+        let mut stats = GenStats {
+            routes: self.paths.len(),
+            ..Default::default()
+        };
+
+        self.apply_default_error_responses();
+
+        let schemas = mem::take(&mut self.schemas);
+        in_context(|ctx| {
+            let mut definitions = mem::take(ctx.schema.definitions_mut());
+            merge_schemas(ctx, &mut definitions, schemas);
+            *ctx.schema.definitions_mut() = definitions;
+        });
+
         if api.paths.is_none() {
             api.paths = Some(Default::default());
         }
 
         let paths = api.paths.as_mut().unwrap();
 
-        paths.paths = mem::take(&mut self.paths)
-            .into_iter()
-            .map(|(route, path)| {
-                (
-                    path_colon_params(&route).into_owned(),
-                    ReferenceOr::Item(path),
-                )
-            })
-            .collect();
+        paths.paths = in_context(|ctx| {
+            mem::take(&mut self.paths)
+                .into_iter()
+                .map(|(route, mut path)| {
+                    check_path_template(ctx, &route);
+                    name_path_parameters(ctx, &route, &mut path);
+                    if let Some(wildcard) = path_wildcard(&route) {
+                        path.extensions.insert(WILDCARD.into(), wildcard.into());
+                    }
+                    derive_implicit_operations(ctx, &mut path);
+                    add_method_not_allowed_responses(ctx, &mut path);
+                    stats.operations += path
+                        .iter()
+                        .filter(|(_, op)| !is_hidden(&op.extensions))
+                        .count();
+                    (
+                        path_colon_params(&route).into_owned(),
+                        ReferenceOr::Item(path),
+                    )
+                })
+                .collect()
+        });
+        stats.paths_time = start.elapsed();
 
+        let phase = Instant::now();
         let _ = transform(TransformOpenApi::new(api));
+        prune_hidden(api);
+        require_security_headers(api);
+
+        in_context(|ctx| {
+            if ctx.generate_examples {
+                populate_examples(api, ctx.schema.definitions());
+            }
+        });
+        stats.transform_time = phase.elapsed();
+
+        let phase = Instant::now();
 
         let needs_reset =
             in_context(|ctx| {
@@ -411,9 +885,73 @@ where
                 true
             });
 
+        let (
+            schema_hook,
+            post_process,
+            description_files,
+            localization,
+            interpolation,
+            redaction,
+            sdk_hints,
+        ) = in_context(|ctx| {
+            (
+                ctx.schema_hook.clone(),
+                ctx.post_process(),
+                ctx.description_files.clone(),
+                ctx.localization.clone(),
+                ctx.interpolation.clone(),
+                ctx.redaction.clone(),
+                ctx.sdk_hints.clone(),
+            )
+        });
+
+        if let (Some(hook), Some(components)) = (schema_hook, &mut api.components) {
+            for (name, schema) in &mut components.schemas {
+                hook(name, &mut schema.json_schema);
+            }
+        }
+        stats.schemas_time = phase.elapsed();
+
+        let phase = Instant::now();
+        stats.duplicate_schemas = post_process.apply(api);
+        if let Some(description_files) = description_files {
+            description_files.apply(api);
+        }
+        if let Some(localization) = localization {
+            localization.apply(api);
+        }
+        if let Some(interpolation) = interpolation {
+            interpolation.apply(api);
+        }
+        if let Some(redaction) = redaction {
+            redaction.apply(api);
+        }
+        if let Some(hints) = sdk_hints {
+            hints.apply(api);
+        }
+        stats.post_process_time = phase.elapsed();
+
         if needs_reset {
             gen::reset_context();
         }
+
+        stats.schemas = api.components.as_ref().map_or(0, |c| c.schemas.len());
+        stats.total_time = start.elapsed();
+
+        tracing::debug!(
+            routes = stats.routes,
+            operations = stats.operations,
+            schemas = stats.schemas,
+            duplicate_schemas = stats.duplicate_schemas,
+            paths_time = ?stats.paths_time,
+            transform_time = ?stats.transform_time,
+            schemas_time = ?stats.schemas_time,
+            post_process_time = ?stats.post_process_time,
+            total_time = ?stats.total_time,
+            "generated the documentation"
+        );
+
+        stats
     }
 }
 
@@ -445,20 +983,24 @@ where
 
     /// See [`axum::Router::nest`] for details.
     ///
-    /// The generated documentations are nested as well.
+    /// The generated documentations are nested as well. The parameters
+    /// of the prefix, e.g. of `/tenants/:tenant_id`, are documented
+    /// as string path parameters for all nested operations that do not
+    /// document them already.
     #[tracing::instrument(skip_all)]
-    pub fn nest(mut self, mut path: &str, router: ApiRouter<S>) -> Self {
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut))]
+    pub fn nest(mut self, path: &str, mut router: ApiRouter<S>) -> Self {
+        #[cfg(not(aide_docs_disabled))]
+        {
+            router.apply_default_error_responses();
+            for item in router.paths.values_mut() {
+                self.apply_security(item);
+            }
+            self.nest_paths(path, router.paths, router.handlers);
+            in_context(|ctx| merge_schemas(ctx, &mut self.schemas, router.schemas));
+        }
         self.router = self.router.nest(path, router.router);
 
-        path = path.trim_end_matches('/');
-
-        self.paths.extend(
-            router
-                .paths
-                .into_iter()
-                .map(|(route, path_item)| (path.to_string() + &route, path_item)),
-        );
-
         self
     }
 
@@ -471,16 +1013,23 @@ where
     ///
     /// Thus the primary and probably the only use-case
     /// of this function is nesting routers with different states.
+    ///
+    /// The parameters of the prefix are documented
+    /// as for [`nest`](Self::nest).
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut))]
     pub fn nest_api_service(mut self, mut path: &str, service: impl Into<ApiRouter<()>>) -> Self {
-        let router: ApiRouter<()> = service.into();
-
+        let mut router: ApiRouter<()> = service.into();
         path = path.trim_end_matches('/');
-        self.paths.extend(
-            router
-                .paths
-                .into_iter()
-                .map(|(route, path_item)| (path.to_string() + &route, path_item)),
-        );
+
+        #[cfg(not(aide_docs_disabled))]
+        {
+            router.apply_default_error_responses();
+            for item in router.paths.values_mut() {
+                self.apply_security(item);
+            }
+            self.nest_paths(path, router.paths, router.handlers);
+            in_context(|ctx| merge_schemas(ctx, &mut self.schemas, router.schemas));
+        }
         self.router = self.router.nest_service(path, router.router);
         self
     }
@@ -501,27 +1050,166 @@ where
     /// See [`axum::Router::merge`] for details.
     ///
     /// If an another [`ApiRouter`] is provided, the generated documentations
-    /// are merged as well..
-    pub fn merge<R>(mut self, other: R) -> Self
+    /// are merged as well. Operations that are documented by both routers
+    /// are reported as [`Error::OperationConflict`](crate::Error::OperationConflict),
+    /// or [`Error::OperationExists`](crate::Error::OperationExists) if their
+    /// handlers are not known, and the documentation of this router is kept, see
+    /// [`merge_with_policy`](Self::merge_with_policy) to resolve them otherwise.
+    pub fn merge<R>(self, other: R) -> Self
     where
         R: Into<ApiRouter<S>>,
     {
-        let other: ApiRouter<S> = other.into();
+        self.merge_with_policy(other, MergePolicy::Error)
+    }
 
-        for (key, path) in other.paths {
-            match self.paths.entry(key) {
-                Entry::Occupied(mut o) => {
-                    o.get_mut().merge_with(path);
-                }
-                Entry::Vacant(v) => {
-                    v.insert(path);
-                }
+    /// Same as [`merge`](Self::merge), but operations that are documented
+    /// by both routers are resolved with the given policy.
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut, unused_variables))]
+    pub fn merge_with_policy<R>(mut self, other: R, policy: MergePolicy) -> Self
+    where
+        R: Into<ApiRouter<S>>,
+    {
+        let mut other: ApiRouter<S> = other.into();
+
+        #[cfg(not(aide_docs_disabled))]
+        {
+            other.apply_default_error_responses();
+            for item in other.paths.values_mut() {
+                self.apply_security(item);
             }
+
+            in_context(|ctx| {
+                merge_schemas(ctx, &mut self.schemas, other.schemas);
+
+                for (key, mut path) in other.paths {
+                    let handlers = other.handlers.swap_remove(&key).unwrap_or_default();
+
+                    if let Some(target) = self.paths.get_mut(&key) {
+                        let target_handlers = self.handlers.entry(key.clone()).or_default();
+                        merge_operations(
+                            ctx,
+                            &key,
+                            (target, target_handlers),
+                            (&mut path, handlers),
+                            policy,
+                        );
+                        target.merge_with(path);
+                    } else {
+                        self.handlers.insert(key.clone(), handlers);
+                        self.paths.insert(key, path);
+                    }
+                }
+            });
         }
         self.router = self.router.merge(other.router);
         self
     }
 
+    /// Build routers on separate threads and merge them into this one
+    /// as with [`merge`](Self::merge).
+    ///
+    /// The documentation of large APIs takes a while to generate,
+    /// mostly because of the schemas of the inputs and outputs of
+    /// the handlers. The builders run on at most
+    /// [`available_parallelism`](std::thread::available_parallelism)
+    /// threads, each builder with a fresh [generation context](crate::gen),
+    /// the schemas generated by the builders are merged when the
+    /// documentation is finished.
+    ///
+    /// The generation context is thread-local, and the settings of the
+    /// context of the calling thread are **not** carried over to the
+    /// builders, e.g. [`gen::extract_schemas`](crate::gen::extract_schemas),
+    /// [`gen::infer_responses`](crate::gen::infer_responses), the hooks
+    /// and [`gen::on_error`](crate::gen::on_error). `setup` is called
+    /// before every builder to apply them again.
+    ///
+    /// Schemas with the same name but different definitions, e.g. of
+    /// types with the same name in different modules, are reported
+    /// as [`Error::SchemaConflict`](crate::Error::SchemaConflict),
+    /// and the first definition is kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aide::{
+    ///     axum::{routing::get, ApiRouter},
+    ///     gen,
+    /// };
+    ///
+    /// async fn list_users() {}
+    ///
+    /// async fn list_teams() {}
+    ///
+    /// let app: ApiRouter = ApiRouter::new().merge_parallel(
+    ///     || gen::infer_responses(true),
+    ///     [
+    ///         || ApiRouter::new().api_route("/users", get(list_users)),
+    ///         || ApiRouter::new().api_route("/teams", get(list_teams)),
+    ///     ],
+    /// );
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if a builder panics.
+    #[cfg_attr(aide_docs_disabled, allow(unused_mut))]
+    pub fn merge_parallel<I, F>(self, setup: impl Fn() + Sync, builders: I) -> Self
+    where
+        I: IntoIterator<Item = F>,
+        F: FnOnce() -> ApiRouter<S> + Send,
+    {
+        let builders = builders.into_iter().enumerate().collect::<Vec<_>>();
+        let threads = thread::available_parallelism()
+            .map_or(1, NonZeroUsize::get)
+            .min(builders.len());
+        let builders = Mutex::new(builders.into_iter());
+
+        let worker = || {
+            let mut routers = Vec::new();
+
+            loop {
+                let next = builders
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .next();
+                let Some((index, builder)) = next else {
+                    break;
+                };
+
+                // The previous builder of the thread may have changed the context.
+                crate::gen::reset_context();
+                setup();
+                let mut router = builder();
+                #[cfg(not(aide_docs_disabled))]
+                in_context(|ctx| {
+                    let definitions = ctx.schema.take_definitions();
+                    merge_schemas(ctx, &mut router.schemas, definitions);
+                });
+                routers.push((index, router));
+            }
+
+            routers
+        };
+
+        let mut routers = thread::scope(|scope| {
+            let threads = (0..threads)
+                .map(|_| scope.spawn(worker))
+                .collect::<Vec<_>>();
+
+            threads
+                .into_iter()
+                .flat_map(|thread| thread.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .collect::<Vec<_>>()
+        });
+
+        // Merge in the order of the builders, regardless of the threads.
+        routers.sort_by_key(|(index, _)| *index);
+        routers
+            .into_iter()
+            .map(|(_, router)| router)
+            .fold(self, Self::merge)
+    }
+
     /// See [`axum::Router::layer`] for details.
     pub fn layer<L>(self, layer: L) -> ApiRouter<S>
     where
@@ -533,7 +1221,12 @@ where
     {
         ApiRouter {
             paths: self.paths,
+            handlers: self.handlers,
             router: self.router.layer(layer),
+            default_error_responses: self.default_error_responses,
+            fallback_response: self.fallback_response,
+            security: self.security,
+            schemas: self.schemas,
         }
     }
 
@@ -560,6 +1253,39 @@ where
         self
     }
 
+    /// Route the requests that match no route to the handler,
+    /// and document its response as the `404` response of every
+    /// operation of this router.
+    ///
+    /// The response is added like the
+    /// [default error responses](Self::default_error_response),
+    /// responses that are already documented for an operation are kept.
+    ///
+    /// See [`axum::Router::fallback`] for details.
+    #[cfg_attr(aide_docs_disabled, allow(unused_variables))]
+    pub fn api_fallback<H, I, O, T, F>(mut self, handler: H, transform: F) -> Self
+    where
+        H: Handler<T, S> + OperationHandler<I, O>,
+        I: OperationInput,
+        O: OperationOutput,
+        T: 'static,
+        F: FnOnce(TransformResponse<O::Inner>) -> TransformResponse<O::Inner>,
+    {
+        #[cfg(not(aide_docs_disabled))]
+        {
+            let mut res = in_context(|ctx| O::operation_response(ctx, &mut Operation::default()))
+                .unwrap_or_else(|| Response {
+                    description: "no route matches the request".into(),
+                    ..Default::default()
+                });
+            let _ = transform(TransformResponse::new(&mut res));
+
+            self.fallback_response = Some(Box::new(res));
+        }
+        self.router = self.router.fallback(handler);
+        self
+    }
+
     /// See [`axum::Router::fallback_service`] for details.
     pub fn fallback_service<T>(mut self, svc: T) -> Self
     where
@@ -607,30 +1333,252 @@ impl ApiRouter<()> {
     }
 }
 
-impl<S> From<Router<S>> for ApiRouter<S> {
-    fn from(router: Router<S>) -> Self {
-        ApiRouter {
-            paths: IndexMap::new(),
-            router,
+/// How operations that are documented by both routers are resolved
+/// by [`ApiRouter::merge_with_policy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the documentation of the router that is merged into.
+    PreferFirst,
+    /// Keep the documentation of the router that is merged.
+    PreferLast,
+    /// Report the conflict as [`Error::OperationConflict`](crate::Error::OperationConflict)
+    /// and keep the documentation of the router that is merged into.
+    #[default]
+    Error,
+}
+
+/// Replace the operations and components of a document
+/// with the ones of another document.
+#[cfg(not(aide_docs_disabled))]
+fn update_document(api: &mut OpenApi, fragment: OpenApi) {
+    let paths = api.paths.get_or_insert_with(Default::default);
+    for (route, item) in fragment.paths.into_iter().flat_map(|p| p.paths) {
+        let target = paths
+            .paths
+            .get_mut(&route)
+            .and_then(ReferenceOr::as_item_mut);
+        match (target, item) {
+            (Some(target), ReferenceOr::Item(item)) => update_path_item(target, item),
+            (_, item) => {
+                paths.paths.insert(route, item);
+            }
         }
     }
-}
 
-impl<S> From<ApiRouter<S>> for Router<S> {
-    fn from(api: ApiRouter<S>) -> Self {
-        api.router
+    if let Some(from) = fragment.components {
+        let components = api.components.get_or_insert_with(Default::default);
+        components.schemas.extend(from.schemas);
+        components.security_schemes.extend(from.security_schemes);
+        components.responses.extend(from.responses);
+        components.parameters.extend(from.parameters);
+        components.examples.extend(from.examples);
+        components.request_bodies.extend(from.request_bodies);
+        components.headers.extend(from.headers);
+        components.links.extend(from.links);
+        components.callbacks.extend(from.callbacks);
+        components.path_items.extend(from.path_items);
+        components.extensions.extend(from.extensions);
+    }
+
+    for tag in fragment.tags {
+        if !api.tags.iter().any(|t| t.name == tag.name) {
+            api.tags.push(tag);
+        }
     }
 }
 
-/// A trait analogous to [`IntoResponse`] that allows writing
-/// `impl IntoApiResponse` for documented handlers.
-/// Axum's `IntoResponse` cannot be used for these handlers
-/// since the return type has to implement [`OperationOutput`].
-///
-/// This trait has a blanket implementation for all types
-/// that implement [`IntoResponse`] and [`OperationOutput`],
-/// it should not be implemented manually.
-pub trait IntoApiResponse: IntoResponse + OperationOutput {}
+/// Replace the operations and the path-level documentation
+/// of a path item with the ones of another path item.
+#[cfg(not(aide_docs_disabled))]
+fn update_path_item(target: &mut PathItem, mut item: PathItem) {
+    for method in METHODS {
+        if let Some(operation) = operation_mut(&mut item, method).take() {
+            *operation_mut(target, method) = Some(operation);
+        }
+    }
+
+    if item.summary.is_some() {
+        target.summary = item.summary;
+    }
+    if item.description.is_some() {
+        target.description = item.description;
+    }
+    if !item.parameters.is_empty() {
+        target.parameters = item.parameters;
+    }
+    target.extensions.extend(item.extensions);
+}
+
+/// Merge schema definitions into another set of definitions, keeping
+/// the existing definition if a schema is defined differently.
+#[cfg(not(aide_docs_disabled))]
+fn merge_schemas(
+    ctx: &mut GenContext,
+    target: &mut Map<String, Schema>,
+    from: Map<String, Schema>,
+) {
+    for (name, schema) in from {
+        match target.get(&name) {
+            Some(existing) if *existing != schema => ctx.error(Error::SchemaConflict(name)),
+            Some(_) => {}
+            None => {
+                target.insert(name, schema);
+            }
+        }
+    }
+}
+
+/// Move the operations of a path item into another one, resolving
+/// the operations that are documented in both with the policy.
+#[cfg(not(aide_docs_disabled))]
+fn merge_operations(
+    ctx: &mut GenContext,
+    path: &str,
+    (target, target_handlers): (&mut PathItem, &mut IndexMap<&'static str, &'static str>),
+    (from, from_handlers): (&mut PathItem, IndexMap<&'static str, &'static str>),
+    policy: MergePolicy,
+) {
+    for method in METHODS {
+        let Some(operation) = operation_mut(from, method).take() else {
+            continue;
+        };
+        let handler = from_handlers.get(method).copied();
+        let existing = operation_mut(target, method);
+
+        if existing.is_some() {
+            match policy {
+                MergePolicy::PreferFirst => continue,
+                MergePolicy::PreferLast => {}
+                MergePolicy::Error => {
+                    match (target_handlers.get(method).copied(), handler) {
+                        (Some(first), Some(second)) => ctx.error(Error::OperationConflict {
+                            path: path.into(),
+                            method,
+                            first,
+                            second,
+                        }),
+                        _ => ctx.error(Error::OperationExists(path.into(), method)),
+                    }
+                    continue;
+                }
+            }
+        }
+
+        *existing = Some(operation);
+        match handler {
+            Some(handler) => target_handlers.insert(method, handler),
+            None => target_handlers.shift_remove(method),
+        };
+    }
+}
+
+impl<S> From<Router<S>> for ApiRouter<S> {
+    fn from(router: Router<S>) -> Self {
+        ApiRouter {
+            paths: IndexMap::new(),
+            handlers: IndexMap::new(),
+            router,
+            default_error_responses: Vec::new(),
+            fallback_response: None,
+            security: Vec::new(),
+            schemas: Map::new(),
+        }
+    }
+}
+
+/// Add the responses of `E` to an operation,
+/// see [`ApiRouter::default_error_response`].
+#[cfg(not(aide_docs_disabled))]
+fn add_default_error_response<E: OperationOutput>(ctx: &mut GenContext, operation: &mut Operation) {
+    for (status, res) in responses_of::<E>(ctx, operation, None) {
+        let responses = operation.responses.get_or_insert_with(Default::default);
+        match status {
+            Some(200..=299) | None => {
+                if responses.default.is_none() {
+                    responses.default = Some(ReferenceOr::Item(res));
+                }
+            }
+            Some(status) => {
+                responses
+                    .responses
+                    .entry(StatusCode::Code(status))
+                    .or_insert(ReferenceOr::Item(res));
+            }
+        }
+    }
+}
+
+impl<S> From<ApiRouter<S>> for Router<S> {
+    fn from(api: ApiRouter<S>) -> Self {
+        api.router
+    }
+}
+
+/// Whether the documentation of a route is enabled,
+/// see [`ApiRouter::api_route_if`].
+///
+/// Besides a flag known at compile time, e.g. `cfg!(debug_assertions)`,
+/// it can be decided at runtime from the environment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DocsEnabled(bool);
+
+impl DocsEnabled {
+    /// Enable or disable the documentation.
+    #[must_use]
+    pub fn new(enabled: bool) -> Self {
+        Self(enabled)
+    }
+
+    /// Enable the documentation if the environment variable
+    /// is set to `1`, `true`, `yes` or `on`.
+    #[must_use]
+    pub fn from_env(var: &str) -> Self {
+        Self(std::env::var(var).is_ok_and(|value| {
+            ["1", "true", "yes", "on"]
+                .iter()
+                .any(|v| value.trim().eq_ignore_ascii_case(v))
+        }))
+    }
+
+    /// Enable the documentation if the environment variable
+    /// is set to one of the environments.
+    #[must_use]
+    pub fn for_environments<'a>(
+        var: &str,
+        environments: impl IntoIterator<Item = &'a str>,
+    ) -> Self {
+        Self(std::env::var(var).is_ok_and(|value| {
+            environments
+                .into_iter()
+                .any(|env| value.trim().eq_ignore_ascii_case(env))
+        }))
+    }
+
+    /// Whether the documentation is enabled.
+    #[must_use]
+    pub fn is_enabled(self) -> bool {
+        self.0
+    }
+}
+
+impl From<bool> for DocsEnabled {
+    fn from(enabled: bool) -> Self {
+        Self(enabled)
+    }
+}
+
+/// A trait analogous to [`IntoResponse`] that allows writing
+/// `impl IntoApiResponse` for documented handlers.
+/// Axum's `IntoResponse` cannot be used for these handlers
+/// since the return type has to implement [`OperationOutput`].
+///
+/// This trait has a blanket implementation for all types
+/// that implement [`IntoResponse`] and [`OperationOutput`],
+/// it should not be implemented manually.
+///
+/// The responses of handlers returning `impl IntoApiResponse`
+/// are still inferred from the actual return type.
+pub trait IntoApiResponse: IntoResponse + OperationOutput {}
 
 impl<T> IntoApiResponse for T where T: IntoResponse + OperationOutput {}
 
@@ -664,6 +1612,7 @@ where
 impl<S> private::Sealed for Router<S> {}
 
 #[doc(hidden)]
+#[allow(clippy::large_enum_variant)]
 pub enum ServiceOrApiRouter<T> {
     Service(T),
     Router(ApiRouter<()>),
@@ -746,7 +1695,7 @@ where
 #[cfg(test)]
 #[allow(clippy::unused_async)]
 mod tests {
-    use crate::axum::{routing, ApiRouter};
+    use crate::axum::{routing, ApiRouter, DocsEnabled};
     use axum::{extract::State, handler::Handler};
 
     async fn test_handler1(State(_): State<TestState>) {}
@@ -811,4 +1760,1302 @@ mod tests {
             routing::get(test_handler3.layer(tower_layer::Identity::new())),
         );
     }
+
+    #[test]
+    fn test_derived_head_and_options_operations() {
+        crate::gen::derive_head_operations(true);
+        crate::gen::derive_options_operations(true);
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/test", routing::get(test_handler3).post(test_handler3))
+            .finish_api(&mut api);
+
+        crate::gen::reset_context();
+
+        let item = api.paths.unwrap().paths.swap_remove("/test").unwrap();
+        let item = item.as_item().unwrap();
+
+        assert!(item.head.is_some());
+        assert!(item.options.is_some());
+    }
+
+    #[test]
+    fn test_schema_hook() {
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct NewTodo {
+            title: String,
+        }
+
+        async fn create_todo(_: axum::Form<NewTodo>) {}
+
+        crate::gen::on_schema(|name, schema| {
+            if let schemars::schema::Schema::Object(schema) = schema {
+                schema.metadata().title = Some(format!("{name}Model"));
+            }
+        });
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/todos", routing::post(create_todo))
+            .finish_api(&mut api);
+
+        crate::gen::reset_context();
+
+        let schemas = serde_json::to_value(api.components.unwrap().schemas).unwrap();
+        assert_eq!(schemas["NewTodo"]["title"], "NewTodoModel");
+    }
+
+    #[test]
+    fn test_query_arrays() {
+        use crate::{
+            helpers::separated::CommaSeparated,
+            openapi::{Operation, ParameterStyle},
+            OperationInput,
+        };
+
+        #[derive(serde::Deserialize, schemars::JsonSchema)]
+        #[allow(dead_code)]
+        struct Search {
+            tags: Vec<String>,
+            #[serde(default)]
+            ids: Option<Vec<u32>>,
+            #[schemars(with = "CommaSeparated<String>")]
+            fields: String,
+            page: Option<u32>,
+        }
+
+        fn parameters<T: OperationInput>() -> Vec<(String, bool, ParameterStyle, bool)> {
+            let mut operation = Operation::default();
+            crate::gen::in_context(|ctx| T::operation_input(ctx, &mut operation));
+
+            operation
+                .parameters
+                .iter()
+                .filter_map(|p| p.as_item())
+                .map(|p| {
+                    let data = p.parameter_data_ref();
+                    (data.name.clone(), data.required, p.style(), p.explode())
+                })
+                .collect()
+        }
+
+        let param =
+            |name: &str, required, style, explode| (name.to_string(), required, style, explode);
+        let repeated = [
+            param("fields", true, ParameterStyle::Form, false),
+            param("ids", false, ParameterStyle::Form, true),
+            param("page", false, ParameterStyle::Form, true),
+            param("tags", true, ParameterStyle::Form, true),
+        ];
+
+        assert_eq!(parameters::<axum::extract::Query<Search>>(), repeated);
+        #[cfg(feature = "axum-extra-query")]
+        assert_eq!(parameters::<axum_extra::extract::Query<Search>>(), repeated);
+
+        crate::gen::query_arrays(crate::operation::QueryArrays::PipeDelimited);
+        assert_eq!(
+            parameters::<axum::extract::Query<Search>>(),
+            [
+                param("fields", true, ParameterStyle::Form, false),
+                param("ids", false, ParameterStyle::PipeDelimited, false),
+                param("page", false, ParameterStyle::Form, true),
+                param("tags", true, ParameterStyle::PipeDelimited, false),
+            ]
+        );
+
+        #[cfg(feature = "axum-extra-form")]
+        {
+            let mut operation = Operation::default();
+            let definitions = crate::gen::in_context(|ctx| {
+                <axum_extra::extract::Form<Search>>::operation_input(ctx, &mut operation);
+                serde_json::to_value(ctx.schema.definitions()).unwrap()
+            });
+            let body = serde_json::to_value(operation.request_body).unwrap();
+            let schema = &body["content"]["application/x-www-form-urlencoded"]["schema"];
+            assert_eq!(schema["$ref"], "#/components/schemas/Search");
+
+            let properties = &definitions["Search"]["properties"];
+            assert_eq!(properties["tags"]["type"], "array");
+            assert_eq!(
+                properties["ids"]["type"],
+                serde_json::json!(["array", "null"])
+            );
+        }
+
+        crate::gen::reset_context();
+    }
+
+    #[test]
+    fn test_header_parameter() {
+        use crate::openapi::{ApiKeyLocation, SecurityScheme};
+
+        async fn handler(_: axum::http::HeaderMap) {}
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos",
+                routing::get_with(handler, |op| {
+                    op.header_parameter::<String, _>("X-Tenant-Id", |p| p)
+                        .header_parameter::<Option<String>, _>("X-Request-Id", |p| p)
+                        .header_parameter::<Option<String>, _>("X-Api-Key", |p| p)
+                        .header_parameter::<String, _>("x-request-id", |p| {
+                            p.description("Overrides the existing parameter.")
+                        })
+                }),
+            )
+            .finish_api_with(&mut api, |api| {
+                api.security_scheme(
+                    "ApiKey",
+                    SecurityScheme::ApiKey {
+                        location: ApiKeyLocation::Header,
+                        name: "X-API-KEY".into(),
+                        description: None,
+                        extensions: Default::default(),
+                    },
+                )
+                .security_requirement("ApiKey")
+            });
+
+        let paths = api.paths.unwrap();
+        let todos = paths.paths["/todos"].as_item().unwrap();
+        let params = todos
+            .get
+            .as_ref()
+            .unwrap()
+            .parameters
+            .iter()
+            .map(|p| {
+                let data = p.as_item().unwrap().parameter_data_ref();
+                let described = data.description.is_some();
+                (data.name.as_str(), data.required, described)
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            params,
+            [
+                ("X-Tenant-Id", true, false),
+                ("X-Request-Id", false, true),
+                ("X-Api-Key", true, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_path_parameter_names() {
+        use std::{cell::RefCell, rc::Rc};
+
+        use crate::Error;
+
+        async fn repo(_: axum::extract::Path<(String, u32)>) {}
+        async fn user(_: axum::extract::Path<u64>) {}
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_ = errors.clone();
+        crate::gen::on_error(move |err| errors_.borrow_mut().push(err));
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/orgs/:org_id/repos/:repo_id", routing::get(repo))
+            .api_route("/users/:user_id/*rest", routing::get(user))
+            .finish_api(&mut api);
+
+        crate::gen::reset_context();
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let params = &paths["/orgs/{org_id}/repos/{repo_id}"]["get"]["parameters"];
+        assert_eq!(params[0]["name"], "org_id");
+        assert_eq!(params[0]["schema"]["type"], "string");
+        assert_eq!(params[1]["name"], "repo_id");
+        assert_eq!(params[1]["schema"]["type"], "integer");
+        assert!(params[0].get(crate::operation::PATH_POSITION).is_none());
+
+        assert_eq!(
+            paths["/users/{user_id}/{rest}"][crate::util::WILDCARD],
+            "rest"
+        );
+        let params = &paths["/users/{user_id}/{rest}"]["get"]["parameters"];
+        assert_eq!(params.as_array().unwrap().len(), 1);
+        assert_eq!(params[0]["name"], "user_id");
+
+        let errors = errors.borrow();
+        assert_eq!(errors.len(), 1);
+        assert!(matches!(
+            &errors[0],
+            Error::PathParameterCount(path, 2, 1) if path == "/users/:user_id/*rest"
+        ));
+    }
+
+    #[test]
+    fn test_hidden() {
+        async fn handler() {}
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos",
+                routing::get_with(handler, |op| op.id("list").hidden(true))
+                    .post_with(handler, |op| op.id("create").hidden(true)),
+            )
+            .api_route_with("/debug", routing::get(handler), |p| p.hidden(true))
+            .finish_api_with(&mut api, |api| {
+                api.all_operations(|mut op| {
+                    assert!(op.is_hidden());
+                    let create = op.inner_mut().operation_id.as_deref() == Some("create");
+                    op.hidden(!create)
+                })
+            });
+
+        let paths = api.paths.unwrap();
+        assert_eq!(paths.paths.keys().collect::<Vec<_>>(), ["/todos"]);
+
+        let todos = paths.paths["/todos"].as_item().unwrap();
+        assert!(todos.get.is_none());
+        assert!(todos.post.as_ref().unwrap().extensions.is_empty());
+    }
+
+    #[test]
+    fn test_hidden_methods_not_allowed() {
+        async fn handler() {}
+
+        crate::gen::derive_options_operations(true);
+        crate::gen::document_method_not_allowed(true);
+
+        let mut api = crate::openapi::OpenApi::default();
+        let (_app, stats): (axum::Router, _) = ApiRouter::new()
+            .api_route(
+                "/todos",
+                routing::get(handler).delete_with(handler, |op| op.hidden(true)),
+            )
+            .finish_api_with_stats(&mut api, |api| api);
+
+        crate::gen::reset_context();
+
+        assert_eq!(stats.operations, 2);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let todos = &paths["/todos"];
+        assert!(todos.get("delete").is_none());
+        assert_eq!(
+            todos["get"]["responses"]["405"]["headers"]["Allow"]["example"],
+            "GET, OPTIONS"
+        );
+        assert_eq!(
+            todos["options"]["responses"]["204"]["headers"]["Allow"]["example"],
+            "GET, OPTIONS"
+        );
+    }
+
+    #[test]
+    fn test_api_route_if() {
+        async fn handler() {}
+
+        std::env::set_var("AIDE_TEST_DOCS_ENV", "Staging");
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route_if(true, "/stable", routing::get(handler), |p| p)
+            .api_route_if(false, "/debug", routing::get(handler), |p| p)
+            .api_route_if(
+                DocsEnabled::for_environments("AIDE_TEST_DOCS_ENV", ["staging"]),
+                "/preview",
+                routing::get(handler),
+                |p| p,
+            )
+            .api_route_if(
+                DocsEnabled::from_env("AIDE_TEST_DOCS_MISSING"),
+                "/experimental",
+                routing::get(handler),
+                |p| p,
+            )
+            .finish_api(&mut api);
+
+        let paths = api.paths.unwrap();
+        assert_eq!(
+            paths.paths.keys().collect::<Vec<_>>(),
+            ["/stable", "/preview"]
+        );
+    }
+
+    #[test]
+    fn test_inferred_result_responses() {
+        use std::{cell::RefCell, rc::Rc};
+
+        use axum::{
+            http::StatusCode,
+            response::{IntoResponse, Response},
+            Json,
+        };
+
+        use crate::{axum::IntoApiResponse, gen::GenContext, openapi, OperationOutput};
+
+        struct AppError;
+
+        impl IntoResponse for AppError {
+            fn into_response(self) -> Response {
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+
+        impl OperationOutput for AppError {
+            type Inner = ();
+
+            fn operation_response(
+                _ctx: &mut GenContext,
+                _operation: &mut openapi::Operation,
+            ) -> Option<openapi::Response> {
+                Some(openapi::Response {
+                    description: "an error".into(),
+                    ..Default::default()
+                })
+            }
+        }
+
+        async fn app_error() -> Result<Json<String>, AppError> {
+            Ok(Json(String::new()))
+        }
+
+        async fn json_error() -> Result<Json<String>, (StatusCode, Json<String>)> {
+            Ok(Json(String::new()))
+        }
+
+        async fn opaque() -> impl IntoApiResponse {
+            (StatusCode::CREATED, Json(String::new()))
+        }
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_ = errors.clone();
+        crate::gen::on_error(move |err| errors_.borrow_mut().push(err.to_string()));
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/app-error", routing::get(app_error))
+            .api_route("/json-error", routing::get(json_error))
+            .api_route("/opaque", routing::get(opaque))
+            .finish_api(&mut api);
+
+        crate::gen::reset_context();
+        assert!(errors.borrow().is_empty(), "{:?}", errors.borrow());
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let responses = &paths["/app-error"]["get"]["responses"];
+        assert!(responses["200"]["content"]["application/json"].is_object());
+        assert_eq!(responses["default"]["description"], "an error");
+
+        let responses = &paths["/json-error"]["get"]["responses"];
+        assert!(responses["200"].is_object());
+        assert!(responses["default"]["content"]["application/json"].is_object());
+
+        let responses = &paths["/opaque"]["get"]["responses"];
+        assert!(responses["200"]["content"]["application/json"].is_object());
+    }
+    #[test]
+    fn test_default_error_response() {
+        use axum::Json;
+
+        use crate::helpers::{one_of::OneOfResponse, status::WithStatus};
+
+        type ApiError =
+            OneOfResponse<(WithStatus<404, Json<String>>, WithStatus<500, Json<String>>)>;
+
+        async fn handler() -> Json<u64> {
+            Json(0)
+        }
+
+        let users = ApiRouter::new()
+            .api_route(
+                "/users/:id",
+                routing::get_with(handler, |op| op.id("get_user").response::<404, ()>()),
+            )
+            .default_error_response::<ApiError>()
+            .api_route("/users", routing::get(handler));
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/health", routing::get(handler))
+            .nest("/api", users)
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let responses = &paths["/api/users"]["get"]["responses"];
+        assert!(responses["200"].is_object());
+        assert!(responses["404"]["content"]["application/json"].is_object());
+        assert!(responses["500"]["content"]["application/json"].is_object());
+
+        let responses = &paths["/api/users/{id}"]["get"]["responses"];
+        assert!(responses["404"].get("content").is_none());
+        assert!(responses["500"].is_object());
+
+        let responses = paths["/health"]["get"]["responses"].as_object().unwrap();
+        assert_eq!(responses.keys().collect::<Vec<_>>(), ["200"]);
+    }
+    #[test]
+    fn test_operation_limits() {
+        use std::time::Duration;
+
+        use crate::transform::{MAX_BODY_SIZE, TIMEOUT_MS};
+
+        async fn upload(_body: axum::Json<String>) {}
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/upload",
+                routing::post_with(upload, |op| {
+                    op.timeout(Duration::from_secs(30)).max_body_size(1024)
+                }),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let op = &paths["/upload"]["post"];
+        assert_eq!(op[TIMEOUT_MS], 30_000);
+        assert_eq!(op[MAX_BODY_SIZE], 1024);
+        assert_eq!(op["requestBody"][MAX_BODY_SIZE], 1024);
+        assert_eq!(
+            op["requestBody"]["content"]["application/json"]["schema"]["maxLength"],
+            1024
+        );
+        assert!(op["responses"]["408"].is_object());
+        assert!(op["responses"]["413"].is_object());
+    }
+    #[test]
+    fn test_require_security() {
+        async fn handler() {}
+
+        let nested = ApiRouter::new().api_route("/items", routing::get(handler));
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/login", routing::post(handler))
+            .require_security("bearerAuth", ["todos"])
+            .api_route("/todos", routing::get(handler))
+            .api_route(
+                "/todos/public",
+                routing::get_with(handler, |op| op.id("public").allow_anonymous()),
+            )
+            .api_route(
+                "/todos/optional",
+                routing::get_with(handler, |op| {
+                    op.security_requirement("apiKey").allow_anonymous()
+                }),
+            )
+            .api_route(
+                "/todos/health",
+                routing::get_with(handler, |op| {
+                    op.security_requirement("apiKey").no_security()
+                }),
+            )
+            .nest("/nested", nested)
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        assert!(paths["/login"]["post"].get("security").is_none());
+        assert_eq!(
+            paths["/todos"]["get"]["security"],
+            serde_json::json!([{ "bearerAuth": ["todos"] }])
+        );
+        assert_eq!(
+            paths["/todos/public"]["get"]["security"],
+            serde_json::json!([{}])
+        );
+        assert_eq!(
+            paths["/todos/optional"]["get"]["security"],
+            serde_json::json!([{ "apiKey": [] }, {}])
+        );
+        assert_eq!(
+            paths["/todos/health"]["get"]["security"],
+            serde_json::json!([{}])
+        );
+        assert_eq!(
+            paths["/nested/items"]["get"]["security"],
+            serde_json::json!([{ "bearerAuth": ["todos"] }])
+        );
+    }
+    #[test]
+    fn test_security_optional() {
+        async fn handler() {}
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos",
+                routing::get_with(handler, |op| {
+                    op.security_requirement("token")
+                        .security_optional()
+                        .security_optional()
+                }),
+            )
+            .api_route(
+                "/todos/public",
+                routing::get_with(handler, |op| op.id("public").security_optional()),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        assert_eq!(
+            paths["/todos"]["get"]["security"],
+            serde_json::json!([{ "token": [] }, {}])
+        );
+        assert_eq!(
+            paths["/todos/public"]["get"]["security"],
+            serde_json::json!([{}])
+        );
+    }
+    #[test]
+    fn test_input_example_named() {
+        use axum::{Form, Json};
+        use schemars::JsonSchema;
+
+        #[derive(serde::Serialize, serde::Deserialize, JsonSchema)]
+        struct NewUser {
+            name: String,
+            email: Option<String>,
+        }
+
+        async fn create_user(Json(_user): Json<NewUser>) {}
+
+        async fn create_user_form(Form(_user): Form<NewUser>) {}
+
+        let user = |email: Option<&str>| NewUser {
+            name: "alice".into(),
+            email: email.map(Into::into),
+        };
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users",
+                routing::post_with(create_user, |op| {
+                    op.input_example_named::<NewUser>("minimal", "Required fields", user(None))
+                        .input_example_named::<NewUser>(
+                            "full",
+                            "All fields",
+                            user(Some("alice@example.com")),
+                        )
+                }),
+            )
+            .api_route(
+                "/users/form",
+                routing::post_with(create_user_form, |op| {
+                    op.input_example_named::<NewUser>("minimal", "Required fields", user(None))
+                }),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let examples =
+            &paths["/users"]["post"]["requestBody"]["content"]["application/json"]["examples"];
+        assert_eq!(examples.as_object().unwrap().len(), 2);
+        assert_eq!(examples["minimal"]["summary"], "Required fields");
+        assert_eq!(
+            examples["full"]["value"],
+            serde_json::json!({ "name": "alice", "email": "alice@example.com" })
+        );
+        assert_eq!(
+            paths["/users/form"]["post"]["requestBody"]["content"]
+                ["application/x-www-form-urlencoded"]["examples"]["minimal"]["value"],
+            serde_json::json!({ "name": "alice", "email": null })
+        );
+    }
+
+    #[test]
+    fn test_compose_schemas() {
+        use schemars::JsonSchema;
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Cat {
+            kind: String,
+            lives: u8,
+        }
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Dog {
+            kind: String,
+            good: bool,
+        }
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new().finish_api_with(&mut api, |api| {
+            api.schema::<Cat>(|s| s)
+                .schema::<Dog>(|s| s)
+                .component_schema("Pet", |s| {
+                    s.description("A pet.")
+                        .one_of(["Cat"])
+                        .one_of(["Cat", "Dog"])
+                        .discriminator("kind")
+                })
+                .component_schema("Cat", |s| s.all_of(["Animal"]))
+        });
+
+        let schemas = serde_json::to_value(api.components.unwrap().schemas).unwrap();
+        assert_eq!(
+            schemas["Pet"],
+            serde_json::json!({
+                "description": "A pet.",
+                "oneOf": [
+                    { "$ref": "#/components/schemas/Cat" },
+                    { "$ref": "#/components/schemas/Dog" }
+                ],
+                "discriminator": { "propertyName": "kind" }
+            })
+        );
+        assert_eq!(
+            schemas["Cat"]["allOf"],
+            serde_json::json!([{ "$ref": "#/components/schemas/Animal" }])
+        );
+        assert!(schemas["Dog"].is_object());
+    }
+
+    #[test]
+    fn test_tag_groups() {
+        use crate::{openapi::Tag, transform::TAG_GROUPS};
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new().finish_api_with(&mut api, |api| {
+            api.tag(Tag {
+                name: "users".into(),
+                description: Some("The users.".into()),
+                ..Default::default()
+            })
+            .tag_group("Accounts", ["users", "orgs"])
+            .tag_group("Content", ["todos"])
+            .tag_group("Accounts", ["orgs", "teams"])
+        });
+
+        let tags = api.tags.iter().map(|t| t.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(tags, ["users", "orgs", "todos", "teams"]);
+        assert_eq!(api.tags[0].description.as_deref(), Some("The users."));
+        assert_eq!(
+            api.extensions[TAG_GROUPS],
+            serde_json::json!([
+                { "name": "Accounts", "tags": ["users", "orgs", "teams"] },
+                { "name": "Content", "tags": ["todos"] },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_annotate_responses() {
+        use crate::openapi::StatusCode;
+
+        async fn handler() -> axum::Json<u64> {
+            axum::Json(0)
+        }
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users",
+                routing::get_with(handler, |op| op.id("list_users").response::<404, ()>()),
+            )
+            .finish_api_with(&mut api, |api| {
+                api.annotate_responses(|status, op, res| {
+                    if let Some(StatusCode::Code(code @ 400..)) = status {
+                        let id = op.operation_id.as_deref().unwrap_or_default();
+                        res.description = format!(
+                            "{}\n\nSee the [runbook](https://runbooks.example.com/{id}/{code}).",
+                            res.description
+                        );
+                    }
+                })
+            });
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let responses = &paths["/users"]["get"]["responses"];
+        assert!(!responses["200"]["description"]
+            .as_str()
+            .unwrap()
+            .contains("runbook"));
+        assert_eq!(
+            responses["404"]["description"],
+            "no content\n\nSee the [runbook](https://runbooks.example.com/list_users/404)."
+        );
+    }
+
+    #[test]
+    fn test_response_header_all() {
+        use crate::openapi::StatusCode;
+
+        async fn handler() -> axum::Json<u64> {
+            axum::Json(0)
+        }
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users",
+                routing::get_with(handler, |op| op.id("list_users").response::<500, ()>()),
+            )
+            .finish_api_with(&mut api, |api| {
+                api.response_header_all::<String>("X-Request-Id", "The ID of the request.")
+                    .response_header_all_with::<String, _>(
+                        "Server-Timing",
+                        "The timing of the request.",
+                        |status| matches!(status, Some(StatusCode::Code(200..=299))),
+                    )
+            });
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let responses = &paths["/users"]["get"]["responses"];
+        assert_eq!(
+            responses["200"]["headers"]["X-Request-Id"]["description"],
+            "The ID of the request."
+        );
+        assert!(responses["200"]["headers"]["Server-Timing"].is_object());
+        assert!(responses["500"]["headers"]["X-Request-Id"].is_object());
+        assert!(responses["500"]["headers"].get("Server-Timing").is_none());
+    }
+
+    #[test]
+    fn test_all_operations() {
+        async fn handler() {}
+
+        let mut api = crate::openapi::OpenApi::default();
+        let mut ids = Vec::new();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users",
+                routing::get_with(handler, |op| op.id("list_users")).post_with(handler, |op| {
+                    op.id("create_user")
+                        .header_parameter::<String, _>("x-request-id", |p| {
+                            p.description("Declared by the route.")
+                        })
+                }),
+            )
+            .api_route(
+                "/users/:id",
+                routing::delete_with(handler, |op| op.id("delete_user")),
+            )
+            .finish_api_with(&mut api, |api| {
+                api.all_operations(|mut op| {
+                    ids.push(op.inner_mut().operation_id.clone().unwrap());
+                    op.header_parameter::<String, _>("X-Request-Id", |p| p.required(true))
+                })
+            });
+
+        assert_eq!(ids, ["list_users", "create_user", "delete_user"]);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let header = |path: &str, method: &str| {
+            let params = paths[path][method]["parameters"].as_array().unwrap();
+            let headers = params
+                .iter()
+                .filter(|p| p["in"] == "header")
+                .collect::<Vec<_>>();
+            assert_eq!(headers.len(), 1, "{params:?}");
+            assert_eq!(headers[0]["required"], true);
+            headers[0].clone()
+        };
+
+        assert_eq!(header("/users", "get")["name"], "X-Request-Id");
+        assert_eq!(header("/users/{id}", "delete")["name"], "X-Request-Id");
+
+        // The header that the operation already declares is updated.
+        let declared = header("/users", "post");
+        assert_eq!(declared["name"], "x-request-id");
+        assert_eq!(declared["description"], "Declared by the route.");
+    }
+
+    #[test]
+    fn test_schema_example() {
+        use std::{cell::RefCell, collections::BTreeMap, rc::Rc};
+
+        use schemars::schema::Schema;
+
+        use crate::{gen, transform::TransformSchema};
+
+        #[derive(schemars::JsonSchema)]
+        struct User {
+            #[allow(dead_code)]
+            email: String,
+        }
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        gen::on_error({
+            let errors = errors.clone();
+            move |error| errors.borrow_mut().push(error.to_string())
+        });
+
+        let mut schema = Schema::Object(schemars::schema_for!(User).schema);
+        let _ = TransformSchema::new(&mut schema)
+            .example(serde_json::json!({ "email": "alice@example.com" }))
+            // JSON object keys must be strings.
+            .example(BTreeMap::from([((1, 2), "alice@example.com")]));
+        gen::reset_context();
+
+        let schema = serde_json::to_value(&schema).unwrap();
+        assert_eq!(
+            schema["examples"],
+            serde_json::json!([{ "email": "alice@example.com" }])
+        );
+        assert_eq!(errors.borrow().len(), 1);
+        assert!(errors.borrow()[0].starts_with("the example cannot be serialized to JSON"));
+    }
+
+    #[test]
+    fn test_merge_with_policy() {
+        use std::{cell::RefCell, rc::Rc};
+
+        use crate::{axum::MergePolicy, gen};
+
+        async fn list_users() {}
+
+        async fn list_users_v2() {}
+
+        let first = || {
+            ApiRouter::new().api_route("/users", routing::get_with(list_users, |op| op.id("v1")))
+        };
+        let last = || {
+            let mut router = ApiRouter::new()
+                .api_route("/users", routing::get_with(list_users_v2, |op| op.id("v2")))
+                .api_route("/teams", routing::get(list_users_v2));
+            // Axum rejects overlapping routes, only the documentation is merged.
+            router.router = axum::Router::new();
+            router
+        };
+        let operation_id = |router: ApiRouter| {
+            let mut api = crate::openapi::OpenApi::default();
+            let _app: axum::Router = router.finish_api(&mut api);
+            let paths = api.paths.unwrap();
+            assert!(paths.paths.contains_key("/teams"));
+            serde_json::to_value(paths).unwrap()["/users"]["get"]["operationId"].clone()
+        };
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        gen::on_error({
+            let errors = errors.clone();
+            move |error| errors.borrow_mut().push(error.to_string())
+        });
+        let merged = first().merge(last());
+        assert_eq!(errors.borrow().len(), 1);
+        assert!(errors.borrow()[0].starts_with(r#"the operation "get" of the path "/users""#));
+        assert!(errors.borrow()[0].contains("list_users\""));
+        assert!(errors.borrow()[0].contains("list_users_v2"));
+        assert_eq!(operation_id(merged), "v1");
+
+        let merged = first().merge_with_policy(last(), MergePolicy::PreferFirst);
+        assert_eq!(operation_id(merged), "v1");
+
+        let merged = first().merge_with_policy(last(), MergePolicy::PreferLast);
+        assert_eq!(operation_id(merged), "v2");
+    }
+
+    #[test]
+    fn test_merge_parallel() {
+        use std::sync::{Arc, Mutex};
+
+        use axum::Json;
+        use schemars::JsonSchema;
+
+        use crate::gen;
+
+        mod v1 {
+            #[derive(serde::Serialize, schemars::JsonSchema)]
+            pub struct Team {
+                pub name: String,
+            }
+        }
+
+        mod v2 {
+            #[derive(serde::Serialize, schemars::JsonSchema)]
+            pub struct Team {
+                pub id: u64,
+            }
+        }
+
+        #[derive(serde::Serialize, JsonSchema)]
+        struct User {
+            name: String,
+        }
+
+        async fn list_users() -> Json<Vec<User>> {
+            Json(Vec::new())
+        }
+
+        async fn get_user() -> Json<User> {
+            Json(User {
+                name: String::new(),
+            })
+        }
+
+        async fn list_teams() -> Json<Vec<v1::Team>> {
+            Json(Vec::new())
+        }
+
+        async fn list_teams_v2() -> Json<Vec<v2::Team>> {
+            Json(Vec::new())
+        }
+
+        let errors = Arc::new(Mutex::new(Vec::new()));
+        let setup = || {
+            let errors = errors.clone();
+            gen::on_error(move |error| errors.lock().unwrap().push(error.to_string()));
+        };
+        setup();
+
+        let app = ApiRouter::new().merge_parallel(
+            setup,
+            [
+                || ApiRouter::new().api_route("/users", routing::get(list_users)),
+                || ApiRouter::new().api_route("/users/:id", routing::get(get_user)),
+            ],
+        );
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = app.finish_api(&mut api);
+
+        assert!(errors.lock().unwrap().is_empty());
+        let api = serde_json::to_value(api).unwrap();
+        assert_eq!(api["components"]["schemas"]["User"]["required"][0], "name");
+
+        // Finishing the documentation resets the context.
+        setup();
+        assert_eq!(
+            api["paths"]["/users/{id}"]["get"]["responses"]["200"]["content"]["application/json"]
+                ["schema"]["$ref"],
+            "#/components/schemas/User"
+        );
+
+        let app = ApiRouter::new().merge_parallel(
+            setup,
+            [
+                || ApiRouter::new().api_route("/v1/teams", routing::get(list_teams)),
+                || ApiRouter::new().api_route("/v2/teams", routing::get(list_teams_v2)),
+            ],
+        );
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = app.finish_api(&mut api);
+
+        assert_eq!(
+            *errors.lock().unwrap(),
+            [r#"the schema "Team" has different definitions in the merged routers"#]
+        );
+        let api = serde_json::to_value(api).unwrap();
+        assert_eq!(api["components"]["schemas"]["Team"]["required"][0], "name");
+    }
+
+    #[test]
+    fn test_merge_parallel_settings() {
+        use std::{
+            collections::HashSet,
+            sync::Mutex,
+            thread::{self, ThreadId},
+        };
+
+        use axum::Json;
+
+        use crate::gen;
+
+        async fn list_users() -> Json<Vec<String>> {
+            Json(Vec::new())
+        }
+
+        let parallelism = thread::available_parallelism().unwrap().get();
+        let builders = |count: usize| {
+            (0..count).map(|i| {
+                move || ApiRouter::new().api_route(&format!("/users/{i}"), routing::get(list_users))
+            })
+        };
+        let finish = |app: ApiRouter| {
+            let mut api = crate::openapi::OpenApi::default();
+            let _app: axum::Router = app.finish_api(&mut api);
+            let paths = api.paths.unwrap();
+            let order = paths.paths.keys().cloned().collect::<Vec<_>>();
+            (order, serde_json::to_value(paths).unwrap())
+        };
+
+        // More builders than threads.
+        let count = parallelism * 2 + 1;
+
+        // The settings of the calling thread are not carried over.
+        gen::infer_responses(false);
+        let threads = Mutex::new(HashSet::<ThreadId>::new());
+        let (order, paths) = finish(ApiRouter::new().merge_parallel(
+            || {
+                threads.lock().unwrap().insert(thread::current().id());
+            },
+            builders(count),
+        ));
+        gen::reset_context();
+
+        // The routers are merged in the order of the builders.
+        assert_eq!(
+            order,
+            (0..count)
+                .map(|i| format!("/users/{i}"))
+                .collect::<Vec<_>>()
+        );
+        assert!(paths
+            .as_object()
+            .unwrap()
+            .values()
+            .all(|path| path["get"]["responses"]["200"].is_object()));
+        assert!(threads.lock().unwrap().len() <= parallelism);
+
+        // The settings applied by `setup` are.
+        let (_, paths) =
+            finish(ApiRouter::new().merge_parallel(|| gen::infer_responses(false), builders(2)));
+        assert!(paths
+            .as_object()
+            .unwrap()
+            .values()
+            .all(|path| path["get"].get("responses").is_none()));
+    }
+
+    #[test]
+    fn test_update_api() {
+        use axum::Json;
+        use schemars::{schema::Schema, JsonSchema};
+
+        use crate::{gen, openapi::OpenApi};
+
+        #[derive(serde::Serialize, JsonSchema)]
+        struct User {
+            name: String,
+        }
+
+        #[derive(serde::Serialize, JsonSchema)]
+        struct Team {
+            name: String,
+        }
+
+        async fn get_user() -> Json<User> {
+            Json(User {
+                name: String::new(),
+            })
+        }
+
+        async fn delete_user() {}
+
+        async fn get_team() -> Json<Team> {
+            Json(Team {
+                name: String::new(),
+            })
+        }
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users/:id",
+                routing::get_with(get_user, |op| op.id("get_user"))
+                    .delete_with(delete_user, |op| op.id("delete_user")),
+            )
+            .finish_api(&mut api);
+
+        // The reused schemas are not generated again.
+        let components = api.components.as_mut().unwrap();
+        let Schema::Object(user) = &mut components.schemas["User"].json_schema else {
+            unreachable!()
+        };
+        user.metadata().description = Some("Reused.".into());
+
+        gen::reuse_schemas(&api);
+        let _plugin: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users/:id",
+                routing::get_with(get_user, |op| op.id("get_user_v2")),
+            )
+            .api_route("/teams/:id", routing::get(get_team))
+            .update_api(&mut api);
+
+        let api = serde_json::to_value(api).unwrap();
+        let user = &api["paths"]["/users/{id}"];
+        assert_eq!(user["get"]["operationId"], "get_user_v2");
+        assert_eq!(user["delete"]["operationId"], "delete_user");
+        assert!(api["paths"]["/teams/{id}"]["get"].is_object());
+        assert_eq!(
+            api["components"]["schemas"]["User"]["description"],
+            "Reused."
+        );
+        assert!(api["components"]["schemas"]["Team"].is_object());
+    }
+
+    #[test]
+    #[cfg(not(aide_docs_disabled))]
+    fn test_finish_api_with_stats() {
+        use axum::Json;
+        use schemars::JsonSchema;
+
+        use crate::{gen, openapi::OpenApi};
+
+        #[derive(serde::Serialize, JsonSchema)]
+        struct User {
+            name: String,
+        }
+
+        #[derive(serde::Serialize, JsonSchema)]
+        struct Customer {
+            name: String,
+        }
+
+        async fn get_user() -> Json<User> {
+            Json(User {
+                name: String::new(),
+            })
+        }
+
+        async fn get_customer() -> Json<Customer> {
+            Json(Customer {
+                name: String::new(),
+            })
+        }
+
+        async fn delete_user() {}
+
+        gen::deduplicate_schemas(true);
+
+        let mut api = OpenApi::default();
+        let (_app, stats): (axum::Router, _) = ApiRouter::new()
+            .api_route("/users/:id", routing::get(get_user).delete(delete_user))
+            .api_route("/customers/:id", routing::get(get_customer))
+            .finish_api_with_stats(&mut api, |api| api.title("Stats"));
+
+        assert_eq!(stats.routes, 2);
+        assert_eq!(stats.operations, 3);
+        assert_eq!(stats.schemas, 1);
+        assert_eq!(stats.duplicate_schemas, 1);
+        assert!(stats.total_time >= stats.paths_time + stats.post_process_time);
+    }
+
+    #[cfg(aide_docs_disabled)]
+    #[tokio::test]
+    async fn test_docs_disabled() {
+        use axum::{body::Body, http::Request};
+        use tower_service::Service;
+
+        async fn list_users() -> &'static str {
+            "users"
+        }
+
+        async fn not_found() -> (axum::http::StatusCode, &'static str) {
+            (axum::http::StatusCode::NOT_FOUND, "not found")
+        }
+
+        let teams = ApiRouter::new().api_route_with("/:id", routing::get(list_users), |_| {
+            unreachable!("transforms are not called")
+        });
+        let admin = ApiRouter::new().api_route("/admin", routing::get(list_users));
+
+        let mut api = crate::openapi::OpenApi::default();
+        let mut app: axum::Router = ApiRouter::new()
+            .require_security("token", ["users"])
+            .api_route(
+                "/users",
+                routing::get_with(list_users, |_| unreachable!("transforms are not called")),
+            )
+            .nest("/teams", teams)
+            .merge(admin)
+            .with_path_items(|_| unreachable!("transforms are not called"))
+            .default_error_response::<axum::Json<String>>()
+            .api_fallback(not_found, |_| unreachable!("transforms are not called"))
+            .finish_api_with(&mut api, |_| unreachable!("transforms are not called"));
+
+        assert!(api.paths.is_none());
+
+        let _plugin: axum::Router = ApiRouter::new()
+            .api_route("/plugin", routing::get(list_users))
+            .update_api_with(&mut api, |_| unreachable!("transforms are not called"));
+
+        assert!(api.paths.is_none());
+        assert!(api.components.is_none());
+
+        for (uri, status) in [
+            ("/users", 200),
+            ("/teams/1", 200),
+            ("/admin", 200),
+            ("/missing", 404),
+        ] {
+            let res = app
+                .call(Request::get(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(res.status(), status, "{uri}");
+        }
+    }
+
+    #[test]
+    fn test_nest_prefix_parameters() {
+        use axum::extract::Path;
+
+        async fn list_todos(_tenant_id: Path<String>) {}
+
+        async fn get_todo(_ids: Path<(String, u64)>) {}
+
+        async fn tenant() {}
+
+        let todos = ApiRouter::new()
+            .api_route("/", routing::get(list_todos))
+            .api_route("/:id", routing::get(get_todo));
+
+        let tenants = ApiRouter::new()
+            .api_route("/", routing::get(tenant))
+            .nest("/todos", todos);
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .nest("/tenants/:tenant_id/", tenants)
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let names = |path: &str| {
+            paths[path]["get"]["parameters"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names("/tenants/{tenant_id}"), ["tenant_id"]);
+        assert_eq!(names("/tenants/{tenant_id}/todos"), ["tenant_id"]);
+        assert_eq!(
+            names("/tenants/{tenant_id}/todos/{id}"),
+            ["tenant_id", "id"]
+        );
+        assert_eq!(
+            paths["/tenants/{tenant_id}"]["get"]["parameters"][0]["required"],
+            true
+        );
+    }
+
+    #[test]
+    fn test_api_fallback() {
+        use axum::Json;
+
+        async fn handler() {}
+
+        async fn not_found() -> Json<String> {
+            Json("no route".into())
+        }
+
+        crate::gen::document_method_not_allowed(true);
+
+        let mut api = crate::openapi::OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users",
+                routing::get(handler)
+                    .post_with(handler, |op| op.id("create_user").response::<404, ()>()),
+            )
+            .api_fallback(not_found, |res| res.description("no route matches"))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let get = &paths["/users"]["get"]["responses"];
+        assert_eq!(get["404"]["description"], "no route matches");
+        assert!(get["404"]["content"]["application/json"].is_object());
+        assert_eq!(get["405"]["headers"]["Allow"]["example"], "GET, POST");
+
+        let post = &paths["/users"]["post"]["responses"];
+        assert!(post["404"].get("content").is_none());
+        assert!(post["405"].is_object());
+    }
 }
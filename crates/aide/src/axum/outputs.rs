@@ -240,7 +240,10 @@ mod extra {
     use axum_extra::extract;
 
     use super::*;
-    use crate::operation::OperationOutput;
+    use crate::{
+        helpers::one_of::{merge_inferred_responses, merge_responses},
+        operation::OperationOutput,
+    };
 
 
     #[cfg(feature = "axum-extra-cookie")]
@@ -248,4 +251,41 @@ mod extra {
 
     #[cfg(feature = "axum-extra-cookie-private")]
     impl OperationOutput for extract::PrivateCookieJar { type Inner = (); }
+
+    macro_rules! impl_either {
+        ($either:ident => $($ty:ident),*) => {
+            impl<$($ty),*> OperationOutput for axum_extra::either::$either<$($ty),*>
+            where
+                $($ty: OperationOutput,)*
+            {
+                type Inner = std::convert::Infallible;
+
+                fn operation_response(
+                    ctx: &mut GenContext,
+                    operation: &mut Operation,
+                ) -> Option<Response> {
+                    merge_responses(
+                        [$($ty::operation_response(ctx, operation),)*]
+                            .into_iter()
+                            .flatten(),
+                    )
+                }
+
+                fn inferred_responses(
+                    ctx: &mut GenContext,
+                    operation: &mut Operation,
+                ) -> Vec<(Option<u16>, Response)> {
+                    merge_inferred_responses([$($ty::inferred_responses(ctx, operation),)*])
+                }
+            }
+        };
+    }
+
+    impl_either!(Either => E1, E2);
+    impl_either!(Either3 => E1, E2, E3);
+    impl_either!(Either4 => E1, E2, E3, E4);
+    impl_either!(Either5 => E1, E2, E3, E4, E5);
+    impl_either!(Either6 => E1, E2, E3, E4, E5, E6);
+    impl_either!(Either7 => E1, E2, E3, E4, E5, E6, E7);
+    impl_either!(Either8 => E1, E2, E3, E4, E5, E6, E7, E8);
 }
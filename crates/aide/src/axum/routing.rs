@@ -1,11 +1,16 @@
 //! Method routing that closely mimics [`axum::routing`] while extending
 //! it with API documentation-specific features..
 
-use std::{convert::Infallible, mem};
+use std::convert::Infallible;
+#[cfg(not(aide_docs_disabled))]
+use std::{any::type_name, mem};
 
+use crate::openapi::Operation;
+#[cfg(not(aide_docs_disabled))]
 use crate::{
-    gen::GenContext,
-    openapi::{Operation, PathItem, ReferenceOr, Response, StatusCode},
+    gen::{in_context, GenContext},
+    openapi::{PathItem, ReferenceOr, Response, StatusCode},
+    util::operation_mut,
     Error,
 };
 use axum::routing::{MethodFilter, Route};
@@ -20,7 +25,6 @@ use tower_layer::Layer;
 use tower_service::Service;
 
 use crate::{
-    gen::in_context,
     operation::{OperationHandler, OperationInput, OperationOutput},
     transform::TransformOperation,
 };
@@ -30,6 +34,8 @@ use crate::{
 #[must_use]
 pub struct ApiMethodRouter<S = (), E = Infallible> {
     pub(crate) operations: IndexMap<&'static str, Operation>,
+    /// The type names of the handlers of the operations.
+    pub(crate) handlers: IndexMap<&'static str, &'static str>,
     pub(crate) router: MethodRouter<S, E>,
 }
 
@@ -37,6 +43,7 @@ impl<S, E> Clone for ApiMethodRouter<S, E> {
     fn clone(&self) -> Self {
         Self {
             operations: self.operations.clone(),
+            handlers: self.handlers.clone(),
             router: self.router.clone(),
         }
     }
@@ -52,27 +59,19 @@ impl<S, E> From<MethodRouter<S, E>> for ApiMethodRouter<S, E> {
     fn from(router: MethodRouter<S, E>) -> Self {
         Self {
             operations: IndexMap::default(),
+            handlers: IndexMap::default(),
             router,
         }
     }
 }
 
+#[cfg(not(aide_docs_disabled))]
 impl<S, E> ApiMethodRouter<S, E> {
     pub(crate) fn take_path_item(&mut self) -> PathItem {
         let mut path = PathItem::default();
 
         for (method, op) in mem::take(&mut self.operations) {
-            match method {
-                "delete" => path.delete = Some(op),
-                "get" => path.get = Some(op),
-                "head" => path.head = Some(op),
-                "options" => path.options = Some(op),
-                "patch" => path.patch = Some(op),
-                "post" => path.post = Some(op),
-                "put" => path.put = Some(op),
-                "trace" => path.trace = Some(op),
-                _ => unreachable!(),
-            }
+            *operation_mut(&mut path, method) = Some(op);
         }
 
         path
@@ -104,30 +103,14 @@ macro_rules! method_router_chain_method {
             T: 'static,
             F: FnOnce(TransformOperation) -> TransformOperation,
         {
-            let mut operation = Operation::default();
-            in_context(|ctx| {
-                I::operation_input(ctx, &mut operation);
-
-                if ctx.infer_responses {
-                    for (code, res) in O::inferred_responses(ctx, &mut operation) {
-                        set_inferred_response(ctx, &mut operation, code, res);
-                    }
-
-                    // On conflict, input early responses potentially overwrite
-                    // output inferred responses on purpose, as they
-                    // are stronger in a sense that the request won't
-                    // even reach the handler body.
-                    for (code, res) in I::inferred_early_responses(ctx, &mut operation) {
-                        set_inferred_response(ctx, &mut operation, code, res);
-                    }
-                }
-            });
-
-            let t = transform(TransformOperation::new(&mut operation));
-
-            if !t.hidden {
+            #[cfg(not(aide_docs_disabled))]
+            {
+                let operation = handler_operation::<I, O, F>(transform);
                 self.operations.insert(stringify!($name), operation);
+                self.handlers.insert(stringify!($name), type_name::<H>());
             }
+            #[cfg(aide_docs_disabled)]
+            let _ = transform;
 
             self.router = self.router.$name(handler);
             self
@@ -167,37 +150,54 @@ macro_rules! method_router_top_level {
             T: 'static,
             F: FnOnce(TransformOperation) -> TransformOperation,
         {
+            #[cfg_attr(aide_docs_disabled, allow(unused_mut))]
             let mut router = ApiMethodRouter::from(routing::$name(handler));
-            let mut operation = Operation::default();
-            in_context(|ctx| {
-                I::operation_input(ctx, &mut operation);
-
-                if ctx.infer_responses {
-                    for (code, res) in O::inferred_responses(ctx, &mut operation) {
-                        set_inferred_response(ctx, &mut operation, code, res);
-                    }
-
-                    // On conflict, input early responses potentially overwrite
-                    // output inferred responses on purpose, as they
-                    // are stronger in a sense that the request won't
-                    // even reach the handler body.
-                    for (code, res) in I::inferred_early_responses(ctx, &mut operation) {
-                        set_inferred_response(ctx, &mut operation, code, res);
-                    }
-                }
-            });
-
-            let t = transform(TransformOperation::new(&mut operation));
-
-            if !t.hidden {
+            #[cfg(not(aide_docs_disabled))]
+            {
+                let operation = handler_operation::<I, O, F>(transform);
                 router.operations.insert(stringify!($name), operation);
+                router.handlers.insert(stringify!($name), type_name::<H>());
             }
+            #[cfg(aide_docs_disabled)]
+            let _ = transform;
 
             router
         }
     };
 }
 
+/// Generate the operation of a handler.
+#[cfg(not(aide_docs_disabled))]
+fn handler_operation<I, O, F>(transform: F) -> Operation
+where
+    I: OperationInput,
+    O: OperationOutput,
+    F: FnOnce(TransformOperation) -> TransformOperation,
+{
+    let mut operation = Operation::default();
+    in_context(|ctx| {
+        I::operation_input(ctx, &mut operation);
+
+        if ctx.infer_responses {
+            for (code, res) in O::inferred_responses(ctx, &mut operation) {
+                set_inferred_response(ctx, &mut operation, code, res);
+            }
+
+            // On conflict, input early responses potentially overwrite
+            // output inferred responses on purpose, as they
+            // are stronger in a sense that the request won't
+            // even reach the handler body.
+            for (code, res) in I::inferred_early_responses(ctx, &mut operation) {
+                set_inferred_response(ctx, &mut operation, code, res);
+            }
+        }
+    });
+
+    let _ = transform(TransformOperation::new(&mut operation));
+    operation
+}
+
+#[cfg(not(aide_docs_disabled))]
 fn set_inferred_response(
     ctx: &mut GenContext,
     operation: &mut Operation,
@@ -252,6 +252,7 @@ where
     pub fn new() -> Self {
         Self {
             operations: IndexMap::default(),
+            handlers: IndexMap::default(),
             router: MethodRouter::<S, E>::new(),
         }
     }
@@ -263,6 +264,7 @@ where
     {
         let other = other.into();
         self.operations.extend(other.operations);
+        self.handlers.extend(other.handlers);
         self.router = self.router.merge(other.router);
         self
     }
@@ -282,6 +284,7 @@ where
         ApiMethodRouter {
             router: self.router.layer(layer),
             operations: self.operations,
+            handlers: self.handlers,
         }
     }
 
@@ -290,6 +293,7 @@ where
         ApiMethodRouter {
             router: self.router.with_state(state),
             operations: self.operations,
+            handlers: self.handlers,
         }
     }
 
@@ -328,6 +332,7 @@ where
         ApiMethodRouter {
             router: self.router.route_layer(layer),
             operations: self.operations,
+            handlers: self.handlers,
         }
     }
 }
@@ -0,0 +1,352 @@
+//! Operations with declared request and response types.
+//!
+//! A [`TypedOperation`] declares the method, path, parameters, body and
+//! response of an operation in one place. It is registered in the router
+//! with [`ApiRouter::typed_api_route`], and the same declaration can be
+//! used to call the operation with checked types, e.g. with the
+//! `TypedClient` of the `testing` feature, instead of string URLs.
+//!
+//! Operations are usually declared with the [`typed_operation`](crate::typed_operation)
+//! macro. The operation ID is the name of the declared type.
+//!
+//! The parameters, request body and response that are documented for
+//! the handler are checked against the declared types when the route
+//! is added, and every difference is reported as
+//! [`Error::TypedOperationMismatch`](crate::Error::TypedOperationMismatch).
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::axum::ApiRouter;
+//! use axum::{extract::Path, Json};
+//! use schemars::JsonSchema;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize, JsonSchema)]
+//! struct TodoPath {
+//!     id: u32,
+//! }
+//!
+//! #[derive(Deserialize, Serialize, JsonSchema)]
+//! struct Todo {
+//!     title: String,
+//! }
+//!
+//! aide::typed_operation! {
+//!     /// Get a single todo.
+//!     struct GetTodo: GET "/todos/:id" {
+//!         params: TodoPath,
+//!         body: (),
+//!         response: Todo,
+//!     }
+//! }
+//!
+//! async fn get_todo(Path(path): Path<TodoPath>) -> Json<Todo> {
+//!     Json(Todo {
+//!         title: format!("todo {}", path.id),
+//!     })
+//! }
+//!
+//! let app: ApiRouter = ApiRouter::new().typed_api_route::<GetTodo, _, _, _, _>(get_todo);
+//! ```
+
+use ::axum::{handler::Handler, http::Method};
+use schemars::JsonSchema;
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{routing, ApiRouter};
+use crate::{
+    operation::OperationHandler, transform::TransformOperation, OperationInput, OperationOutput,
+};
+
+// Only used to check the documentation of the handlers.
+#[cfg(not(aide_docs_disabled))]
+use std::collections::BTreeSet;
+
+#[cfg(not(aide_docs_disabled))]
+use indexmap::IndexMap;
+#[cfg(not(aide_docs_disabled))]
+use schemars::schema::{InstanceType, Schema, SingleOrVec};
+
+#[cfg(not(aide_docs_disabled))]
+use crate::{
+    gen::{in_context, GenContext},
+    helpers::is_success,
+    openapi::{MediaType, Operation, Parameter},
+    operation::{parameters_from_schema, ParamLocation, PATH_POSITION},
+    util::path_param_names,
+    Error,
+};
+
+#[doc(hidden)]
+pub use ::axum::http::Method as __Method;
+
+/// An operation with declared request and response types.
+pub trait TypedOperation {
+    /// The method of the operation.
+    const METHOD: Method;
+    /// The path of the operation in the notation of axum, e.g. `/todos/:id`.
+    const PATH: &'static str;
+    /// The ID of the operation.
+    const OPERATION_ID: &'static str;
+
+    /// The path and query parameters, the fields are
+    /// matched with the parameters in the path by name
+    /// and the remaining fields are sent in the query.
+    type Params: Serialize + JsonSchema;
+    /// The JSON request body, `()` if there is none.
+    type Body: Serialize + JsonSchema;
+    /// The JSON body of successful responses.
+    type Response: DeserializeOwned + JsonSchema;
+}
+
+/// Declare a [`TypedOperation`](crate::axum::typed::TypedOperation).
+///
+/// See the [module documentation](crate::axum::typed) for an example.
+#[macro_export]
+macro_rules! typed_operation {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident: $method:ident $path:literal {
+            params: $params:ty,
+            body: $body:ty,
+            response: $response:ty $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default)]
+        $vis struct $name;
+
+        impl $crate::axum::typed::TypedOperation for $name {
+            const METHOD: $crate::axum::typed::__Method = $crate::axum::typed::__Method::$method;
+            const PATH: &'static str = $path;
+            const OPERATION_ID: &'static str = stringify!($name);
+
+            type Params = $params;
+            type Body = $body;
+            type Response = $response;
+        }
+    };
+}
+
+impl<S> ApiRouter<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    /// Add a route for a [`TypedOperation`] with the given handler,
+    /// and include it in the API documentation.
+    ///
+    /// The operation ID of the documented operation is set to
+    /// [`TypedOperation::OPERATION_ID`]. The parameters, request body
+    /// and response of the handler must match the declared types,
+    /// see the [module documentation](crate::axum::typed).
+    ///
+    /// # Panics
+    ///
+    /// If the method of the operation is not supported by axum.
+    #[tracing::instrument(skip_all, fields(path = Op::PATH))]
+    pub fn typed_api_route<Op, H, I, O, T>(self, handler: H) -> Self
+    where
+        Op: TypedOperation,
+        H: Handler<T, S> + OperationHandler<I, O>,
+        I: OperationInput,
+        O: OperationOutput,
+        T: 'static,
+    {
+        let transform = typed_operation_docs::<Op>;
+
+        let method_router = match Op::METHOD.as_str() {
+            "DELETE" => routing::delete_with(handler, transform),
+            "GET" => routing::get_with(handler, transform),
+            "HEAD" => routing::head_with(handler, transform),
+            "OPTIONS" => routing::options_with(handler, transform),
+            "PATCH" => routing::patch_with(handler, transform),
+            "POST" => routing::post_with(handler, transform),
+            "PUT" => routing::put_with(handler, transform),
+            "TRACE" => routing::trace_with(handler, transform),
+            method => panic!("the method {method} is not supported"),
+        };
+
+        self.api_route(Op::PATH, method_router)
+    }
+}
+
+#[cfg(aide_docs_disabled)]
+fn typed_operation_docs<Op: TypedOperation>(op: TransformOperation) -> TransformOperation {
+    op
+}
+
+#[cfg(not(aide_docs_disabled))]
+fn typed_operation_docs<Op: TypedOperation>(mut op: TransformOperation) -> TransformOperation {
+    in_context(|ctx| {
+        for reason in mismatches::<Op>(ctx, op.inner_mut()) {
+            ctx.error(Error::TypedOperationMismatch {
+                operation: Op::OPERATION_ID,
+                reason,
+            });
+        }
+    });
+
+    op.id(Op::OPERATION_ID)
+}
+
+/// The differences between the documented operation
+/// of the handler and the declared types.
+#[cfg(not(aide_docs_disabled))]
+fn mismatches<Op: TypedOperation>(ctx: &mut GenContext, operation: &Operation) -> Vec<String> {
+    let mut mismatches = Vec::new();
+
+    let params_schema = ctx.schema.subschema_for::<Op::Params>().into_object();
+    let declared = parameters_from_schema(ctx, params_schema, ParamLocation::Query)
+        .iter()
+        .map(|param| param.parameter_data_ref().name.clone())
+        .collect::<BTreeSet<_>>();
+
+    // Positional path parameters, e.g. of `Path<u32>`, are named after the route.
+    let route_params = path_param_names(Op::PATH);
+    let documented = operation
+        .parameters
+        .iter()
+        .filter_map(|param| param.as_item())
+        .filter(|param| matches!(param, Parameter::Path { .. } | Parameter::Query { .. }))
+        .map(|param| {
+            let data = param.parameter_data_ref();
+            data.extensions
+                .get(PATH_POSITION)
+                .and_then(serde_json::Value::as_u64)
+                .and_then(|position| route_params.get(usize::try_from(position).ok()?))
+                .map_or_else(|| data.name.clone(), |name| (*name).to_string())
+        })
+        .collect::<BTreeSet<_>>();
+
+    for name in documented.difference(&declared) {
+        mismatches.push(format!(
+            r#"has the parameter "{name}" that is not declared"#
+        ));
+    }
+    for name in declared.difference(&documented) {
+        mismatches.push(format!(r#"does not have the declared parameter "{name}""#));
+    }
+
+    let body = operation
+        .request_body
+        .as_ref()
+        .and_then(|body| body.as_item())
+        .and_then(|body| json_schema(&body.content));
+    if !matches_declared::<Op::Body>(ctx, body) {
+        mismatches.push("has a different request body than declared".into());
+    }
+
+    let success = operation.responses.as_ref().and_then(|responses| {
+        responses
+            .responses
+            .iter()
+            .find(|(status, _)| is_success(status))
+            .and_then(|(_, res)| res.as_item())
+    });
+    if let Some(res) = success {
+        if !matches_declared::<Op::Response>(ctx, json_schema(&res.content)) {
+            mismatches.push("has a different response than declared".into());
+        }
+    }
+
+    mismatches
+}
+
+#[cfg(not(aide_docs_disabled))]
+fn json_schema(content: &IndexMap<String, MediaType>) -> Option<&Schema> {
+    content
+        .get("application/json")
+        .and_then(|media| media.schema.as_ref())
+        .map(|schema| &schema.json_schema)
+}
+
+/// Whether the documented JSON schema is the schema of `T`,
+/// a missing schema matches `()`.
+#[cfg(not(aide_docs_disabled))]
+fn matches_declared<T: JsonSchema>(ctx: &mut GenContext, documented: Option<&Schema>) -> bool {
+    let declared = ctx.schema.subschema_for::<T>();
+
+    match documented {
+        Some(documented) => {
+            serde_json::to_value(documented).ok() == serde_json::to_value(&declared).ok()
+        }
+        None => matches!(
+            &ctx.resolve_schema(&declared.into_object()).instance_type,
+            Some(SingleOrVec::Single(ty)) if **ty == InstanceType::Null
+        ),
+    }
+}
+
+#[cfg(all(test, not(aide_docs_disabled)))]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use axum::{
+        extract::{Path, Query},
+        Json,
+    };
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{axum::ApiRouter, gen};
+
+    #[derive(Deserialize, Serialize, JsonSchema)]
+    struct TodoParams {
+        id: u32,
+        #[serde(default)]
+        done: Option<bool>,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct TodoQuery {
+        #[allow(dead_code)]
+        done: Option<bool>,
+    }
+
+    #[derive(Deserialize, Serialize, JsonSchema)]
+    struct Todo {
+        title: String,
+    }
+
+    crate::typed_operation! {
+        struct UpdateTodo: PUT "/todos/:id" {
+            params: TodoParams,
+            body: Todo,
+            response: Todo,
+        }
+    }
+
+    async fn update_todo(
+        Path(_id): Path<u32>,
+        Query(_query): Query<TodoQuery>,
+        Json(todo): Json<Todo>,
+    ) -> Json<Todo> {
+        Json(todo)
+    }
+
+    async fn update_todo_title(Path(_id): Path<u32>, Json(todo): Json<Todo>) -> Json<String> {
+        Json(todo.title)
+    }
+
+    #[test]
+    fn test_typed_operation_mismatch() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_handler = errors.clone();
+        gen::on_error(move |error| errors_handler.borrow_mut().push(error.to_string()));
+
+        let _app: ApiRouter =
+            ApiRouter::new().typed_api_route::<UpdateTodo, _, _, _, _>(update_todo);
+        assert!(errors.borrow().is_empty(), "{:?}", errors.borrow());
+
+        let _app: ApiRouter =
+            ApiRouter::new().typed_api_route::<UpdateTodo, _, _, _, _>(update_todo_title);
+        assert_eq!(
+            *errors.borrow(),
+            [
+                r#"the handler of the typed operation "UpdateTodo" does not have the declared parameter "done""#,
+                r#"the handler of the typed operation "UpdateTodo" has a different response than declared"#,
+            ]
+        );
+    }
+}
@@ -0,0 +1,869 @@
+//! Typed client generation.
+//!
+//! [`ClientGenerator`] generates the source code of a Rust client
+//! for the documented operations, based on [`reqwest`]:
+//!
+//! - a client struct with an `async` method for every operation,
+//! - a parameters struct for the operations with parameters,
+//!   with the path parameters interpolated into the URL,
+//! - a response enum for every operation with a variant for every
+//!   documented status code.
+//!
+//! Request and response bodies refer to the types by the names of their
+//! schemas, so the models should be shared by the server and the client,
+//! e.g. in a common crate, and brought into scope with
+//! [`ClientGenerator::prelude`].
+//!
+//! The generated code requires the `json` feature of `reqwest`,
+//! the models must implement [`Debug`](std::fmt::Debug), `Serialize`
+//! and `Deserialize`, and the parameters must implement
+//! [`Display`](std::fmt::Display).
+//! Cookie parameters are not supported.
+//!
+//! # Limitations
+//!
+//! The client is generated from the finished documentation rather than
+//! from the handlers registered with the `ApiRouter`, whose types are
+//! erased by then. The routes, methods, parameters and status codes
+//! therefore always match the server, but the body types only do as
+//! long as the names of their schemas are the names of the Rust types:
+//!
+//! - types renamed with `#[schemars(rename = "...")]` or
+//!   `#[serde(rename = "...")]` are referred to by the new name,
+//! - generic types are referred to by their generated schema names,
+//!   e.g. `PageForTodo` for `Page<Todo>`,
+//! - schemas without a name, e.g. inline objects, are
+//!   [`serde_json::Value`](https://docs.rs/serde_json/latest/serde_json/enum.Value.html).
+//!
+//! A mismatch is reported when the generated client is compiled,
+//! usually as an unknown type, and not at runtime. Type aliases in the
+//! [`ClientGenerator::prelude`] can map the schema names to the Rust types.
+//!
+//! [`reqwest`]: https://docs.rs/reqwest
+//!
+//! # Examples
+//!
+//! Generate the client in a build script:
+//!
+//! ```ignore
+//! fn main() {
+//!     let mut api = OpenApi::default();
+//!     let _ = server::app().finish_api(&mut api);
+//!
+//!     let client = ClientGenerator::new(&api)
+//!         .client_name("TodoClient")
+//!         .prelude("use models::*;")
+//!         .generate();
+//!
+//!     let out_dir = std::env::var("OUT_DIR").unwrap();
+//!     std::fs::write(format!("{out_dir}/client.rs"), client).unwrap();
+//! }
+//! ```
+//!
+//! And include it in the client crate:
+//!
+//! ```ignore
+//! include!(concat!(env!("OUT_DIR"), "/client.rs"));
+//! ```
+
+use std::fmt::{self, Write};
+
+use serde_json::Value;
+
+use crate::{
+    helpers::is_json_media_type,
+    openapi::{
+        OpenApi, Operation, Parameter, ParameterSchemaOrContent, ReferenceOr, Response, StatusCode,
+    },
+    util::{self, case_words},
+};
+
+/// Generates the source code of a typed client.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct ClientGenerator<'a> {
+    api: &'a OpenApi,
+    client_name: String,
+    prelude: Vec<String>,
+}
+
+impl<'a> ClientGenerator<'a> {
+    /// Create a generator for the finished documentation.
+    pub fn new(api: &'a OpenApi) -> Self {
+        Self {
+            api,
+            client_name: "ApiClient".into(),
+            prelude: Vec::new(),
+        }
+    }
+
+    /// The name of the client struct, `ApiClient` by default.
+    pub fn client_name(mut self, name: &str) -> Self {
+        self.client_name = name.into();
+        self
+    }
+
+    /// A line of code at the start of the generated code,
+    /// usually to bring the models into scope.
+    pub fn prelude(mut self, line: &str) -> Self {
+        self.prelude.push(line.into());
+        self
+    }
+
+    /// Generate the source code of the client.
+    #[must_use]
+    pub fn generate(&self) -> String {
+        let mut out = String::new();
+        // Writing to a string cannot fail.
+        let _ = self.write(&mut out);
+        out
+    }
+
+    fn write(&self, out: &mut String) -> fmt::Result {
+        writeln!(
+            out,
+            "// Generated by aide from the API documentation, do not edit.\n"
+        )?;
+
+        for line in &self.prelude {
+            writeln!(out, "{line}")?;
+        }
+        if !self.prelude.is_empty() {
+            writeln!(out)?;
+        }
+
+        let operations = self.operations();
+        let client = &self.client_name;
+
+        let title = match self.api.info.title.as_str() {
+            "" => "the API",
+            title => title,
+        };
+        writeln!(out, "/// A client of {title}.")?;
+        writeln!(out, "#[derive(Debug, Clone)]")?;
+        writeln!(out, "pub struct {client} {{")?;
+        writeln!(out, "    client: reqwest::Client,")?;
+        writeln!(out, "    base_url: reqwest::Url,")?;
+        writeln!(out, "}}\n")?;
+
+        writeln!(out, "#[allow(clippy::all, unused_mut)]")?;
+        writeln!(out, "impl {client} {{")?;
+        writeln!(
+            out,
+            "    /// Create a client for the API at the given base URL."
+        )?;
+        writeln!(out, "    pub fn new(base_url: reqwest::Url) -> Self {{")?;
+        writeln!(
+            out,
+            "        Self::with_client(reqwest::Client::new(), base_url)"
+        )?;
+        writeln!(out, "    }}\n")?;
+        writeln!(
+            out,
+            "    /// Create a client for the API at the given base URL with an existing HTTP client."
+        )?;
+        writeln!(
+            out,
+            "    pub fn with_client(client: reqwest::Client, base_url: reqwest::Url) -> Self {{"
+        )?;
+        writeln!(out, "        Self {{ client, base_url }}")?;
+        writeln!(out, "    }}\n")?;
+        writeln!(
+            out,
+            "    fn url(&self, segments: &[&str]) -> reqwest::Url {{"
+        )?;
+        writeln!(out, "        let mut url = self.base_url.clone();")?;
+        writeln!(out, "        url.path_segments_mut()")?;
+        writeln!(
+            out,
+            "            .expect(\"the base URL must be able to have a path\")"
+        )?;
+        writeln!(out, "            .pop_if_empty()")?;
+        writeln!(out, "            .extend(segments);")?;
+        writeln!(out, "        url")?;
+        writeln!(out, "    }}")?;
+
+        for operation in &operations {
+            writeln!(out)?;
+            operation.write_method(out)?;
+        }
+
+        writeln!(out, "}}")?;
+
+        for operation in &operations {
+            operation.write_types(out, client)?;
+        }
+
+        Ok(())
+    }
+
+    fn operations(&self) -> Vec<ClientOperation<'a>> {
+        let mut operations = Vec::new();
+
+        for (path, item) in self.api.paths.iter().flat_map(|paths| paths.iter()) {
+            let Some(item) = item.as_item() else {
+                continue;
+            };
+
+            for (method, operation) in item.iter() {
+                let name = match &operation.operation_id {
+                    Some(id) => id.clone(),
+                    None => format!("{method} {path}"),
+                };
+
+                let params = item
+                    .parameters
+                    .iter()
+                    .chain(&operation.parameters)
+                    .filter_map(ReferenceOr::as_item)
+                    .collect();
+
+                operations.push(ClientOperation {
+                    method,
+                    path,
+                    type_name: pascal_case(&name),
+                    name: snake_case(&name),
+                    operation,
+                    params,
+                });
+            }
+        }
+
+        operations
+    }
+}
+
+struct ClientOperation<'a> {
+    method: &'a str,
+    path: &'a str,
+    name: String,
+    type_name: String,
+    operation: &'a Operation,
+    params: Vec<&'a Parameter>,
+}
+
+impl ClientOperation<'_> {
+    fn write_method(&self, out: &mut String) -> fmt::Result {
+        let op = self.operation;
+
+        write_docs(out, "    ", op.summary.as_deref())?;
+        if op.summary.is_some() && op.description.is_some() {
+            writeln!(out, "    ///")?;
+        }
+        write_docs(out, "    ", op.description.as_deref())?;
+        if op.summary.is_some() || op.description.is_some() {
+            writeln!(out, "    ///")?;
+        }
+        writeln!(
+            out,
+            "    /// `{} {}`",
+            self.method.to_ascii_uppercase(),
+            self.path
+        )?;
+        if op.deprecated {
+            writeln!(out, "    #[deprecated]")?;
+        }
+
+        let mut args = String::from("&self");
+        if !self.params.is_empty() {
+            write!(args, ", params: &{}Params", self.type_name)?;
+        }
+        let body = self.request_body();
+        match &body {
+            Some(Body::Json(ty, true)) => write!(args, ", body: &{ty}")?,
+            Some(Body::Json(ty, false)) => write!(args, ", body: Option<&{ty}>")?,
+            Some(Body::Raw(_)) => write!(args, ", body: reqwest::Body")?,
+            None => {}
+        }
+
+        writeln!(
+            out,
+            "    pub async fn {}({args}) -> Result<{}Response, reqwest::Error> {{",
+            self.name, self.type_name
+        )?;
+
+        let segments = self
+            .path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(
+                |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                    Some(param) => format!(
+                        "&params.{}.to_string()",
+                        snake_case(param.trim_end_matches('+'))
+                    ),
+                    None => format!("{segment:?}"),
+                },
+            )
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        writeln!(out, "        let url = self.url(&[{segments}]);")?;
+        writeln!(
+            out,
+            "        let mut req = self.client.request(reqwest::Method::{}, url);",
+            self.method.to_ascii_uppercase()
+        )?;
+
+        let query = self
+            .params
+            .iter()
+            .filter(|p| matches!(p, Parameter::Query { .. }))
+            .collect::<Vec<_>>();
+
+        if !query.is_empty() {
+            writeln!(out, "        let mut query = Vec::<(&str, String)>::new();")?;
+            for param in query {
+                let data = param.parameter_data_ref();
+                let field = snake_case(&data.name);
+                let (ty, optional) = parameter_type(param);
+                let is_array = ty.starts_with("Vec<");
+
+                match (!optional, is_array) {
+                    (true, false) => writeln!(
+                        out,
+                        "        query.push(({:?}, params.{field}.to_string()));",
+                        data.name
+                    )?,
+                    (true, true) => writeln!(
+                        out,
+                        "        query.extend(params.{field}.iter().map(|v| ({:?}, v.to_string())));",
+                        data.name
+                    )?,
+                    (false, false) => writeln!(
+                        out,
+                        "        if let Some(value) = &params.{field} {{ query.push(({:?}, value.to_string())); }}",
+                        data.name
+                    )?,
+                    (false, true) => writeln!(
+                        out,
+                        "        for value in params.{field}.iter().flatten() {{ query.push(({:?}, value.to_string())); }}",
+                        data.name
+                    )?,
+                }
+            }
+            writeln!(out, "        req = req.query(&query);")?;
+        }
+
+        for param in &self.params {
+            let data = param.parameter_data_ref();
+            let field = snake_case(&data.name);
+            let Parameter::Header { .. } = param else {
+                continue;
+            };
+            let header = &data.name;
+            let (_, optional) = parameter_type(param);
+
+            if optional {
+                writeln!(
+                    out,
+                    "        if let Some(value) = &params.{field} {{ req = req.header({header:?}, value.to_string()); }}"
+                )?;
+            } else {
+                writeln!(
+                    out,
+                    "        req = req.header({header:?}, params.{field}.to_string());"
+                )?;
+            }
+        }
+
+        match &body {
+            Some(Body::Json(_, true)) => writeln!(out, "        req = req.json(body);")?,
+            Some(Body::Json(_, false)) => {
+                writeln!(
+                    out,
+                    "        if let Some(body) = body {{ req = req.json(body); }}"
+                )?;
+            }
+            Some(Body::Raw(content_type)) => {
+                writeln!(
+                    out,
+                    "        req = req.header(\"content-type\", {content_type:?}).body(body);"
+                )?;
+            }
+            None => {}
+        }
+
+        writeln!(out, "        let res = req.send().await?;")?;
+        writeln!(out, "        Ok(match res.status().as_u16() {{")?;
+
+        let responses = self.responses();
+        for response in &responses {
+            let arm = match response.status {
+                Some(StatusCode::Code(code)) => code.to_string(),
+                Some(StatusCode::Range(range)) => format!("{range}00..={range}99"),
+                None => continue,
+            };
+            let value = match &response.body {
+                Some(ResponseBody::Json(_)) => "(res.json().await?)",
+                Some(ResponseBody::Text) => "(res.text().await?)",
+                None => "",
+            };
+            writeln!(
+                out,
+                "            {arm} => {}Response::{}{value},",
+                self.type_name, response.variant
+            )?;
+        }
+
+        match responses.iter().find(|r| r.status.is_none()) {
+            Some(default) => {
+                let value = match &default.body {
+                    Some(ResponseBody::Json(_)) => "res.json().await?",
+                    Some(ResponseBody::Text) => "res.text().await?",
+                    None => "()",
+                };
+                writeln!(
+                    out,
+                    "            status => {}Response::Default(status, {value}),",
+                    self.type_name
+                )?;
+            }
+            None => writeln!(
+                out,
+                "            status => {}Response::Other(status, res.text().await?),",
+                self.type_name
+            )?,
+        }
+
+        writeln!(out, "        }})")?;
+        writeln!(out, "    }}")
+    }
+
+    fn write_types(&self, out: &mut String, client: &str) -> fmt::Result {
+        if !self.params.is_empty() {
+            writeln!(out)?;
+            writeln!(out, "/// The parameters of [`{client}::{}`].", self.name)?;
+            writeln!(out, "#[derive(Debug, Clone)]")?;
+            writeln!(out, "pub struct {}Params {{", self.type_name)?;
+            for param in &self.params {
+                let data = param.parameter_data_ref();
+                write_docs(out, "    ", data.description.as_deref())?;
+                let (ty, optional) = parameter_type(param);
+                if optional {
+                    writeln!(out, "    pub {}: Option<{ty}>,", snake_case(&data.name))?;
+                } else {
+                    writeln!(out, "    pub {}: {ty},", snake_case(&data.name))?;
+                }
+            }
+            writeln!(out, "}}")?;
+        }
+
+        writeln!(out)?;
+        writeln!(out, "/// The responses of [`{client}::{}`].", self.name)?;
+        writeln!(out, "#[derive(Debug)]")?;
+        writeln!(out, "pub enum {}Response {{", self.type_name)?;
+
+        let responses = self.responses();
+        for response in &responses {
+            write_docs(out, "    ", response.description)?;
+            let value = match &response.body {
+                Some(ResponseBody::Json(ty)) => ty.as_str(),
+                Some(ResponseBody::Text) => "String",
+                None => "()",
+            };
+            match (&response.status, &response.body) {
+                (None, _) => writeln!(out, "    Default(u16, {value}),")?,
+                (Some(_), None) => writeln!(out, "    {},", response.variant)?,
+                (Some(_), Some(_)) => writeln!(out, "    {}({value}),", response.variant)?,
+            }
+        }
+
+        if !responses.iter().any(|r| r.status.is_none()) {
+            writeln!(
+                out,
+                "    /// An undocumented response with its status code and body."
+            )?;
+            writeln!(out, "    Other(u16, String),")?;
+        }
+
+        writeln!(out, "}}")
+    }
+
+    fn request_body(&self) -> Option<Body> {
+        let body = self.operation.request_body.as_ref()?.as_item()?;
+
+        if let Some((_, media)) = body.content.iter().find(|(ct, _)| is_json_media_type(ct)) {
+            let ty = media
+                .schema
+                .as_ref()
+                .and_then(|s| serde_json::to_value(&s.json_schema).ok())
+                .map_or("serde_json::Value".into(), |s| rust_type(&s));
+            return Some(Body::Json(ty, body.required));
+        }
+
+        body.content
+            .keys()
+            .next()
+            .map(|content_type| Body::Raw(content_type.clone()))
+    }
+
+    fn responses(&self) -> Vec<ClientResponse<'_>> {
+        let Some(responses) = &self.operation.responses else {
+            return Vec::new();
+        };
+
+        responses
+            .responses
+            .iter()
+            .map(|(status, res)| (Some(status.clone()), res))
+            .chain(responses.default.iter().map(|res| (None, res)))
+            .filter_map(|(status, res)| {
+                let res = res.as_item()?;
+                Some(ClientResponse {
+                    variant: status
+                        .as_ref()
+                        .map_or_else(|| "Default".into(), variant_name),
+                    status,
+                    description: Some(res.description.as_str()).filter(|d| !d.is_empty()),
+                    body: response_body(res),
+                })
+            })
+            .collect()
+    }
+}
+
+enum Body {
+    Json(String, bool),
+    Raw(String),
+}
+
+struct ClientResponse<'a> {
+    status: Option<StatusCode>,
+    variant: String,
+    description: Option<&'a str>,
+    body: Option<ResponseBody>,
+}
+
+enum ResponseBody {
+    Json(String),
+    Text,
+}
+
+fn response_body(res: &Response) -> Option<ResponseBody> {
+    if let Some((_, media)) = res.content.iter().find(|(ct, _)| is_json_media_type(ct)) {
+        let ty = media
+            .schema
+            .as_ref()
+            .and_then(|s| serde_json::to_value(&s.json_schema).ok())
+            .map_or("serde_json::Value".into(), |s| rust_type(&s));
+        return Some(ResponseBody::Json(ty));
+    }
+
+    (!res.content.is_empty()).then_some(ResponseBody::Text)
+}
+
+/// The Rust type of a parameter without the [`Option`], and whether
+/// the parameter is optional, either because it is not required or
+/// because its schema is nullable, e.g. for an `Option<u32>` query field.
+fn parameter_type(param: &Parameter) -> (String, bool) {
+    let data = param.parameter_data_ref();
+    let ty = match &data.format {
+        ParameterSchemaOrContent::Schema(schema) => {
+            serde_json::to_value(&schema.json_schema).map_or("String".into(), |s| rust_type(&s))
+        }
+        ParameterSchemaOrContent::Content(_) => "String".into(),
+    };
+
+    match ty
+        .strip_prefix("Option<")
+        .and_then(|ty| ty.strip_suffix('>'))
+    {
+        Some(ty) => (ty.into(), true),
+        None => (ty, !data.required),
+    }
+}
+
+/// The Rust type of a JSON schema, references
+/// are referred to by the names of the schemas.
+fn rust_type(schema: &Value) -> String {
+    let Value::Object(schema) = schema else {
+        return "serde_json::Value".into();
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit_once('/')
+            .map_or("serde_json::Value".into(), |(_, name)| pascal_case(name));
+    }
+
+    // A single reference with additional keywords, e.g. a description.
+    if let Some([schema]) = schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+    {
+        return rust_type(schema);
+    }
+
+    let (ty, nullable) = match schema.get("type") {
+        Some(Value::String(ty)) => (ty.as_str(), false),
+        Some(Value::Array(types)) => {
+            let nullable = types.iter().any(|ty| ty == "null");
+            let types = types
+                .iter()
+                .filter_map(Value::as_str)
+                .filter(|ty| *ty != "null")
+                .collect::<Vec<_>>();
+            match types.as_slice() {
+                [ty] => (*ty, nullable),
+                _ => return "serde_json::Value".into(),
+            }
+        }
+        _ => return "serde_json::Value".into(),
+    };
+
+    let format = schema.get("format").and_then(Value::as_str);
+
+    let ty = match (ty, format) {
+        ("string", _) => "String".into(),
+        ("boolean", _) => "bool".into(),
+        ("integer", Some(format @ ("int8" | "int16" | "int32" | "int64"))) => {
+            format.replace("int", "i")
+        }
+        ("integer", Some(format @ ("uint8" | "uint16" | "uint32" | "uint64"))) => {
+            format.replace("uint", "u")
+        }
+        ("integer", Some("uint")) => "u64".into(),
+        ("integer", _) => "i64".into(),
+        ("number", Some("float")) => "f32".into(),
+        ("number", _) => "f64".into(),
+        ("array", _) => match schema.get("items") {
+            Some(items @ Value::Object(_)) => format!("Vec<{}>", rust_type(items)),
+            _ => "Vec<serde_json::Value>".into(),
+        },
+        ("object", _) => match schema.get("additionalProperties") {
+            Some(values @ Value::Object(_)) if !schema.contains_key("properties") => {
+                format!("std::collections::HashMap<String, {}>", rust_type(values))
+            }
+            _ => "serde_json::Value".into(),
+        },
+        _ => "serde_json::Value".into(),
+    };
+
+    if nullable {
+        format!("Option<{ty}>")
+    } else {
+        ty
+    }
+}
+
+fn variant_name(status: &StatusCode) -> String {
+    let code = match *status {
+        StatusCode::Code(code) => code,
+        StatusCode::Range(range) => return format!("Status{range}XX"),
+    };
+
+    let name = match code {
+        200 => "Ok",
+        201 => "Created",
+        202 => "Accepted",
+        204 => "NoContent",
+        301 => "MovedPermanently",
+        302 => "Found",
+        303 => "SeeOther",
+        304 => "NotModified",
+        307 => "TemporaryRedirect",
+        308 => "PermanentRedirect",
+        400 => "BadRequest",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "NotFound",
+        405 => "MethodNotAllowed",
+        409 => "Conflict",
+        410 => "Gone",
+        412 => "PreconditionFailed",
+        413 => "PayloadTooLarge",
+        415 => "UnsupportedMediaType",
+        422 => "UnprocessableEntity",
+        429 => "TooManyRequests",
+        500 => "InternalServerError",
+        501 => "NotImplemented",
+        502 => "BadGateway",
+        503 => "ServiceUnavailable",
+        504 => "GatewayTimeout",
+        code => return format!("Status{code}"),
+    };
+
+    name.into()
+}
+
+fn write_docs(out: &mut String, indent: &str, docs: Option<&str>) -> fmt::Result {
+    for line in docs.into_iter().flat_map(str::lines) {
+        let line = line.trim_end();
+        if line.is_empty() {
+            writeln!(out, "{indent}///")?;
+        } else {
+            writeln!(out, "{indent}/// {line}")?;
+        }
+    }
+    Ok(())
+}
+
+/// Split an identifier into lowercase words.
+fn snake_case(s: &str) -> String {
+    let ident = case_words(s).join("_");
+
+    match ident.as_str() {
+        "" => "_".into(),
+        "self" | "super" | "crate" | "Self" => format!("{ident}_"),
+        "as" | "async" | "await" | "break" | "const" | "continue" | "dyn" | "else" | "enum"
+        | "extern" | "false" | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop" | "match"
+        | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "static" | "struct" | "trait"
+        | "true" | "type" | "unsafe" | "use" | "where" | "while" => format!("r#{ident}"),
+        ident if ident.starts_with(|c: char| c.is_ascii_digit()) => format!("_{ident}"),
+        _ => ident,
+    }
+}
+
+fn pascal_case(s: &str) -> String {
+    let ident = util::pascal_case(s);
+
+    if ident.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{ident}")
+    } else {
+        ident
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{
+        extract::{Path, Query},
+        http::StatusCode,
+        Json,
+    };
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use super::{pascal_case, snake_case, ClientGenerator};
+    use crate::{
+        axum::{
+            routing::{get_with, put_with},
+            ApiRouter,
+        },
+        openapi::OpenApi,
+    };
+
+    #[derive(Debug, Deserialize, Serialize, JsonSchema)]
+    struct Todo {
+        title: String,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct TodoPath {
+        #[allow(dead_code)]
+        list_id: u32,
+        #[allow(dead_code)]
+        id: u64,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct TodoQuery {
+        #[allow(dead_code)]
+        page: Option<u32>,
+    }
+
+    async fn update_todo(
+        Path(_path): Path<TodoPath>,
+        Json(todo): Json<Todo>,
+    ) -> Result<Json<Todo>, StatusCode> {
+        Ok(Json(todo))
+    }
+
+    async fn list_todos(Query(_query): Query<TodoQuery>) -> Json<Vec<Todo>> {
+        Json(Vec::new())
+    }
+
+    fn todo_api() -> OpenApi {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/todos", get_with(list_todos, |op| op.id("listTodos")))
+            .api_route(
+                "/lists/:list_id/todos/:id",
+                put_with(update_todo, |op| {
+                    op.id("updateTodo")
+                        .summary("Update a todo.")
+                        .response::<404, ()>()
+                }),
+            )
+            .finish_api(&mut api);
+        api
+    }
+
+    /// The client generated from [`todo_api`], compiled
+    /// with the tests so that the generated code is checked.
+    #[allow(dead_code, clippy::pedantic)]
+    mod generated {
+        include!("../res/client/todo_client.rs");
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(snake_case("updateTodo"), "update_todo");
+        assert_eq!(snake_case("get /todos/{id}"), "get_todos_id");
+        assert_eq!(snake_case("type"), "r#type");
+        assert_eq!(pascal_case("update_todo"), "UpdateTodo");
+        assert_eq!(pascal_case("HTTPServer"), "HttpServer");
+        assert_eq!(pascal_case("2fa"), "_2fa");
+    }
+
+    #[test]
+    fn test_generate_client() {
+        let client = ClientGenerator::new(&todo_api())
+            .client_name("TodoClient")
+            .prelude("use models::*;")
+            .generate();
+
+        for expected in [
+            "use models::*;",
+            "pub struct TodoClient {",
+            "    /// Update a todo.",
+            "    /// `PUT /lists/{list_id}/todos/{id}`",
+            "    pub async fn update_todo(&self, params: &UpdateTodoParams, body: &Todo) -> Result<UpdateTodoResponse, reqwest::Error> {",
+            r#"        let url = self.url(&["lists", &params.list_id.to_string(), "todos", &params.id.to_string()]);"#,
+            "            200 => UpdateTodoResponse::Ok(res.json().await?),",
+            "            404 => UpdateTodoResponse::NotFound,",
+            "pub struct UpdateTodoParams {",
+            "    pub list_id: u32,",
+            "    pub id: u64,",
+            "pub enum UpdateTodoResponse {",
+            "    Ok(Todo),",
+            "    Other(u16, String),",
+        ] {
+            assert!(client.contains(expected), "missing `{expected}` in:\n{client}");
+        }
+    }
+    #[test]
+    fn test_nullable_query_parameter() {
+        let client = ClientGenerator::new(&todo_api()).generate();
+
+        // `Option<u32>` is nullable, which must not add another `Option`.
+        for expected in [
+            "pub struct ListTodosParams {",
+            "    pub page: Option<u32>,",
+            r#"        if let Some(value) = &params.page { query.push(("page", value.to_string())); }"#,
+            "    Ok(Vec<Todo>),",
+        ] {
+            assert!(
+                client.contains(expected),
+                "missing `{expected}` in:\n{client}"
+            );
+        }
+        assert!(!client.contains("Option<Option<"), "{client}");
+    }
+
+    #[test]
+    fn test_generated_client() {
+        let client = ClientGenerator::new(&todo_api())
+            .client_name("TodoClient")
+            .prelude("use super::Todo;")
+            .generate();
+
+        assert_eq!(
+            client,
+            include_str!("../res/client/todo_client.rs"),
+            "the generated client changed, update `res/client/todo_client.rs`"
+        );
+    }
+}
@@ -0,0 +1,278 @@
+//! Integrity digests of the generated documentation.
+//!
+//! [`embed_digest`] stores a SHA-256 digest of the canonicalized
+//! document in the `x-spec-digest` extension of the [`Info`] object,
+//! which consumers can check with [`verify_digest`] to detect
+//! modified or stale documents.
+//!
+//! The digest covers the whole document except for the digest
+//! itself, so it must be embedded after all other changes, e.g.
+//! after `ApiRouter::finish_api` when using `axum`.
+//!
+//! The document is canonicalized by serializing it as compact JSON
+//! with the keys of all objects sorted.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     digest::{embed_digest, verify_digest, DigestError},
+//!     openapi::OpenApi,
+//! };
+//!
+//! let mut api = OpenApi::default();
+//! api.info.title = "Example".into();
+//!
+//! embed_digest(&mut api).unwrap();
+//! assert!(verify_digest(&api).is_ok());
+//!
+//! api.info.title = "Changed".into();
+//! assert!(matches!(
+//!     verify_digest(&api),
+//!     Err(DigestError::Mismatch { .. })
+//! ));
+//! ```
+//!
+//! [`Info`]: crate::openapi::Info
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::openapi::OpenApi;
+
+/// The extension of the [`Info`](crate::openapi::Info) object that holds the digest.
+pub const DIGEST_EXTENSION: &str = "x-spec-digest";
+
+/// The digest of a document.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpecDigest {
+    /// The digest algorithm, always `sha256`.
+    pub algorithm: String,
+    /// The hex encoded digest.
+    pub value: String,
+    /// The path or URL of a detached signature of the document.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+/// Errors during the verification of a digest.
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum DigestError {
+    #[error("the document has no digest")]
+    Missing,
+    #[error(r#"unsupported digest algorithm "{0}""#)]
+    UnsupportedAlgorithm(String),
+    #[error("digest mismatch, expected {expected} but the document has {actual}")]
+    Mismatch { expected: String, actual: String },
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Compute the hex encoded SHA-256 digest of the document,
+/// ignoring any embedded digest.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be serialized.
+pub fn compute_digest(api: &OpenApi) -> Result<String, serde_json::Error> {
+    let mut document = serde_json::to_value(api)?;
+
+    if let Some(Value::Object(info)) = document.get_mut("info") {
+        info.remove(DIGEST_EXTENSION);
+    }
+
+    let mut canonical = String::new();
+    write_canonical(&document, &mut canonical)?;
+
+    let hash = Sha256::digest(canonical.as_bytes());
+
+    Ok(hash.iter().fold(String::new(), |mut hex, byte| {
+        let _ = write!(hex, "{byte:02x}");
+        hex
+    }))
+}
+
+/// Embed the digest of the document in the `x-spec-digest` extension.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be serialized.
+pub fn embed_digest(api: &mut OpenApi) -> Result<(), serde_json::Error> {
+    embed(api, None)
+}
+
+/// Same as [`embed_digest`], but also record the path or URL
+/// of a detached signature of the document.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be serialized.
+pub fn embed_digest_with_signature(
+    api: &mut OpenApi,
+    signature: &str,
+) -> Result<(), serde_json::Error> {
+    embed(api, Some(signature.into()))
+}
+
+fn embed(api: &mut OpenApi, signature: Option<String>) -> Result<(), serde_json::Error> {
+    let digest = SpecDigest {
+        algorithm: "sha256".into(),
+        value: compute_digest(api)?,
+        signature,
+    };
+
+    api.info
+        .extensions
+        .insert(DIGEST_EXTENSION.into(), serde_json::to_value(digest)?);
+
+    Ok(())
+}
+
+/// Verify the embedded digest of the document.
+///
+/// # Errors
+///
+/// Returns an error if the document has no digest,
+/// or it does not match the contents of the document.
+pub fn verify_digest(api: &OpenApi) -> Result<SpecDigest, DigestError> {
+    let digest: SpecDigest = serde_json::from_value(
+        api.info
+            .extensions
+            .get(DIGEST_EXTENSION)
+            .cloned()
+            .ok_or(DigestError::Missing)?,
+    )?;
+
+    if digest.algorithm != "sha256" {
+        return Err(DigestError::UnsupportedAlgorithm(digest.algorithm));
+    }
+
+    let actual = compute_digest(api)?;
+
+    if actual != digest.value {
+        return Err(DigestError::Mismatch {
+            expected: digest.value,
+            actual,
+        });
+    }
+
+    Ok(digest)
+}
+
+/// Write the value as compact JSON with sorted object keys.
+fn write_canonical(value: &Value, out: &mut String) -> Result<(), serde_json::Error> {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (i, (key, value)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::to_string(key)?);
+                out.push(':');
+                write_canonical(value, out)?;
+            }
+            out.push('}');
+        }
+        Value::Array(items) => {
+            out.push('[');
+            for (i, value) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(value, out)?;
+            }
+            out.push(']');
+        }
+        value => out.push_str(&serde_json::to_string(value)?),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn api(extensions: &[(&str, Value)]) -> OpenApi {
+        let mut api = OpenApi::default();
+        api.info.title = "Example".into();
+        api.info.extensions.extend(
+            extensions
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), value.clone())),
+        );
+        api
+    }
+
+    #[test]
+    fn test_key_order() {
+        let a = api(&[("x-a", json!({ "b": 1, "c": [2, 3] })), ("x-d", json!(4))]);
+        let b = api(&[("x-d", json!(4)), ("x-a", json!({ "c": [2, 3], "b": 1 }))]);
+        assert_ne!(
+            serde_json::to_string(&a).unwrap(),
+            serde_json::to_string(&b).unwrap()
+        );
+        assert_eq!(compute_digest(&a).unwrap(), compute_digest(&b).unwrap());
+
+        // The order of array items is significant.
+        let c = api(&[("x-a", json!({ "b": 1, "c": [3, 2] })), ("x-d", json!(4))]);
+        assert_ne!(compute_digest(&a).unwrap(), compute_digest(&c).unwrap());
+    }
+
+    #[test]
+    fn test_ignores_digest() {
+        let mut api = api(&[]);
+        let digest = compute_digest(&api).unwrap();
+
+        embed_digest_with_signature(&mut api, "openapi.json.sig").unwrap();
+        assert_eq!(compute_digest(&api).unwrap(), digest);
+
+        // Embedding again does not change the digest.
+        embed_digest(&mut api).unwrap();
+        let embedded = verify_digest(&api).unwrap();
+        assert_eq!(embedded.value, digest);
+        assert_eq!(embedded.signature, None);
+    }
+
+    #[test]
+    fn test_verify_after_change() {
+        let mut api = api(&[]);
+        embed_digest(&mut api).unwrap();
+        assert!(verify_digest(&api).is_ok());
+
+        api.info.version = "2.0.0".into();
+        let Err(DigestError::Mismatch { expected, actual }) = verify_digest(&api) else {
+            panic!("expected a mismatch");
+        };
+        assert_ne!(expected, actual);
+        assert_eq!(actual, compute_digest(&api).unwrap());
+    }
+
+    #[test]
+    fn test_verify_errors() {
+        let mut api = api(&[]);
+        assert!(matches!(verify_digest(&api), Err(DigestError::Missing)));
+
+        api.info.extensions.insert(
+            DIGEST_EXTENSION.into(),
+            json!({ "algorithm": "md5", "value": "" }),
+        );
+        assert!(matches!(
+            verify_digest(&api),
+            Err(DigestError::UnsupportedAlgorithm(algorithm)) if algorithm == "md5"
+        ));
+    }
+}
@@ -0,0 +1,199 @@
+//! Serving a document that was generated ahead of time.
+//!
+//! Instead of generating the documentation when the application starts,
+//! the document can be generated by a test or a build script, e.g. with
+//! [`emit::write`](crate::emit::write), and embedded in the binary with
+//! [`embed!`](crate::embed!), which includes a file relative to the
+//! manifest directory of the crate:
+//!
+//! ```ignore
+//! #[test]
+//! fn openapi_json() {
+//!     let api = my_app::api_docs();
+//!     aide::emit::write(&api, "openapi.json", aide::emit::Format::Json).unwrap();
+//! }
+//! ```
+//!
+//! ```ignore
+//! use aide::embed::EmbeddedApi;
+//! use axum::routing::get;
+//!
+//! static API: EmbeddedApi = aide::embed!("openapi.json");
+//!
+//! let api = API.check_in_debug(my_app::api_docs);
+//! let app = axum::Router::new().route("/openapi.json", get(move || async move { api }));
+//! ```
+//!
+//! Nothing is generated at runtime in release builds, in debug builds
+//! [`EmbeddedApi::check_in_debug`] generates the document anyway and
+//! panics if the embedded one is outdated, so that it is regenerated
+//! before it is released.
+
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::openapi::OpenApi;
+
+/// Embed a JSON document relative to the manifest directory
+/// of the crate when the code is compiled, see [`embed`](crate::embed).
+#[macro_export]
+macro_rules! embed {
+    ($path:literal) => {
+        $crate::embed::EmbeddedApi::new(include_bytes!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/",
+            $path
+        )))
+    };
+}
+
+/// A JSON document embedded in the binary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EmbeddedApi {
+    json: &'static [u8],
+}
+
+/// Errors of an [`EmbeddedApi`].
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum EmbedError {
+    #[error("the embedded document is not valid: {0}")]
+    Invalid(#[from] serde_json::Error),
+    #[error(r#"the embedded document is outdated, it differs at "{0}""#)]
+    Outdated(String),
+}
+
+impl EmbeddedApi {
+    /// Embed a JSON document, usually with [`embed!`](crate::embed!).
+    #[must_use]
+    pub const fn new(json: &'static [u8]) -> Self {
+        Self { json }
+    }
+
+    /// The JSON document.
+    #[must_use]
+    pub fn as_bytes(&self) -> &'static [u8] {
+        self.json
+    }
+
+    /// Deserialize the document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document is not valid.
+    pub fn parse(&self) -> Result<OpenApi, EmbedError> {
+        Ok(serde_json::from_slice(self.json)?)
+    }
+
+    /// Check whether the embedded document is the same as the given one,
+    /// regardless of how the JSON is formatted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error with the JSON pointer of the first difference
+    /// if the documents are different.
+    pub fn check(&self, api: &OpenApi) -> Result<(), EmbedError> {
+        let embedded: Value = serde_json::from_slice(self.json)?;
+        let current = serde_json::to_value(api)?;
+
+        match first_difference(&embedded, &current, String::new()) {
+            Some(pointer) => Err(EmbedError::Outdated(pointer)),
+            None => Ok(()),
+        }
+    }
+
+    /// [Check](Self::check) the embedded document against the generated
+    /// one if debug assertions are enabled, the document is not generated
+    /// otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if debug assertions are enabled and
+    /// the embedded document is invalid or outdated.
+    #[track_caller]
+    #[must_use]
+    pub fn check_in_debug(self, generate: impl FnOnce() -> OpenApi) -> Self {
+        if cfg!(debug_assertions) {
+            if let Err(error) = self.check(&generate()) {
+                panic!("{error}, regenerate it");
+            }
+        }
+        self
+    }
+}
+
+/// The JSON pointer of the first difference of two values.
+fn first_difference(a: &Value, b: &Value, pointer: String) -> Option<String> {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => a
+            .keys()
+            .chain(b.keys().filter(|key| !a.contains_key(*key)))
+            .find_map(|key| {
+                let pointer = format!("{pointer}/{}", key.replace('~', "~0").replace('/', "~1"));
+                match (a.get(key), b.get(key)) {
+                    (Some(a), Some(b)) => first_difference(a, b, pointer),
+                    _ => Some(pointer),
+                }
+            }),
+        (Value::Array(a), Value::Array(b)) => a
+            .iter()
+            .zip(b)
+            .enumerate()
+            .find_map(|(i, (a, b))| first_difference(a, b, format!("{pointer}/{i}")))
+            .or_else(|| {
+                (a.len() != b.len()).then(|| format!("{pointer}/{}", a.len().min(b.len())))
+            }),
+        (a, b) => (a != b).then_some(pointer),
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for EmbeddedApi {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::{header::CONTENT_TYPE, HeaderValue};
+
+        (
+            [(CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+            bytes::Bytes::from_static(self.json),
+        )
+            .into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EMBEDDED: EmbeddedApi = EmbeddedApi::new(
+        br#"{
+            "openapi": "3.1.0",
+            "info": { "title": "Embedded", "version": "1" },
+            "paths": { "/users/{id}": { "get": { "operationId": "get_user" } } }
+        }"#,
+    );
+
+    #[test]
+    fn test_check() {
+        let mut api = EMBEDDED.parse().unwrap();
+        assert!(EMBEDDED.check(&api).is_ok());
+        let _ = EMBEDDED.check_in_debug(|| api.clone());
+
+        let path = api.paths.as_mut().unwrap().paths.get_index_mut(0).unwrap();
+        let operation = path.1.as_item_mut().unwrap().get.as_mut().unwrap();
+        operation.operation_id = Some("find_user".into());
+
+        let error = EMBEDDED.check(&api).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            r#"the embedded document is outdated, it differs at "/paths/~1users~1{id}/get/operationId""#
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic = "regenerate it"]
+    fn test_check_in_debug() {
+        let _ = EMBEDDED.check_in_debug(OpenApi::default);
+    }
+}
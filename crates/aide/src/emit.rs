@@ -0,0 +1,282 @@
+//! Writing the documentation to files without starting a server.
+//!
+//! [`write`] writes a document to a file, which can be used from
+//! tests or build scripts. [`run`] implements a small command line
+//! interface around a function that builds the documentation, so that
+//! the document can be generated with a dedicated binary, for example
+//! `src/bin/openapi.rs`:
+//!
+//! ```ignore
+//! fn main() -> Result<(), aide::emit::EmitError> {
+//!     aide::emit::run(|| {
+//!         let mut api = OpenApi::default();
+//!         let _ = my_app::router().finish_api_with(&mut api, my_app::api_docs);
+//!         api
+//!     })
+//! }
+//! ```
+//!
+//! The binary is then invoked with:
+//!
+//! ```text
+//! cargo run --bin openapi -- --out openapi.yaml --format yaml
+//! ```
+//!
+//! Without `--out` the document is written to the standard output.
+//! The format defaults to the extension of the output file or JSON.
+//! YAML requires the `emit-yaml` feature.
+//!
+//! The document is serialized directly into the file or the standard
+//! output with [`to_writer`], without building it in memory first.
+//!
+//! The `example-axum` example has such a binary.
+
+use std::{
+    fs::{self, File},
+    io::{self, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+use crate::openapi::OpenApi;
+
+/// The output format of the document.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Pretty-printed JSON.
+    #[default]
+    Json,
+    /// YAML, requires the `emit-yaml` feature.
+    Yaml,
+}
+
+impl Format {
+    /// Guess the format from the extension of a path.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        path.as_ref()
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .and_then(|ext| ext.parse().ok())
+    }
+}
+
+impl FromStr for Format {
+    type Err = EmitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            _ => Err(EmitError::InvalidArguments(format!(
+                r#"unknown format "{s}""#
+            ))),
+        }
+    }
+}
+
+/// Errors during writing the documentation.
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum EmitError {
+    #[error("{0}")]
+    InvalidArguments(String),
+    #[error("YAML output requires the `emit-yaml` feature")]
+    YamlNotEnabled,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "emit-yaml")]
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+}
+
+/// Serialize the document in the given format.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be serialized.
+pub fn to_string(api: &OpenApi, format: Format) -> Result<String, EmitError> {
+    match format {
+        Format::Json => Ok(serde_json::to_string_pretty(api)?),
+        #[cfg(feature = "emit-yaml")]
+        Format::Yaml => Ok(serde_yaml::to_string(api)?),
+        #[cfg(not(feature = "emit-yaml"))]
+        Format::Yaml => Err(EmitError::YamlNotEnabled),
+    }
+}
+
+/// Serialize the document in the given format into a writer.
+///
+/// The document is written as it is serialized, writers
+/// that are not buffered should be wrapped in a [`BufWriter`].
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be serialized or written.
+pub fn to_writer<W: Write>(api: &OpenApi, writer: W, format: Format) -> Result<(), EmitError> {
+    match format {
+        Format::Json => Ok(serde_json::to_writer_pretty(writer, api)?),
+        #[cfg(feature = "emit-yaml")]
+        Format::Yaml => Ok(serde_yaml::to_writer(writer, api)?),
+        #[cfg(not(feature = "emit-yaml"))]
+        Format::Yaml => {
+            let _ = writer;
+            Err(EmitError::YamlNotEnabled)
+        }
+    }
+}
+
+/// Write the document to a file in the given format,
+/// the parent directories are created if needed.
+///
+/// # Errors
+///
+/// Returns an error if the document cannot be serialized or written.
+pub fn write(api: &OpenApi, path: impl AsRef<Path>, format: Format) -> Result<(), EmitError> {
+    let path = path.as_ref();
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = BufWriter::new(File::create(path)?);
+    to_writer(api, &mut file, format)?;
+    file.flush()?;
+    Ok(())
+}
+
+/// Read a document from a file in the given format.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a valid document.
+pub fn read(path: impl AsRef<Path>, format: Format) -> Result<OpenApi, EmitError> {
+    let reader = io::BufReader::new(File::open(path)?);
+
+    match format {
+        Format::Json => Ok(serde_json::from_reader(reader)?),
+        #[cfg(feature = "emit-yaml")]
+        Format::Yaml => Ok(serde_yaml::from_reader(reader)?),
+        #[cfg(not(feature = "emit-yaml"))]
+        Format::Yaml => {
+            let _ = reader;
+            Err(EmitError::YamlNotEnabled)
+        }
+    }
+}
+
+/// Build the documentation and write it according
+/// to the command line arguments of the process.
+///
+/// See the [module documentation](self) for the arguments.
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, or
+/// the document cannot be serialized or written.
+pub fn run(build: impl FnOnce() -> OpenApi) -> Result<(), EmitError> {
+    run_with_args(std::env::args().skip(1), build)
+}
+
+/// Same as [`run`], but with the given arguments
+/// (without the name of the program).
+///
+/// # Errors
+///
+/// Returns an error if the arguments are invalid, or
+/// the document cannot be serialized or written.
+pub fn run_with_args(
+    args: impl IntoIterator<Item = String>,
+    build: impl FnOnce() -> OpenApi,
+) -> Result<(), EmitError> {
+    let mut out: Option<String> = None;
+    let mut format: Option<Format> = None;
+
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        let (name, value) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+
+        let value = || {
+            value
+                .or_else(|| args.next())
+                .ok_or_else(|| EmitError::InvalidArguments(format!("missing value for {name}")))
+        };
+
+        match name.as_str() {
+            "-o" | "--out" => out = Some(value()?),
+            "-f" | "--format" => format = Some(value()?.parse()?),
+            _ => {
+                return Err(EmitError::InvalidArguments(format!(
+                    r#"unknown argument "{name}", expected --out <path> or --format <json|yaml>"#
+                )))
+            }
+        }
+    }
+
+    let api = build();
+
+    match out {
+        Some(out) => {
+            let format = format
+                .or_else(|| Format::from_path(&out))
+                .unwrap_or_default();
+            write(&api, out, format)
+        }
+        None => {
+            let mut stdout = io::stdout().lock();
+            to_writer(&api, &mut stdout, format.unwrap_or_default())?;
+            writeln!(stdout)?;
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_writer() {
+        let mut api = OpenApi::default();
+        api.info.title = "Streamed".into();
+
+        let mut out = Vec::new();
+        to_writer(&api, &mut out, Format::Json).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            to_string(&api, Format::Json).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let mut api = OpenApi {
+            openapi: "3.1.0".into(),
+            ..Default::default()
+        };
+        api.info.title = "Written".into();
+
+        let path = std::env::temp_dir().join(format!("aide-emit-{}.json", std::process::id()));
+        run_with_args(["--out".into(), path.display().to_string()], || api.clone()).unwrap();
+
+        let read_api = read(&path, Format::from_path(&path).unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read_api, api);
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let err = run_with_args(["--format".into(), "toml".into()], OpenApi::default);
+        assert!(matches!(err, Err(EmitError::InvalidArguments(_))));
+
+        let err = run_with_args(["--out".into()], OpenApi::default);
+        assert!(matches!(err, Err(EmitError::InvalidArguments(_))));
+    }
+}
@@ -23,6 +23,13 @@ pub enum Error {
     ResponseExists(StatusCode),
     #[error(r#"the operation "{1}" already exists for the path "{0}""#)]
     OperationExists(String, &'static str),
+    #[error(r#"the operation "{method}" of the path "{path}" is documented by both "{first}" and "{second}""#)]
+    OperationConflict {
+        path: String,
+        method: &'static str,
+        first: &'static str,
+        second: &'static str,
+    },
     #[error(r#"duplicate request body for the operation"#)]
     DuplicateRequestBody,
     #[error(r#"duplicate parameter "{0}" for the operation"#)]
@@ -33,6 +40,43 @@ pub enum Error {
     InferredResponseConflict(u16),
     #[error("did not apply inferred default response because a default response already exists")]
     InferredDefaultResponseConflict,
+    #[error(r#"the schema "{0}" does not exist"#)]
+    SchemaNotExists(String),
+    #[error(r#"the value "{0}" does not exist in the enum"#)]
+    EnumValueNotExists(String),
+    #[error(r#"the property "{0}" does not exist in the schema"#)]
+    PropertyNotExists(String),
+    #[error("the example cannot be serialized to JSON: {0}")]
+    InvalidExample(serde_json::Error),
+    #[error(r#"the path "{0}" has {1} parameters, but the operation extracts {2}"#)]
+    PathParameterCount(String, usize, usize),
+    #[error(r#"the path "{0}" is not a valid OpenAPI path template: {1}"#)]
+    InvalidPathTemplate(String, String),
+    #[error(r#"the parameter "{0}" does not support {1}"#)]
+    UnsupportedParameterOption(String, String),
+    #[error(r#"the extractor "{0}" is not documented"#)]
+    UnknownExtractor(&'static str),
+    #[error(r#"the translation of "{0}" is missing for the locale "{1}""#)]
+    MissingTranslation(String, String),
+    #[error(r#"there is no variable for the placeholder "{{{{{0}}}}}""#)]
+    UnknownPlaceholder(String),
+    #[error(r#"failed to read the description file "{0}": {1}"#)]
+    DescriptionFile(String, String),
+    #[error(r#"code samples in "{0}" cannot be generated"#)]
+    UnsupportedSampleLanguage(String),
+    #[error(r#"the schema "{0}" has different definitions in the merged routers"#)]
+    SchemaConflict(String),
+    #[error(r#"the internal schema "{0}" is referenced by external items"#)]
+    InternalSchemaReferenced(String),
+    #[error("the operation does not have a request body")]
+    RequestBodyNotExists,
+    #[error(r#"the handler of the typed operation "{operation}" {reason}"#)]
+    TypedOperationMismatch {
+        operation: &'static str,
+        reason: String,
+    },
+    #[error(transparent)]
+    Patch(#[from] crate::patch::PatchError),
     #[error("{0}")]
     Other(Box<dyn std::error::Error + Send>),
 }
@@ -0,0 +1,524 @@
+//! Example values generated from schemas.
+//!
+//! [`generate`] creates a value that is valid for a JSON schema,
+//! with realistic values for the common string formats (`uuid`,
+//! `date-time`, `email`, ...), the first value of enums, numbers
+//! in the allowed ranges and strings that match simple patterns.
+//!
+//! The generated examples are also used to fill in the missing
+//! examples of request and response bodies if enabled with
+//! [`gen::generate_examples`](crate::gen::generate_examples),
+//! and by the mock server.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::example::generate;
+//! use schemars::{schema_for, JsonSchema};
+//! use serde_json::json;
+//!
+//! #[derive(JsonSchema)]
+//! struct User {
+//!     /// The email address.
+//!     #[schemars(email)]
+//!     email: String,
+//!     #[schemars(range(min = 18))]
+//!     age: u8,
+//!     roles: Vec<Role>,
+//! }
+//!
+//! #[derive(JsonSchema)]
+//! enum Role {
+//!     Admin,
+//!     User,
+//! }
+//!
+//! let schema = schema_for!(User);
+//!
+//! assert_eq!(
+//!     generate(&schema.schema.into(), &schema.definitions),
+//!     json!({
+//!         "email": "user@example.com",
+//!         "age": 18,
+//!         "roles": ["Admin"],
+//!     })
+//! );
+//! ```
+
+use schemars::{schema::Schema, Map as SchemaMap};
+use serde_json::{Map, Value};
+
+use crate::{
+    openapi::{MediaType, OpenApi, ParameterData, ParameterSchemaOrContent, ReferenceOr},
+    util::iter_operations_mut,
+};
+
+/// The maximum depth of nested schemas, deeper
+/// (usually recursive) schemas are generated as `null`.
+const MAX_DEPTH: usize = 8;
+
+/// Generate an example value that is valid for the schema.
+///
+/// References are resolved by their name in `definitions`, unknown
+/// references are generated as `null`.
+#[must_use]
+pub fn generate(schema: &Schema, definitions: &SchemaMap<String, Schema>) -> Value {
+    let schema = serde_json::to_value(schema).unwrap_or_default();
+    let definitions = serde_json::to_value(definitions).unwrap_or_default();
+    generate_value(&schema, &definitions)
+}
+
+/// Fill in the missing examples of all request and response
+/// bodies of the documentation with generated ones.
+///
+/// References are resolved in the schemas of the components
+/// and the given definitions.
+pub fn populate_examples(api: &mut OpenApi, definitions: &SchemaMap<String, Schema>) {
+    let mut schemas = component_schemas(api);
+    if let (Value::Object(schemas), Ok(Value::Object(definitions))) =
+        (&mut schemas, serde_json::to_value(definitions))
+    {
+        for (name, schema) in definitions {
+            schemas.entry(name).or_insert(schema);
+        }
+    }
+
+    let Some(paths) = &mut api.paths else {
+        return;
+    };
+
+    for (_, path) in &mut paths.paths {
+        let ReferenceOr::Item(path) = path else {
+            continue;
+        };
+
+        for (_, operation) in iter_operations_mut(path) {
+            let request_media = operation
+                .request_body
+                .as_mut()
+                .and_then(ReferenceOr::as_item_mut)
+                .into_iter()
+                .flat_map(|body| body.content.values_mut());
+
+            let response_media = operation
+                .responses
+                .iter_mut()
+                .flat_map(|res| res.default.iter_mut().chain(res.responses.values_mut()))
+                .filter_map(ReferenceOr::as_item_mut)
+                .flat_map(|res| res.content.values_mut());
+
+            for media in request_media.chain(response_media) {
+                if media.example.is_none()
+                    && media.examples.is_empty()
+                    && media.schema.as_ref().is_some_and(|s| s.example.is_none())
+                {
+                    media.example = Some(media_example(media, &schemas));
+                }
+            }
+        }
+    }
+}
+
+/// The schemas of the components as JSON, to resolve references.
+pub(crate) fn component_schemas(api: &OpenApi) -> Value {
+    api.components
+        .as_ref()
+        .and_then(|c| serde_json::to_value(&c.schemas).ok())
+        .unwrap_or_default()
+}
+
+/// The documented example of a media type, or one generated from its schema.
+pub(crate) fn media_example(media: &MediaType, schemas: &Value) -> Value {
+    if let Some(example) = &media.example {
+        return example.clone();
+    }
+
+    if let Some(example) = media
+        .examples
+        .values()
+        .filter_map(ReferenceOr::as_item)
+        .find_map(|example| example.value.clone())
+    {
+        return example;
+    }
+
+    let Some(schema) = &media.schema else {
+        return Value::Null;
+    };
+
+    if let Some(example) = &schema.example {
+        return example.clone();
+    }
+
+    serde_json::to_value(&schema.json_schema)
+        .map(|schema| generate_value(&schema, schemas))
+        .unwrap_or_default()
+}
+
+/// The documented example of a parameter, or one generated from its schema.
+pub(crate) fn parameter_example(data: &ParameterData, schemas: &Value) -> Value {
+    if let Some(example) = &data.example {
+        return example.clone();
+    }
+
+    match &data.format {
+        ParameterSchemaOrContent::Schema(schema) => {
+            if let Some(example) = &schema.example {
+                return example.clone();
+            }
+            serde_json::to_value(&schema.json_schema)
+                .map(|schema| generate_value(&schema, schemas))
+                .unwrap_or_default()
+        }
+        ParameterSchemaOrContent::Content(content) => content
+            .values()
+            .next()
+            .map(|media| media_example(media, schemas))
+            .unwrap_or_default(),
+    }
+}
+
+/// Generate an example value that is valid for the JSON schema.
+///
+/// References are resolved by their name in `schemas`.
+pub(crate) fn generate_value(schema: &Value, schemas: &Value) -> Value {
+    generate_at_depth(schema, schemas, 0)
+}
+
+fn generate_at_depth(schema: &Value, schemas: &Value, depth: usize) -> Value {
+    let Value::Object(schema) = schema else {
+        return Value::Null;
+    };
+
+    if depth > MAX_DEPTH {
+        return Value::Null;
+    }
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        return reference
+            .rsplit_once('/')
+            .and_then(|(_, name)| schemas.get(name))
+            .map_or(Value::Null, |s| generate_at_depth(s, schemas, depth + 1));
+    }
+
+    for keyword in ["const", "example", "default"] {
+        if let Some(value) = schema.get(keyword) {
+            return value.clone();
+        }
+    }
+
+    for keyword in ["enum", "examples"] {
+        if let Some(value) = schema.get(keyword).and_then(|v| v.as_array()?.first()) {
+            return value.clone();
+        }
+    }
+
+    if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for value in all_of
+            .iter()
+            .map(|s| generate_at_depth(s, schemas, depth + 1))
+        {
+            match value {
+                Value::Object(map) => merged.extend(map),
+                value if all_of.len() == 1 => return value,
+                _ => {}
+            }
+        }
+        return Value::Object(merged);
+    }
+
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(first) = schema.get(keyword).and_then(|v| v.as_array()?.first()) {
+            return generate_at_depth(first, schemas, depth + 1);
+        }
+    }
+
+    let instance_type = match schema.get("type") {
+        Some(Value::String(ty)) => Some(ty.as_str()),
+        Some(Value::Array(types)) => types
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|ty| *ty != "null")
+            .or(Some("null")),
+        _ if schema.contains_key("properties") => Some("object"),
+        _ if schema.contains_key("items") => Some("array"),
+        _ => None,
+    };
+
+    match instance_type {
+        Some("object") => generate_object(schema, schemas, depth),
+        Some("array") => generate_array(schema, schemas, depth),
+        Some("string") => Value::String(generate_string(schema)),
+        Some("integer") => Value::from(generate_integer(schema)),
+        Some("number") => Value::from(generate_number(schema)),
+        Some("boolean") => Value::Bool(false),
+        _ => Value::Null,
+    }
+}
+
+fn generate_object(schema: &Map<String, Value>, schemas: &Value, depth: usize) -> Value {
+    let mut object = Map::new();
+
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (name, property) in properties {
+            object.insert(
+                name.clone(),
+                generate_at_depth(property, schemas, depth + 1),
+            );
+        }
+    } else if let Some(additional @ Value::Object(_)) = schema.get("additionalProperties") {
+        object.insert(
+            "key".into(),
+            generate_at_depth(additional, schemas, depth + 1),
+        );
+    }
+
+    Value::Object(object)
+}
+
+fn generate_array(schema: &Map<String, Value>, schemas: &Value, depth: usize) -> Value {
+    let items = match schema.get("items") {
+        Some(Value::Array(items)) => {
+            return items
+                .iter()
+                .map(|s| generate_at_depth(s, schemas, depth + 1))
+                .collect()
+        }
+        Some(items) => items,
+        None => return Value::Array(Vec::new()),
+    };
+
+    if depth >= MAX_DEPTH {
+        return Value::Array(Vec::new());
+    }
+
+    let min = schema.get("minItems").and_then(Value::as_u64).unwrap_or(1);
+    let max = schema.get("maxItems").and_then(Value::as_u64);
+    let len = max.map_or(min.max(1), |max| min.max(1).min(max));
+
+    let item = generate_at_depth(items, schemas, depth + 1);
+    std::iter::repeat_n(item, usize::try_from(len).unwrap_or(1)).collect()
+}
+
+fn generate_string(schema: &Map<String, Value>) -> String {
+    if let Some(example) = schema
+        .get("format")
+        .and_then(Value::as_str)
+        .and_then(format_example)
+    {
+        return example.into();
+    }
+
+    if let Some(example) = schema
+        .get("pattern")
+        .and_then(Value::as_str)
+        .and_then(pattern_example)
+    {
+        return example;
+    }
+
+    let min = schema.get("minLength").and_then(Value::as_u64);
+    let max = schema.get("maxLength").and_then(Value::as_u64);
+
+    let mut example = String::from("string");
+    if let Some(min) = min.and_then(|min| usize::try_from(min).ok()) {
+        while example.len() < min {
+            example.push('x');
+        }
+    }
+    if let Some(max) = max.and_then(|max| usize::try_from(max).ok()) {
+        example.truncate(max);
+    }
+    example
+}
+
+fn format_example(format: &str) -> Option<&'static str> {
+    Some(match format {
+        "uuid" => "3fa85f64-5717-4562-b3fc-2c963f66afa6",
+        "date-time" => "2024-01-01T00:00:00Z",
+        "date" => "2024-01-01",
+        "time" => "00:00:00Z",
+        "duration" => "P1D",
+        "email" | "idn-email" => "user@example.com",
+        "hostname" | "idn-hostname" => "example.com",
+        "ipv4" => "192.0.2.1",
+        "ipv6" => "2001:db8::1",
+        "uri" | "url" | "iri" => "https://example.com",
+        "uri-reference" | "iri-reference" => "/example",
+        "byte" => "c3RyaW5n",
+        "password" => "password",
+        _ => return None,
+    })
+}
+
+/// Generate a string that matches simple regular expressions made of
+/// literals, character classes and quantifiers.
+///
+/// Patterns with groups or alternatives are not supported.
+fn pattern_example(pattern: &str) -> Option<String> {
+    let pattern = pattern.strip_prefix('^').unwrap_or(pattern);
+    let pattern = pattern.strip_suffix('$').unwrap_or(pattern);
+
+    let mut example = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let atom = match c {
+            '\\' => escape_example(chars.next()?),
+            '[' => {
+                let mut first = None;
+                loop {
+                    match chars.next()? {
+                        ']' if first.is_some() => break,
+                        '^' if first.is_none() => return None,
+                        '\\' => {
+                            let c = escape_example(chars.next()?);
+                            first.get_or_insert(c);
+                        }
+                        c => {
+                            first.get_or_insert(c);
+                        }
+                    }
+                }
+                first?
+            }
+            '.' => 'a',
+            '(' | ')' | '|' | '*' | '+' | '?' | '{' => return None,
+            c => c,
+        };
+
+        let count = match chars.peek() {
+            Some('?' | '*') => 0,
+            Some('+') => 1,
+            Some('{') => {
+                chars.next();
+                let mut quantifier = String::new();
+                loop {
+                    match chars.next()? {
+                        '}' => break,
+                        c => quantifier.push(c),
+                    }
+                }
+                quantifier.split(',').next()?.trim().parse().ok()?
+            }
+            _ => {
+                example.push(atom);
+                continue;
+            }
+        };
+
+        if matches!(chars.peek(), Some('?' | '*' | '+')) {
+            chars.next();
+        }
+        // Lazy quantifiers.
+        chars.next_if_eq(&'?');
+
+        example.extend(std::iter::repeat_n(atom, count));
+    }
+
+    Some(example)
+}
+
+fn escape_example(c: char) -> char {
+    match c {
+        'd' => '0',
+        'w' | 'D' | 'S' => 'a',
+        's' => ' ',
+        'W' => '-',
+        c => c,
+    }
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_precision_loss)]
+fn generate_integer(schema: &Map<String, Value>) -> i64 {
+    let number = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+
+    // Schemars writes the bounds of integers as floats.
+    let min = number("minimum")
+        .map(f64::ceil)
+        .or_else(|| number("exclusiveMinimum").map(|min| min.floor() + 1.0));
+    let max = number("maximum")
+        .map(f64::floor)
+        .or_else(|| number("exclusiveMaximum").map(|max| max.ceil() - 1.0));
+
+    let mut value = match (min, max) {
+        (Some(min), _) => min,
+        (None, Some(max)) if max < 0.0 => max,
+        _ => 0.0,
+    };
+
+    if let Some(multiple) = number("multipleOf").filter(|m| *m >= 1.0) {
+        let multiple = multiple.round();
+        let rounded = (value / multiple).ceil() * multiple;
+        if max.is_none_or(|max| rounded <= max) {
+            value = rounded;
+        }
+    }
+
+    value as i64
+}
+
+fn generate_number(schema: &Map<String, Value>) -> f64 {
+    let number = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+
+    let max = number("maximum").or_else(|| number("exclusiveMaximum"));
+
+    let value = if let Some(min) = number("minimum") {
+        min
+    } else if let Some(min) = number("exclusiveMinimum") {
+        match max {
+            Some(max) if max <= min + 1.0 => f64::midpoint(min, max),
+            _ => min + 1.0,
+        }
+    } else {
+        match max {
+            Some(max) if max < 0.0 => max - 1.0,
+            _ => 0.0,
+        }
+    };
+
+    match number("multipleOf").filter(|m| *m > 0.0) {
+        Some(multiple) => (value / multiple).ceil() * multiple,
+        None => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{generate_value, pattern_example};
+
+    #[test]
+    fn test_pattern_example() {
+        assert_eq!(pattern_example("^[A-Z]{3}-\\d{4}$").unwrap(), "AAA-0000");
+        assert_eq!(pattern_example("^a+b*c?\\.[xyz]$").unwrap(), "a.x");
+        assert_eq!(pattern_example("^(a|b)$"), None);
+        assert_eq!(pattern_example("[^a]"), None);
+    }
+
+    #[test]
+    fn test_generate() {
+        let schemas = json!({
+            "Node": {
+                "type": "object",
+                "properties": {
+                    "id": { "type": "string", "format": "uuid" },
+                    "weight": { "type": "number", "exclusiveMinimum": 0, "maximum": 1 },
+                    "rank": { "type": "integer", "minimum": 3.0, "multipleOf": 5 },
+                    "name": { "type": "string", "maxLength": 3 },
+                    "children": { "type": "array", "items": { "$ref": "#/components/schemas/Node" } },
+                },
+            },
+        });
+
+        let value = generate_value(&json!({ "$ref": "#/components/schemas/Node" }), &schemas);
+
+        assert_eq!(value["id"], "3fa85f64-5717-4562-b3fc-2c963f66afa6");
+        assert_eq!(value["weight"], 0.5);
+        assert_eq!(value["rank"], 5);
+        assert_eq!(value["name"], "str");
+        assert!(value["children"][0]["children"][0].is_object());
+    }
+}
@@ -0,0 +1,290 @@
+//! Insomnia exports (v4).
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{base_url, operations, param_string, security_scheme, ExportOperation, Security};
+use crate::{
+    example,
+    openapi::{ApiKeyLocation, OpenApi, Parameter, SecurityScheme},
+};
+
+/// The ID of the exported workspace.
+pub const WORKSPACE_ID: &str = "wrk_1";
+
+/// The ID of the exported base environment.
+pub const ENVIRONMENT_ID: &str = "env_1";
+
+/// An Insomnia export.
+///
+/// The resources are kept as JSON values, as their
+/// fields depend on their `_type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Export {
+    /// Always `export`.
+    #[serde(rename = "_type")]
+    pub kind: String,
+    /// The version of the export format, always `4`.
+    #[serde(rename = "__export_format")]
+    pub export_format: u32,
+    /// The application that created the export.
+    #[serde(rename = "__export_source")]
+    pub export_source: String,
+    /// The workspace, environment, request groups and requests.
+    pub resources: Vec<Value>,
+}
+
+pub(super) fn export(api: &OpenApi) -> Export {
+    let schemas = example::component_schemas(api);
+
+    let mut resources = vec![
+        json!({
+            "_id": WORKSPACE_ID,
+            "_type": "workspace",
+            "parentId": null,
+            "name": api.info.title,
+            "description": api.info.description.clone().unwrap_or_default(),
+            "scope": "collection",
+        }),
+        json!({
+            "_id": ENVIRONMENT_ID,
+            "_type": "environment",
+            "parentId": WORKSPACE_ID,
+            "name": "Base Environment",
+            "data": { "base_url": base_url(api) },
+        }),
+    ];
+
+    let mut groups: IndexMap<&str, String> = IndexMap::new();
+
+    for (index, operation) in operations(api).iter().enumerate() {
+        let parent_id = match operation.tag() {
+            Some(tag) => {
+                let next_id = format!("fld_{}", groups.len() + 1);
+                groups
+                    .entry(tag)
+                    .or_insert_with(|| {
+                        resources.push(json!({
+                            "_id": next_id,
+                            "_type": "request_group",
+                            "parentId": WORKSPACE_ID,
+                            "name": tag,
+                            "description": api
+                                .tags
+                                .iter()
+                                .find(|t| t.name == tag)
+                                .and_then(|t| t.description.clone())
+                                .unwrap_or_default(),
+                        }));
+                        next_id
+                    })
+                    .clone()
+            }
+            None => WORKSPACE_ID.into(),
+        };
+
+        resources.push(request(
+            api,
+            operation,
+            &schemas,
+            &format!("req_{}", index + 1),
+            &parent_id,
+        ));
+    }
+
+    Export {
+        kind: "export".into(),
+        export_format: 4,
+        export_source: "aide".into(),
+        resources,
+    }
+}
+
+fn request(
+    api: &OpenApi,
+    operation: &ExportOperation,
+    schemas: &Value,
+    id: &str,
+    parent_id: &str,
+) -> Value {
+    let mut path = operation.path.to_string();
+    let mut headers = Vec::new();
+    let mut parameters = Vec::new();
+
+    for param in &operation.parameters {
+        let data = param.parameter_data_ref();
+        let value = param_string(&example::parameter_example(data, schemas));
+
+        match param {
+            Parameter::Path { .. } => {
+                path = path
+                    .replace(&format!("{{{}}}", data.name), &value)
+                    .replace(&format!("{{{}+}}", data.name), &value);
+            }
+            Parameter::Query { .. } => parameters.push(json!({
+                "name": data.name,
+                "value": value,
+                "description": data.description.clone().unwrap_or_default(),
+                "disabled": !data.required,
+            })),
+            Parameter::Header { .. } => headers.push(json!({
+                "name": data.name,
+                "value": value,
+                "description": data.description.clone().unwrap_or_default(),
+                "disabled": !data.required,
+            })),
+            Parameter::Cookie { .. } => headers.push(json!({
+                "name": "Cookie",
+                "value": format!("{}={value}", data.name),
+                "description": data.description.clone().unwrap_or_default(),
+                "disabled": !data.required,
+            })),
+        }
+    }
+
+    let body = match operation.body(schemas) {
+        Some((content_type, text)) => {
+            headers.push(json!({ "name": "Content-Type", "value": content_type }));
+            json!({ "mimeType": content_type, "text": text })
+        }
+        None => json!({}),
+    };
+
+    let authentication = security_scheme(api, &operation.operation.security)
+        .or_else(|| security_scheme(api, &api.security))
+        .map_or_else(|| json!({}), authentication);
+
+    json!({
+        "_id": id,
+        "_type": "request",
+        "parentId": parent_id,
+        "name": operation.name(),
+        "description": operation.operation.description.clone().unwrap_or_default(),
+        "method": operation.method.to_ascii_uppercase(),
+        "url": format!("{{{{ _.base_url }}}}{path}"),
+        "headers": headers,
+        "parameters": parameters,
+        "body": body,
+        "authentication": authentication,
+    })
+}
+
+fn authentication(security: Security) -> Value {
+    let Security::Scheme(scheme) = security else {
+        return json!({});
+    };
+
+    match scheme {
+        SecurityScheme::Http { scheme, .. } if scheme.eq_ignore_ascii_case("basic") => json!({
+            "type": "basic",
+            "username": "{{ _.username }}",
+            "password": "{{ _.password }}",
+        }),
+        SecurityScheme::Http { .. } => json!({
+            "type": "bearer",
+            "token": "{{ _.bearer_token }}",
+        }),
+        SecurityScheme::ApiKey { location, name, .. } => json!({
+            "type": "apikey",
+            "key": name,
+            "value": "{{ _.api_key }}",
+            "addTo": match location {
+                ApiKeyLocation::Header => "header",
+                ApiKeyLocation::Query => "queryParams",
+                ApiKeyLocation::Cookie => "cookie",
+            },
+        }),
+        SecurityScheme::OAuth2 { .. } | SecurityScheme::OpenIdConnect { .. } => json!({
+            "type": "bearer",
+            "token": "{{ _.access_token }}",
+        }),
+        SecurityScheme::MutualTls { .. } => json!({}),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::{export, WORKSPACE_ID};
+    use crate::export::tests::tagged_api as api;
+
+    fn resources() -> Vec<Value> {
+        export(&api()).resources
+    }
+
+    fn find<'a>(resources: &'a [Value], kind: &str, name: &str) -> &'a Value {
+        resources
+            .iter()
+            .find(|resource| resource["_type"] == kind && resource["name"] == name)
+            .unwrap_or_else(|| panic!("missing {kind} `{name}`"))
+    }
+
+    #[test]
+    fn test_request_groups() {
+        let resources = resources();
+
+        let groups = resources
+            .iter()
+            .filter(|resource| resource["_type"] == "request_group")
+            .collect::<Vec<_>>();
+        assert_eq!(groups.len(), 2);
+
+        let files = find(&resources, "request_group", "files");
+        assert_eq!(files["parentId"], WORKSPACE_ID);
+        assert_eq!(files["description"], "");
+        let users = find(&resources, "request_group", "users");
+        assert_eq!(users["description"], "User accounts.");
+
+        // Operations are only in the group of their first tag.
+        for (name, group) in [
+            ("get_file", files),
+            ("put_file", files),
+            ("create_user", users),
+        ] {
+            assert_eq!(find(&resources, "request", name)["parentId"], group["_id"]);
+        }
+        assert_eq!(
+            find(&resources, "request", "status")["parentId"],
+            WORKSPACE_ID
+        );
+    }
+
+    #[test]
+    fn test_path_parameters() {
+        let resources = resources();
+        let request = find(&resources, "request", "get_file");
+
+        assert_eq!(
+            request["url"],
+            "{{ _.base_url }}/users/42/files/docs/readme.md"
+        );
+        assert_eq!(request["parameters"][0]["name"], "download");
+        assert_eq!(request["parameters"][0]["disabled"], true);
+    }
+
+    #[test]
+    fn test_bodies() {
+        let resources = resources();
+
+        let request = find(&resources, "request", "put_file");
+        assert_eq!(
+            request["body"],
+            json!({ "mimeType": "text/plain", "text": "hello" })
+        );
+        assert_eq!(
+            request["headers"],
+            json!([{ "name": "Content-Type", "value": "text/plain" }])
+        );
+
+        let body = &find(&resources, "request", "create_user")["body"];
+        assert_eq!(body["mimeType"], "application/json");
+        assert_eq!(
+            serde_json::from_str::<Value>(body["text"].as_str().unwrap()).unwrap(),
+            json!({ "name": "admin" })
+        );
+
+        assert_eq!(find(&resources, "request", "get_file")["body"], json!({}));
+    }
+}
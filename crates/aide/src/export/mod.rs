@@ -0,0 +1,347 @@
+//! Exporting the documentation as API client collections.
+//!
+//! Many teams use API clients such as Postman or Insomnia rather
+//! than the documentation itself, [`postman()`] and [`insomnia()`]
+//! convert the documentation into collections for them:
+//!
+//! - operations are grouped into folders by their first tag,
+//! - authentication is configured from the security schemes,
+//! - parameters and bodies are filled in with the documented
+//!   examples, or examples generated from the schemas.
+//!
+//! The base URL is the first documented server,
+//! and is stored in a variable of the collection.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{export, openapi::OpenApi};
+//!
+//! let api = OpenApi::default();
+//!
+//! let collection = export::postman(&api);
+//! let json = serde_json::to_string_pretty(&collection).unwrap();
+//! ```
+
+use serde_json::Value;
+
+use crate::{
+    example,
+    openapi::{
+        OpenApi, Operation, Parameter, ReferenceOr, SecurityRequirement, SecurityScheme, Server,
+    },
+};
+
+pub mod insomnia;
+pub mod postman;
+
+/// Convert the documentation into a Postman collection (v2.1).
+#[must_use]
+pub fn postman(api: &OpenApi) -> postman::Collection {
+    postman::collection(api)
+}
+
+/// Convert the documentation into an Insomnia export (v4).
+#[must_use]
+pub fn insomnia(api: &OpenApi) -> insomnia::Export {
+    insomnia::export(api)
+}
+
+/// An operation with the information shared by the exports.
+struct ExportOperation<'a> {
+    method: &'a str,
+    path: &'a str,
+    operation: &'a Operation,
+    parameters: Vec<&'a Parameter>,
+}
+
+impl<'a> ExportOperation<'a> {
+    fn name(&self) -> String {
+        self.operation
+            .summary
+            .clone()
+            .or_else(|| self.operation.operation_id.clone())
+            .unwrap_or_else(|| format!("{} {}", self.method.to_ascii_uppercase(), self.path))
+    }
+
+    fn tag(&self) -> Option<&'a str> {
+        self.operation.tags.first().map(String::as_str)
+    }
+
+    /// The JSON request body with its content type.
+    fn body(&self, schemas: &Value) -> Option<(String, String)> {
+        let body = self.operation.request_body.as_ref()?.as_item()?;
+        let (content_type, media) = body.content.first()?;
+
+        let example = example::media_example(media, schemas);
+        let text = match example {
+            Value::String(text) if !content_type.contains("json") => text,
+            example => serde_json::to_string_pretty(&example).unwrap_or_default(),
+        };
+
+        Some((content_type.clone(), text))
+    }
+}
+
+fn operations(api: &OpenApi) -> Vec<ExportOperation<'_>> {
+    let mut operations = Vec::new();
+
+    for (path, item) in api.paths.iter().flat_map(|paths| paths.iter()) {
+        let Some(item) = item.as_item() else {
+            continue;
+        };
+
+        for (method, operation) in item.iter() {
+            operations.push(ExportOperation {
+                method,
+                path,
+                operation,
+                parameters: item
+                    .parameters
+                    .iter()
+                    .chain(&operation.parameters)
+                    .filter_map(ReferenceOr::as_item)
+                    .collect(),
+            });
+        }
+    }
+
+    operations
+}
+
+/// The URL of the first server with the default values of its variables.
+fn base_url(api: &OpenApi) -> String {
+    let Some(server) = api.servers.first() else {
+        return "http://localhost".into();
+    };
+
+    server_url(server).trim_end_matches('/').into()
+}
+
+fn server_url(server: &Server) -> String {
+    server
+        .variables
+        .iter()
+        .fold(server.url.clone(), |url, (name, variable)| {
+            url.replace(&format!("{{{name}}}"), &variable.default)
+        })
+}
+
+/// The authentication of an operation or the whole API.
+#[derive(Clone, Copy)]
+enum Security<'a> {
+    /// The security requirement is empty, authentication is optional.
+    Optional,
+    Scheme(&'a SecurityScheme),
+}
+
+/// The security scheme of the first security requirement,
+/// `None` if there is no requirement.
+fn security_scheme<'a>(
+    api: &'a OpenApi,
+    security: &'a [SecurityRequirement],
+) -> Option<Security<'a>> {
+    let requirement = security.first()?;
+
+    let Some(name) = requirement.keys().next() else {
+        return Some(Security::Optional);
+    };
+
+    let scheme = api
+        .components
+        .as_ref()?
+        .security_schemes
+        .get(name)?
+        .as_item()?;
+
+    Some(Security::Scheme(scheme))
+}
+
+/// A parameter value as a string.
+fn param_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Array(items) => items.iter().map(param_string).collect::<Vec<_>>().join(","),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn api() -> OpenApi {
+        serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Todos", "version": "1" },
+            "servers": [{
+                "url": "https://{region}.example.com/api/",
+                "variables": { "region": { "default": "eu" } }
+            }],
+            "tags": [{ "name": "todos", "description": "Todo items." }],
+            "security": [{ "token": [] }],
+            "paths": {
+                "/todos/{id}": {
+                    "put": {
+                        "operationId": "update_todo",
+                        "tags": ["todos"],
+                        "parameters": [{
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "integer", "minimum": 1 }
+                        }],
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Todo" }
+                                }
+                            }
+                        },
+                        "responses": { "204": { "description": "no content" } }
+                    }
+                },
+                "/health": {
+                    "get": {
+                        "summary": "Health check",
+                        "security": [{}],
+                        "responses": { "200": { "description": "ok" } }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Todo": {
+                        "type": "object",
+                        "required": ["title"],
+                        "properties": { "title": { "type": "string", "example": "milk" } }
+                    }
+                },
+                "securitySchemes": {
+                    "token": { "type": "http", "scheme": "bearer" }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    /// Operations with path parameters, bodies and tags,
+    /// for the tests of the exports.
+    pub(super) fn tagged_api() -> OpenApi {
+        serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Files", "version": "1" },
+            "tags": [{ "name": "users", "description": "User accounts." }],
+            "paths": {
+                "/users/{user_id}/files/{path}": {
+                    "parameters": [{
+                        "name": "user_id",
+                        "in": "path",
+                        "required": true,
+                        "example": 42,
+                        "schema": { "type": "integer" }
+                    }],
+                    "get": {
+                        "operationId": "get_file",
+                        "tags": ["files", "users"],
+                        "parameters": [
+                            {
+                                "name": "path",
+                                "in": "path",
+                                "required": true,
+                                "schema": { "type": "string", "example": "docs/readme.md" }
+                            },
+                            { "name": "download", "in": "query", "schema": { "type": "boolean" } }
+                        ],
+                        "responses": {}
+                    },
+                    "put": {
+                        "operationId": "put_file",
+                        "tags": ["files"],
+                        "requestBody": {
+                            "content": { "text/plain": { "example": "hello" } }
+                        },
+                        "responses": {}
+                    }
+                },
+                "/users": {
+                    "post": {
+                        "operationId": "create_user",
+                        "tags": ["users"],
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "type": "object" },
+                                    "examples": { "admin": { "value": { "name": "admin" } } }
+                                }
+                            }
+                        },
+                        "responses": {}
+                    }
+                },
+                "/status": {
+                    "get": { "operationId": "status", "responses": {} }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_postman() {
+        let collection = serde_json::to_value(postman(&api())).unwrap();
+
+        assert_eq!(
+            collection["variable"][0]["value"],
+            "https://eu.example.com/api"
+        );
+        assert_eq!(collection["auth"]["type"], "bearer");
+
+        let folder = &collection["item"][0];
+        assert_eq!(folder["name"], "todos");
+        assert_eq!(folder["description"], "Todo items.");
+
+        let request = &folder["item"][0]["request"];
+        assert_eq!(folder["item"][0]["name"], "update_todo");
+        assert_eq!(request["method"], "PUT");
+        assert_eq!(request["url"]["raw"], "{{baseUrl}}/todos/:id");
+        assert_eq!(request["url"]["variable"][0]["value"], "1");
+        assert_eq!(
+            serde_json::from_str::<Value>(request["body"]["raw"].as_str().unwrap()).unwrap(),
+            json!({ "title": "milk" })
+        );
+
+        let health = &collection["item"][1];
+        assert_eq!(health["name"], "Health check");
+        assert_eq!(health["request"]["auth"]["type"], "noauth");
+    }
+
+    #[test]
+    fn test_insomnia() {
+        let export = insomnia(&api());
+        assert_eq!(export.export_format, 4);
+
+        let resources = &export.resources;
+        assert_eq!(
+            resources[1]["data"]["base_url"],
+            "https://eu.example.com/api"
+        );
+        let health = &resources[2];
+        assert_eq!(health["parentId"], insomnia::WORKSPACE_ID);
+        assert_eq!(health["name"], "Health check");
+
+        assert_eq!(resources[3]["_type"], "request_group");
+        assert_eq!(resources[3]["name"], "todos");
+
+        let request = &resources[4];
+        assert_eq!(request["parentId"], resources[3]["_id"]);
+        assert_eq!(request["url"], "{{ _.base_url }}/todos/1");
+        assert_eq!(request["body"]["mimeType"], "application/json");
+        assert_eq!(request["authentication"]["type"], "bearer");
+
+        assert_eq!(health["authentication"], json!({}));
+    }
+}
@@ -0,0 +1,511 @@
+//! Postman collections (v2.1).
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use super::{base_url, operations, param_string, security_scheme, ExportOperation, Security};
+use crate::{
+    example,
+    openapi::{ApiKeyLocation, OAuth2Flow, OpenApi, Parameter, SecurityScheme},
+};
+
+/// The schema of Postman collections v2.1.
+pub const SCHEMA: &str = "https://schema.getpostman.com/json/collection/v2.1.0/collection.json";
+
+/// The variable of the base URL.
+pub const BASE_URL_VARIABLE: &str = "baseUrl";
+
+/// A Postman collection.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Collection {
+    pub info: Info,
+    pub item: Vec<Item>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variable: Vec<Variable>,
+}
+
+/// The information of a collection.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Info {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub schema: String,
+}
+
+/// A folder or a request of a collection.
+#[allow(missing_docs)]
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Item {
+    Request(RequestItem),
+    Folder(Folder),
+}
+
+/// A folder of a collection.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Folder {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub item: Vec<Item>,
+}
+
+/// A request of a collection.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestItem {
+    pub name: String,
+    pub request: Request,
+    #[serde(default)]
+    pub response: Vec<Value>,
+}
+
+/// The details of a request.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub method: String,
+    #[serde(default)]
+    pub header: Vec<Header>,
+    pub url: Url,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Body>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<Auth>,
+}
+
+/// A header of a request.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Header {
+    pub key: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+/// The URL of a request.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Url {
+    pub raw: String,
+    #[serde(default)]
+    pub host: Vec<String>,
+    #[serde(default)]
+    pub path: Vec<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub query: Vec<QueryParam>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub variable: Vec<Variable>,
+}
+
+/// A query parameter of a request.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryParam {
+    pub key: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
+}
+
+/// A variable of a collection or a path parameter of a request.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Variable {
+    pub key: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// The body of a request.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Body {
+    pub mode: String,
+    pub raw: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub options: Option<Value>,
+}
+
+/// The authentication of a collection or a request.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Auth {
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The attributes by the type of the authentication.
+    #[serde(flatten)]
+    pub attributes: IndexMap<String, Vec<AuthAttribute>>,
+}
+
+/// An attribute of an authentication.
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthAttribute {
+    pub key: String,
+    pub value: String,
+    #[serde(rename = "type")]
+    pub kind: String,
+}
+
+pub(super) fn collection(api: &OpenApi) -> Collection {
+    let schemas = example::component_schemas(api);
+
+    let mut folders: IndexMap<&str, Vec<Item>> = IndexMap::new();
+    let mut items = Vec::new();
+
+    for operation in operations(api) {
+        let item = Item::Request(request_item(api, &operation, &schemas));
+        match operation.tag() {
+            Some(tag) => folders.entry(tag).or_default().push(item),
+            None => items.push(item),
+        }
+    }
+
+    let folders = folders.into_iter().map(|(name, item)| {
+        Item::Folder(Folder {
+            name: name.into(),
+            description: api
+                .tags
+                .iter()
+                .find(|tag| tag.name == name)
+                .and_then(|tag| tag.description.clone()),
+            item,
+        })
+    });
+
+    Collection {
+        info: Info {
+            name: api.info.title.clone(),
+            description: api.info.description.clone(),
+            schema: SCHEMA.into(),
+        },
+        item: folders.chain(items).collect(),
+        auth: security_scheme(api, &api.security).map(auth),
+        variable: vec![Variable {
+            key: BASE_URL_VARIABLE.into(),
+            value: base_url(api),
+            description: None,
+        }],
+    }
+}
+
+fn request_item(api: &OpenApi, operation: &ExportOperation, schemas: &Value) -> RequestItem {
+    let mut header = Vec::new();
+    let mut query = Vec::new();
+    let mut variable = Vec::new();
+
+    for param in &operation.parameters {
+        let data = param.parameter_data_ref();
+        let value = param_string(&example::parameter_example(data, schemas));
+
+        match param {
+            Parameter::Path { .. } => variable.push(Variable {
+                key: data.name.clone(),
+                value,
+                description: data.description.clone(),
+            }),
+            Parameter::Query { .. } => query.push(QueryParam {
+                key: data.name.clone(),
+                value,
+                description: data.description.clone(),
+                disabled: !data.required,
+            }),
+            Parameter::Header { .. } => header.push(Header {
+                key: data.name.clone(),
+                value,
+                description: data.description.clone(),
+                disabled: !data.required,
+            }),
+            Parameter::Cookie { .. } => header.push(Header {
+                key: "Cookie".into(),
+                value: format!("{}={value}", data.name),
+                description: data.description.clone(),
+                disabled: !data.required,
+            }),
+        }
+    }
+
+    let body = operation.body(schemas).map(|(content_type, raw)| {
+        header.push(Header {
+            key: "Content-Type".into(),
+            value: content_type.clone(),
+            description: None,
+            disabled: false,
+        });
+
+        Body {
+            mode: "raw".into(),
+            raw,
+            options: content_type
+                .contains("json")
+                .then(|| json!({ "raw": { "language": "json" } })),
+        }
+    });
+
+    // Path parameters use the `:name` notation of Postman.
+    let path = operation
+        .path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(name) => format!(":{}", name.trim_end_matches('+')),
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let host = format!("{{{{{BASE_URL_VARIABLE}}}}}");
+    let mut raw = format!("{host}/{}", path.join("/"));
+    let enabled_query = query
+        .iter()
+        .filter(|q| !q.disabled)
+        .map(|q| format!("{}={}", q.key, q.value))
+        .collect::<Vec<_>>();
+    if !enabled_query.is_empty() {
+        raw.push('?');
+        raw.push_str(&enabled_query.join("&"));
+    }
+
+    RequestItem {
+        name: operation.name(),
+        request: Request {
+            method: operation.method.to_ascii_uppercase(),
+            header,
+            url: Url {
+                raw,
+                host: vec![host],
+                path,
+                query,
+                variable,
+            },
+            body,
+            description: operation.operation.description.clone(),
+            auth: security_scheme(api, &operation.operation.security).map(auth),
+        },
+        response: Vec::new(),
+    }
+}
+
+fn auth(security: Security) -> Auth {
+    let scheme = match security {
+        Security::Optional => {
+            return Auth {
+                kind: "noauth".into(),
+                attributes: IndexMap::new(),
+            }
+        }
+        Security::Scheme(scheme) => scheme,
+    };
+
+    let (kind, attributes) = match scheme {
+        SecurityScheme::Http { scheme, .. } if scheme.eq_ignore_ascii_case("basic") => (
+            "basic",
+            vec![
+                attribute("username", "{{username}}"),
+                attribute("password", "{{password}}"),
+            ],
+        ),
+        SecurityScheme::Http { .. } => ("bearer", vec![attribute("token", "{{bearerToken}}")]),
+        SecurityScheme::ApiKey { location, name, .. } => (
+            "apikey",
+            vec![
+                attribute("key", name),
+                attribute("value", "{{apiKey}}"),
+                attribute(
+                    "in",
+                    match location {
+                        ApiKeyLocation::Query => "query",
+                        ApiKeyLocation::Header | ApiKeyLocation::Cookie => "header",
+                    },
+                ),
+            ],
+        ),
+        SecurityScheme::OAuth2 { flows, .. } => {
+            let mut attributes = vec![
+                attribute("accessToken", "{{accessToken}}"),
+                attribute("addTokenTo", "header"),
+            ];
+            let flow = [
+                &flows.authorization_code,
+                &flows.client_credentials,
+                &flows.password,
+                &flows.implicit,
+            ]
+            .into_iter()
+            .find_map(Option::as_ref);
+            attributes.extend(flow.map(oauth2_flow).into_iter().flatten());
+            ("oauth2", attributes)
+        }
+        SecurityScheme::OpenIdConnect { .. } => (
+            "oauth2",
+            vec![
+                attribute("accessToken", "{{accessToken}}"),
+                attribute("addTokenTo", "header"),
+            ],
+        ),
+        SecurityScheme::MutualTls { .. } => ("noauth", Vec::new()),
+    };
+
+    let mut auth = Auth {
+        kind: kind.into(),
+        attributes: IndexMap::new(),
+    };
+    if !attributes.is_empty() {
+        auth.attributes.insert(kind.into(), attributes);
+    }
+    auth
+}
+
+fn oauth2_flow(flow: &OAuth2Flow) -> Vec<AuthAttribute> {
+    let (grant_type, auth_url, token_url, scopes) = match flow {
+        OAuth2Flow::Implicit {
+            authorization_url,
+            scopes,
+            ..
+        } => ("implicit", Some(authorization_url), None, scopes),
+        OAuth2Flow::Password {
+            token_url, scopes, ..
+        } => ("password_credentials", None, Some(token_url), scopes),
+        OAuth2Flow::ClientCredentials {
+            token_url, scopes, ..
+        } => ("client_credentials", None, Some(token_url), scopes),
+        OAuth2Flow::AuthorizationCode {
+            authorization_url,
+            token_url,
+            scopes,
+            ..
+        } => (
+            "authorization_code",
+            Some(authorization_url),
+            Some(token_url),
+            scopes,
+        ),
+    };
+
+    let mut attributes = vec![attribute("grant_type", grant_type)];
+    attributes.extend(auth_url.map(|url| attribute("authUrl", url)));
+    attributes.extend(token_url.map(|url| attribute("accessTokenUrl", url)));
+    if !scopes.is_empty() {
+        let scopes = scopes.keys().cloned().collect::<Vec<_>>().join(" ");
+        attributes.push(attribute("scope", &scopes));
+    }
+    attributes
+}
+
+fn attribute(key: &str, value: &str) -> AuthAttribute {
+    AuthAttribute {
+        key: key.into(),
+        value: value.into(),
+        kind: "string".into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::collection;
+    use crate::export::tests::tagged_api as api;
+
+    fn find<'a>(items: &'a Value, name: &str) -> &'a Value {
+        items
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|item| item["name"] == name)
+            .unwrap_or_else(|| panic!("missing `{name}`"))
+    }
+
+    #[test]
+    fn test_folders() {
+        let collection = serde_json::to_value(collection(&api())).unwrap();
+        let items = &collection["item"];
+
+        // The folders come before the operations without tags.
+        let mut names = items
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["name"].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names.pop(), Some("status"));
+        names.sort_unstable();
+        assert_eq!(names, ["files", "users"]);
+
+        // Operations are only in the folder of their first tag.
+        let files = find(items, "files");
+        assert!(files.get("description").is_none());
+        find(&files["item"], "get_file");
+        find(&files["item"], "put_file");
+
+        let users = find(items, "users");
+        assert_eq!(users["description"], "User accounts.");
+        assert_eq!(users["item"].as_array().unwrap().len(), 1);
+        find(&users["item"], "create_user");
+
+        assert_eq!(
+            find(items, "status")["request"]["url"]["raw"],
+            "{{baseUrl}}/status"
+        );
+    }
+
+    #[test]
+    fn test_path_variables() {
+        let collection = serde_json::to_value(collection(&api())).unwrap();
+        let url = &find(&find(&collection["item"], "files")["item"], "get_file")["request"]["url"];
+
+        assert_eq!(url["raw"], "{{baseUrl}}/users/:user_id/files/:path");
+        assert_eq!(url["path"], json!(["users", ":user_id", "files", ":path"]));
+        assert_eq!(
+            url["variable"],
+            json!([
+                { "key": "user_id", "value": "42" },
+                { "key": "path", "value": "docs/readme.md" }
+            ])
+        );
+        assert_eq!(url["query"][0]["key"], "download");
+        assert_eq!(url["query"][0]["disabled"], true);
+    }
+
+    #[test]
+    fn test_bodies() {
+        let collection = serde_json::to_value(collection(&api())).unwrap();
+        let items = &collection["item"];
+
+        let request = &find(&find(items, "files")["item"], "put_file")["request"];
+        assert_eq!(request["body"], json!({ "mode": "raw", "raw": "hello" }));
+        assert_eq!(
+            request["header"],
+            json!([{ "key": "Content-Type", "value": "text/plain" }])
+        );
+
+        let request = &find(&find(items, "users")["item"], "create_user")["request"];
+        let body = &request["body"];
+        assert_eq!(body["options"]["raw"]["language"], "json");
+        assert_eq!(
+            serde_json::from_str::<Value>(body["raw"].as_str().unwrap()).unwrap(),
+            json!({ "name": "admin" })
+        );
+
+        let request = &find(&find(items, "files")["item"], "get_file")["request"];
+        assert!(request.get("body").is_none());
+    }
+}
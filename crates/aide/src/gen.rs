@@ -1,14 +1,31 @@
 //! Thread-local context for common settings for documentation generation.
 
-use std::cell::RefCell;
+use std::{any::TypeId, cell::RefCell, collections::HashMap, rc::Rc, time::Duration};
 
 use cfg_if::cfg_if;
 use schemars::{
     gen::{SchemaGenerator, SchemaSettings},
-    schema::SchemaObject,
+    schema::{Schema, SchemaObject},
 };
 
-use crate::error::Error;
+use crate::{
+    error::Error,
+    helpers::extractor::UnknownExtractors,
+    i18n::Localization,
+    include::DescriptionFiles,
+    interpolation::Interpolation,
+    openapi::{OpenApi, Operation},
+    operation::{QueryArrays, QueryObjects},
+    redaction::Redaction,
+    schema::{
+        DiscriminatorMapping, EnumDescriptions, FormatMap, NewtypeSchemas, OptionFields,
+        PostProcess, ReadWriteSchemas, SchemaDocs,
+    },
+    sdk_hints::SdkHints,
+};
+
+type SchemaHook = Rc<dyn Fn(&str, &mut Schema)>;
+type ExtractorDocs = Rc<dyn Fn(&mut GenContext, &mut Operation)>;
 
 thread_local! {
     static GEN_CTX: RefCell<GenContext> = RefCell::new(GenContext::new());
@@ -39,6 +56,32 @@ pub fn on_error(handler: impl Fn(Error) + 'static) {
     in_context(|ctx| ctx.error_handler = Some(Box::new(handler)));
 }
 
+/// Register a hook in the current thread-local context that is called
+/// with the name and the schema of every component schema when the
+/// collected schemas are merged into the documentation, before the
+/// other post-processing of the schemas.
+///
+/// The hook enables policies that apply to all schemas,
+/// e.g. marking schemas as internal:
+///
+/// ```
+/// use schemars::schema::Schema;
+///
+/// aide::gen::on_schema(|name, schema| {
+///     if let Schema::Object(schema) = schema {
+///         if name.starts_with("Internal") {
+///             schema.extensions.insert("x-internal".into(), true.into());
+///         }
+///     }
+/// });
+/// ```
+///
+/// Only one hook is allowed at a time, this
+/// function will overwrite the existing one.
+pub fn on_schema(hook: impl Fn(&str, &mut Schema) + 'static) {
+    in_context(|ctx| ctx.schema_hook = Some(Rc::new(hook)));
+}
+
 /// Collect common schemas in the thread-local context,
 /// then store them under `#/components/schemas` the next
 /// time generated content is merged into [`OpenApi`].
@@ -54,6 +97,34 @@ pub fn extract_schemas(extract: bool) {
     });
 }
 
+/// Reuse the component schemas of a document that was generated
+/// earlier, the schemas of the types that are already documented
+/// are not generated again.
+///
+/// This is useful for documenting a few routes at a time with
+/// [`ApiRouter::update_api`](crate::axum::ApiRouter::update_api),
+/// the schemas must be reused before the routes are documented.
+///
+/// It has no effect unless [`extract_schemas`] is enabled.
+pub fn reuse_schemas(api: &OpenApi) {
+    in_context(|ctx| {
+        if !ctx.extract_schemas {
+            return;
+        }
+
+        let Some(components) = &api.components else {
+            return;
+        };
+
+        let definitions = ctx.schema.definitions_mut();
+        for (name, schema) in &components.schemas {
+            definitions
+                .entry(name.clone())
+                .or_insert_with(|| schema.json_schema.clone());
+        }
+    });
+}
+
 /// Set the inferred status code of empty responses (`()`).
 ///
 /// Some frameworks might use `204` for empty responses, whereas
@@ -86,6 +157,239 @@ pub fn all_error_responses(infer: bool) {
     });
 }
 
+/// Derive a `HEAD` operation from every documented `GET`
+/// operation that does not have one.
+///
+/// The derived operation has the same parameters and response
+/// headers, but no response bodies. This matches frameworks
+/// such as `axum` that serve `HEAD` requests for `GET` routes.
+///
+/// This is disabled by default.
+pub fn derive_head_operations(derive: bool) {
+    in_context(|ctx| {
+        ctx.derive_head_operations = derive;
+    });
+}
+
+/// Derive an `OPTIONS` operation for every path that does not have
+/// one, documenting the supported methods in the `Allow` header.
+///
+/// This is disabled by default.
+pub fn derive_options_operations(derive: bool) {
+    in_context(|ctx| {
+        ctx.derive_options_operations = derive;
+    });
+}
+
+/// Document a `405 Method Not Allowed` response for every operation
+/// of a path that does not document all methods, with the supported
+/// methods in the `Allow` header.
+///
+/// This is disabled by default.
+pub fn document_method_not_allowed(document: bool) {
+    in_context(|ctx| {
+        ctx.document_method_not_allowed = document;
+    });
+}
+
+/// Fill in the missing examples of request and response bodies
+/// with values generated from their schemas, see [`example`](crate::example).
+///
+/// This is disabled by default.
+pub fn generate_examples(generate: bool) {
+    in_context(|ctx| {
+        ctx.generate_examples = generate;
+    });
+}
+
+/// Set the representation of enums with documented variants,
+/// see [`schema`](crate::schema#enum-descriptions).
+///
+/// The representation generated by `schemars` is kept by default.
+pub fn enum_descriptions(style: EnumDescriptions) {
+    in_context(|ctx| {
+        ctx.enum_descriptions = style;
+    });
+}
+
+/// Add discriminators to tagged enums, with the variants moved to
+/// component schemas named by `mapping`, see
+/// [`schema`](crate::schema#discriminators).
+///
+/// This is disabled by default.
+pub fn discriminator_mapping(mapping: DiscriminatorMapping) {
+    in_context(|ctx| {
+        ctx.discriminator_mapping = mapping;
+    });
+}
+
+/// Set how the component schemas of newtypes are documented,
+/// see [`schema`](crate::schema#newtypes).
+///
+/// The components generated by `schemars` are kept by default.
+pub fn newtype_schemas(newtypes: NewtypeSchemas) {
+    in_context(|ctx| {
+        ctx.newtype_schemas = newtypes;
+    });
+}
+
+/// Set how nullable properties, usually `Option` fields, are documented,
+/// see [`schema`](crate::schema#optional-fields).
+///
+/// They are documented as not required and nullable by default.
+pub fn option_fields(style: OptionFields) {
+    in_context(|ctx| {
+        ctx.option_fields = style;
+    });
+}
+
+/// Document object schemas without additional properties with
+/// `additionalProperties: false`, see [`schema`](crate::schema#maps-and-strict-objects).
+///
+/// This is disabled by default.
+pub fn strict_objects(strict: bool) {
+    in_context(|ctx| {
+        ctx.strict_objects = strict;
+    });
+}
+
+/// Merge component schemas that are identical except for their
+/// names, see [`schema`](crate::schema#deduplication).
+///
+/// This is disabled by default.
+pub fn deduplicate_schemas(deduplicate: bool) {
+    in_context(|ctx| {
+        ctx.deduplicate_schemas = deduplicate;
+    });
+}
+
+/// Set how the component schemas with read-only or write-only
+/// properties are documented, see
+/// [`schema`](crate::schema#read-and-write-schemas).
+///
+/// A single schema is kept by default.
+pub fn read_write_schemas(schemas: ReadWriteSchemas) {
+    in_context(|ctx| {
+        ctx.read_write_schemas = schemas;
+    });
+}
+
+/// Add descriptions to the component schemas and their properties
+/// that are not described, see [`schema`](crate::schema#schema-docs).
+pub fn schema_docs(docs: SchemaDocs) {
+    in_context(|ctx| {
+        ctx.schema_docs = docs;
+    });
+}
+
+/// Set how object-valued query parameters, e.g. nested structs
+/// or maps, are documented.
+///
+/// They are documented as a single `deepObject` parameter by default.
+pub fn query_objects(objects: QueryObjects) {
+    in_context(|ctx| {
+        ctx.query_objects = objects;
+    });
+}
+
+/// Set how array-valued query parameters, e.g. `Vec` fields,
+/// are serialized, see [`separated`](crate::helpers::separated)
+/// for single parameters.
+///
+/// They are documented as repeated parameters by default.
+pub fn query_arrays(arrays: QueryArrays) {
+    in_context(|ctx| {
+        ctx.query_arrays = arrays;
+    });
+}
+
+/// Override the `type` and `format` of the schemas of types,
+/// see [`schema`](crate::schema#formats).
+///
+/// The schemas generated by `schemars` are kept by default.
+pub fn format_map(formats: FormatMap) {
+    in_context(|ctx| {
+        ctx.format_map = formats;
+    });
+}
+
+/// Add vendor extensions with hints for client generators
+/// to the generated documentation, see [`sdk_hints`](crate::sdk_hints).
+///
+/// This is disabled by default.
+pub fn sdk_hints(hints: SdkHints) {
+    in_context(|ctx| {
+        ctx.sdk_hints = Some(hints);
+    });
+}
+
+/// Redact sensitive properties in the generated
+/// documentation, see [`redaction`](crate::redaction).
+///
+/// This is disabled by default.
+pub fn redaction(redaction: Redaction) {
+    in_context(|ctx| {
+        ctx.redaction = Some(redaction);
+    });
+}
+
+/// Load the descriptions that reference files in the generated
+/// documentation, see [`include`](crate::include).
+///
+/// This is disabled by default.
+pub fn description_files(files: DescriptionFiles) {
+    in_context(|ctx| {
+        ctx.description_files = Some(files);
+    });
+}
+
+/// Resolve the translation keys of descriptions in the generated
+/// documentation, see [`i18n`](crate::i18n).
+///
+/// This is disabled by default.
+pub fn localization(localization: Localization) {
+    in_context(|ctx| {
+        ctx.localization = Some(localization);
+    });
+}
+
+/// Replace the placeholders in descriptions of the generated
+/// documentation, see [`interpolation`](crate::interpolation).
+///
+/// This is disabled by default.
+pub fn interpolation(interpolation: Interpolation) {
+    in_context(|ctx| {
+        ctx.interpolation = Some(interpolation);
+    });
+}
+
+/// Set how [`Extractor`](crate::helpers::extractor::Extractor)s
+/// without registered documentation are handled.
+///
+/// They are reported to the error handler by default.
+pub fn unknown_extractors(policy: UnknownExtractors) {
+    in_context(|ctx| {
+        ctx.unknown_extractors = policy;
+    });
+}
+
+/// Register the documentation of the extractor `T` wrapped in an
+/// [`Extractor`](crate::helpers::extractor::Extractor).
+///
+/// Registering documentation for a type again replaces it.
+pub fn document_extractor<T: 'static>(docs: impl Fn(&mut GenContext, &mut Operation) + 'static) {
+    in_context(|ctx| {
+        ctx.extractor_docs.insert(TypeId::of::<T>(), Rc::new(docs));
+    });
+}
+
+/// Register the extractor `T` wrapped in an
+/// [`Extractor`](crate::helpers::extractor::Extractor)
+/// as intentionally undocumented.
+pub fn ignore_extractor<T: 'static>() {
+    document_extractor::<T>(|_, _| {});
+}
+
 /// Reset the state of the thread-local context.
 ///
 /// Currently clears:
@@ -100,6 +404,7 @@ pub fn reset_context() {
 
 /// A context for API document generation
 /// that provides settings and a [`SchemaGenerator`].
+#[allow(clippy::struct_excessive_bools)]
 pub struct GenContext {
     /// Schema generator that should be used
     /// for generating JSON schemas.
@@ -109,6 +414,50 @@ pub struct GenContext {
 
     pub(crate) all_error_responses: bool,
 
+    pub(crate) derive_head_operations: bool,
+
+    pub(crate) derive_options_operations: bool,
+
+    pub(crate) document_method_not_allowed: bool,
+
+    pub(crate) generate_examples: bool,
+
+    pub(crate) option_fields: OptionFields,
+
+    pub(crate) enum_descriptions: EnumDescriptions,
+
+    pub(crate) discriminator_mapping: DiscriminatorMapping,
+
+    pub(crate) newtype_schemas: NewtypeSchemas,
+
+    pub(crate) read_write_schemas: ReadWriteSchemas,
+
+    pub(crate) strict_objects: bool,
+
+    pub(crate) deduplicate_schemas: bool,
+
+    pub(crate) schema_docs: SchemaDocs,
+
+    pub(crate) format_map: FormatMap,
+
+    pub(crate) query_objects: QueryObjects,
+
+    pub(crate) query_arrays: QueryArrays,
+
+    pub(crate) sdk_hints: Option<SdkHints>,
+
+    pub(crate) redaction: Option<Redaction>,
+
+    pub(crate) description_files: Option<DescriptionFiles>,
+
+    pub(crate) localization: Option<Localization>,
+
+    pub(crate) interpolation: Option<Interpolation>,
+
+    pub(crate) unknown_extractors: UnknownExtractors,
+
+    pub(crate) extractor_docs: HashMap<TypeId, ExtractorDocs>,
+
     /// Extract schemas.
     pub(crate) extract_schemas: bool,
 
@@ -120,6 +469,7 @@ pub struct GenContext {
     /// when possible.
     pub(crate) show_error: fn(&Error) -> bool,
     error_handler: Option<Box<dyn Fn(Error)>>,
+    pub(crate) schema_hook: Option<SchemaHook>,
 }
 
 impl GenContext {
@@ -136,15 +486,53 @@ impl GenContext {
             schema: SchemaGenerator::new(SchemaSettings::draft07()),
             infer_responses: true,
             all_error_responses: false,
+            derive_head_operations: false,
+            derive_options_operations: false,
+            document_method_not_allowed: false,
+            generate_examples: false,
+            option_fields: OptionFields::default(),
+            enum_descriptions: EnumDescriptions::default(),
+            discriminator_mapping: DiscriminatorMapping::default(),
+            newtype_schemas: NewtypeSchemas::default(),
+            read_write_schemas: ReadWriteSchemas::default(),
+            strict_objects: false,
+            deduplicate_schemas: false,
+            schema_docs: SchemaDocs::default(),
+            format_map: FormatMap::default(),
+            query_objects: QueryObjects::default(),
+            query_arrays: QueryArrays::default(),
+            sdk_hints: None,
+            redaction: None,
+            description_files: None,
+            localization: None,
+            interpolation: None,
+            unknown_extractors: UnknownExtractors::default(),
+            extractor_docs: HashMap::new(),
             extract_schemas: true,
             show_error: default_error_filter,
             error_handler: None,
+            schema_hook: None,
             no_content_status,
         };
         this.set_extract_schemas(true);
         this
     }
 
+    #[cfg_attr(any(not(feature = "axum"), aide_docs_disabled), allow(dead_code))]
+    pub(crate) fn post_process(&self) -> PostProcess {
+        PostProcess {
+            formats: self.format_map.resolve(self.schema.settings()),
+            option_fields: self.option_fields,
+            enum_descriptions: self.enum_descriptions,
+            discriminator_mapping: self.discriminator_mapping,
+            newtype_schemas: self.newtype_schemas,
+            read_write_schemas: self.read_write_schemas,
+            strict_objects: self.strict_objects,
+            deduplicate_schemas: self.deduplicate_schemas,
+            schema_docs: self.schema_docs.clone(),
+        }
+    }
+
     pub(crate) fn reset_error_filter(&mut self) {
         self.show_error = default_error_filter;
     }
@@ -202,6 +590,39 @@ impl GenContext {
     }
 }
 
+/// Statistics of a documentation generation, returned by
+/// [`ApiRouter::finish_api_with_stats`](crate::axum::ApiRouter::finish_api_with_stats)
+/// to help diagnose slow generations.
+///
+/// The statistics are also emitted as a `debug` event of
+/// the `tracing` crate with the same field names.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct GenStats {
+    /// The number of documented routes.
+    pub routes: usize,
+    /// The number of documented operations of the routes,
+    /// including the derived `HEAD` and `OPTIONS` operations.
+    pub operations: usize,
+    /// The number of component schemas in the document.
+    pub schemas: usize,
+    /// The number of duplicate schemas that were merged,
+    /// see [`deduplicate_schemas`].
+    pub duplicate_schemas: usize,
+    /// The time spent on processing the paths of the routes.
+    pub paths_time: Duration,
+    /// The time spent on the transform function, including
+    /// pruning hidden operations and generating examples.
+    pub transform_time: Duration,
+    /// The time spent on collecting the component schemas.
+    pub schemas_time: Duration,
+    /// The time spent on post-processing the schemas
+    /// and the document.
+    pub post_process_time: Duration,
+    /// The total time of the generation.
+    pub total_time: Duration,
+}
+
 fn default_error_filter(_: &Error) -> bool {
     true
 }
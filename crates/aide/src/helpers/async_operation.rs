@@ -0,0 +1,255 @@
+//! Long-running operations.
+//!
+//! Operations that are processed in the background respond with
+//! `202 Accepted`, a `Location` header pointing to a status resource
+//! and a `Retry-After` header suggesting when to poll it. The status
+//! resource is documented as [`OperationStatus<T>`] where `T` is
+//! the result of the operation.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::async_operation::{accepted_with_status, polling},
+//!     transform::TransformOperation,
+//! };
+//! # #[derive(schemars::JsonSchema)]
+//! # struct Report { url: String }
+//!
+//! fn create_report_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("Start generating a report.")
+//!         .with(accepted_with_status::<Report>("get_report_status", "job_id"))
+//! }
+//!
+//! fn get_report_status_docs(op: TransformOperation) -> TransformOperation {
+//!     op.id("get_report_status")
+//!         .description("Poll the status of a report.")
+//!         .with(polling::<Report>)
+//! }
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Link, LinkOperation, Operation, ReferenceOr, StatusCode},
+    transform::TransformOperation,
+};
+
+use super::{add_response_header, add_response_if_missing, json_response, response_header};
+
+/// The status resource of a long-running operation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct OperationStatus<T = serde_json::Value, E = StatusError> {
+    /// The identifier of the operation.
+    pub id: String,
+    /// The current state of the operation.
+    pub state: OperationState,
+    /// The result of the operation once it succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<T>,
+    /// The error of the operation if it failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<E>,
+}
+
+/// The state of a long-running operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OperationState {
+    /// The operation has not started yet.
+    Pending,
+    /// The operation is in progress.
+    Running,
+    /// The operation finished successfully.
+    Succeeded,
+    /// The operation failed.
+    Failed,
+}
+
+/// The default error of a failed operation.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct StatusError {
+    /// An error message.
+    pub message: String,
+}
+
+/// Document a `202 Accepted` response with the `Location`
+/// and `Retry-After` headers and an [`OperationStatus<T>`] body.
+pub fn accepted<T: JsonSchema>(mut op: TransformOperation) -> TransformOperation {
+    in_context(|ctx| document_accepted::<T>(ctx, op.inner_mut(), None));
+    op
+}
+
+/// Same as [`accepted`], but also link the response to the
+/// operation with the ID `status_operation_id` that returns the
+/// status, passing the ID of the job as the `id_parameter` parameter.
+pub fn accepted_with_status<T: JsonSchema>(
+    status_operation_id: &str,
+    id_parameter: &str,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    let link = Link {
+        description: Some("Poll the status of the operation.".into()),
+        operation: LinkOperation::OperationId(status_operation_id.into()),
+        request_body: None,
+        parameters: [(id_parameter.into(), "$response.body#/id".into())]
+            .into_iter()
+            .collect(),
+        server: None,
+        extensions: Default::default(),
+    };
+
+    move |mut op| {
+        in_context(|ctx| document_accepted::<T>(ctx, op.inner_mut(), Some(link)));
+        op
+    }
+}
+
+/// Document the status endpoint of a long-running operation
+/// with a `200` response of [`OperationStatus<T>`] and a
+/// `Retry-After` header.
+///
+/// A `200` response that is already documented, e.g. by the
+/// output of the handler, is kept and gets the header.
+pub fn polling<T: JsonSchema>(mut op: TransformOperation) -> TransformOperation {
+    let operation = op.inner_mut();
+
+    in_context(|ctx| {
+        let res = json_response::<OperationStatus<T>>(ctx, "the status of the operation");
+        add_response_if_missing(operation, 200, res);
+
+        // The handler usually documents the response already.
+        add_response_header(
+            operation,
+            "Retry-After",
+            &response_header::<u64>(
+                ctx,
+                "The amount of seconds to wait before polling again while the operation is in progress.",
+            ),
+            |status| status == Some(&StatusCode::Code(200)),
+        );
+    });
+
+    op
+}
+
+fn document_accepted<T: JsonSchema>(
+    ctx: &mut GenContext,
+    operation: &mut Operation,
+    link: Option<Link>,
+) {
+    let mut res = json_response::<OperationStatus<T>>(ctx, "the operation was accepted");
+
+    res.headers.insert(
+        "Location".into(),
+        ReferenceOr::Item(response_header::<String>(
+            ctx,
+            "The URL of the status of the operation.",
+        )),
+    );
+    res.headers.insert(
+        "Retry-After".into(),
+        ReferenceOr::Item(response_header::<u64>(
+            ctx,
+            "The amount of seconds to wait before polling the status.",
+        )),
+    );
+
+    add_response_if_missing(operation, 202, res);
+
+    if let Some(link) = link {
+        if let Some(res) = operation
+            .responses
+            .as_mut()
+            .and_then(|responses| responses.responses.get_mut(&StatusCode::Code(202)))
+            .and_then(ReferenceOr::as_item_mut)
+        {
+            res.links.insert("status".into(), ReferenceOr::Item(link));
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use schemars::JsonSchema;
+    use serde_json::json;
+
+    use super::{accepted, accepted_with_status, polling};
+    use crate::{
+        axum::{
+            routing::{get_with, post_with},
+            ApiRouter,
+        },
+        openapi::{OpenApi, Operation},
+        transform::TransformOperation,
+    };
+
+    #[derive(JsonSchema)]
+    #[allow(dead_code)]
+    struct Report {
+        url: String,
+    }
+
+    async fn handler() {}
+
+    #[test]
+    fn test_async_operation() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/reports",
+                post_with(
+                    handler,
+                    accepted_with_status::<Report>("get_report_status", "job_id"),
+                ),
+            )
+            .api_route("/exports", post_with(handler, accepted::<Report>))
+            .api_route(
+                "/reports/:job_id",
+                get_with(handler, |op| {
+                    op.id("get_report_status").with(polling::<Report>)
+                }),
+            )
+            .finish_api(&mut api);
+
+        let api = serde_json::to_value(api).unwrap();
+        let paths = &api["paths"];
+
+        let accepted = &paths["/reports"]["post"]["responses"]["202"];
+        assert_eq!(accepted["description"], "the operation was accepted");
+        assert!(accepted["headers"]["Location"].is_object());
+        assert!(accepted["headers"]["Retry-After"].is_object());
+        let schema = accepted["content"]["application/json"]["schema"]["$ref"]
+            .as_str()
+            .unwrap();
+        let schema = &api["components"]["schemas"][schema.rsplit('/').next().unwrap()];
+        assert_eq!(schema["required"], json!(["id", "state"]));
+        assert!(schema["properties"]["result"].is_object());
+
+        let link = &accepted["links"]["status"];
+        assert_eq!(link["operationId"], "get_report_status");
+        assert_eq!(link["parameters"]["job_id"], "$response.body#/id");
+
+        let accepted = &paths["/exports"]["post"]["responses"]["202"];
+        assert!(accepted["headers"]["Location"].is_object());
+        assert!(accepted.get("links").is_none());
+
+        // The response of the handler is kept.
+        let status = &paths["/reports/{job_id}"]["get"]["responses"]["200"];
+        assert_eq!(status["description"], "no content");
+        assert!(status["headers"]["Retry-After"].is_object());
+    }
+
+    #[test]
+    fn test_polling() {
+        let mut operation = Operation::default();
+        let _ = TransformOperation::new(&mut operation).with(polling::<Report>);
+
+        let responses = serde_json::to_value(operation.responses).unwrap();
+        let status = &responses["200"];
+        assert_eq!(status["description"], "the status of the operation");
+        assert!(status["headers"]["Retry-After"].is_object());
+        assert!(status["content"]["application/json"]["schema"].is_object());
+    }
+}
@@ -0,0 +1,244 @@
+//! Batch operation documentation.
+//!
+//! Batch endpoints accept a list of items in a [`Batch<T>`] and
+//! respond with `207 Multi-Status` containing a result with its
+//! own status and error for every item, described by [`MultiStatus<T>`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::batch::batch, transform::TransformOperation};
+//! # #[derive(schemars::JsonSchema)]
+//! # struct NewUser { name: String }
+//! # #[derive(schemars::JsonSchema)]
+//! # struct User { id: u64 }
+//!
+//! fn create_users_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("Create multiple users.")
+//!         .with(batch::<NewUser, User>)
+//! }
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Operation, ReferenceOr, Response},
+    transform::TransformOperation,
+    OperationOutput,
+};
+
+use super::{add_response_if_missing, json_response, media_request_body};
+
+/// The items of a batch request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct Batch<T>(pub Vec<T>);
+
+/// The results of a batch request, one for each item
+/// in the same order as the request.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct MultiStatus<T, E = ItemError>(pub Vec<ItemResult<T, E>>);
+
+/// The result of a single item of a batch request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemResult<T, E = ItemError> {
+    /// The HTTP status code of the item.
+    pub status: u16,
+    /// The result if the item succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<T>,
+    /// The error if the item failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<E>,
+}
+
+impl<T, E> ItemResult<T, E> {
+    /// A successful result.
+    pub fn success(status: u16, data: T) -> Self {
+        Self {
+            status,
+            data: Some(data),
+            error: None,
+        }
+    }
+
+    /// A failed result.
+    pub fn failure(status: u16, error: E) -> Self {
+        Self {
+            status,
+            data: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// The default error of a failed item.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ItemError {
+    /// An error message.
+    pub message: String,
+    /// An application-specific error code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+}
+
+impl<T, E> OperationOutput for MultiStatus<T, E>
+where
+    T: JsonSchema,
+    E: JsonSchema,
+{
+    type Inner = Self;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(json_response::<Self>(ctx, "the results of the batch items"))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(207), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Document a batch operation that accepts items of type `T`
+/// and returns results of type `R` with [`ItemError`] errors.
+///
+/// The request body is only documented if the operation
+/// does not have one yet (e.g. from a `Json<Batch<T>>` extractor).
+pub fn batch<T: JsonSchema, R: JsonSchema>(op: TransformOperation) -> TransformOperation {
+    batch_with::<T, R, ItemError>(op)
+}
+
+/// Document a batch operation that accepts items of type `T`
+/// and returns results of type `R` with errors of type `E`.
+pub fn batch_with<T: JsonSchema, R: JsonSchema, E: JsonSchema>(
+    mut op: TransformOperation,
+) -> TransformOperation {
+    let operation = op.inner_mut();
+
+    in_context(|ctx| {
+        if operation.request_body.is_none() {
+            let body = media_request_body::<Batch<T>>(ctx, "application/json");
+            operation.request_body = Some(ReferenceOr::Item(body));
+        }
+
+        if let Some(res) = MultiStatus::<R, E>::operation_response(ctx, operation) {
+            add_response_if_missing(operation, 207, res);
+        }
+    });
+
+    op
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::response::{IntoResponse, Response};
+    use http::{header, HeaderValue, StatusCode};
+    use serde::Serialize;
+
+    use super::MultiStatus;
+
+    impl<T, E> IntoResponse for MultiStatus<T, E>
+    where
+        T: Serialize,
+        E: Serialize,
+    {
+        fn into_response(self) -> Response {
+            match serde_json::to_vec(&self) {
+                Ok(body) => (
+                    StatusCode::MULTI_STATUS,
+                    [(
+                        header::CONTENT_TYPE,
+                        HeaderValue::from_static("application/json"),
+                    )],
+                    body,
+                )
+                    .into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::response::IntoResponse;
+    use schemars::JsonSchema;
+    use serde::Serialize;
+
+    use super::{batch, ItemError, ItemResult, MultiStatus};
+    use crate::{
+        axum::{routing::post_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Serialize, JsonSchema)]
+    struct NewUser {
+        name: String,
+    }
+
+    #[derive(Serialize, JsonSchema)]
+    struct User {
+        id: u64,
+    }
+
+    async fn create_users() {}
+
+    #[test]
+    fn test_batch() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users/batch",
+                post_with(create_users, batch::<NewUser, User>),
+            )
+            .finish_api(&mut api);
+
+        let api = serde_json::to_value(api).unwrap();
+        let op = &api["paths"]["/users/batch"]["post"];
+        assert_eq!(
+            op["requestBody"]["content"]["application/json"]["schema"]["items"]["$ref"],
+            "#/components/schemas/NewUser"
+        );
+
+        let results = &op["responses"]["207"]["content"]["application/json"]["schema"];
+        assert_eq!(results["type"], "array");
+        assert_eq!(
+            results["items"]["$ref"],
+            "#/components/schemas/ItemResult_for_User_and_ItemError"
+        );
+
+        let result = &api["components"]["schemas"]["ItemResult_for_User_and_ItemError"];
+        assert_eq!(result["required"], serde_json::json!(["status"]));
+        assert!(result["properties"]["error"]["anyOf"][0]["$ref"]
+            .as_str()
+            .unwrap()
+            .ends_with("/ItemError"));
+    }
+
+    #[test]
+    fn test_multi_status_response() {
+        let results: MultiStatus<u64> = MultiStatus(vec![
+            ItemResult::success(201, 1),
+            ItemResult::failure(
+                409,
+                ItemError {
+                    message: "exists".into(),
+                    code: None,
+                },
+            ),
+        ]);
+
+        let res = results.into_response();
+        assert_eq!(res.status(), 207);
+        assert_eq!(res.headers()["content-type"], "application/json");
+    }
+}
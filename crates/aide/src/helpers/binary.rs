@@ -0,0 +1,119 @@
+//! The body types of the binary formats that are documented
+//! with the schema of the JSON representation of the value,
+//! e.g. [`Cbor`](super::cbor::Cbor) and [`Msgpack`](super::msgpack::Msgpack).
+
+/// Define a request and response body type of a binary format and the
+/// transform function that documents the format next to JSON bodies.
+///
+/// The `encode` function takes a reference to the value and returns the
+/// bytes, `decode` takes the bytes and returns the value, the errors of
+/// both are returned as the body of the error responses.
+macro_rules! binary_body {
+    (
+        $(#[$meta:meta])*
+        $name:ident,
+        $(#[$also_meta:meta])*
+        $also:ident,
+        media_type: $media_type:path,
+        encode: $encode:path,
+        decode: $decode:path $(,)?
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+        pub struct $name<T>(pub T);
+
+        impl<T> crate::OperationInput for $name<T>
+        where
+            T: schemars::JsonSchema,
+        {
+            fn operation_input(
+                ctx: &mut crate::gen::GenContext,
+                operation: &mut crate::openapi::Operation,
+            ) {
+                let body = crate::helpers::media_request_body::<T>(ctx, $media_type);
+                crate::operation::set_body(ctx, operation, body);
+            }
+        }
+
+        impl<T> crate::OperationOutput for $name<T>
+        where
+            T: schemars::JsonSchema,
+        {
+            type Inner = T;
+
+            fn operation_response(
+                ctx: &mut crate::gen::GenContext,
+                _operation: &mut crate::openapi::Operation,
+            ) -> Option<crate::openapi::Response> {
+                Some(crate::helpers::media_response::<T>(ctx, $media_type, ""))
+            }
+
+            fn inferred_responses(
+                ctx: &mut crate::gen::GenContext,
+                operation: &mut crate::openapi::Operation,
+            ) -> Vec<(Option<u16>, crate::openapi::Response)> {
+                if let Some(res) = Self::operation_response(ctx, operation) {
+                    Vec::from([(Some(200), res)])
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+
+        $(#[$also_meta])*
+        pub fn $also(
+            mut op: crate::transform::TransformOperation,
+        ) -> crate::transform::TransformOperation {
+            crate::helpers::add_alternative_media_type(op.inner_mut(), $media_type);
+            op
+        }
+
+        #[cfg(feature = "axum")]
+        impl<T> ::axum::response::IntoResponse for $name<T>
+        where
+            T: serde::Serialize,
+        {
+            fn into_response(self) -> ::axum::response::Response {
+                match $encode(&self.0) {
+                    Ok(body) => (
+                        [(
+                            http::header::CONTENT_TYPE,
+                            http::HeaderValue::from_static($media_type),
+                        )],
+                        body,
+                    )
+                        .into_response(),
+                    Err(err) => (http::StatusCode::INTERNAL_SERVER_ERROR, err.to_string())
+                        .into_response(),
+                }
+            }
+        }
+
+        #[cfg(feature = "axum")]
+        #[::axum::async_trait]
+        impl<T, S> ::axum::extract::FromRequest<S> for $name<T>
+        where
+            T: serde::de::DeserializeOwned,
+            S: Send + Sync,
+        {
+            type Rejection = ::axum::response::Response;
+
+            async fn from_request(
+                req: http::Request<::axum::body::Body>,
+                state: &S,
+            ) -> Result<Self, Self::Rejection> {
+                use ::axum::response::IntoResponse;
+
+                let body = ::axum::body::Bytes::from_request(req, state)
+                    .await
+                    .map_err(IntoResponse::into_response)?;
+
+                $decode(&body).map(Self).map_err(|err| {
+                    (http::StatusCode::BAD_REQUEST, err.to_string()).into_response()
+                })
+            }
+        }
+    };
+}
+
+pub(crate) use binary_body;
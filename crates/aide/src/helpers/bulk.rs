@@ -0,0 +1,368 @@
+//! CSV and newline-delimited JSON bodies for export and bulk endpoints.
+//!
+//! Neither format can be described by a JSON schema directly,
+//! so the body is documented as a string and the schema of a
+//! single row ([`Csv<T>`]) or item ([`NdJson<T>`]) is attached
+//! to the media type in a vendor extension.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::bulk::Csv, transform::TransformOperation};
+//! # #[derive(schemars::JsonSchema)]
+//! # struct Order { id: u64 }
+//!
+//! fn export_orders_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("Export all orders.")
+//!         .response::<200, Csv<Order>>()
+//! }
+//! ```
+
+use std::marker::PhantomData;
+
+use indexmap::IndexMap;
+use schemars::{
+    schema::{InstanceType, SchemaObject as JsonSchemaObject},
+    JsonSchema,
+};
+
+use crate::{
+    gen::GenContext,
+    openapi::{MediaType, Operation, RequestBody, Response, SchemaObject},
+    operation::set_body,
+    OperationInput, OperationOutput,
+};
+
+/// The CSV media type.
+pub const CSV_MEDIA_TYPE: &str = "text/csv";
+
+/// The newline-delimited JSON media type.
+pub const NDJSON_MEDIA_TYPE: &str = "application/x-ndjson";
+
+/// The media type extension that holds the schema of a CSV row.
+pub const ROW_SCHEMA_EXTENSION: &str = "x-row-schema";
+
+/// The media type extension that holds the schema of an NDJSON item.
+pub const ITEM_SCHEMA_EXTENSION: &str = "x-item-schema";
+
+/// A CSV body where each row is described by `T`.
+///
+/// The body is kept as already encoded text, use
+/// a CSV library of choice to produce or parse it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Csv<T> {
+    /// The encoded CSV content.
+    pub body: String,
+    _row: PhantomData<fn() -> T>,
+}
+
+impl<T> Csv<T> {
+    /// Create a CSV body from already encoded content.
+    pub fn new(body: impl Into<String>) -> Self {
+        Self {
+            body: body.into(),
+            _row: PhantomData,
+        }
+    }
+
+    /// Unwraps [Self] into the encoded content.
+    #[must_use]
+    pub fn into_inner(self) -> String {
+        self.body
+    }
+}
+
+/// A newline-delimited JSON body with items of type `T`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NdJson<T>(pub Vec<T>);
+
+impl<T> NdJson<T> {
+    /// Unwraps [Self] into the items.
+    #[must_use]
+    pub fn into_inner(self) -> Vec<T> {
+        self.0
+    }
+}
+
+impl<T> From<Vec<T>> for NdJson<T> {
+    fn from(items: Vec<T>) -> Self {
+        Self(items)
+    }
+}
+
+impl<T> OperationInput for Csv<T>
+where
+    T: JsonSchema,
+{
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        let body = text_request_body::<T>(ctx, CSV_MEDIA_TYPE, ROW_SCHEMA_EXTENSION);
+        set_body(ctx, operation, body);
+    }
+}
+
+impl<T> OperationOutput for Csv<T>
+where
+    T: JsonSchema,
+{
+    type Inner = T;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(text_response::<T>(
+            ctx,
+            CSV_MEDIA_TYPE,
+            ROW_SCHEMA_EXTENSION,
+            "CSV content",
+        ))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+impl<T> OperationInput for NdJson<T>
+where
+    T: JsonSchema,
+{
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        let body = text_request_body::<T>(ctx, NDJSON_MEDIA_TYPE, ITEM_SCHEMA_EXTENSION);
+        set_body(ctx, operation, body);
+    }
+}
+
+impl<T> OperationOutput for NdJson<T>
+where
+    T: JsonSchema,
+{
+    type Inner = T;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(text_response::<T>(
+            ctx,
+            NDJSON_MEDIA_TYPE,
+            ITEM_SCHEMA_EXTENSION,
+            "newline-delimited JSON content",
+        ))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+fn text_response<T: JsonSchema>(
+    ctx: &mut GenContext,
+    media_type: &str,
+    extension: &str,
+    description: &str,
+) -> Response {
+    Response {
+        description: description.into(),
+        content: IndexMap::from_iter([(media_type.into(), text_media_type::<T>(ctx, extension))]),
+        ..Default::default()
+    }
+}
+
+fn text_request_body<T: JsonSchema>(
+    ctx: &mut GenContext,
+    media_type: &str,
+    extension: &str,
+) -> RequestBody {
+    RequestBody {
+        description: None,
+        content: IndexMap::from_iter([(media_type.into(), text_media_type::<T>(ctx, extension))]),
+        required: true,
+        extensions: IndexMap::default(),
+    }
+}
+
+fn text_media_type<T: JsonSchema>(ctx: &mut GenContext, extension: &str) -> MediaType {
+    let item_schema = ctx.schema.subschema_for::<T>();
+
+    MediaType {
+        schema: Some(SchemaObject {
+            json_schema: JsonSchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                ..Default::default()
+            }
+            .into(),
+            example: None,
+            external_docs: None,
+        }),
+        extensions: IndexMap::from_iter([(
+            extension.into(),
+            serde_json::to_value(item_schema).unwrap_or_default(),
+        )]),
+        ..Default::default()
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::{
+        async_trait,
+        body::Body,
+        extract::{rejection::StringRejection, FromRequest},
+        response::{IntoResponse, Response},
+    };
+    use http::{header, HeaderValue, Request, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::{Csv, NdJson, CSV_MEDIA_TYPE, NDJSON_MEDIA_TYPE};
+
+    impl<T> IntoResponse for Csv<T> {
+        fn into_response(self) -> Response {
+            (
+                [(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static(CSV_MEDIA_TYPE),
+                )],
+                self.body,
+            )
+                .into_response()
+        }
+    }
+
+    impl<T> IntoResponse for NdJson<T>
+    where
+        T: Serialize,
+    {
+        fn into_response(self) -> Response {
+            let mut body = Vec::new();
+
+            for item in &self.0 {
+                if let Err(err) = serde_json::to_writer(&mut body, item) {
+                    return (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response();
+                }
+                body.push(b'\n');
+            }
+
+            (
+                [(
+                    header::CONTENT_TYPE,
+                    HeaderValue::from_static(NDJSON_MEDIA_TYPE),
+                )],
+                body,
+            )
+                .into_response()
+        }
+    }
+
+    #[async_trait]
+    impl<T, S> FromRequest<S> for Csv<T>
+    where
+        S: Send + Sync,
+    {
+        type Rejection = StringRejection;
+
+        async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+            Ok(Self::new(String::from_request(req, state).await?))
+        }
+    }
+
+    #[async_trait]
+    impl<T, S> FromRequest<S> for NdJson<T>
+    where
+        T: DeserializeOwned,
+        S: Send + Sync,
+    {
+        type Rejection = Response;
+
+        async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+            let body = String::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+
+            body.lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(serde_json::from_str)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Self)
+                .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()).into_response())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{body::Body, extract::FromRequest, response::IntoResponse};
+    use http::Request;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use super::{
+        Csv, NdJson, CSV_MEDIA_TYPE, ITEM_SCHEMA_EXTENSION, NDJSON_MEDIA_TYPE, ROW_SCHEMA_EXTENSION,
+    };
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+    struct Order {
+        id: u64,
+    }
+
+    async fn export_orders() -> Csv<Order> {
+        Csv::new("id\n1\n")
+    }
+
+    async fn import_orders(_orders: NdJson<Order>) {}
+
+    #[test]
+    fn test_bulk() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/orders", get(export_orders).post(import_orders))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let csv = &paths["/orders"]["get"]["responses"]["200"]["content"][CSV_MEDIA_TYPE];
+        assert_eq!(csv["schema"]["type"], "string");
+        assert_eq!(
+            csv[ROW_SCHEMA_EXTENSION]["$ref"],
+            "#/components/schemas/Order"
+        );
+
+        let ndjson = &paths["/orders"]["post"]["requestBody"]["content"][NDJSON_MEDIA_TYPE];
+        assert_eq!(ndjson["schema"]["type"], "string");
+        assert_eq!(
+            ndjson[ITEM_SCHEMA_EXTENSION]["$ref"],
+            "#/components/schemas/Order"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_ndjson_body() {
+        let res = NdJson(vec![Order { id: 1 }, Order { id: 2 }]).into_response();
+        assert_eq!(res.headers()["content-type"], NDJSON_MEDIA_TYPE);
+
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"{\"id\":1}\n{\"id\":2}\n");
+
+        let req = Request::new(Body::from(body));
+        let orders = NdJson::<Order>::from_request(req, &()).await.unwrap();
+        assert_eq!(orders.into_inner(), [Order { id: 1 }, Order { id: 2 }]);
+
+        let req = Request::new(Body::from("{\"id\":1}\nnot json\n"));
+        let rejection = NdJson::<Order>::from_request(req, &()).await.unwrap_err();
+        assert_eq!(rejection.status(), 400);
+    }
+}
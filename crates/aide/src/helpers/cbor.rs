@@ -0,0 +1,153 @@
+//! CBOR bodies.
+//!
+//! [`Cbor<T>`] documents `application/cbor` request and
+//! response bodies with the same schema as the JSON representation
+//! of `T`. Routes that accept or return both representations can
+//! document the CBOR alternative next to the existing JSON
+//! bodies with [`also_cbor`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::cbor::Cbor, transform::TransformOperation};
+//! # #[derive(schemars::JsonSchema)]
+//! # struct User { name: String }
+//!
+//! fn get_user_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, Cbor<User>>()
+//! }
+//! ```
+//!
+//! For handlers that negotiate between JSON and CBOR:
+//!
+//! ```ignore
+//! fn get_user_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, Json<User>>().with(also_cbor)
+//! }
+//! ```
+
+use super::binary::binary_body;
+
+/// The CBOR media type.
+pub const MEDIA_TYPE: &str = "application/cbor";
+
+binary_body! {
+    /// A CBOR request or response body of `T`.
+    Cbor,
+    /// Document `application/cbor` next to every
+    /// `application/json` body of an operation.
+    also_cbor,
+    media_type: MEDIA_TYPE,
+    encode: encode,
+    decode: decode,
+}
+
+#[cfg(feature = "axum")]
+fn encode<T: serde::Serialize>(value: &T) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+    let mut body = Vec::new();
+    ciborium::into_writer(value, &mut body)?;
+    Ok(body)
+}
+
+#[cfg(feature = "axum")]
+fn decode<T: serde::de::DeserializeOwned>(
+    body: &[u8],
+) -> Result<T, ciborium::de::Error<std::io::Error>> {
+    ciborium::from_reader(body)
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header::CONTENT_TYPE, Request, StatusCode},
+        routing::post,
+        Json,
+    };
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+    use tower_service::Service;
+
+    use super::{also_cbor, Cbor, MEDIA_TYPE};
+    use crate::{
+        axum::{routing::post_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+    struct User {
+        name: String,
+    }
+
+    async fn echo_user(Cbor(user): Cbor<User>) -> Cbor<User> {
+        Cbor(user)
+    }
+
+    async fn create_user(Json(user): Json<User>) -> Json<User> {
+        Json(user)
+    }
+
+    fn media_types(content: &Value) -> Vec<&String> {
+        content.as_object().unwrap().keys().collect()
+    }
+
+    #[test]
+    fn test_cbor_docs() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/users/echo", post_with(echo_user, |op| op))
+            .api_route("/users", post_with(create_user, |op| op.with(also_cbor)))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let echo = &paths["/users/echo"]["post"];
+        assert_eq!(media_types(&echo["requestBody"]["content"]), [MEDIA_TYPE]);
+        assert_eq!(
+            media_types(&echo["responses"]["200"]["content"]),
+            [MEDIA_TYPE]
+        );
+
+        // The alternative has the same schema as the JSON body.
+        let create = &paths["/users"]["post"];
+        for content in [
+            &create["requestBody"]["content"],
+            &create["responses"]["200"]["content"],
+        ] {
+            assert_eq!(media_types(content), [MEDIA_TYPE, "application/json"]);
+            assert_eq!(
+                content[MEDIA_TYPE]["schema"],
+                content["application/json"]["schema"]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cbor_round_trip() {
+        let mut app = axum::Router::new().route("/users", post(echo_user));
+
+        let user = User {
+            name: "Ferris".into(),
+        };
+        let res = app
+            .call(
+                Request::post("/users")
+                    .header(CONTENT_TYPE, MEDIA_TYPE)
+                    .body(Body::from(super::encode(&user).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[CONTENT_TYPE], MEDIA_TYPE);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(super::decode::<User>(&body).unwrap(), user);
+
+        let res = app
+            .call(Request::post("/users").body(Body::from("{}")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,576 @@
+//! Code samples of operations.
+//!
+//! Code samples are documented in the [`CODE_SAMPLES`] extension of
+//! operations, which is rendered by Redoc and Scalar. Samples can be
+//! added by hand with
+//! [`TransformOperation::code_sample`](crate::transform::TransformOperation::code_sample),
+//! or generated as [`CURL`] and [`HTTPIE`] commands for all operations with
+//! [`TransformOpenApi::generate_request_samples`](crate::transform::TransformOpenApi::generate_request_samples).
+//!
+//! Generated commands use the first documented server, the documented
+//! examples of required parameters and request bodies (or examples
+//! generated from their schemas) and placeholders for credentials.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::code_samples::{CURL, HTTPIE},
+//!     transform::{TransformOpenApi, TransformOperation},
+//! };
+//!
+//! fn list_todos_docs(op: TransformOperation) -> TransformOperation {
+//!     op.code_sample("JavaScript", "const todos = await client.todos.list();")
+//! }
+//!
+//! fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+//!     api.title("Todos").generate_request_samples(&[CURL, HTTPIE])
+//! }
+//! ```
+
+use indexmap::IndexMap;
+use serde_json::{json, Value};
+
+use crate::{
+    error::Error,
+    example::{component_schemas, media_example, parameter_example},
+    gen::in_context,
+    openapi::{
+        ApiKeyLocation, OpenApi, Operation, Parameter, ReferenceOr, SecurityRequirement,
+        SecurityScheme,
+    },
+    util::iter_operations_mut,
+};
+
+/// The extension of operations with their code samples.
+pub const CODE_SAMPLES: &str = "x-codeSamples";
+
+/// The language of generated `curl` samples.
+pub const CURL: &str = "curl";
+
+/// The language of generated `HTTPie` samples.
+pub const HTTPIE: &str = "httpie";
+
+/// Add a code sample to the operation.
+pub(crate) fn add_code_sample(operation: &mut Operation, lang: &str, source: &str) {
+    let samples = operation
+        .extensions
+        .entry(CODE_SAMPLES.into())
+        .or_insert_with(|| Value::Array(Vec::new()));
+
+    if let Some(samples) = samples.as_array_mut() {
+        samples.push(json!({ "lang": lang, "source": source }));
+    }
+}
+
+fn has_code_sample(operation: &Operation, lang: &str) -> bool {
+    operation
+        .extensions
+        .get(CODE_SAMPLES)
+        .and_then(Value::as_array)
+        .is_some_and(|samples| samples.iter().any(|s| s["lang"] == lang))
+}
+
+/// Add generated samples in the given languages, e.g. [`CURL`] and
+/// [`HTTPIE`], to all operations without a sample in the language.
+pub(crate) fn add_request_samples(api: &mut OpenApi, langs: &[&str]) {
+    let langs = langs
+        .iter()
+        .copied()
+        .filter(|lang| {
+            let supported = [CURL, HTTPIE].contains(lang);
+            if !supported {
+                in_context(|ctx| ctx.error(Error::UnsupportedSampleLanguage((*lang).into())));
+            }
+            supported
+        })
+        .collect::<Vec<_>>();
+    if langs.is_empty() {
+        return;
+    }
+
+    let base_url = api.servers.first().map_or_else(
+        || "http://localhost".into(),
+        |server| {
+            server
+                .variables
+                .iter()
+                .fold(server.url.clone(), |url, (name, variable)| {
+                    url.replace(&format!("{{{name}}}"), &variable.default)
+                })
+                .trim_end_matches('/')
+                .to_string()
+        },
+    );
+
+    // The schemas are not yet extracted into the components.
+    let mut schemas =
+        in_context(|ctx| serde_json::to_value(ctx.schema.definitions()).unwrap_or_default());
+    if let (Value::Object(schemas), Value::Object(components)) =
+        (&mut schemas, component_schemas(api))
+    {
+        schemas.extend(components);
+    }
+
+    let security_schemes = api
+        .components
+        .as_ref()
+        .map(|c| c.security_schemes.clone())
+        .unwrap_or_default();
+    let global_security = api.security.clone();
+
+    let Some(paths) = &mut api.paths else {
+        return;
+    };
+
+    for (path, item) in &mut paths.paths {
+        let ReferenceOr::Item(item) = item else {
+            continue;
+        };
+        let path_parameters = item.parameters.clone();
+
+        for (method, operation) in iter_operations_mut(item) {
+            let parameters = path_parameters
+                .iter()
+                .chain(&operation.parameters)
+                .filter_map(ReferenceOr::as_item)
+                .collect::<Vec<_>>();
+            let security = if operation.security.is_empty() {
+                &global_security
+            } else {
+                &operation.security
+            };
+
+            let request = SampleRequest::new(
+                method,
+                format!("{base_url}{path}"),
+                &parameters,
+                security_scheme(&security_schemes, security),
+                operation,
+                &schemas,
+            );
+
+            for lang in &langs {
+                if has_code_sample(operation, lang) {
+                    continue;
+                }
+                let sample = match *lang {
+                    CURL => request.curl(),
+                    _ => request.httpie(),
+                };
+                add_code_sample(operation, lang, &sample);
+            }
+        }
+    }
+}
+
+/// The security scheme of the first security requirement.
+fn security_scheme<'a>(
+    schemes: &'a IndexMap<String, ReferenceOr<SecurityScheme>>,
+    security: &[SecurityRequirement],
+) -> Option<&'a SecurityScheme> {
+    let name = security.first()?.keys().next()?;
+    schemes.get(name)?.as_item()
+}
+
+/// A shell argument, `Expand` arguments refer to environment
+/// variables for credentials, e.g. `$TOKEN`.
+enum Arg {
+    Literal(String),
+    Expand(String),
+}
+
+impl Arg {
+    fn value(&self) -> &str {
+        match self {
+            Self::Literal(arg) | Self::Expand(arg) => arg,
+        }
+    }
+
+    fn quoted(&self) -> String {
+        match self {
+            Self::Literal(arg) => format!("'{}'", arg.replace('\'', r"'\''")),
+            Self::Expand(arg) => format!("\"{arg}\""),
+        }
+    }
+}
+
+/// An example request of an operation, rendered as a command.
+struct SampleRequest {
+    method: String,
+    url: String,
+    query: Vec<(String, Arg)>,
+    headers: Vec<(String, Arg)>,
+    basic_auth: bool,
+    body: Option<(String, String)>,
+}
+
+impl SampleRequest {
+    fn new(
+        method: &str,
+        mut url: String,
+        parameters: &[&Parameter],
+        security: Option<&SecurityScheme>,
+        operation: &Operation,
+        schemas: &Value,
+    ) -> Self {
+        let mut query = Vec::new();
+        let mut headers = Vec::new();
+        let mut basic_auth = false;
+
+        for param in parameters {
+            let data = param.parameter_data_ref();
+            let value = param_string(&parameter_example(data, schemas));
+
+            match param {
+                Parameter::Path { .. } => {
+                    url = url.replace(&format!("{{{}}}", data.name), &value);
+                }
+                Parameter::Query { .. } if data.required => {
+                    query.push((data.name.clone(), Arg::Literal(value)));
+                }
+                Parameter::Header { .. } if data.required => {
+                    headers.push((data.name.clone(), Arg::Literal(value)));
+                }
+                _ => {}
+            }
+        }
+
+        match security {
+            Some(SecurityScheme::Http { scheme, .. }) if scheme.eq_ignore_ascii_case("basic") => {
+                basic_auth = true;
+            }
+            Some(
+                SecurityScheme::Http { .. }
+                | SecurityScheme::OAuth2 { .. }
+                | SecurityScheme::OpenIdConnect { .. },
+            ) => {
+                headers.push(("Authorization".into(), Arg::Expand("Bearer $TOKEN".into())));
+            }
+            Some(SecurityScheme::ApiKey {
+                location: ApiKeyLocation::Header,
+                name,
+                ..
+            }) => {
+                headers.push((name.clone(), Arg::Expand("$API_KEY".into())));
+            }
+            Some(SecurityScheme::ApiKey {
+                location: ApiKeyLocation::Query,
+                name,
+                ..
+            }) => {
+                query.push((name.clone(), Arg::Expand("$API_KEY".into())));
+            }
+            _ => {}
+        }
+
+        let body = operation
+            .request_body
+            .as_ref()
+            .and_then(ReferenceOr::as_item)
+            .and_then(|body| body.content.first())
+            .map(|(content_type, media)| {
+                let body = match media_example(media, schemas) {
+                    Value::String(text) if !content_type.contains("json") => text,
+                    example => example.to_string(),
+                };
+                (content_type.clone(), body)
+            });
+
+        Self {
+            method: method.to_ascii_uppercase(),
+            url,
+            query,
+            headers,
+            basic_auth,
+            body,
+        }
+    }
+
+    fn curl(&self) -> String {
+        let mut url = self.url.clone();
+        if !self.query.is_empty() {
+            let query = self
+                .query
+                .iter()
+                .map(|(name, value)| format!("{name}={}", value.value()))
+                .collect::<Vec<_>>();
+            url = format!("{url}?{}", query.join("&"));
+        }
+        let url = if self.query.iter().any(|(_, v)| matches!(v, Arg::Expand(_))) {
+            Arg::Expand(url)
+        } else {
+            Arg::Literal(url)
+        };
+
+        let mut args = Vec::new();
+        if self.basic_auth {
+            args.push(format!(
+                "-u {}",
+                Arg::Expand("$USERNAME:$PASSWORD".into()).quoted()
+            ));
+        }
+        for (name, value) in &self.headers {
+            args.push(format!("-H {}", header_arg(name, value, ": ").quoted()));
+        }
+        if let Some((content_type, body)) = &self.body {
+            let content_type = Arg::Literal(format!("Content-Type: {content_type}"));
+            args.push(format!("-H {}", content_type.quoted()));
+            args.push(format!("-d {}", Arg::Literal(body.clone()).quoted()));
+        }
+
+        command(format!("curl -X {} {}", self.method, url.quoted()), args)
+    }
+
+    fn httpie(&self) -> String {
+        let mut args = Vec::new();
+        if self.basic_auth {
+            args.push(format!(
+                "-a {}",
+                Arg::Expand("$USERNAME:$PASSWORD".into()).quoted()
+            ));
+        }
+        for (name, value) in &self.query {
+            args.push(header_arg(name, value, "==").quoted());
+        }
+        for (name, value) in &self.headers {
+            args.push(header_arg(name, value, ":").quoted());
+        }
+        if let Some((content_type, body)) = &self.body {
+            args.push(Arg::Literal(format!("Content-Type:{content_type}")).quoted());
+            args.push(format!("--raw {}", Arg::Literal(body.clone()).quoted()));
+        }
+
+        command(
+            format!(
+                "http {} {}",
+                self.method,
+                Arg::Literal(self.url.clone()).quoted()
+            ),
+            args,
+        )
+    }
+}
+
+/// A `name<separator>value` argument.
+fn header_arg(name: &str, value: &Arg, separator: &str) -> Arg {
+    let arg = format!("{name}{separator}{}", value.value());
+    match value {
+        Arg::Literal(_) => Arg::Literal(arg),
+        Arg::Expand(_) => Arg::Expand(arg),
+    }
+}
+
+/// A command with one argument per line.
+fn command(mut command: String, args: Vec<String>) -> String {
+    for arg in args {
+        command.push_str(" \\\n  ");
+        command.push_str(&arg);
+    }
+    command
+}
+
+/// A parameter value as a string.
+fn param_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        Value::Array(items) => items.iter().map(param_string).collect::<Vec<_>>().join(","),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use axum::{extract::Path, Json};
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    use super::{add_request_samples, Arg, CODE_SAMPLES, CURL, HTTPIE};
+    use crate::{
+        axum::{
+            routing::{get_with, put},
+            ApiRouter,
+        },
+        gen,
+        openapi::{OpenApi, SecurityScheme, Server},
+    };
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, JsonSchema)]
+    struct Todo {
+        title: String,
+    }
+
+    async fn update_todo(_id: Path<u64>, _todo: Json<Todo>) {}
+
+    async fn list_todos() {}
+
+    #[test]
+    fn test_code_samples() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/todos/{id}", put(update_todo))
+            .api_route(
+                "/todos",
+                get_with(list_todos, |op| {
+                    op.code_sample("JavaScript", "client.todos.list()")
+                        .code_sample(CURL, "curl https://api.example.com/todos")
+                }),
+            )
+            .finish_api_with(&mut api, |api| {
+                api.server(Server {
+                    url: "https://api.example.com/".into(),
+                    ..Default::default()
+                })
+                .security_scheme(
+                    "token",
+                    SecurityScheme::Http {
+                        scheme: "bearer".into(),
+                        bearer_format: None,
+                        description: None,
+                        extensions: Default::default(),
+                    },
+                )
+                .security_requirement("token")
+                .generate_request_samples(&[CURL, HTTPIE])
+            });
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let samples = paths["/todos"]["get"][CODE_SAMPLES].as_array().unwrap();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0]["lang"], "JavaScript");
+        assert_eq!(samples[1]["source"], "curl https://api.example.com/todos");
+        assert_eq!(
+            samples[2]["source"],
+            "http GET 'https://api.example.com/todos' \\\n  \
+             \"Authorization:Bearer $TOKEN\""
+        );
+
+        let samples = &paths["/todos/{id}"]["put"][CODE_SAMPLES];
+        assert_eq!(samples[0]["lang"], CURL);
+        assert_eq!(
+            samples[0]["source"],
+            "curl -X PUT 'https://api.example.com/todos/0' \\\n  \
+             -H \"Authorization: Bearer $TOKEN\" \\\n  \
+             -H 'Content-Type: application/json' \\\n  \
+             -d '{\"title\":\"string\"}'"
+        );
+        assert_eq!(samples[1]["lang"], HTTPIE);
+        assert_eq!(
+            samples[1]["source"],
+            "http PUT 'https://api.example.com/todos/0' \\\n  \
+             \"Authorization:Bearer $TOKEN\" \\\n  \
+             'Content-Type:application/json' \\\n  \
+             --raw '{\"title\":\"string\"}'"
+        );
+    }
+
+    fn samples(api: serde_json::Value, langs: &[&str]) -> (serde_json::Value, Vec<String>) {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        gen::on_error({
+            let errors = errors.clone();
+            move |e| errors.borrow_mut().push(e.to_string())
+        });
+
+        let mut api: OpenApi = serde_json::from_value(api).unwrap();
+        add_request_samples(&mut api, langs);
+
+        let errors = errors.borrow().clone();
+        (serde_json::to_value(api.paths.unwrap()).unwrap(), errors)
+    }
+
+    #[test]
+    fn test_quoted() {
+        assert_eq!(Arg::Literal("it's".into()).quoted(), r"'it'\''s'");
+        assert_eq!(
+            Arg::Expand("Bearer $TOKEN".into()).quoted(),
+            "\"Bearer $TOKEN\""
+        );
+    }
+
+    #[test]
+    fn test_httpie_samples() {
+        let (paths, errors) = samples(
+            json!({
+                "openapi": "3.1.0",
+                "info": { "title": "Todos", "version": "1" },
+                "components": {
+                    "securitySchemes": {
+                        "basic": { "type": "http", "scheme": "basic" },
+                        "key": { "type": "apiKey", "in": "query", "name": "key" }
+                    }
+                },
+                "paths": {
+                    "/todos": {
+                        "get": {
+                            "parameters": [
+                                { "name": "q", "in": "query", "required": true, "schema": {}, "example": "o'clock" },
+                                { "name": "X-Trace", "in": "header", "required": true, "schema": {}, "example": "1" },
+                                { "name": "page", "in": "query", "schema": {}, "example": 2 }
+                            ],
+                            "security": [{ "key": [] }],
+                            "responses": {}
+                        },
+                        "post": {
+                            "requestBody": {
+                                "content": {
+                                    "text/plain": { "schema": {}, "example": "don't" }
+                                }
+                            },
+                            "security": [{ "basic": [] }],
+                            "responses": {}
+                        }
+                    }
+                }
+            }),
+            &[HTTPIE],
+        );
+        assert!(errors.is_empty(), "{errors:?}");
+
+        let get = &paths["/todos"]["get"][CODE_SAMPLES];
+        assert_eq!(get.as_array().unwrap().len(), 1);
+        assert_eq!(get[0]["lang"], HTTPIE);
+        assert_eq!(
+            get[0]["source"],
+            "http GET 'http://localhost/todos' \\\n  \
+             'q==o'\\''clock' \\\n  \
+             \"key==$API_KEY\" \\\n  \
+             'X-Trace:1'"
+        );
+
+        assert_eq!(
+            paths["/todos"]["post"][CODE_SAMPLES][0]["source"],
+            "http POST 'http://localhost/todos' \\\n  \
+             -a \"$USERNAME:$PASSWORD\" \\\n  \
+             'Content-Type:text/plain' \\\n  \
+             --raw 'don'\\''t'"
+        );
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        let (paths, errors) = samples(
+            json!({
+                "openapi": "3.1.0",
+                "info": { "title": "Todos", "version": "1" },
+                "paths": {
+                    "/todos": { "get": { "responses": {} } }
+                }
+            }),
+            &["python", CURL],
+        );
+
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("python"), "{errors:?}");
+
+        let get = &paths["/todos"]["get"][CODE_SAMPLES];
+        assert_eq!(get.as_array().unwrap().len(), 1);
+        assert_eq!(get[0]["lang"], CURL);
+        assert_eq!(get[0]["source"], "curl -X GET 'http://localhost/todos'");
+    }
+}
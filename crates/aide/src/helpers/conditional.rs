@@ -0,0 +1,182 @@
+//! Transform functions for conditional requests (RFC 9110).
+//!
+//! - [`conditional_get`] documents cache validation with
+//!   `ETag`/`Last-Modified` response headers, the
+//!   `If-None-Match`/`If-Modified-Since` request headers and
+//!   the `304 Not Modified` response.
+//! - [`conditional_update`] documents optimistic concurrency with
+//!   the `If-Match`/`If-Unmodified-Since` request headers and
+//!   the `412 Precondition Failed` response.
+//!
+//! Response headers are only added to successful responses that
+//! already exist, so these functions should be applied after the
+//! responses are documented (or inferred).
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::conditional::conditional_update, transform::TransformOperation};
+//!
+//! fn update_item_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("Update an item.")
+//!         .response::<200, ()>()
+//!         .with(conditional_update)
+//! }
+//! ```
+
+use crate::{
+    gen::in_context,
+    openapi::{Operation, Response},
+    transform::TransformOperation,
+};
+
+use super::{
+    add_missing_parameters, add_response_if_missing, add_success_response_header, header_parameter,
+    response_header,
+};
+
+/// Document a conditional `GET` with `ETag` and `Last-Modified` validators.
+pub fn conditional_get(mut op: TransformOperation) -> TransformOperation {
+    let operation = op.inner_mut();
+
+    in_context(|ctx| {
+        document_validators(ctx, operation);
+
+        let params = [
+            header_parameter::<String>(
+                ctx,
+                "If-None-Match",
+                "Only return the resource if its entity tag does not match any of the given ones.",
+                false,
+            ),
+            header_parameter::<String>(
+                ctx,
+                "If-Modified-Since",
+                "Only return the resource if it was modified after the given HTTP date.",
+                false,
+            ),
+        ];
+        add_missing_parameters(ctx, operation, params);
+    });
+
+    add_response_if_missing(
+        operation,
+        304,
+        Response {
+            description: "the resource was not modified".into(),
+            ..Default::default()
+        },
+    );
+
+    op
+}
+
+/// Document a conditional update with `If-Match` preconditions
+/// for optimistic concurrency control.
+pub fn conditional_update(mut op: TransformOperation) -> TransformOperation {
+    let operation = op.inner_mut();
+
+    in_context(|ctx| {
+        document_validators(ctx, operation);
+
+        let params = [
+            header_parameter::<String>(
+                ctx,
+                "If-Match",
+                "Only perform the operation if the entity tag of the resource matches one of the given ones.",
+                false,
+            ),
+            header_parameter::<String>(
+                ctx,
+                "If-Unmodified-Since",
+                "Only perform the operation if the resource was not modified after the given HTTP date.",
+                false,
+            ),
+        ];
+        add_missing_parameters(ctx, operation, params);
+    });
+
+    add_response_if_missing(
+        operation,
+        412,
+        Response {
+            description: "a precondition of the request was not met".into(),
+            ..Default::default()
+        },
+    );
+
+    op
+}
+
+fn document_validators(ctx: &mut crate::gen::GenContext, operation: &mut Operation) {
+    add_success_response_header(
+        operation,
+        "ETag",
+        &response_header::<String>(ctx, "The entity tag of the current representation."),
+    );
+    add_success_response_header(
+        operation,
+        "Last-Modified",
+        &response_header::<String>(
+            ctx,
+            "The HTTP date of the last modification of the resource.",
+        ),
+    );
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use super::{conditional_get, conditional_update};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    async fn item() -> String {
+        String::new()
+    }
+
+    #[test]
+    fn test_conditional() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/items/:id",
+                get_with(item, conditional_get).put_with(item, conditional_update),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let header_names = |operation: &serde_json::Value| {
+            operation["parameters"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter(|p| p["in"] == "header")
+                .map(|p| p["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        let get = &paths["/items/{id}"]["get"];
+        assert_eq!(header_names(get), ["If-None-Match", "If-Modified-Since"]);
+        let responses = &get["responses"];
+        assert!(responses["200"]["headers"]["ETag"].is_object());
+        assert!(responses["200"]["headers"]["Last-Modified"].is_object());
+        assert_eq!(
+            responses["304"]["description"],
+            "the resource was not modified"
+        );
+        assert!(responses["304"]["content"].is_null());
+        assert!(responses["412"].is_null());
+
+        let put = &paths["/items/{id}"]["put"];
+        assert_eq!(header_names(put), ["If-Match", "If-Unmodified-Since"]);
+        let responses = &put["responses"];
+        assert!(responses["200"]["headers"]["ETag"].is_object());
+        assert_eq!(
+            responses["412"]["description"],
+            "a precondition of the request was not met"
+        );
+        assert!(responses["304"].is_null());
+    }
+}
@@ -0,0 +1,380 @@
+//! CORS documentation.
+//!
+//! CORS is usually handled by a middleware layer, which is invisible
+//! to the generated documentation. [`cors_preflight`] describes the
+//! same policy in the documentation by adding an `OPTIONS` preflight
+//! operation to every path, and the `Access-Control-*` response headers
+//! to all successful responses of the existing operations.
+//!
+//! The policy is also stored in the `x-cors` extension of the
+//! preflight operations, so that it can be validated by tools
+//! consuming the specification.
+//!
+//! # Examples
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use aide::{
+//!     helpers::cors::{cors_preflight, CorsPolicy},
+//!     transform::TransformOpenApi,
+//! };
+//!
+//! fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+//!     let policy = CorsPolicy::new()
+//!         .allow_origin("https://app.example.com")
+//!         .allow_headers(&["Authorization", "Content-Type"])
+//!         .max_age(Duration::from_secs(3600));
+//!
+//!     api.title("Example API").with(cors_preflight(policy))
+//! }
+//! ```
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Header, Operation, ReferenceOr, Response, StatusCode},
+    transform::TransformOpenApi,
+    util::iter_operations_mut,
+};
+
+use super::{add_success_response_header, header_parameter, response_header};
+
+/// The extension that holds the CORS policy.
+pub const CORS_EXTENSION: &str = "x-cors";
+
+/// A CORS policy used for documentation.
+///
+/// It should mirror the configuration of the CORS layer.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[must_use]
+pub struct CorsPolicy {
+    allow_origins: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    allow_methods: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    allow_headers: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    expose_headers: Vec<String>,
+    allow_credentials: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_age: Option<u64>,
+}
+
+impl CorsPolicy {
+    /// Create a policy that allows no origins.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow the given origin.
+    pub fn allow_origin(mut self, origin: &str) -> Self {
+        self.allow_origins.push(origin.into());
+        self
+    }
+
+    /// Allow any origin.
+    pub fn allow_any_origin(self) -> Self {
+        self.allow_origin("*")
+    }
+
+    /// Allow the given methods, by default the methods
+    /// of the documented operations of each path are used.
+    pub fn allow_methods(mut self, methods: &[&str]) -> Self {
+        self.allow_methods
+            .extend(methods.iter().map(|m| m.to_uppercase()));
+        self
+    }
+
+    /// Allow the given request headers.
+    pub fn allow_headers(mut self, headers: &[&str]) -> Self {
+        self.allow_headers
+            .extend(headers.iter().map(|h| (*h).to_string()));
+        self
+    }
+
+    /// Expose the given response headers.
+    pub fn expose_headers(mut self, headers: &[&str]) -> Self {
+        self.expose_headers
+            .extend(headers.iter().map(|h| (*h).to_string()));
+        self
+    }
+
+    /// Whether credentials are allowed.
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+
+    /// How long the results of a preflight request can be cached.
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age.as_secs());
+        self
+    }
+
+    fn preflight(&self, ctx: &mut GenContext, methods: &[String]) -> Operation {
+        let mut request_method = header_parameter::<String>(
+            ctx,
+            "Access-Control-Request-Method",
+            "The method of the actual request.",
+            true,
+        );
+        request_method.parameter_data_mut().example = methods.first().cloned().map(Into::into);
+
+        let parameters = [
+            header_parameter::<String>(ctx, "Origin", "The origin of the actual request.", true),
+            request_method,
+            header_parameter::<String>(
+                ctx,
+                "Access-Control-Request-Headers",
+                "The headers of the actual request.",
+                false,
+            ),
+        ];
+
+        let mut res = Response {
+            description: "the preflight request was accepted".into(),
+            ..Default::default()
+        };
+
+        let mut headers = vec![
+            (
+                "Access-Control-Allow-Origin",
+                self.allow_origin_header(ctx),
+                None,
+            ),
+            (
+                "Access-Control-Allow-Methods",
+                response_header::<String>(ctx, "The allowed methods."),
+                Some(methods.join(", ")),
+            ),
+        ];
+
+        if !self.allow_headers.is_empty() {
+            headers.push((
+                "Access-Control-Allow-Headers",
+                response_header::<String>(ctx, "The allowed request headers."),
+                Some(self.allow_headers.join(", ")),
+            ));
+        }
+
+        if self.allow_credentials {
+            headers.push((
+                "Access-Control-Allow-Credentials",
+                response_header::<bool>(ctx, "Whether credentials are allowed."),
+                Some("true".into()),
+            ));
+        }
+
+        if let Some(max_age) = self.max_age {
+            headers.push((
+                "Access-Control-Max-Age",
+                response_header::<u64>(
+                    ctx,
+                    "How long the preflight response can be cached in seconds.",
+                ),
+                Some(max_age.to_string()),
+            ));
+        }
+
+        for (name, mut header, example) in headers {
+            if let Some(example) = example {
+                header.example = Some(example.into());
+            }
+            res.headers.insert(name.into(), ReferenceOr::Item(header));
+        }
+
+        let mut operation = Operation {
+            summary: Some("CORS preflight".into()),
+            parameters: parameters.into_iter().map(ReferenceOr::Item).collect(),
+            ..Default::default()
+        };
+
+        operation
+            .responses
+            .get_or_insert_with(Default::default)
+            .responses
+            .insert(StatusCode::Code(204), ReferenceOr::Item(res));
+
+        if let Ok(policy) = serde_json::to_value(self) {
+            operation.extensions.insert(CORS_EXTENSION.into(), policy);
+        }
+
+        operation
+    }
+
+    /// The `Access-Control-Allow-Origin` header, which holds
+    /// a single origin that is one of the allowed origins.
+    fn allow_origin_header(&self, ctx: &mut GenContext) -> Header {
+        let description = match self.allow_origins.as_slice() {
+            [] | [_] => "The allowed origin.".to_string(),
+            origins => format!(
+                "The origin of the request if it is one of {}.",
+                origins
+                    .iter()
+                    .map(|origin| format!("`{origin}`"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+
+        let mut header = response_header::<String>(ctx, &description);
+        header.example = Some(
+            self.allow_origins
+                .first()
+                .map_or("*", String::as_str)
+                .into(),
+        );
+        header
+    }
+
+    fn response_headers(&self, ctx: &mut GenContext) -> Vec<(&'static str, Header)> {
+        let mut headers = vec![("Access-Control-Allow-Origin", self.allow_origin_header(ctx))];
+
+        if !self.expose_headers.is_empty() {
+            let mut expose =
+                response_header::<String>(ctx, "The response headers exposed to the client.");
+            expose.example = Some(self.expose_headers.join(", ").into());
+            headers.push(("Access-Control-Expose-Headers", expose));
+        }
+
+        headers
+    }
+}
+
+/// Document the CORS policy for all paths of the API.
+///
+/// Paths that already have an `OPTIONS` operation
+/// do not get a preflight operation.
+pub fn cors_preflight(
+    policy: CorsPolicy,
+) -> impl FnOnce(TransformOpenApi) -> TransformOpenApi + Clone {
+    move |mut api| {
+        let Some(paths) = &mut api.inner_mut().paths else {
+            return api;
+        };
+
+        in_context(|ctx| {
+            let response_headers = policy.response_headers(ctx);
+
+            for (_, path) in &mut paths.paths {
+                let ReferenceOr::Item(path) = path else {
+                    continue;
+                };
+
+                let mut methods = Vec::new();
+                for (method, op) in iter_operations_mut(path) {
+                    methods.push(method.to_uppercase());
+                    for (name, header) in &response_headers {
+                        add_success_response_header(op, name, header);
+                    }
+                }
+
+                if path.options.is_some() || methods.is_empty() {
+                    continue;
+                }
+
+                if !policy.allow_methods.is_empty() {
+                    methods.clone_from(&policy.allow_methods);
+                }
+
+                path.options = Some(policy.preflight(ctx, &methods));
+            }
+        });
+
+        api
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use std::time::Duration;
+
+    use serde_json::json;
+
+    use super::{cors_preflight, CorsPolicy, CORS_EXTENSION};
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    async fn handler() -> String {
+        String::new()
+    }
+
+    #[test]
+    fn test_cors_preflight() {
+        let policy = CorsPolicy::new()
+            .allow_origin("https://app.example.com")
+            .allow_origin("https://admin.example.com")
+            .allow_headers(&["Authorization"])
+            .expose_headers(&["ETag"])
+            .allow_credentials(true)
+            .max_age(Duration::from_hours(1));
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/users", get(handler).post(handler))
+            .finish_api_with(&mut api, |api| api.with(cors_preflight(policy)));
+
+        let path = &serde_json::to_value(api.paths.unwrap()).unwrap()["/users"];
+
+        let headers = &path["get"]["responses"]["200"]["headers"];
+        let allow_origin = &headers["Access-Control-Allow-Origin"];
+        assert_eq!(allow_origin["example"], "https://app.example.com");
+        assert_eq!(
+            allow_origin["description"],
+            "The origin of the request if it is one of \
+             `https://app.example.com`, `https://admin.example.com`."
+        );
+        assert_eq!(headers["Access-Control-Expose-Headers"]["example"], "ETag");
+
+        let preflight = &path["options"];
+        let request_method = preflight["parameters"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|p| p["name"] == "Access-Control-Request-Method")
+            .unwrap();
+        assert_eq!(request_method["example"], "GET");
+
+        let headers = &preflight["responses"]["204"]["headers"];
+        assert_eq!(
+            headers["Access-Control-Allow-Origin"]["example"],
+            "https://app.example.com"
+        );
+        assert_eq!(
+            headers["Access-Control-Allow-Methods"]["example"],
+            "GET, POST"
+        );
+        assert_eq!(
+            headers["Access-Control-Allow-Credentials"]["example"],
+            "true"
+        );
+        assert_eq!(headers["Access-Control-Max-Age"]["example"], "3600");
+        assert_eq!(
+            preflight[CORS_EXTENSION]["allowOrigins"],
+            json!(["https://app.example.com", "https://admin.example.com"])
+        );
+    }
+
+    #[test]
+    fn test_cors_any_origin() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/users", get(handler))
+            .finish_api_with(&mut api, |api| {
+                api.with(cors_preflight(CorsPolicy::new().allow_any_origin()))
+            });
+
+        let path = &serde_json::to_value(api.paths.unwrap()).unwrap()["/users"];
+        let allow_origin =
+            &path["get"]["responses"]["200"]["headers"]["Access-Control-Allow-Origin"];
+        assert_eq!(allow_origin["example"], "*");
+        assert_eq!(allow_origin["description"], "The allowed origin.");
+    }
+}
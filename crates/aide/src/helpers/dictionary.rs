@@ -0,0 +1,156 @@
+//! Central documentation of common parameters.
+//!
+//! Parameters like `org_id`, `page` or `limit` appear in many
+//! operations, and their descriptions tend to drift apart when they
+//! are written for every operation. A [`ParameterDictionary`] holds
+//! their documentation in one place, it is applied to all parameters
+//! without a description with
+//! [`TransformOpenApi::parameter_dictionary`](crate::transform::TransformOpenApi::parameter_dictionary).
+//!
+//! Parameters are matched by name only. Documentation that is already
+//! present, e.g. from doc comments or transforms, is never replaced.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::dictionary::ParameterDictionary, transform::TransformOpenApi};
+//!
+//! fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+//!     let dictionary = ParameterDictionary::new()
+//!         .parameter("org_id", "The ID of the organization.")
+//!         .format("org_id", "uuid")
+//!         .parameter("page", "The page to return, starting at 1.")
+//!         .example("page", 1);
+//!
+//!     api.parameter_dictionary(&dictionary)
+//! }
+//! ```
+
+use indexmap::IndexMap;
+use schemars::schema::Schema;
+use serde_json::Value;
+
+use crate::openapi::{Parameter, ParameterSchemaOrContent};
+
+/// The documentation of a parameter in a [`ParameterDictionary`].
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Entry {
+    description: Option<String>,
+    example: Option<Value>,
+    format: Option<String>,
+}
+
+/// Documentation of parameters by name, see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+#[must_use]
+pub struct ParameterDictionary {
+    entries: IndexMap<String, Entry>,
+}
+
+impl ParameterDictionary {
+    /// An empty dictionary.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the description of the parameter `name`.
+    pub fn parameter(mut self, name: &str, description: &str) -> Self {
+        self.entries.entry(name.into()).or_default().description = Some(description.into());
+        self
+    }
+
+    /// Set an example value of the parameter `name`.
+    pub fn example(mut self, name: &str, example: impl Into<Value>) -> Self {
+        self.entries.entry(name.into()).or_default().example = Some(example.into());
+        self
+    }
+
+    /// Set the `format` of the schema of the parameter `name`.
+    pub fn format(mut self, name: &str, format: &str) -> Self {
+        self.entries.entry(name.into()).or_default().format = Some(format.into());
+        self
+    }
+
+    /// Fill in the missing documentation of a parameter
+    /// from the dictionary.
+    pub(crate) fn apply(&self, parameter: &mut Parameter) {
+        let data = parameter.parameter_data_mut();
+        let Some(entry) = self.entries.get(&data.name) else {
+            return;
+        };
+
+        if data.description.is_none() {
+            data.description.clone_from(&entry.description);
+        }
+
+        let ParameterSchemaOrContent::Schema(schema) = &mut data.format else {
+            return;
+        };
+
+        if data.example.is_none() && schema.example.is_none() && data.examples.is_empty() {
+            data.example.clone_from(&entry.example);
+        }
+
+        if let (Some(format), Schema::Object(object)) = (&entry.format, &mut schema.json_schema) {
+            if object.format.is_none() && object.reference.is_none() {
+                object.format = Some(format.clone());
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::extract::{Path, Query};
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    use super::ParameterDictionary;
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[allow(dead_code)]
+    #[derive(Deserialize, JsonSchema)]
+    struct Pagination {
+        /// The page of the todos.
+        page: Option<u32>,
+        limit: Option<u32>,
+    }
+
+    async fn list_todos(_org_id: Path<String>, _pagination: Query<Pagination>) {}
+
+    #[test]
+    fn test_parameter_dictionary() {
+        let dictionary = ParameterDictionary::new()
+            .parameter("org_id", "The ID of the organization.")
+            .format("org_id", "uuid")
+            .parameter("page", "The page to return.")
+            .parameter("limit", "The maximum number of items.")
+            .example("limit", 50);
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/orgs/{org_id}/todos", get(list_todos))
+            .finish_api_with(&mut api, |api| api.parameter_dictionary(&dictionary));
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let params = paths["/orgs/{org_id}/todos"]["get"]["parameters"]
+            .as_array()
+            .unwrap();
+        let param = |name: &str| params.iter().find(|p| p["name"] == name).unwrap();
+
+        assert_eq!(
+            param("org_id")["description"],
+            "The ID of the organization."
+        );
+        assert_eq!(param("org_id")["schema"]["format"], "uuid");
+        assert_eq!(param("page")["description"], "The page of the todos.");
+        assert_eq!(
+            param("limit")["description"],
+            "The maximum number of items."
+        );
+        assert_eq!(param("limit")["example"], 50);
+    }
+}
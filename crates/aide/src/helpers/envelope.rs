@@ -0,0 +1,279 @@
+//! Response envelopes that wrap every payload in a common structure.
+//!
+//! Many APIs wrap each response body in an object such as
+//! `{ "data": ..., "meta": ... }`. Instead of documenting the wrapper
+//! for every route, the envelope can be declared once with
+//! [`TransformOpenApi::envelope`](crate::transform::TransformOpenApi::envelope)
+//! and every successful JSON response schema is composed with it
+//! via `allOf`.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! #[derive(Serialize, JsonSchema)]
+//! struct Meta {
+//!     request_id: String,
+//! }
+//!
+//! app.finish_api_with(&mut api, |api| api.envelope::<Meta>());
+//! ```
+//!
+//! Handlers can also return [`Envelope`] directly, which yields the same
+//! composed schema:
+//!
+//! ```ignore
+//! async fn get_user() -> Json<Envelope<User, Meta>> { /* ... */ }
+//! ```
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, ObjectValidation, Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
+use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
+
+use crate::{gen::GenContext, helpers::is_json_media_type, openapi::Response};
+
+/// The property that holds the payload in an [`Envelope`].
+pub const DATA_FIELD: &str = "data";
+
+/// The property that holds the metadata in an [`Envelope`].
+pub const META_FIELD: &str = "meta";
+
+/// A response envelope that wraps a payload of type `T`
+/// along with metadata of type `M`.
+///
+/// The generated schema is an `allOf` composition of
+/// the envelope (metadata) and the payload, if `M` is
+/// zero-sized (e.g. `()`) the `meta` property is omitted.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(bound(deserialize = "T: Deserialize<'de>, M: Deserialize<'de> + Default"))]
+pub struct Envelope<T, M = ()> {
+    /// The wrapped payload.
+    pub data: T,
+    /// Additional metadata.
+    #[serde(default)]
+    pub meta: M,
+}
+
+impl<T> Envelope<T> {
+    /// Wrap the given payload without metadata.
+    pub fn new(data: T) -> Self {
+        Self { data, meta: () }
+    }
+}
+
+impl<T, M> Envelope<T, M> {
+    /// Wrap the given payload with metadata.
+    pub fn with_meta(data: T, meta: M) -> Self {
+        Self { data, meta }
+    }
+}
+
+impl<T, M> Serialize for Envelope<T, M>
+where
+    T: Serialize,
+    M: Serialize,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if has_meta::<M>() {
+            let mut s = serializer.serialize_struct("Envelope", 2)?;
+            s.serialize_field(DATA_FIELD, &self.data)?;
+            s.serialize_field(META_FIELD, &self.meta)?;
+            s.end()
+        } else {
+            let mut s = serializer.serialize_struct("Envelope", 1)?;
+            s.serialize_field(DATA_FIELD, &self.data)?;
+            s.end()
+        }
+    }
+}
+
+impl<T, M> JsonSchema for Envelope<T, M>
+where
+    T: JsonSchema,
+    M: JsonSchema,
+{
+    fn schema_name() -> String {
+        if has_meta::<M>() {
+            format!("Envelope_for_{}_and_{}", T::schema_name(), M::schema_name())
+        } else {
+            format!("Envelope_for_{}", T::schema_name())
+        }
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let meta = has_meta::<M>().then(|| gen.subschema_for::<M>());
+
+        compose_envelope(meta, gen.subschema_for::<T>())
+    }
+}
+
+/// Compose an envelope schema from an optional metadata
+/// schema and the payload schema.
+///
+/// The result is an `allOf` of the envelope and an object
+/// that requires the payload under the `data` property.
+#[must_use]
+pub fn compose_envelope(meta: Option<Schema>, data: Schema) -> Schema {
+    let mut all_of = Vec::with_capacity(2);
+
+    if let Some(meta) = meta {
+        all_of.push(object_with_property(META_FIELD, meta, false));
+    }
+
+    all_of.push(object_with_property(DATA_FIELD, data, true));
+
+    Schema::Object(SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            all_of: Some(all_of),
+            ..Default::default()
+        })),
+        ..Default::default()
+    })
+}
+
+/// Wrap all JSON content of a response into an envelope
+/// with the metadata type `M`.
+pub(crate) fn wrap_response<M: JsonSchema>(ctx: &mut GenContext, response: &mut Response) {
+    let meta = has_meta::<M>().then(|| ctx.schema.subschema_for::<M>());
+
+    for (media_type, content) in &mut response.content {
+        if !is_json_media_type(media_type) {
+            continue;
+        }
+
+        if let Some(schema) = &mut content.schema {
+            let payload = std::mem::replace(&mut schema.json_schema, Schema::Bool(true));
+            schema.json_schema = compose_envelope(meta.clone(), payload);
+        }
+
+        // Examples describe the bare payload, so they
+        // are wrapped as well.
+        if let Some(example) = content.example.take() {
+            content.example = Some(serde_json::json!({ DATA_FIELD: example }));
+        }
+    }
+}
+
+fn object_with_property(name: &str, schema: Schema, required: bool) -> Schema {
+    let mut object = ObjectValidation::default();
+    object.properties.insert(name.into(), schema);
+    if required {
+        object.required.insert(name.into());
+    }
+
+    Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(object)),
+        ..Default::default()
+    })
+}
+
+/// Zero-sized metadata types (such as `()`) carry no
+/// information and are left out of the envelope.
+const fn has_meta<M>() -> bool {
+    std::mem::size_of::<M>() != 0
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::Json;
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::Envelope;
+    use crate::{
+        axum::{
+            routing::{get, get_with},
+            ApiRouter,
+        },
+        openapi::OpenApi,
+    };
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct User {
+        name: String,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Meta {
+        request_id: String,
+    }
+
+    async fn get_user() -> Json<User> {
+        Json(User {
+            name: String::new(),
+        })
+    }
+
+    async fn get_enveloped_user() -> Json<Envelope<User>> {
+        Json(Envelope::new(User {
+            name: String::new(),
+        }))
+    }
+
+    #[test]
+    // The methods themselves are not general over the lifetime of the transforms.
+    #[allow(clippy::redundant_closure_for_method_calls)]
+    fn test_envelope() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users/:id",
+                get_with(get_user, |op| op.response::<404, Json<String>>()),
+            )
+            .finish_api_with(&mut api, |api| api.envelope::<Meta>());
+
+        let api = serde_json::to_value(api).unwrap();
+        let responses = &api["paths"]["/users/{id}"]["get"]["responses"];
+
+        let all_of = &responses["200"]["content"]["application/json"]["schema"]["allOf"];
+        assert_eq!(
+            all_of[0]["properties"]["meta"]["$ref"],
+            "#/components/schemas/Meta"
+        );
+        assert_eq!(all_of[0].get("required"), None);
+        assert_eq!(
+            all_of[1]["properties"]["data"]["$ref"],
+            "#/components/schemas/User"
+        );
+        assert_eq!(all_of[1]["required"], json!(["data"]));
+
+        // Error responses are not enveloped.
+        assert_eq!(
+            responses["404"]["content"]["application/json"]["schema"]["type"],
+            "string"
+        );
+    }
+
+    #[test]
+    fn test_envelope_output() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/users/:id", get(get_enveloped_user))
+            .finish_api(&mut api);
+
+        let api = serde_json::to_value(api).unwrap();
+        let schema = &api["paths"]["/users/{id}"]["get"]["responses"]["200"]["content"]
+            ["application/json"]["schema"];
+        assert_eq!(schema["$ref"], "#/components/schemas/Envelope_for_User");
+
+        let envelope = &api["components"]["schemas"]["Envelope_for_User"];
+        assert_eq!(envelope["allOf"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            envelope["allOf"][0]["properties"]["data"]["$ref"],
+            "#/components/schemas/User"
+        );
+
+        assert_eq!(
+            serde_json::to_value(Envelope::new(1)).unwrap(),
+            json!({ "data": 1 })
+        );
+        assert_eq!(
+            serde_json::to_value(Envelope::with_meta(1, "abc")).unwrap(),
+            json!({ "data": 1, "meta": "abc" })
+        );
+    }
+}
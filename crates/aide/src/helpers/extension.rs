@@ -0,0 +1,193 @@
+//! Documented request extensions.
+//!
+//! [`State`](axum::extract::State), [`Extension`] and
+//! [`ConnectInfo`](axum::extract::ConnectInfo) are infrastructure
+//! extractors and are not documented. Some extensions are derived from
+//! the request by a middleware however, e.g. the authenticated user from
+//! the `Authorization` header, and the requirements of the middleware
+//! belong in the documentation of the operations that extract them.
+//!
+//! [`DocumentedExtension`] extracts the extension like [`Extension`] and
+//! documents the headers and security requirements declared by
+//! [`ExtensionDocs`] for the type.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::helpers::extension::{DocumentedExtension, ExtensionApi, ExtensionDocs};
+//!
+//! #[derive(Clone)]
+//! struct CurrentUser {
+//!     id: u64,
+//! }
+//!
+//! impl ExtensionDocs for CurrentUser {
+//!     fn document(api: &mut ExtensionApi) {
+//!         api.security_requirement("oauth2", ["profile"])
+//!             .header::<String>("X-Tenant", "The tenant of the user.");
+//!     }
+//! }
+//!
+//! async fn get_profile(DocumentedExtension(user): DocumentedExtension<CurrentUser>) -> String {
+//!     user.id.to_string()
+//! }
+//! ```
+
+use std::ops::{Deref, DerefMut};
+
+use axum::{async_trait, extract::FromRequestParts, Extension};
+use http::request::Parts;
+use schemars::JsonSchema;
+
+use crate::{
+    gen::GenContext,
+    openapi::{Operation, Parameter, SecurityRequirement},
+    OperationInput,
+};
+
+use super::{add_missing_parameters, header_parameter};
+
+/// The documentation of an extension extracted
+/// with [`DocumentedExtension`].
+pub trait ExtensionDocs {
+    /// Declare what the operations that extract the extension require.
+    fn document(api: &mut ExtensionApi);
+}
+
+/// The requirements of an extension, see [`ExtensionDocs`].
+pub struct ExtensionApi<'a> {
+    ctx: &'a mut GenContext,
+    parameters: Vec<Parameter>,
+    security: Vec<(String, Vec<String>)>,
+}
+
+impl ExtensionApi<'_> {
+    /// A request header the extension is derived from.
+    pub fn header<T: JsonSchema>(&mut self, name: &str, description: &str) -> &mut Self {
+        let param = header_parameter::<T>(self.ctx, name, description, true);
+        self.parameters.push(param);
+        self
+    }
+
+    /// A security requirement of the extension, e.g. a token
+    /// that is validated by the middleware.
+    pub fn security_requirement<I, S>(&mut self, scheme: &str, scopes: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.security
+            .push((scheme.into(), scopes.into_iter().map(Into::into).collect()));
+        self
+    }
+}
+
+/// An [`Extension`] that is documented with the [`ExtensionDocs`] of `T`.
+#[derive(Debug, Clone, Copy, Default)]
+#[must_use]
+pub struct DocumentedExtension<T>(pub T);
+
+impl<T> DocumentedExtension<T> {
+    /// Unwraps [Self] into its inner type
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for DocumentedExtension<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for DocumentedExtension<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T: ExtensionDocs> OperationInput for DocumentedExtension<T> {
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        let mut api = ExtensionApi {
+            ctx: &mut *ctx,
+            parameters: Vec::new(),
+            security: Vec::new(),
+        };
+        T::document(&mut api);
+        let ExtensionApi {
+            parameters,
+            security,
+            ..
+        } = api;
+
+        add_missing_parameters(ctx, operation, parameters);
+
+        if !security.is_empty() {
+            let requirement: SecurityRequirement = security.into_iter().collect();
+            if !operation.security.contains(&requirement) {
+                operation.security.push(requirement);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for DocumentedExtension<T>
+where
+    T: Clone + Send + Sync + 'static,
+    S: Send + Sync,
+{
+    type Rejection = <Extension<T> as FromRequestParts<S>>::Rejection;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Extension(value) = Extension::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{extract::State, Extension};
+
+    use super::{DocumentedExtension, ExtensionApi, ExtensionDocs};
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Clone)]
+    struct CurrentUser;
+
+    impl ExtensionDocs for CurrentUser {
+        fn document(api: &mut ExtensionApi) {
+            api.security_requirement("oauth2", ["todos:read"])
+                .header::<String>("X-Tenant", "The tenant of the user.");
+        }
+    }
+
+    async fn documented(_user: DocumentedExtension<CurrentUser>) {}
+
+    async fn infrastructure(_state: State<()>, _user: Extension<CurrentUser>) {}
+
+    #[test]
+    fn test_documented_extension() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/documented", get(documented))
+            .api_route("/infrastructure", get(infrastructure))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let op = &paths["/documented"]["get"];
+        assert_eq!(op["parameters"][0]["name"], "X-Tenant");
+        assert_eq!(op["parameters"][0]["in"], "header");
+        assert_eq!(op["security"][0]["oauth2"][0], "todos:read");
+
+        let op = paths["/infrastructure"]["get"].as_object().unwrap();
+        assert!(!op.contains_key("parameters"));
+        assert!(!op.contains_key("security"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Extractors without [`OperationInput`] implementations.
+//!
+//! Extractors of other crates, or extractors implemented with generic
+//! blanket rules, often do not implement [`OperationInput`], and whether
+//! they do can depend on feature flags. Such extractors cannot be used in
+//! documented handlers directly, and hiding them with
+//! [`NoApi`](crate::NoApi) silently leaves the documentation incomplete.
+//!
+//! [`Extractor`] wraps these extractors and documents them with the
+//! documentation [registered](crate::gen::document_extractor) for the
+//! type at runtime. Extractors without registered documentation are
+//! handled according to the [`UnknownExtractors`] policy.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! use aide::{gen, helpers::extractor::Extractor};
+//!
+//! gen::document_extractor::<TenantId>(|ctx, operation| {
+//!     // Document the header the tenant is extracted from.
+//! });
+//! gen::ignore_extractor::<Tx<sqlx::Any>>();
+//!
+//! async fn list_todos(
+//!     Extractor(tenant): Extractor<TenantId>,
+//!     Extractor(mut tx): Extractor<Tx<sqlx::Any>>,
+//! ) -> Json<Vec<Todo>> {
+//!     # todo!()
+//! }
+//! ```
+
+use std::{
+    any::{type_name, TypeId},
+    ops::{Deref, DerefMut},
+};
+
+use crate::{
+    error::Error,
+    gen::{in_context, GenContext},
+    openapi::Operation,
+    OperationInput,
+};
+
+/// How extractors without registered documentation are handled,
+/// see [`unknown_extractors`](crate::gen::unknown_extractors).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownExtractors {
+    /// Report an [`Error::UnknownExtractor`] to the error handler.
+    #[default]
+    Warn,
+    /// Leave the extractor undocumented.
+    Ignore,
+    /// Panic while the documentation is generated.
+    Fail,
+}
+
+/// An extractor that is documented with the documentation registered
+/// for `T`, see the [module documentation](self).
+#[derive(Copy, Clone, Debug, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub struct Extractor<T>(pub T);
+
+impl<T> Extractor<T> {
+    /// Unwraps [Self] into its inner type
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Extractor<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Extractor<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+
+impl<T> From<T> for Extractor<T> {
+    fn from(value: T) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: 'static> OperationInput for Extractor<T> {
+    /// # Panics
+    ///
+    /// If no documentation is registered for `T`
+    /// and the policy is [`UnknownExtractors::Fail`].
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        // The documentation is cloned so that it can borrow the context.
+        if let Some(docs) = ctx.extractor_docs.get(&TypeId::of::<T>()).cloned() {
+            docs(ctx, operation);
+            return;
+        }
+
+        match ctx.unknown_extractors {
+            UnknownExtractors::Warn => ctx.error(Error::UnknownExtractor(type_name::<T>())),
+            UnknownExtractors::Ignore => {}
+            UnknownExtractors::Fail => panic!("{}", Error::UnknownExtractor(type_name::<T>())),
+        }
+    }
+}
+
+/// Whether documentation is registered for the extractor `T`.
+#[must_use]
+pub fn is_documented<T: 'static>() -> bool {
+    in_context(|ctx| ctx.extractor_docs.contains_key(&TypeId::of::<T>()))
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::{
+        async_trait,
+        body::Body,
+        extract::{FromRequest, FromRequestParts},
+    };
+    use http::{request::Parts, Request};
+
+    use super::Extractor;
+
+    #[async_trait]
+    impl<T, S> FromRequestParts<S> for Extractor<T>
+    where
+        T: FromRequestParts<S>,
+        S: Send + Sync,
+    {
+        type Rejection = T::Rejection;
+
+        async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+            Ok(Self(T::from_request_parts(parts, state).await?))
+        }
+    }
+
+    #[async_trait]
+    impl<T, S> FromRequest<S> for Extractor<T>
+    where
+        T: FromRequest<S>,
+        S: Send + Sync,
+    {
+        type Rejection = T::Rejection;
+
+        async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+            Ok(Self(T::from_request(req, state).await?))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use std::{cell::RefCell, rc::Rc};
+
+    use axum::{extract::FromRequestParts, http::request::Parts};
+
+    use super::{is_documented, Extractor, UnknownExtractors};
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        gen,
+        openapi::OpenApi,
+    };
+
+    struct TenantId;
+
+    struct Session;
+
+    #[axum::async_trait]
+    impl<S: Send + Sync> FromRequestParts<S> for TenantId {
+        type Rejection = ();
+
+        async fn from_request_parts(_parts: &mut Parts, _state: &S) -> Result<Self, ()> {
+            Ok(Self)
+        }
+    }
+
+    #[axum::async_trait]
+    impl<S: Send + Sync> FromRequestParts<S> for Session {
+        type Rejection = ();
+
+        async fn from_request_parts(_parts: &mut Parts, _state: &S) -> Result<Self, ()> {
+            Ok(Self)
+        }
+    }
+
+    async fn handler(_tenant: Extractor<TenantId>, _session: Extractor<Session>) {}
+
+    fn document(policy: UnknownExtractors) -> OpenApi {
+        gen::unknown_extractors(policy);
+        gen::document_extractor::<TenantId>(|_ctx, operation| {
+            operation.description = Some("tenant".into());
+        });
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/todos", get(handler))
+            .finish_api(&mut api);
+        api
+    }
+
+    #[test]
+    fn test_unknown_extractors() {
+        gen::reset_context();
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let on_error = {
+            let errors = errors.clone();
+            move |error: crate::Error| errors.borrow_mut().push(error.to_string())
+        };
+
+        gen::on_error(on_error.clone());
+        let api = document(UnknownExtractors::Warn);
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        assert_eq!(paths["/todos"]["get"]["description"], "tenant");
+        assert_eq!(errors.borrow().len(), 1);
+        assert!(errors.borrow()[0].contains("Session"));
+
+        errors.borrow_mut().clear();
+        gen::on_error(on_error);
+        document(UnknownExtractors::Ignore);
+        assert!(errors.borrow().is_empty());
+
+        let failed = std::panic::catch_unwind(|| document(UnknownExtractors::Fail));
+        assert!(failed.is_err());
+
+        gen::ignore_extractor::<Session>();
+        assert!(is_documented::<Session>());
+        document(UnknownExtractors::Fail);
+
+        gen::reset_context();
+    }
+}
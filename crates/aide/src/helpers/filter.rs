@@ -0,0 +1,230 @@
+//! Documentation of filter and sort query conventions for list endpoints.
+//!
+//! The filters are documented as `filter[field]=operator:value`
+//! query parameters, e.g. `filter[status]=in:active,pending`, and the
+//! order as a `sort` query parameter with a comma-separated list of
+//! fields, prefixed with `-` for descending order, e.g.
+//! `sort=-created_at,name`.
+//!
+//! The fields and operators are declared once for a resource type by
+//! implementing [`Filterable`], the parameters are documented with
+//! [`filtered`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::{
+//!         filter::{filtered, FilterOp, Filterable},
+//!         pagination::paginated,
+//!     },
+//!     transform::TransformOperation,
+//! };
+//! # #[derive(schemars::JsonSchema)]
+//! # struct User;
+//!
+//! impl Filterable for User {
+//!     const FILTER_FIELDS: &'static [(&'static str, &'static [FilterOp])] = &[
+//!         ("status", &[FilterOp::Eq, FilterOp::In]),
+//!         ("created_at", &[FilterOp::Gt, FilterOp::Lt]),
+//!     ];
+//!     const SORT_FIELDS: &'static [&'static str] = &["created_at", "name"];
+//! }
+//!
+//! fn list_users_docs(op: TransformOperation) -> TransformOperation {
+//!     op.with(paginated::<User>).with(filtered::<User>)
+//! }
+//! ```
+
+use schemars::schema::{ArrayValidation, InstanceType, Schema, SchemaObject, StringValidation};
+
+use crate::{
+    gen::in_context,
+    openapi::{Parameter, QueryStyle},
+    transform::TransformOperation,
+};
+
+use super::{add_missing_parameters, query_parameter};
+
+/// The name of the query parameter of the order.
+pub const SORT_PARAMETER: &str = "sort";
+
+/// An operator of a filter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FilterOp {
+    /// Equal to the value (`eq`).
+    Eq,
+    /// Not equal to the value (`ne`).
+    Ne,
+    /// Greater than the value (`gt`).
+    Gt,
+    /// Greater than or equal to the value (`gte`).
+    Gte,
+    /// Less than the value (`lt`).
+    Lt,
+    /// Less than or equal to the value (`lte`).
+    Lte,
+    /// Equal to one of the comma-separated values (`in`).
+    In,
+    /// Contains the value (`contains`).
+    Contains,
+}
+
+impl FilterOp {
+    /// The operator as it appears in the query, e.g. `gte`.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "eq",
+            Self::Ne => "ne",
+            Self::Gt => "gt",
+            Self::Gte => "gte",
+            Self::Lt => "lt",
+            Self::Lte => "lte",
+            Self::In => "in",
+            Self::Contains => "contains",
+        }
+    }
+}
+
+/// The fields of a resource type that list endpoints
+/// can be filtered and sorted by.
+pub trait Filterable {
+    /// The fields that can be filtered by, with their operators.
+    const FILTER_FIELDS: &'static [(&'static str, &'static [FilterOp])];
+
+    /// The fields that can be sorted by.
+    const SORT_FIELDS: &'static [&'static str] = &[];
+}
+
+/// Document the `filter[field]` and `sort` query parameters
+/// of the fields of `T` for an operation.
+///
+/// Parameters that are already documented are not changed.
+pub fn filtered<T: Filterable>(mut op: TransformOperation) -> TransformOperation {
+    let mut params = T::FILTER_FIELDS
+        .iter()
+        .map(|(field, operators)| filter_parameter(field, operators))
+        .collect::<Vec<_>>();
+
+    if !T::SORT_FIELDS.is_empty() {
+        params.push(sort_parameter(T::SORT_FIELDS));
+    }
+
+    in_context(|ctx| add_missing_parameters(ctx, op.inner_mut(), params));
+    op
+}
+
+fn filter_parameter(field: &str, operators: &[FilterOp]) -> Parameter {
+    let operators = operators.iter().map(|op| op.as_str()).collect::<Vec<_>>();
+
+    let description = format!(
+        "Filter by `{field}` as `operator:value`, the operator is one of {}.",
+        operators
+            .iter()
+            .map(|op| format!("`{op}`"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let schema = Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        string: Some(Box::new(StringValidation {
+            pattern: Some(format!("^({}):", operators.join("|"))),
+            ..Default::default()
+        })),
+        ..Default::default()
+    });
+
+    query_parameter(
+        &format!("filter[{field}]"),
+        &description,
+        schema,
+        QueryStyle::Form,
+    )
+}
+
+fn sort_parameter(fields: &[&str]) -> Parameter {
+    let values = fields
+        .iter()
+        .flat_map(|field| [(*field).into(), format!("-{field}").into()])
+        .collect();
+
+    let schema = Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Array.into()),
+        array: Some(Box::new(ArrayValidation {
+            items: Some(
+                Schema::Object(SchemaObject {
+                    instance_type: Some(InstanceType::String.into()),
+                    enum_values: Some(values),
+                    ..Default::default()
+                })
+                .into(),
+            ),
+            unique_items: Some(true),
+            ..Default::default()
+        })),
+        ..Default::default()
+    });
+
+    let mut param = query_parameter(
+        SORT_PARAMETER,
+        "A comma-separated list of the fields to sort by, \
+         prefixed with `-` for descending order.",
+        schema,
+        QueryStyle::Form,
+    );
+    if let Parameter::Query { parameter_data, .. } = &mut param {
+        parameter_data.explode = Some(false);
+    }
+    param
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use serde_json::json;
+
+    use super::{filtered, FilterOp, Filterable};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    struct Todo;
+
+    impl Filterable for Todo {
+        const FILTER_FIELDS: &'static [(&'static str, &'static [FilterOp])] = &[
+            ("done", &[FilterOp::Eq]),
+            ("due", &[FilterOp::Gte, FilterOp::Lt]),
+        ];
+        const SORT_FIELDS: &'static [&'static str] = &["due"];
+    }
+
+    async fn list_todos() {}
+
+    #[test]
+    fn test_filtered() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/todos", get_with(list_todos, filtered::<Todo>))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let params = paths["/todos"]["get"]["parameters"].as_array().unwrap();
+        let param = |name: &str| params.iter().find(|p| p["name"] == name).unwrap();
+
+        assert_eq!(params.len(), 3);
+        assert_eq!(param("filter[done]")["in"], "query");
+        assert_eq!(param("filter[due]")["schema"]["pattern"], "^(gte|lt):");
+        assert_eq!(
+            param("filter[due]")["description"],
+            "Filter by `due` as `operator:value`, the operator is one of `gte`, `lt`."
+        );
+        assert_eq!(param("sort")["explode"], false);
+        assert_eq!(
+            param("sort")["schema"]["items"]["enum"],
+            json!(["due", "-due"])
+        );
+    }
+}
@@ -0,0 +1,370 @@
+//! [GeoJSON](https://datatracker.ietf.org/doc/html/rfc7946) types.
+//!
+//! The types in this module follow RFC 7946 and generate matching
+//! schemas, [`GeoJson<T>`] documents them with the
+//! `application/geo+json` media type.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::geojson::{FeatureCollection, GeoJson},
+//!     transform::TransformOperation,
+//! };
+//! # #[derive(schemars::JsonSchema)]
+//! # struct Station { name: String }
+//!
+//! fn list_stations_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, GeoJson<FeatureCollection<Station>>>()
+//! }
+//! ```
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{ArrayValidation, InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gen::GenContext,
+    openapi::{Operation, Response},
+    operation::set_body,
+    OperationInput, OperationOutput,
+};
+
+use super::{media_request_body, media_response};
+
+/// The `GeoJSON` media type.
+pub const MEDIA_TYPE: &str = "application/geo+json";
+
+/// A `GeoJSON` request or response body of `T`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GeoJson<T>(pub T);
+
+/// A position of longitude, latitude and optional altitude.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Position(pub Vec<f64>);
+
+impl JsonSchema for Position {
+    fn schema_name() -> String {
+        "Position".into()
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::Array.into()),
+            array: Some(Box::new(ArrayValidation {
+                items: Some(gen.subschema_for::<f64>().into()),
+                min_items: Some(2),
+                max_items: Some(3),
+                ..Default::default()
+            })),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// A `GeoJSON` geometry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type")]
+pub enum Geometry {
+    /// A single position.
+    Point {
+        /// The position of the point.
+        coordinates: Position,
+    },
+    /// Multiple positions.
+    MultiPoint {
+        /// The positions of the points.
+        coordinates: Vec<Position>,
+    },
+    /// A line of two or more positions.
+    LineString {
+        /// The positions of the line.
+        coordinates: Vec<Position>,
+    },
+    /// Multiple lines.
+    MultiLineString {
+        /// The positions of the lines.
+        coordinates: Vec<Vec<Position>>,
+    },
+    /// A polygon of linear rings, the first one is the exterior ring.
+    Polygon {
+        /// The linear rings of the polygon.
+        coordinates: Vec<Vec<Position>>,
+    },
+    /// Multiple polygons.
+    MultiPolygon {
+        /// The linear rings of the polygons.
+        coordinates: Vec<Vec<Vec<Position>>>,
+    },
+    /// A collection of geometries.
+    GeometryCollection {
+        /// The geometries in the collection.
+        geometries: Vec<Geometry>,
+    },
+}
+
+/// The type of a [`Feature`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum FeatureType {
+    /// A feature.
+    #[default]
+    Feature,
+}
+
+/// The type of a [`FeatureCollection`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub enum FeatureCollectionType {
+    /// A feature collection.
+    #[default]
+    FeatureCollection,
+}
+
+/// The identifier of a [`Feature`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum FeatureId {
+    /// A string identifier.
+    String(String),
+    /// A numeric identifier.
+    Number(f64),
+}
+
+/// A spatially bounded entity with properties `P`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Feature<P = serde_json::Map<String, serde_json::Value>> {
+    /// Always `Feature`.
+    #[serde(rename = "type")]
+    pub kind: FeatureType,
+    /// The identifier of the feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<FeatureId>,
+    /// The geometry of the feature, `null` if unlocated.
+    pub geometry: Option<Geometry>,
+    /// The properties of the feature.
+    pub properties: Option<P>,
+    /// The bounding box of the feature.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<Vec<f64>>,
+}
+
+impl<P> Feature<P> {
+    /// Create a feature with the given geometry and properties.
+    #[must_use]
+    pub fn new(geometry: Option<Geometry>, properties: P) -> Self {
+        Self {
+            kind: FeatureType::Feature,
+            id: None,
+            geometry,
+            properties: Some(properties),
+            bbox: None,
+        }
+    }
+}
+
+/// A collection of features with properties `P`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FeatureCollection<P = serde_json::Map<String, serde_json::Value>> {
+    /// Always `FeatureCollection`.
+    #[serde(rename = "type")]
+    pub kind: FeatureCollectionType,
+    /// The features in the collection.
+    pub features: Vec<Feature<P>>,
+    /// The bounding box of the collection.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bbox: Option<Vec<f64>>,
+}
+
+impl<P> FeatureCollection<P> {
+    /// Create a collection of the given features.
+    #[must_use]
+    pub fn new(features: Vec<Feature<P>>) -> Self {
+        Self {
+            kind: FeatureCollectionType::FeatureCollection,
+            features,
+            bbox: None,
+        }
+    }
+}
+
+impl<T> OperationInput for GeoJson<T>
+where
+    T: JsonSchema,
+{
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        let body = media_request_body::<T>(ctx, MEDIA_TYPE);
+        set_body(ctx, operation, body);
+    }
+}
+
+impl<T> OperationOutput for GeoJson<T>
+where
+    T: JsonSchema,
+{
+    type Inner = T;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(media_response::<T>(ctx, MEDIA_TYPE, ""))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::{
+        async_trait,
+        body::Body,
+        extract::{rejection::JsonRejection, FromRequest},
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use http::{header, HeaderValue, Request, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::{GeoJson, MEDIA_TYPE};
+
+    impl<T> IntoResponse for GeoJson<T>
+    where
+        T: Serialize,
+    {
+        fn into_response(self) -> Response {
+            match serde_json::to_vec(&self.0) {
+                Ok(body) => (
+                    [(header::CONTENT_TYPE, HeaderValue::from_static(MEDIA_TYPE))],
+                    body,
+                )
+                    .into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl<T, S> FromRequest<S> for GeoJson<T>
+    where
+        T: DeserializeOwned,
+        S: Send + Sync,
+    {
+        type Rejection = JsonRejection;
+
+        async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+            // `+json` media types are accepted by the JSON extractor.
+            let Json(value) = Json::<T>::from_request(req, state).await?;
+            Ok(Self(value))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::JsonSchema;
+    use serde_json::json;
+
+    use super::{Feature, FeatureCollection, GeoJson, Geometry, Position, MEDIA_TYPE};
+    use crate::{openapi::Operation, transform::TransformOperation};
+
+    #[derive(serde::Serialize, serde::Deserialize, JsonSchema, Debug, PartialEq)]
+    struct Station {
+        name: String,
+    }
+
+    #[test]
+    fn test_schema() {
+        let schema =
+            serde_json::to_value(schemars::schema_for!(FeatureCollection<Station>)).unwrap();
+        let definitions = &schema["definitions"];
+
+        assert_eq!(schema["required"], json!(["features", "type"]));
+        assert_eq!(
+            definitions["FeatureCollectionType"]["oneOf"][0]["enum"],
+            json!(["FeatureCollection"])
+        );
+
+        let feature = &definitions["Feature_for_Station"];
+        assert_eq!(
+            feature["properties"]["properties"]["anyOf"][0]["$ref"],
+            "#/definitions/Station"
+        );
+        assert_eq!(
+            definitions["FeatureType"]["oneOf"][0]["enum"],
+            json!(["Feature"])
+        );
+
+        let position = &definitions["Position"];
+        assert_eq!(position["type"], "array");
+        assert_eq!(position["minItems"], 2);
+        assert_eq!(position["maxItems"], 3);
+
+        let geometries = definitions["Geometry"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|geometry| geometry["properties"]["type"]["enum"][0].as_str().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            geometries,
+            [
+                "Point",
+                "MultiPoint",
+                "LineString",
+                "MultiLineString",
+                "Polygon",
+                "MultiPolygon",
+                "GeometryCollection"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_serde() {
+        let collection = FeatureCollection::new(vec![Feature::new(
+            Some(Geometry::Point {
+                coordinates: Position(vec![102.0, 0.5]),
+            }),
+            Station {
+                name: "Central".into(),
+            },
+        )]);
+
+        let value = json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": { "type": "Point", "coordinates": [102.0, 0.5] },
+                "properties": { "name": "Central" }
+            }]
+        });
+
+        assert_eq!(serde_json::to_value(&collection).unwrap(), value);
+        assert_eq!(
+            serde_json::from_value::<FeatureCollection<Station>>(value).unwrap(),
+            collection
+        );
+    }
+
+    #[test]
+    fn test_media_type() {
+        let mut operation = Operation::default();
+        let _ = TransformOperation::new(&mut operation)
+            .response::<200, GeoJson<FeatureCollection<Station>>>();
+
+        let responses = serde_json::to_value(operation.responses).unwrap();
+        let content = responses["200"]["content"].as_object().unwrap();
+        assert_eq!(content.keys().collect::<Vec<_>>(), [MEDIA_TYPE]);
+        assert!(content[MEDIA_TYPE]["schema"].is_object());
+    }
+}
@@ -0,0 +1,358 @@
+//! [HAL](https://datatracker.ietf.org/doc/html/draft-kelly-json-hal)
+//! hypermedia documentation.
+//!
+//! [`Hal<T>`] documents a resource of type `T` extended with the
+//! `_links` and `_embedded` members as `application/hal+json`.
+//! The link relations of an operation can be declared with
+//! [`hal_links`], which documents them as properties of `_links`.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::hal::{hal_links, Hal, HalLinks},
+//!     transform::TransformOperation,
+//! };
+//! # #[derive(schemars::JsonSchema)]
+//! # struct Order { total: f64 }
+//!
+//! fn get_order_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, Hal<Order>>().with(hal_links(
+//!         HalLinks::new()
+//!             .link("self", "The order itself.")
+//!             .optional_link("payment", "The payment of the order, if any."),
+//!     ))
+//! }
+//! ```
+
+use indexmap::IndexMap;
+use schemars::{
+    schema::{InstanceType, Metadata, ObjectValidation, Schema, SchemaObject, SubschemaValidation},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Operation, Response},
+    transform::TransformOperation,
+    OperationOutput,
+};
+
+use super::media_response;
+
+/// The HAL media type.
+pub const MEDIA_TYPE: &str = "application/hal+json";
+
+/// The property that holds the links of a resource.
+pub const LINKS_FIELD: &str = "_links";
+
+/// The property that holds the embedded resources of a resource.
+pub const EMBEDDED_FIELD: &str = "_embedded";
+
+/// A HAL resource of type `T` with links and embedded resources `E`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(bound(deserialize = "T: Deserialize<'de>, E: Deserialize<'de>"))]
+pub struct Hal<T, E = serde_json::Map<String, serde_json::Value>> {
+    /// The resource itself.
+    #[serde(flatten)]
+    pub resource: T,
+    /// Links keyed by their relation.
+    #[serde(rename = "_links", default)]
+    pub links: IndexMap<String, Link>,
+    /// Embedded resources keyed by their relation.
+    #[serde(rename = "_embedded", default, skip_serializing_if = "Option::is_none")]
+    pub embedded: Option<E>,
+}
+
+impl<T> Hal<T> {
+    /// Wrap a resource without any links.
+    pub fn new(resource: T) -> Self {
+        Self {
+            resource,
+            links: IndexMap::default(),
+            embedded: None,
+        }
+    }
+}
+
+impl<T, E> Hal<T, E> {
+    /// Add a link with the given relation.
+    #[must_use]
+    pub fn with_link(mut self, rel: impl Into<String>, link: impl Into<Link>) -> Self {
+        self.links.insert(rel.into(), link.into());
+        self
+    }
+
+    /// Set the embedded resources.
+    #[must_use]
+    pub fn with_embedded(mut self, embedded: E) -> Self {
+        self.embedded = Some(embedded);
+        self
+    }
+}
+
+/// A HAL link object.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Link {
+    /// The target URI or URI template.
+    pub href: String,
+    /// Whether `href` is a URI template.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub templated: Option<bool>,
+    /// A human-readable title of the link.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl From<&str> for Link {
+    fn from(href: &str) -> Self {
+        Self {
+            href: href.into(),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<String> for Link {
+    fn from(href: String) -> Self {
+        Self {
+            href,
+            ..Default::default()
+        }
+    }
+}
+
+impl<T, E> OperationOutput for Hal<T, E>
+where
+    T: JsonSchema,
+    E: JsonSchema,
+{
+    type Inner = Self;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(media_response::<Self>(ctx, MEDIA_TYPE, ""))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Link relations of an operation, see [`hal_links`].
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct HalLinks {
+    relations: IndexMap<String, (String, bool)>,
+}
+
+impl HalLinks {
+    /// Create an empty set of link relations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declare a link relation that is always present.
+    pub fn link(mut self, rel: &str, description: &str) -> Self {
+        self.relations
+            .insert(rel.into(), (description.into(), true));
+        self
+    }
+
+    /// Declare a link relation that might be missing.
+    pub fn optional_link(mut self, rel: &str, description: &str) -> Self {
+        self.relations
+            .insert(rel.into(), (description.into(), false));
+        self
+    }
+
+    fn schema(&self, ctx: &mut GenContext) -> Schema {
+        let link = ctx.schema.subschema_for::<Link>().into_object();
+
+        let mut links = ObjectValidation::default();
+        for (rel, (description, required)) in &self.relations {
+            let mut schema = link.clone();
+            schema.metadata = Some(Box::new(Metadata {
+                description: Some(description.clone()),
+                ..Default::default()
+            }));
+            links.properties.insert(rel.clone(), schema.into());
+
+            if *required {
+                links.required.insert(rel.clone());
+            }
+        }
+
+        let mut object = ObjectValidation::default();
+        object.properties.insert(
+            LINKS_FIELD.into(),
+            SchemaObject {
+                instance_type: Some(InstanceType::Object.into()),
+                object: Some(Box::new(links)),
+                ..Default::default()
+            }
+            .into(),
+        );
+        object.required.insert(LINKS_FIELD.into());
+
+        SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            object: Some(Box::new(object)),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Document the link relations of all `application/hal+json`
+/// responses of an operation.
+///
+/// The relations are composed with the existing response
+/// schemas via `allOf`, so this should be applied after the
+/// responses are documented.
+pub fn hal_links(links: HalLinks) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    move |mut op| {
+        let operation = op.inner_mut();
+
+        in_context(|ctx| {
+            let links = links.schema(ctx);
+
+            let Some(responses) = &mut operation.responses else {
+                return;
+            };
+
+            let responses = responses
+                .default
+                .iter_mut()
+                .chain(responses.responses.values_mut())
+                .filter_map(|res| res.as_item_mut());
+
+            for res in responses {
+                let Some(content) = res.content.get_mut(MEDIA_TYPE) else {
+                    continue;
+                };
+
+                if let Some(schema) = &mut content.schema {
+                    let resource = std::mem::replace(&mut schema.json_schema, Schema::Bool(true));
+                    schema.json_schema = SchemaObject {
+                        subschemas: Some(Box::new(SubschemaValidation {
+                            all_of: Some(vec![resource, links.clone()]),
+                            ..Default::default()
+                        })),
+                        ..Default::default()
+                    }
+                    .into();
+                }
+            }
+        });
+
+        op
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::response::{IntoResponse, Response};
+    use http::{header, HeaderValue, StatusCode};
+    use serde::Serialize;
+
+    use super::{Hal, MEDIA_TYPE};
+
+    impl<T, E> IntoResponse for Hal<T, E>
+    where
+        T: Serialize,
+        E: Serialize,
+    {
+        fn into_response(self) -> Response {
+            match serde_json::to_vec(&self) {
+                Ok(body) => (
+                    [(header::CONTENT_TYPE, HeaderValue::from_static(MEDIA_TYPE))],
+                    body,
+                )
+                    .into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    use super::{hal_links, Hal, HalLinks, MEDIA_TYPE};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+    struct Order {
+        total: u64,
+    }
+
+    async fn get_order() -> Hal<Order> {
+        Hal::new(Order { total: 10 }).with_link("self", "/orders/1")
+    }
+
+    #[test]
+    fn test_hal_links() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/orders/:id",
+                get_with(get_order, |op| {
+                    op.with(hal_links(
+                        HalLinks::new()
+                            .link("self", "The order itself.")
+                            .optional_link("payment", "The payment of the order."),
+                    ))
+                }),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let schema =
+            &paths["/orders/{id}"]["get"]["responses"]["200"]["content"][MEDIA_TYPE]["schema"];
+        let all_of = schema["allOf"].as_array().unwrap();
+
+        assert_eq!(all_of.len(), 2);
+        assert_eq!(
+            all_of[0]["$ref"],
+            "#/components/schemas/Hal_for_Order_and_Map_of_AnyValue"
+        );
+        assert_eq!(all_of[1]["required"], json!(["_links"]));
+
+        let links = &all_of[1]["properties"]["_links"];
+        assert_eq!(links["required"], json!(["self"]));
+        assert_eq!(
+            links["properties"]["payment"]["description"],
+            "The payment of the order."
+        );
+        assert_eq!(
+            links["properties"]["self"]["$ref"],
+            "#/components/schemas/Link"
+        );
+    }
+
+    #[test]
+    fn test_hal_serialization() {
+        let order: Hal<Order> = Hal::new(Order { total: 10 }).with_link("self", "/orders/1");
+        let value = serde_json::to_value(&order).unwrap();
+
+        assert_eq!(
+            value,
+            json!({ "total": 10, "_links": { "self": { "href": "/orders/1" } } })
+        );
+        assert_eq!(serde_json::from_value::<Hal<Order>>(value).unwrap(), order);
+    }
+}
@@ -0,0 +1,364 @@
+//! [JSON:API](https://jsonapi.org) documentation support.
+//!
+//! [`JsonApi<T>`] documents request and response bodies with the
+//! `application/vnd.api+json` media type, the structure of the
+//! documents is described by [`Document`], [`Resource`] and
+//! [`Relationship`] that are generic over the attributes and
+//! relationships of a resource type.
+//!
+//! The `include` and sparse fieldset (`fields[TYPE]`) query parameters
+//! can be documented with [`include()`] and [`sparse_fieldsets`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::jsonapi::{include, sparse_fieldsets, Document, JsonApi, Resource, ToOne},
+//!     transform::TransformOperation,
+//! };
+//! # #[derive(schemars::JsonSchema)]
+//! # struct Article { title: String }
+//! # #[derive(schemars::JsonSchema)]
+//! # struct ArticleRelationships { author: ToOne }
+//!
+//! type ArticleDocument = Document<Resource<Article, ArticleRelationships>>;
+//!
+//! fn get_article_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, JsonApi<ArticleDocument>>()
+//!         .with(include)
+//!         .with(sparse_fieldsets(&["articles", "people"]))
+//! }
+//! ```
+
+use indexmap::IndexMap;
+use schemars::{
+    schema::{InstanceType, ObjectValidation, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Operation, QueryStyle, Response},
+    operation::set_body,
+    transform::TransformOperation,
+    OperationInput, OperationOutput,
+};
+
+use super::{add_missing_parameters, media_request_body, media_response, query_parameter};
+
+/// The JSON:API media type.
+pub const MEDIA_TYPE: &str = "application/vnd.api+json";
+
+/// A JSON:API request or response body of `T`,
+/// usually a [`Document`] or an [`ErrorDocument`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonApi<T>(pub T);
+
+/// A JSON:API top-level document with the primary data `D`.
+///
+/// `D` is either a [`Resource`], an optional one for
+/// single resources or a `Vec` of them for collections.
+/// Included resources are documented by `I`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(bound(deserialize = "D: Deserialize<'de>, I: Deserialize<'de>"))]
+pub struct Document<D, I = Resource<serde_json::Value, serde_json::Value>> {
+    /// The primary data of the document.
+    pub data: D,
+    /// Resources related to the primary data.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub included: Vec<I>,
+    /// Links related to the primary data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<IndexMap<String, String>>,
+    /// Non-standard meta-information.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meta: Option<serde_json::Value>,
+}
+
+/// A JSON:API resource object with the attributes `A`
+/// and the relationships `R`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(bound(deserialize = "A: Deserialize<'de>, R: Deserialize<'de>"))]
+pub struct Resource<A, R = ()> {
+    /// The type of the resource.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The identifier of the resource, it can be omitted
+    /// for resources that are created by the server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// The attributes of the resource.
+    pub attributes: A,
+    /// Relationships to other resources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relationships: Option<R>,
+}
+
+/// Identifies a single resource.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+pub struct ResourceIdentifier {
+    /// The type of the resource.
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The identifier of the resource.
+    pub id: String,
+}
+
+/// A relationship with the resource linkage `D`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Relationship<D> {
+    /// The resource linkage.
+    pub data: D,
+    /// Links related to the relationship.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub links: Option<IndexMap<String, String>>,
+}
+
+/// A to-one relationship, `null` if empty.
+pub type ToOne = Relationship<Option<ResourceIdentifier>>;
+
+/// A to-many relationship.
+pub type ToMany = Relationship<Vec<ResourceIdentifier>>;
+
+/// A JSON:API document containing errors.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorDocument {
+    /// The errors that occurred.
+    pub errors: Vec<ErrorObject>,
+}
+
+/// A single JSON:API error.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct ErrorObject {
+    /// The HTTP status code as a string.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// An application-specific error code.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// A short summary of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// An explanation specific to this occurrence of the problem.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+impl<T> OperationInput for JsonApi<T>
+where
+    T: JsonSchema,
+{
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        let body = media_request_body::<T>(ctx, MEDIA_TYPE);
+        set_body(ctx, operation, body);
+    }
+}
+
+impl<T> OperationOutput for JsonApi<T>
+where
+    T: JsonSchema,
+{
+    type Inner = T;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(media_response::<T>(ctx, MEDIA_TYPE, ""))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Document the `include` query parameter for
+/// including related resources.
+pub fn include(mut op: TransformOperation) -> TransformOperation {
+    in_context(|ctx| {
+        let param = query_parameter(
+            "include",
+            "A comma-separated list of relationship paths to include in the response.",
+            ctx.schema.subschema_for::<String>(),
+            QueryStyle::Form,
+        );
+        add_missing_parameters(ctx, op.inner_mut(), [param]);
+    });
+
+    op
+}
+
+/// Document the `fields[TYPE]` sparse fieldset query parameters
+/// for the given resource types.
+pub fn sparse_fieldsets(
+    types: &[&str],
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    let mut object = ObjectValidation::default();
+
+    for ty in types {
+        object.properties.insert(
+            (*ty).into(),
+            Schema::Object(SchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                ..Default::default()
+            }),
+        );
+    }
+
+    let schema = Schema::Object(SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        object: Some(Box::new(object)),
+        ..Default::default()
+    });
+
+    move |mut op| {
+        let param = query_parameter(
+            "fields",
+            "Comma-separated lists of the fields to return for each resource type.",
+            schema,
+            QueryStyle::DeepObject,
+        );
+        in_context(|ctx| add_missing_parameters(ctx, op.inner_mut(), [param]));
+        op
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::{
+        async_trait,
+        body::Body,
+        extract::{rejection::JsonRejection, FromRequest},
+        response::{IntoResponse, Response},
+        Json,
+    };
+    use http::{header, HeaderValue, Request, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::{JsonApi, MEDIA_TYPE};
+
+    impl<T> IntoResponse for JsonApi<T>
+    where
+        T: Serialize,
+    {
+        fn into_response(self) -> Response {
+            match serde_json::to_vec(&self.0) {
+                Ok(body) => (
+                    [(header::CONTENT_TYPE, HeaderValue::from_static(MEDIA_TYPE))],
+                    body,
+                )
+                    .into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl<T, S> FromRequest<S> for JsonApi<T>
+    where
+        T: DeserializeOwned,
+        S: Send + Sync,
+    {
+        type Rejection = JsonRejection;
+
+        async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+            // `+json` media types are accepted by the JSON extractor.
+            let Json(value) = Json::<T>::from_request(req, state).await?;
+            Ok(Self(value))
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+
+    use super::{include, sparse_fieldsets, Document, JsonApi, Resource, ToOne, MEDIA_TYPE};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct Article {
+        title: String,
+    }
+
+    #[derive(Serialize, Deserialize, JsonSchema)]
+    struct ArticleRelationships {
+        author: ToOne,
+    }
+
+    type ArticleDocument = Document<Resource<Article, ArticleRelationships>>;
+
+    async fn get_article() -> JsonApi<ArticleDocument> {
+        JsonApi(Document {
+            data: Resource {
+                kind: "articles".into(),
+                id: Some("1".into()),
+                attributes: Article {
+                    title: "JSON:API".into(),
+                },
+                relationships: None,
+            },
+            included: Vec::new(),
+            links: None,
+            meta: None,
+        })
+    }
+
+    async fn update_article(JsonApi(_document): JsonApi<ArticleDocument>) {}
+
+    #[test]
+    fn test_jsonapi() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/articles/:id",
+                get_with(get_article, |op| {
+                    op.with(include)
+                        .with(sparse_fieldsets(&["articles", "people"]))
+                })
+                .patch(update_article),
+            )
+            .finish_api(&mut api);
+
+        let value = serde_json::to_value(&api).unwrap();
+        let get = &value["paths"]["/articles/{id}"]["get"];
+        let patch = &value["paths"]["/articles/{id}"]["patch"];
+        let document_ref = "#/components/schemas/\
+            Document_for_Resource_for_Article_and_ArticleRelationships_and_Resource_for_AnyValue_and_AnyValue";
+
+        assert_eq!(
+            get["responses"]["200"]["content"][MEDIA_TYPE]["schema"]["$ref"],
+            document_ref
+        );
+        assert_eq!(
+            patch["requestBody"]["content"][MEDIA_TYPE]["schema"]["$ref"],
+            document_ref
+        );
+
+        let params = get["parameters"].as_array().unwrap();
+        let param = |name: &str| params.iter().find(|p| p["name"] == name).unwrap();
+        assert_eq!(param("include")["in"], "query");
+        assert_eq!(param("fields")["style"], "deepObject");
+        assert_eq!(
+            param("fields")["schema"]["properties"]["people"]["type"],
+            "string"
+        );
+
+        let schemas = &value["components"]["schemas"];
+        assert!(
+            schemas["Resource_for_Article_and_ArticleRelationships"]["properties"]
+                .get("type")
+                .is_some()
+        );
+        assert!(schemas["Relationship_for_Nullable_ResourceIdentifier"].is_object());
+    }
+}
@@ -0,0 +1,179 @@
+//! Documentation of the responses of `tower-http` layers.
+//!
+//! Middleware layers can respond without ever calling the handler,
+//! these responses are invisible to the generated documentation.
+//! [`LayerResponses`] describes the layers that are installed, and
+//! [`layer_responses`] or [`layer_responses_routes`] documents the
+//! responses they produce:
+//!
+//! - `CatchPanicLayer`: `500 Internal Server Error` if a handler panics,
+//! - `TimeoutLayer`: `408 Request Timeout`, or `504 Gateway Timeout`
+//!   if the layer is configured to respond with it,
+//! - `RequestBodyLimitLayer`: `413 Payload Too Large` for
+//!   operations with a request body.
+//!
+//! Responses already documented for an operation are kept.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! let layers = LayerResponses::new()
+//!     .catch_panic()
+//!     .timeout()
+//!     .request_body_limit();
+//!
+//! ApiRouter::new()
+//!     .api_route("/todos", post(create_todo))
+//!     .with_path_items(layer_responses_routes(layers))
+//!     .layer(CatchPanicLayer::new())
+//!     .layer(TimeoutLayer::new(Duration::from_secs(10)))
+//!     .layer(RequestBodyLimitLayer::new(1024 * 1024));
+//! ```
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Operation, Response},
+    transform::{TransformOperation, TransformPathItem},
+    util::iter_operations_mut,
+};
+
+use super::{add_response_if_missing, media_response};
+
+/// The `tower-http` layers in front of the operations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[must_use]
+pub struct LayerResponses {
+    catch_panic: bool,
+    timeout: Option<u16>,
+    request_body_limit: bool,
+}
+
+impl LayerResponses {
+    /// No layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A `CatchPanicLayer`, documented as a
+    /// `500 Internal Server Error` response.
+    pub fn catch_panic(mut self) -> Self {
+        self.catch_panic = true;
+        self
+    }
+
+    /// A `TimeoutLayer`, documented as a `408 Request Timeout` response.
+    pub fn timeout(mut self) -> Self {
+        self.timeout = Some(408);
+        self
+    }
+
+    /// A timeout that responds with `504 Gateway Timeout`,
+    /// e.g. in front of a proxy to an upstream service.
+    pub fn gateway_timeout(mut self) -> Self {
+        self.timeout = Some(504);
+        self
+    }
+
+    /// A `RequestBodyLimitLayer`, documented as a
+    /// `413 Payload Too Large` response of operations with a request body.
+    pub fn request_body_limit(mut self) -> Self {
+        self.request_body_limit = true;
+        self
+    }
+
+    fn apply(self, ctx: &mut GenContext, operation: &mut Operation) {
+        if self.catch_panic {
+            let res = text_response(ctx, "the request failed unexpectedly");
+            add_response_if_missing(operation, 500, res);
+        }
+
+        if let Some(status) = self.timeout {
+            add_response_if_missing(operation, status, timeout_response(ctx, status));
+        }
+
+        if self.request_body_limit && operation.request_body.is_some() {
+            add_response_if_missing(operation, 413, body_limit_response(ctx));
+        }
+    }
+}
+
+/// The response of a timeout with the status `408` or `504`.
+pub(crate) fn timeout_response(ctx: &mut GenContext, status: u16) -> Response {
+    if status == 504 {
+        text_response(ctx, "the upstream service took too long to respond")
+    } else {
+        text_response(ctx, "the request took too long to process")
+    }
+}
+
+/// The `413` response of a request body that exceeds the limit.
+pub(crate) fn body_limit_response(ctx: &mut GenContext) -> Response {
+    text_response(ctx, "the request body is too large")
+}
+
+/// Document the responses of the given layers for an operation.
+pub fn layer_responses(
+    layers: LayerResponses,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    move |mut op| {
+        in_context(|ctx| layers.apply(ctx, op.inner_mut()));
+        op
+    }
+}
+
+/// Document the responses of the given layers for all operations
+/// of path items, meant to be used with
+/// [`ApiRouter::with_path_items`](crate::axum::ApiRouter::with_path_items).
+pub fn layer_responses_routes(
+    layers: LayerResponses,
+) -> impl FnMut(TransformPathItem) -> TransformPathItem + Clone {
+    move |mut path| {
+        in_context(|ctx| {
+            for (_, op) in iter_operations_mut(path.inner_mut()) {
+                layers.apply(ctx, op);
+            }
+        });
+        path
+    }
+}
+
+fn text_response(ctx: &mut GenContext, description: &str) -> Response {
+    media_response::<String>(ctx, "text/plain; charset=utf-8", description)
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use super::{layer_responses_routes, LayerResponses};
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    async fn handler(_body: String) {}
+
+    #[test]
+    fn test_layer_responses() {
+        let layers = LayerResponses::new()
+            .catch_panic()
+            .gateway_timeout()
+            .request_body_limit();
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/body", get(handler).post(handler))
+            .api_route("/empty", get(|| async {}))
+            .with_path_items(layer_responses_routes(layers))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let responses = paths["/body"]["post"]["responses"].as_object().unwrap();
+        for status in ["500", "504", "413"] {
+            assert!(responses.contains_key(status), "{status}");
+        }
+
+        let responses = paths["/empty"]["get"]["responses"].as_object().unwrap();
+        assert!(responses.contains_key("504"));
+        assert!(!responses.contains_key("413"));
+    }
+}
@@ -0,0 +1,240 @@
+//! Documenting JSON Merge Patch ([RFC 7396]) requests without
+//! defining a parallel struct with optional fields.
+//!
+//! The schema of a [`PatchOf<T>`] is the schema of `T` with all
+//! properties optional and nullable, as `null` removes a property
+//! in a merge patch. It is a component schema named after `T`,
+//! e.g. `UserPatch` for `User`.
+//!
+//! Only the top-level properties are changed, the properties of nested
+//! objects are documented as they are in `T`.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! async fn update_user(
+//!     Path(id): Path<u64>,
+//!     Json(patch): Json<PatchOf<User>>,
+//! ) -> Result<Json<User>, ApiError> {
+//!     let user = patch.apply(&load_user(id)?)?;
+//!     Ok(Json(save_user(id, user)?))
+//! }
+//! ```
+//!
+//! [RFC 7396]: https://www.rfc-editor.org/rfc/rfc7396
+
+use std::{fmt, marker::PhantomData};
+
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
+    JsonSchema,
+};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::patch::apply_merge_patch;
+
+/// A JSON Merge Patch of a `T`, see the [module documentation](self).
+pub struct PatchOf<T> {
+    patch: Value,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> PatchOf<T> {
+    /// Wrap a JSON Merge Patch.
+    #[must_use]
+    pub fn new(patch: Value) -> Self {
+        Self {
+            patch,
+            _type: PhantomData,
+        }
+    }
+
+    /// The JSON Merge Patch.
+    #[must_use]
+    pub fn as_value(&self) -> &Value {
+        &self.patch
+    }
+
+    /// Unwrap the JSON Merge Patch.
+    #[must_use]
+    pub fn into_value(self) -> Value {
+        self.patch
+    }
+}
+
+impl<T> PatchOf<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    /// Apply the patch to a copy of `target`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the patched value is not a valid `T`.
+    pub fn apply(&self, target: &T) -> Result<T, serde_json::Error> {
+        let mut document = serde_json::to_value(target)?;
+        apply_merge_patch(&mut document, &self.patch);
+        serde_json::from_value(document)
+    }
+}
+
+impl<T> Clone for PatchOf<T> {
+    fn clone(&self) -> Self {
+        Self::new(self.patch.clone())
+    }
+}
+
+impl<T> fmt::Debug for PatchOf<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("PatchOf").field(&self.patch).finish()
+    }
+}
+
+impl<T> PartialEq for PatchOf<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.patch == other.patch
+    }
+}
+
+impl<T> Serialize for PatchOf<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.patch.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for PatchOf<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(Self::new)
+    }
+}
+
+impl<T: JsonSchema> JsonSchema for PatchOf<T> {
+    fn schema_name() -> String {
+        format!("{}Patch", T::schema_name())
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        let mut schema = match T::json_schema(gen) {
+            Schema::Object(schema) => schema,
+            schema @ Schema::Bool(_) => return schema,
+        };
+
+        if let Some(object) = &mut schema.object {
+            object.required.clear();
+            for property in object.properties.values_mut() {
+                make_nullable(property);
+            }
+        }
+
+        Schema::Object(schema)
+    }
+}
+
+/// Allow `null` as the value of a schema.
+fn make_nullable(schema: &mut Schema) {
+    let Schema::Object(object) = schema else {
+        return;
+    };
+
+    let simple = object.reference.is_none()
+        && object.enum_values.is_none()
+        && object.const_value.is_none()
+        && object.subschemas.is_none();
+
+    match &mut object.instance_type {
+        Some(SingleOrVec::Single(ty)) if simple => {
+            if **ty != InstanceType::Null {
+                object.instance_type = Some(vec![**ty, InstanceType::Null].into());
+            }
+        }
+        Some(SingleOrVec::Vec(types)) if simple => {
+            if !types.contains(&InstanceType::Null) {
+                types.push(InstanceType::Null);
+            }
+        }
+        _ => {
+            let null = SchemaObject {
+                instance_type: Some(InstanceType::Null.into()),
+                ..Default::default()
+            };
+            *schema = Schema::Object(SchemaObject {
+                subschemas: Some(Box::new(SubschemaValidation {
+                    any_of: Some(vec![schema.clone(), null.into()]),
+                    ..Default::default()
+                })),
+                ..Default::default()
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::gen::SchemaSettings;
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+    struct Address {
+        city: String,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+    struct User {
+        name: String,
+        age: Option<u8>,
+        address: Address,
+    }
+
+    #[test]
+    fn test_schema() {
+        let mut gen = SchemaSettings::draft07().into_generator();
+        let _ = gen.subschema_for::<PatchOf<User>>();
+
+        let schema = serde_json::to_value(&gen.definitions()["UserPatch"]).unwrap();
+        assert!(schema.get("required").is_none());
+        assert_eq!(
+            schema["properties"]["name"]["type"],
+            json!(["string", "null"])
+        );
+        assert_eq!(
+            schema["properties"]["age"]["type"],
+            json!(["integer", "null"])
+        );
+        assert_eq!(
+            schema["properties"]["address"]["anyOf"][1],
+            json!({ "type": "null" })
+        );
+    }
+
+    #[test]
+    fn test_apply() {
+        let user = User {
+            name: "alice".into(),
+            age: Some(30),
+            address: Address {
+                city: "Berlin".into(),
+            },
+        };
+
+        let patch: PatchOf<User> =
+            serde_json::from_value(json!({ "age": null, "address": { "city": "Paris" } })).unwrap();
+
+        assert_eq!(
+            patch.apply(&user).unwrap(),
+            User {
+                name: "alice".into(),
+                age: None,
+                address: Address {
+                    city: "Paris".into()
+                },
+            }
+        );
+        assert!(PatchOf::<User>::new(json!({ "name": null }))
+            .apply(&user)
+            .is_err());
+    }
+}
@@ -1,3 +1,395 @@
+//! Reusable helper types and transform functions
+//! for common API conventions.
+
+use indexmap::IndexMap;
+use schemars::{schema::Schema, JsonSchema};
+
+use crate::{
+    gen::GenContext,
+    openapi::{
+        Header, HeaderStyle, MediaType, Operation, Parameter, ParameterData,
+        ParameterSchemaOrContent, PathStyle, QueryStyle, ReferenceOr, RequestBody, Response,
+        SchemaObject, StatusCode,
+    },
+    operation::add_parameters,
+};
+
+pub mod async_operation;
+pub mod batch;
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+mod binary;
+pub mod bulk;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod code_samples;
+pub mod conditional;
+pub mod cors;
+pub mod dictionary;
+pub mod envelope;
+#[cfg(feature = "axum")]
+pub mod extension;
+pub mod extractor;
+pub mod filter;
+#[cfg(feature = "geojson")]
+pub mod geojson;
+pub mod hal;
+#[cfg(feature = "jsonapi")]
+pub mod jsonapi;
+pub mod layers;
+pub mod merge_patch;
+#[cfg(feature = "msgpack")]
+pub mod msgpack;
+pub mod negotiated;
 pub mod no_api;
-pub mod with_api;
+pub mod oidc;
+#[cfg(feature = "axum")]
+pub mod one_of;
+#[cfg(feature = "axum")]
+pub mod ops;
+pub mod pagination;
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+pub mod range;
+pub mod rate_limit;
+pub mod redirect;
+pub mod separated;
+pub mod servers;
+pub mod status;
+pub mod sunset;
+pub mod trace;
 pub mod use_api;
+pub mod with_api;
+#[cfg(feature = "xml")]
+pub mod xml;
+
+pub use bulk::{Csv, NdJson};
+pub use envelope::Envelope;
+pub use merge_patch::PatchOf;
+pub use negotiated::Negotiated;
+#[cfg(feature = "axum")]
+pub use ops::ops_routes;
+pub use pagination::Paginated;
+
+/// Add parameters to an operation, skipping the ones
+/// that already exist (e.g. because an extractor documented them).
+pub(crate) fn add_missing_parameters(
+    ctx: &mut GenContext,
+    operation: &mut Operation,
+    params: impl IntoIterator<Item = Parameter>,
+) {
+    let params = params
+        .into_iter()
+        .filter(|param| {
+            // `add_parameters` reports parameters with the same name in any location.
+            !operation
+                .parameters
+                .iter()
+                .filter_map(ReferenceOr::as_item)
+                .any(|p| {
+                    p.parameter_data_ref().name == param.parameter_data_ref().name
+                        || is_same_parameter(p, param)
+                })
+        })
+        .collect::<Vec<_>>();
+
+    add_parameters(ctx, operation, params);
+}
+
+/// Whether two parameters are the same, i.e. they have the same location
+/// and name, the names of header parameters are case-insensitive.
+pub(crate) fn is_same_parameter(a: &Parameter, b: &Parameter) -> bool {
+    let (a_name, b_name) = (&a.parameter_data_ref().name, &b.parameter_data_ref().name);
+
+    match (a, b) {
+        (Parameter::Header { .. }, Parameter::Header { .. }) => a_name.eq_ignore_ascii_case(b_name),
+        _ => std::mem::discriminant(a) == std::mem::discriminant(b) && a_name == b_name,
+    }
+}
+
+/// A header parameter with the schema of `T`.
+pub(crate) fn header_parameter<T: JsonSchema>(
+    ctx: &mut GenContext,
+    name: &str,
+    description: &str,
+    required: bool,
+) -> Parameter {
+    Parameter::Header {
+        parameter_data: ParameterData {
+            name: name.into(),
+            description: Some(description.into()),
+            required,
+            format: ParameterSchemaOrContent::Schema(SchemaObject {
+                json_schema: ctx.schema.subschema_for::<T>(),
+                example: None,
+                external_docs: None,
+            }),
+            extensions: Default::default(),
+            deprecated: None,
+            example: None,
+            examples: IndexMap::default(),
+            explode: None,
+        },
+        style: HeaderStyle::Simple,
+    }
+}
+
+/// A required path parameter with the schema of `T`.
+pub(crate) fn path_parameter<T: JsonSchema>(ctx: &mut GenContext, name: &str) -> Parameter {
+    Parameter::Path {
+        parameter_data: ParameterData {
+            name: name.into(),
+            description: None,
+            required: true,
+            format: ParameterSchemaOrContent::Schema(SchemaObject {
+                json_schema: ctx.schema.subschema_for::<T>(),
+                example: None,
+                external_docs: None,
+            }),
+            extensions: Default::default(),
+            deprecated: None,
+            example: None,
+            examples: IndexMap::default(),
+            explode: None,
+        },
+        style: PathStyle::Simple,
+    }
+}
+
+/// An optional query parameter with the given schema.
+pub(crate) fn query_parameter(
+    name: &str,
+    description: &str,
+    schema: Schema,
+    style: QueryStyle,
+) -> Parameter {
+    Parameter::Query {
+        parameter_data: ParameterData {
+            name: name.into(),
+            description: Some(description.into()),
+            required: false,
+            format: ParameterSchemaOrContent::Schema(SchemaObject {
+                json_schema: schema,
+                example: None,
+                external_docs: None,
+            }),
+            extensions: Default::default(),
+            deprecated: None,
+            example: None,
+            examples: IndexMap::default(),
+            explode: None,
+        },
+        allow_reserved: false,
+        style,
+        allow_empty_value: None,
+    }
+}
+
+/// A response header with the schema of `T`.
+pub(crate) fn response_header<T: JsonSchema>(ctx: &mut GenContext, description: &str) -> Header {
+    Header {
+        description: Some(description.into()),
+        style: HeaderStyle::Simple,
+        required: false,
+        deprecated: None,
+        format: ParameterSchemaOrContent::Schema(SchemaObject {
+            json_schema: ctx.schema.subschema_for::<T>(),
+            example: None,
+            external_docs: None,
+        }),
+        example: None,
+        examples: IndexMap::default(),
+        extensions: IndexMap::default(),
+    }
+}
+
+/// Whether the media type is JSON or has a `+json` suffix,
+/// media types are compared case-insensitively.
+pub(crate) fn is_json_media_type(media_type: &str) -> bool {
+    let essence = media_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+/// Whether the status code is a successful (`2xx`) one.
+pub(crate) fn is_success(status: &StatusCode) -> bool {
+    match status {
+        StatusCode::Code(code) => (200..300).contains(code),
+        StatusCode::Range(range) => *range == 2,
+    }
+}
+
+/// Add a header to all successful (`2xx`) responses of an operation.
+///
+/// Existing headers with the same name, ignoring the case,
+/// are left untouched.
+pub(crate) fn add_success_response_header(operation: &mut Operation, name: &str, header: &Header) {
+    add_response_header(operation, name, header, |status| {
+        status.is_some_and(is_success)
+    });
+}
+
+/// Add a header to the responses of an operation with a status code
+/// that matches the filter, the status code of the default response
+/// is `None`.
+///
+/// Existing headers with the same name, ignoring the case,
+/// are left untouched.
+pub(crate) fn add_response_header<F>(
+    operation: &mut Operation,
+    name: &str,
+    header: &Header,
+    filter: F,
+) where
+    F: Fn(Option<&StatusCode>) -> bool,
+{
+    let Some(responses) = &mut operation.responses else {
+        return;
+    };
+
+    let default = responses.default.iter_mut().map(|res| (None, res));
+    let responses = responses
+        .responses
+        .iter_mut()
+        .map(|(status, res)| (Some(status), res));
+
+    for (status, res) in default.chain(responses) {
+        if !filter(status) {
+            continue;
+        }
+
+        if let Some(res) = res.as_item_mut() {
+            if !res.headers.keys().any(|h| h.eq_ignore_ascii_case(name)) {
+                res.headers
+                    .insert(name.into(), ReferenceOr::Item(header.clone()));
+            }
+        }
+    }
+}
+
+/// Add a response with the given status code to an operation
+/// unless it is already documented.
+pub(crate) fn add_response_if_missing(operation: &mut Operation, status: u16, response: Response) {
+    operation
+        .responses
+        .get_or_insert_with(Default::default)
+        .responses
+        .entry(StatusCode::Code(status))
+        .or_insert_with(|| ReferenceOr::Item(response));
+}
+
+/// A response with an `application/json` body of `T`.
+pub(crate) fn json_response<T: JsonSchema>(ctx: &mut GenContext, description: &str) -> Response {
+    media_response::<T>(ctx, "application/json", description)
+}
+
+/// A response with a body of `T` with the given media type.
+pub(crate) fn media_response<T: JsonSchema>(
+    ctx: &mut GenContext,
+    media_type: &str,
+    description: &str,
+) -> Response {
+    Response {
+        description: description.into(),
+        content: IndexMap::from_iter([(media_type.into(), media_type_of::<T>(ctx))]),
+        ..Default::default()
+    }
+}
+
+/// A required request body of `T` with the given media type.
+pub(crate) fn media_request_body<T: JsonSchema>(
+    ctx: &mut GenContext,
+    media_type: &str,
+) -> RequestBody {
+    let schema = ctx.schema.subschema_for::<T>().into_object();
+    let description = ctx
+        .resolve_schema(&schema)
+        .metadata
+        .as_ref()
+        .and_then(|m| m.description.clone());
+
+    RequestBody {
+        description,
+        content: IndexMap::from_iter([(media_type.into(), media_type_of::<T>(ctx))]),
+        required: true,
+        extensions: IndexMap::default(),
+    }
+}
+
+fn media_type_of<T: JsonSchema>(ctx: &mut GenContext) -> MediaType {
+    MediaType {
+        schema: Some(SchemaObject {
+            json_schema: ctx.schema.subschema_for::<T>(),
+            example: None,
+            external_docs: None,
+        }),
+        ..Default::default()
+    }
+}
+
+/// The content of the request body and all responses of an operation.
+#[cfg(any(
+    feature = "cbor",
+    feature = "msgpack",
+    feature = "protobuf",
+    feature = "xml"
+))]
+pub(crate) fn body_contents_mut(
+    operation: &mut Operation,
+) -> impl Iterator<Item = &mut IndexMap<String, MediaType>> {
+    let request_body = operation
+        .request_body
+        .as_mut()
+        .and_then(|body| body.as_item_mut())
+        .map(|body| &mut body.content);
+
+    let responses = operation
+        .responses
+        .iter_mut()
+        .flat_map(|responses| {
+            responses
+                .default
+                .iter_mut()
+                .chain(responses.responses.values_mut())
+        })
+        .filter_map(|res| res.as_item_mut())
+        .map(|res| &mut res.content);
+
+    request_body.into_iter().chain(responses)
+}
+
+/// Document the media type `alternative` with the same schema
+/// next to every `application/json` body of an operation.
+#[cfg(any(feature = "cbor", feature = "msgpack"))]
+pub(crate) fn add_alternative_media_type(operation: &mut Operation, alternative: &str) {
+    for content in body_contents_mut(operation) {
+        let Some(json) = content.get("application/json") else {
+            continue;
+        };
+
+        let media = MediaType {
+            schema: json.schema.clone(),
+            ..Default::default()
+        };
+        content.entry(alternative.into()).or_insert(media);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::is_json_media_type;
+
+    #[test]
+    fn test_is_json_media_type() {
+        assert!(is_json_media_type("application/json"));
+        assert!(is_json_media_type("Application/JSON; charset=utf-8"));
+        assert!(is_json_media_type("application/problem+json"));
+        assert!(is_json_media_type("application/vnd.api+JSON"));
+        assert!(!is_json_media_type("text/plain"));
+        assert!(!is_json_media_type("application/jsonl"));
+    }
+}
@@ -0,0 +1,139 @@
+//! `MessagePack` bodies.
+//!
+//! [`Msgpack<T>`] documents `application/msgpack` request and
+//! response bodies with the same schema as the JSON representation
+//! of `T`. Routes that accept or return both representations can
+//! document the `MessagePack` alternative next to the existing JSON
+//! bodies with [`also_msgpack`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::msgpack::Msgpack, transform::TransformOperation};
+//! # #[derive(schemars::JsonSchema)]
+//! # struct User { name: String }
+//!
+//! fn get_user_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, Msgpack<User>>()
+//! }
+//! ```
+//!
+//! For handlers that negotiate between JSON and `MessagePack`:
+//!
+//! ```ignore
+//! fn get_user_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, Json<User>>().with(also_msgpack)
+//! }
+//! ```
+
+use super::binary::binary_body;
+
+/// The `MessagePack` media type.
+pub const MEDIA_TYPE: &str = "application/msgpack";
+
+binary_body! {
+    /// A `MessagePack` request or response body of `T`.
+    Msgpack,
+    /// Document `application/msgpack` next to every
+    /// `application/json` body of an operation.
+    also_msgpack,
+    media_type: MEDIA_TYPE,
+    encode: rmp_serde::to_vec_named,
+    decode: rmp_serde::from_slice,
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header::CONTENT_TYPE, Request, StatusCode},
+        routing::post,
+        Json,
+    };
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+    use tower_service::Service;
+
+    use super::{also_msgpack, Msgpack, MEDIA_TYPE};
+    use crate::{
+        axum::{routing::post_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+    struct User {
+        name: String,
+    }
+
+    async fn echo_user(Msgpack(user): Msgpack<User>) -> Msgpack<User> {
+        Msgpack(user)
+    }
+
+    async fn create_user(Json(user): Json<User>) -> Json<User> {
+        Json(user)
+    }
+
+    fn media_types(content: &Value) -> Vec<&String> {
+        content.as_object().unwrap().keys().collect()
+    }
+
+    #[test]
+    fn test_msgpack_docs() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/users/echo", post_with(echo_user, |op| op))
+            .api_route("/users", post_with(create_user, |op| op.with(also_msgpack)))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let echo = &paths["/users/echo"]["post"];
+        assert_eq!(media_types(&echo["requestBody"]["content"]), [MEDIA_TYPE]);
+        assert_eq!(
+            media_types(&echo["responses"]["200"]["content"]),
+            [MEDIA_TYPE]
+        );
+
+        // The alternative has the same schema as the JSON body.
+        let create = &paths["/users"]["post"];
+        for content in [
+            &create["requestBody"]["content"],
+            &create["responses"]["200"]["content"],
+        ] {
+            assert_eq!(media_types(content), ["application/json", MEDIA_TYPE]);
+            assert_eq!(
+                content[MEDIA_TYPE]["schema"],
+                content["application/json"]["schema"]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_round_trip() {
+        let mut app = axum::Router::new().route("/users", post(echo_user));
+
+        let user = User {
+            name: "Ferris".into(),
+        };
+        let res = app
+            .call(
+                Request::post("/users")
+                    .header(CONTENT_TYPE, MEDIA_TYPE)
+                    .body(Body::from(rmp_serde::to_vec_named(&user).unwrap()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[CONTENT_TYPE], MEDIA_TYPE);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(rmp_serde::from_slice::<User>(&body).unwrap(), user);
+
+        let res = app
+            .call(Request::post("/users").body(Body::from("{}")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,231 @@
+//! Content negotiation.
+//!
+//! Handlers that serve multiple representations of the same resource
+//! based on the `Accept` header can return [`Negotiated<T>`], where `T`
+//! is a tuple of the possible outputs. All of them are documented
+//! under the same status code with their own media types, along with
+//! the `Accept` request header.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! async fn export_orders(headers: HeaderMap) -> Negotiated<(Json<Vec<Order>>, Csv<Order>)> {
+//!     let orders = load_orders().await;
+//!
+//!     if accepts_csv(&headers) {
+//!         Negotiated::new(Csv::new(encode_csv(&orders)))
+//!     } else {
+//!         Negotiated::new(Json(orders))
+//!     }
+//! }
+//! ```
+
+use std::marker::PhantomData;
+
+use schemars::schema::{InstanceType, SchemaObject as JsonSchemaObject};
+
+use crate::{
+    gen::GenContext,
+    openapi::{Operation, ParameterSchemaOrContent, Response, SchemaObject},
+    OperationOutput,
+};
+
+use super::{add_missing_parameters, header_parameter};
+
+/// An output that is one of the representations in `T`,
+/// which is a tuple of [`OperationOutput`] types.
+///
+/// With the `axum` feature it can be created from
+/// any response with [`Negotiated::new`].
+pub struct Negotiated<T> {
+    #[cfg(feature = "axum")]
+    response: ::axum::response::Response,
+    _representations: PhantomData<fn() -> T>,
+}
+
+impl<T> std::fmt::Debug for Negotiated<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Negotiated").finish_non_exhaustive()
+    }
+}
+
+/// A tuple of the representations of a [`Negotiated`] output.
+pub trait Representations {
+    /// The responses of all representations.
+    fn responses(ctx: &mut GenContext, operation: &mut Operation) -> Vec<Response>;
+}
+
+macro_rules! impl_representations {
+    ( $($ty:ident),* $(,)? ) => {
+        impl<$($ty,)*> Representations for ($($ty,)*)
+        where
+            $( $ty: OperationOutput, )*
+        {
+            fn responses(ctx: &mut GenContext, operation: &mut Operation) -> Vec<Response> {
+                let mut responses = Vec::new();
+                $(
+                    responses.extend($ty::operation_response(ctx, operation));
+                )*
+                responses
+            }
+        }
+    };
+}
+
+all_the_tuples!(impl_representations);
+
+impl<T> OperationOutput for Negotiated<T>
+where
+    T: Representations,
+{
+    type Inner = Self;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<Response> {
+        let mut merged: Option<Response> = None;
+
+        for res in T::responses(ctx, operation) {
+            match &mut merged {
+                Some(merged) => {
+                    if merged.description.is_empty() {
+                        merged.description = res.description;
+                    }
+                    for (media_type, content) in res.content {
+                        merged.content.entry(media_type).or_insert(content);
+                    }
+                    for (name, header) in res.headers {
+                        merged.headers.entry(name).or_insert(header);
+                    }
+                    for (name, link) in res.links {
+                        merged.links.entry(name).or_insert(link);
+                    }
+                }
+                None => merged = Some(res),
+            }
+        }
+
+        let merged = merged?;
+
+        document_accept(ctx, operation, merged.content.keys());
+
+        Some(merged)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Document the `Accept` header with the given media types.
+fn document_accept<'m>(
+    ctx: &mut GenContext,
+    operation: &mut Operation,
+    media_types: impl Iterator<Item = &'m String>,
+) {
+    let mut accept = header_parameter::<String>(
+        ctx,
+        "Accept",
+        "The preferred media type of the response.",
+        false,
+    );
+
+    accept.parameter_data_mut().format = ParameterSchemaOrContent::Schema(SchemaObject {
+        json_schema: JsonSchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            enum_values: Some(media_types.map(|m| m.clone().into()).collect()),
+            ..Default::default()
+        }
+        .into(),
+        example: None,
+        external_docs: None,
+    });
+
+    add_missing_parameters(ctx, operation, [accept]);
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use std::marker::PhantomData;
+
+    use axum::response::{IntoResponse, Response};
+
+    use super::Negotiated;
+
+    impl<T> Negotiated<T> {
+        /// Create the output from the chosen representation.
+        pub fn new(response: impl IntoResponse) -> Self {
+            Self {
+                response: response.into_response(),
+                _representations: PhantomData,
+            }
+        }
+    }
+
+    impl<T> IntoResponse for Negotiated<T> {
+        fn into_response(self) -> Response {
+            self.response
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{response::IntoResponse, Json};
+    use schemars::JsonSchema;
+    use serde::Serialize;
+    use serde_json::json;
+
+    use super::Negotiated;
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        helpers::bulk::{Csv, CSV_MEDIA_TYPE},
+        openapi::OpenApi,
+    };
+
+    #[derive(Serialize, JsonSchema)]
+    struct Order {
+        id: u64,
+    }
+
+    async fn export_orders() -> Negotiated<(Json<Vec<Order>>, Csv<Order>)> {
+        Negotiated::new(Json(Vec::<Order>::new()))
+    }
+
+    #[test]
+    fn test_negotiated() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/orders", get(export_orders))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let operation = &paths["/orders"]["get"];
+        let content = &operation["responses"]["200"]["content"];
+
+        assert_eq!(content["application/json"]["schema"]["type"], "array");
+        assert_eq!(content[CSV_MEDIA_TYPE]["schema"]["type"], "string");
+        assert_eq!(operation["responses"]["200"]["description"], "CSV content");
+
+        let accept = &operation["parameters"][0];
+        assert_eq!(accept["name"], "Accept");
+        assert_eq!(accept["in"], "header");
+        assert_eq!(
+            accept["schema"]["enum"],
+            json!(["application/json", CSV_MEDIA_TYPE])
+        );
+    }
+
+    #[test]
+    fn test_negotiated_response() {
+        let res = Negotiated::<(Json<Vec<Order>>, Csv<Order>)>::new(Csv::<Order>::new("id\n"))
+            .into_response();
+
+        assert_eq!(res.headers()["content-type"], "text/csv");
+    }
+}
@@ -1,3 +1,5 @@
+//! Hide the documentation of a type with [`NoApi`].
+
 use std::ops::{Deref, DerefMut};
 
 use serde::{Deserialize, Serialize};
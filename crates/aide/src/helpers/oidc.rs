@@ -0,0 +1,269 @@
+//! `OpenID` Connect security schemes.
+//!
+//! [`OpenIdConnect`] builds an `openIdConnect` security scheme from the
+//! URL of the discovery document of the provider, which is validated
+//! when the builder is created. The scopes of the scheme are documented
+//! in the [`SCOPES_EXTENSION`] extension, either listed by hand or
+//! [discovered](OpenIdConnect::discover) from the discovery document
+//! at startup.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::oidc::OpenIdConnect, transform::TransformOpenApi};
+//!
+//! fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+//!     let oidc = OpenIdConnect::new("https://auth.example.com/.well-known/openid-configuration")
+//!         .expect("the discovery URL is valid")
+//!         .description("Sign in with the company account.")
+//!         .scope("todos:read", "Read todos.");
+//!
+//!     api.security_scheme("oidc", oidc.build())
+//! }
+//! ```
+//!
+//! The scopes supported by the provider can be fetched from the
+//! discovery document with any HTTP client:
+//!
+//! ```ignore
+//! let oidc = OpenIdConnect::new(discovery_url)?.discover(|url| {
+//!     reqwest::blocking::get(url)?.json::<serde_json::Value>()
+//! })?;
+//! ```
+
+use std::error::Error as StdError;
+
+use indexmap::IndexMap;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::openapi::SecurityScheme;
+
+/// The extension of `openIdConnect` security schemes that
+/// holds the documented scopes and their descriptions.
+pub const SCOPES_EXTENSION: &str = "x-scopes";
+
+/// The path of the discovery document relative to the issuer.
+const DISCOVERY_PATH: &str = "/.well-known/openid-configuration";
+
+/// Errors of [`OpenIdConnect`].
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum OidcError {
+    #[error(r#"invalid discovery URL "{url}": {reason}"#)]
+    InvalidUrl { url: String, reason: &'static str },
+    #[error(r#"failed to load the discovery document "{url}": {source}"#)]
+    Load {
+        url: String,
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    #[error("invalid discovery document: {0}")]
+    InvalidDocument(String),
+}
+
+/// A builder of `openIdConnect` security schemes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct OpenIdConnect {
+    url: String,
+    description: Option<String>,
+    scopes: IndexMap<String, String>,
+}
+
+impl OpenIdConnect {
+    /// A scheme with the URL of the discovery document of the provider.
+    ///
+    /// # Errors
+    ///
+    /// If the URL is not an absolute `https` URL (`http` is
+    /// only allowed for `localhost`) ending with
+    /// `/.well-known/openid-configuration`.
+    pub fn new(discovery_url: &str) -> Result<Self, OidcError> {
+        validate_discovery_url(discovery_url)?;
+
+        Ok(Self {
+            url: discovery_url.into(),
+            description: None,
+            scopes: IndexMap::new(),
+        })
+    }
+
+    /// Set the description of the scheme.
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    /// Document a scope of the scheme.
+    pub fn scope(mut self, name: &str, description: &str) -> Self {
+        self.scopes.insert(name.into(), description.into());
+        self
+    }
+
+    /// Document the scopes listed in the discovery document,
+    /// loaded from the discovery URL by `loader`.
+    ///
+    /// Scopes that are already documented keep their descriptions,
+    /// the others are documented without one.
+    ///
+    /// # Errors
+    ///
+    /// If the document cannot be loaded, or it is not
+    /// a discovery document for the discovery URL.
+    pub fn discover<F, E>(mut self, loader: F) -> Result<Self, OidcError>
+    where
+        F: FnOnce(&str) -> Result<Value, E>,
+        E: Into<Box<dyn StdError + Send + Sync>>,
+    {
+        let document = loader(&self.url).map_err(|source| OidcError::Load {
+            url: self.url.clone(),
+            source: source.into(),
+        })?;
+
+        let issuer = document
+            .get("issuer")
+            .and_then(Value::as_str)
+            .ok_or_else(|| OidcError::InvalidDocument("the issuer is missing".into()))?;
+        if format!("{}{DISCOVERY_PATH}", issuer.trim_end_matches('/')) != self.url {
+            return Err(OidcError::InvalidDocument(format!(
+                r#"the issuer "{issuer}" does not match the discovery URL"#
+            )));
+        }
+
+        let scopes = document
+            .get("scopes_supported")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str);
+        for scope in scopes {
+            self.scopes.entry(scope.into()).or_default();
+        }
+
+        Ok(self)
+    }
+
+    /// Build the security scheme.
+    #[must_use]
+    pub fn build(self) -> SecurityScheme {
+        let mut extensions = IndexMap::new();
+        if !self.scopes.is_empty() {
+            extensions.insert(
+                SCOPES_EXTENSION.into(),
+                Value::Object(
+                    self.scopes
+                        .into_iter()
+                        .map(|(name, description)| (name, description.into()))
+                        .collect(),
+                ),
+            );
+        }
+
+        SecurityScheme::OpenIdConnect {
+            open_id_connect_url: self.url,
+            description: self.description,
+            extensions,
+        }
+    }
+}
+
+impl From<OpenIdConnect> for SecurityScheme {
+    fn from(oidc: OpenIdConnect) -> Self {
+        oidc.build()
+    }
+}
+
+fn validate_discovery_url(url: &str) -> Result<(), OidcError> {
+    let invalid = |reason| {
+        Err(OidcError::InvalidUrl {
+            url: url.into(),
+            reason,
+        })
+    };
+
+    let (scheme, rest) = url.split_once("://").unwrap_or_default();
+    let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+    let host = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => host.split(':').next().unwrap_or_default(),
+    };
+
+    if host.is_empty() {
+        return invalid("the URL must be absolute");
+    }
+    match scheme {
+        "https" => {}
+        "http" if matches!(host, "localhost" | "127.0.0.1" | "::1") => {}
+        _ => return invalid("the URL must use https"),
+    }
+    if url.contains(|c: char| c.is_whitespace() || c == '#' || c == '?') {
+        return invalid("the URL must not contain whitespace, a query or a fragment");
+    }
+    if !format!("/{path}").ends_with(DISCOVERY_PATH) {
+        return invalid("the URL must end with /.well-known/openid-configuration");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{OidcError, OpenIdConnect, SCOPES_EXTENSION};
+    use crate::openapi::SecurityScheme;
+
+    const URL: &str = "https://auth.example.com/tenant/.well-known/openid-configuration";
+
+    #[test]
+    fn test_discovery_url() {
+        assert!(OpenIdConnect::new(URL).is_ok());
+        assert!(
+            OpenIdConnect::new("http://localhost:8080/.well-known/openid-configuration").is_ok()
+        );
+
+        for url in [
+            "http://auth.example.com/.well-known/openid-configuration",
+            "/.well-known/openid-configuration",
+            "https://auth.example.com/",
+            "https://auth.example.com/.well-known/openid-configuration?x=1",
+        ] {
+            assert!(
+                matches!(OpenIdConnect::new(url), Err(OidcError::InvalidUrl { .. })),
+                "{url}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_discover() {
+        let document = json!({
+            "issuer": "https://auth.example.com/tenant",
+            "scopes_supported": ["openid", "todos:read"],
+        });
+
+        let scheme = OpenIdConnect::new(URL)
+            .unwrap()
+            .scope("todos:read", "Read todos.")
+            .discover(|_| Ok::<_, OidcError>(document.clone()))
+            .unwrap()
+            .build();
+
+        let SecurityScheme::OpenIdConnect { extensions, .. } = scheme else {
+            panic!("expected an openIdConnect scheme");
+        };
+        assert_eq!(
+            extensions[SCOPES_EXTENSION],
+            json!({ "todos:read": "Read todos.", "openid": "" })
+        );
+
+        let err = OpenIdConnect::new(URL)
+            .unwrap()
+            .discover(|_| Ok::<_, OidcError>(json!({ "issuer": "https://evil.example.com" })));
+        assert!(matches!(err, Err(OidcError::InvalidDocument(_))));
+    }
+}
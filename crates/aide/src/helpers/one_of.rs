@@ -0,0 +1,261 @@
+//! Responses of handlers that branch between several response types.
+//!
+//! The responses of all types are merged by status code, the content
+//! of responses with the same status and media type is documented
+//! with `oneOf` the schemas.
+//!
+//! Besides [`OneOfResponse`], the `Either` types of `axum-extra`
+//! are documented the same way.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::helpers::one_of::OneOfResponse;
+//! use axum::{http::StatusCode, Json};
+//! # #[derive(serde::Serialize, schemars::JsonSchema)]
+//! # struct Todo { id: u64 }
+//! # #[derive(serde::Serialize, schemars::JsonSchema)]
+//! # struct Archived { reason: String }
+//!
+//! async fn get_todo() -> OneOfResponse<(Json<Todo>, Json<Archived>, StatusCode)> {
+//!     OneOfResponse::new(Json(Todo { id: 1 }))
+//! }
+//! ```
+
+use indexmap::map::Entry;
+use schemars::schema::{Schema, SchemaObject, SubschemaValidation};
+
+use crate::openapi::{MediaType, Response};
+
+/// Merge the responses of several types that are returned
+/// with the same status code.
+pub(crate) fn merge_responses(responses: impl IntoIterator<Item = Response>) -> Option<Response> {
+    responses.into_iter().reduce(|mut merged, res| {
+        if merged.description.is_empty() {
+            merged.description = res.description;
+        }
+
+        for (name, header) in res.headers {
+            merged.headers.entry(name).or_insert(header);
+        }
+        for (name, link) in res.links {
+            merged.links.entry(name).or_insert(link);
+        }
+
+        for (media_type, media) in res.content {
+            match merged.content.entry(media_type) {
+                Entry::Occupied(mut existing) => merge_media(existing.get_mut(), media),
+                Entry::Vacant(entry) => {
+                    entry.insert(media);
+                }
+            }
+        }
+
+        merged
+    })
+}
+
+/// Merge inferred `(status, response)` pairs of several types,
+/// responses with the same status are merged with [`merge_responses`].
+pub(crate) fn merge_inferred_responses(
+    responses: impl IntoIterator<Item = Vec<(Option<u16>, Response)>>,
+) -> Vec<(Option<u16>, Response)> {
+    let mut merged: Vec<(Option<u16>, Response)> = Vec::new();
+
+    for (status, res) in responses.into_iter().flatten() {
+        match merged.iter_mut().find(|(s, _)| *s == status) {
+            Some((_, existing)) => {
+                let current = std::mem::take(existing);
+                *existing = merge_responses([current, res]).unwrap_or_default();
+            }
+            None => merged.push((status, res)),
+        }
+    }
+
+    merged
+}
+
+fn merge_media(merged: &mut MediaType, media: MediaType) {
+    let (Some(existing), Some(schema)) = (&mut merged.schema, media.schema) else {
+        return;
+    };
+    if existing.json_schema == schema.json_schema {
+        return;
+    }
+
+    if is_one_of_only(&existing.json_schema) {
+        if let Schema::Object(SchemaObject {
+            subschemas: Some(subschemas),
+            ..
+        }) = &mut existing.json_schema
+        {
+            let one_of = subschemas.one_of.get_or_insert_with(Vec::new);
+            if !one_of.contains(&schema.json_schema) {
+                one_of.push(schema.json_schema);
+            }
+            return;
+        }
+    }
+
+    let first = std::mem::replace(&mut existing.json_schema, Schema::Bool(true));
+    existing.json_schema = Schema::Object(SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            one_of: Some(vec![first, schema.json_schema]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    });
+}
+
+/// Whether the schema is nothing but a `oneOf`, e.g. from a previous merge.
+fn is_one_of_only(schema: &Schema) -> bool {
+    let Schema::Object(object) = schema else {
+        return false;
+    };
+    let Some(subschemas) = &object.subschemas else {
+        return false;
+    };
+
+    subschemas.one_of.is_some()
+        && **subschemas
+            == SubschemaValidation {
+                one_of: subschemas.one_of.clone(),
+                ..Default::default()
+            }
+        && *object
+            == SchemaObject {
+                subschemas: object.subschemas.clone(),
+                ..Default::default()
+            }
+}
+
+#[cfg(feature = "axum")]
+pub use self::axum::OneOfResponse;
+
+#[cfg(feature = "axum")]
+mod axum {
+    use std::{convert::Infallible, marker::PhantomData};
+
+    use axum::response::{IntoResponse, Response};
+
+    use super::{merge_inferred_responses, merge_responses};
+    use crate::{gen::GenContext, openapi, openapi::Operation, OperationOutput};
+
+    /// A response that is documented as one of the types of the tuple `T`,
+    /// e.g. `OneOfResponse<(Json<Todo>, StatusCode)>`.
+    ///
+    /// The response itself can be any type, it is up to the
+    /// handler to only return one of the documented types.
+    pub struct OneOfResponse<T> {
+        response: Response,
+        _types: PhantomData<fn() -> T>,
+    }
+
+    impl<T> OneOfResponse<T> {
+        /// Create a response from one of the types of `T`.
+        pub fn new(response: impl IntoResponse) -> Self {
+            Self {
+                response: response.into_response(),
+                _types: PhantomData,
+            }
+        }
+    }
+
+    impl<T> std::fmt::Debug for OneOfResponse<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("OneOfResponse")
+                .field("response", &self.response)
+                .finish()
+        }
+    }
+
+    impl<T> IntoResponse for OneOfResponse<T> {
+        fn into_response(self) -> Response {
+            self.response
+        }
+    }
+
+    macro_rules! impl_one_of_response {
+        ($($ty:ident),*) => {
+            impl<$($ty),*> OperationOutput for OneOfResponse<($($ty,)*)>
+            where
+                $($ty: OperationOutput,)*
+            {
+                type Inner = Infallible;
+
+                fn operation_response(
+                    ctx: &mut GenContext,
+                    operation: &mut Operation,
+                ) -> Option<openapi::Response> {
+                    merge_responses(
+                        [$($ty::operation_response(ctx, operation),)*]
+                            .into_iter()
+                            .flatten(),
+                    )
+                }
+
+                fn inferred_responses(
+                    ctx: &mut GenContext,
+                    operation: &mut Operation,
+                ) -> Vec<(Option<u16>, openapi::Response)> {
+                    merge_inferred_responses([$($ty::inferred_responses(ctx, operation),)*])
+                }
+            }
+        };
+    }
+
+    impl_one_of_response!(T1, T2);
+    impl_one_of_response!(T1, T2, T3);
+    impl_one_of_response!(T1, T2, T3, T4);
+    impl_one_of_response!(T1, T2, T3, T4, T5);
+    impl_one_of_response!(T1, T2, T3, T4, T5, T6);
+    impl_one_of_response!(T1, T2, T3, T4, T5, T6, T7);
+    impl_one_of_response!(T1, T2, T3, T4, T5, T6, T7, T8);
+}
+
+#[cfg(all(test, feature = "axum-extra"))]
+mod tests {
+    use axum::Json;
+    use axum_extra::either::Either;
+
+    use super::OneOfResponse;
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        helpers::status::WithStatus,
+        openapi::OpenApi,
+    };
+
+    async fn either() -> Either<Json<String>, Json<u32>> {
+        Either::E2(Json(1))
+    }
+
+    async fn one_of() -> OneOfResponse<(Json<String>, Json<String>, WithStatus<201, Json<bool>>)> {
+        OneOfResponse::new(WithStatus::<201, _>(Json(true)))
+    }
+
+    #[test]
+    fn test_one_of_responses() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/either", get(either))
+            .api_route("/one-of", get(one_of))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+
+        let schema =
+            &paths["/either"]["get"]["responses"]["200"]["content"]["application/json"]["schema"];
+        assert_eq!(schema["oneOf"][0]["type"], "string");
+        assert_eq!(schema["oneOf"][1]["type"], "integer");
+
+        let responses = &paths["/one-of"]["get"]["responses"];
+        assert_eq!(
+            responses["200"]["content"]["application/json"]["schema"]["type"],
+            "string"
+        );
+        assert_eq!(
+            responses["201"]["content"]["application/json"]["schema"]["type"],
+            "boolean"
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Conventional operational routes.
+//!
+//! [`ops_routes`] builds the routes that most services expose for
+//! their infrastructure, documented the same way everywhere:
+//!
+//! - `GET /healthz`: liveness, always `200 OK` while the process runs,
+//! - `GET /readyz`: readiness, `200 OK` or `503 Service Unavailable`
+//!   depending on the [readiness check](OpsRoutes::readiness),
+//! - `GET /metrics`: metrics in the Prometheus text format,
+//!   if a [renderer](OpsRoutes::metrics) is configured.
+//!
+//! The operations are tagged with [`OPS_TAG`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{axum::ApiRouter, helpers::ops::ops_routes};
+//!
+//! async fn database_reachable() -> bool {
+//!     true
+//! }
+//!
+//! let app: ApiRouter = ApiRouter::new().merge(
+//!     ops_routes()
+//!         .readiness(database_reachable)
+//!         .metrics(|| async { String::from("requests_total 42\n") })
+//!         .into_router(),
+//! );
+//! ```
+
+use std::{future::Future, pin::Pin, sync::Arc};
+
+use crate::{
+    axum::{routing::get_with, ApiRouter},
+    gen::GenContext,
+    openapi::{self, Operation},
+    transform::TransformOperation,
+    OperationOutput,
+};
+use axum::{
+    http::{header::CONTENT_TYPE, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+
+use super::media_response;
+
+/// The tag of the operational routes.
+pub const OPS_TAG: &str = "ops";
+
+/// The content type of the Prometheus text exposition format.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+
+/// A builder of the operational routes, see [`ops_routes`].
+#[derive(Clone, Default)]
+#[must_use]
+pub struct OpsRoutes {
+    readiness: Option<Arc<dyn Fn() -> BoxFuture<bool> + Send + Sync>>,
+    metrics: Option<Arc<dyn Fn() -> BoxFuture<String> + Send + Sync>>,
+}
+
+impl std::fmt::Debug for OpsRoutes {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OpsRoutes")
+            .field("readiness", &self.readiness.is_some())
+            .field("metrics", &self.metrics.is_some())
+            .finish()
+    }
+}
+
+/// Build the `/healthz`, `/readyz` and `/metrics` routes.
+pub fn ops_routes() -> OpsRoutes {
+    OpsRoutes::default()
+}
+
+impl OpsRoutes {
+    /// Check whether the service is ready to accept traffic,
+    /// `/readyz` always responds with `200 OK` otherwise.
+    pub fn readiness<F, Fut>(mut self, check: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = bool> + Send + 'static,
+    {
+        self.readiness = Some(Arc::new(move || Box::pin(check())));
+        self
+    }
+
+    /// Render the metrics in the Prometheus text format,
+    /// `/metrics` is only routed if this is set.
+    pub fn metrics<F, Fut>(mut self, render: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = String> + Send + 'static,
+    {
+        self.metrics = Some(Arc::new(move || Box::pin(render())));
+        self
+    }
+
+    /// Build the documented routes,
+    /// e.g. to [merge](ApiRouter::merge) them into the application.
+    pub fn into_router<S>(self) -> ApiRouter<S>
+    where
+        S: Clone + Send + Sync + 'static,
+    {
+        let readiness = self.readiness;
+
+        let mut router = ApiRouter::new()
+            .api_route(
+                "/healthz",
+                get_with(
+                    || async { "ok" },
+                    |op| {
+                        ops_operation(op, "healthz", "Check whether the service is alive.")
+                            .response_with::<200, &str, _>(|res| {
+                                res.description("the service is alive")
+                            })
+                    },
+                ),
+            )
+            .api_route(
+                "/readyz",
+                get_with(
+                    move || {
+                        let readiness = readiness.clone();
+                        async move {
+                            let ready = match readiness {
+                                Some(check) => check().await,
+                                None => true,
+                            };
+                            if ready {
+                                (StatusCode::OK, "ok")
+                            } else {
+                                (StatusCode::SERVICE_UNAVAILABLE, "not ready")
+                            }
+                        }
+                    },
+                    |op| {
+                        ops_operation(
+                            op,
+                            "readyz",
+                            "Check whether the service is ready to accept traffic.",
+                        )
+                        .response_with::<200, &str, _>(|res| {
+                            res.description("the service is ready")
+                        })
+                        .response_with::<503, &str, _>(|res| {
+                            res.description("the service is not ready")
+                        })
+                    },
+                ),
+            );
+
+        if let Some(metrics) = self.metrics {
+            router = router.api_route(
+                "/metrics",
+                get_with(
+                    move || {
+                        let metrics = metrics.clone();
+                        async move { PrometheusText(metrics().await) }
+                    },
+                    |op| ops_operation(op, "metrics", "The metrics of the service."),
+                ),
+            );
+        }
+
+        router
+    }
+}
+
+fn ops_operation<'t>(
+    op: TransformOperation<'t>,
+    id: &str,
+    summary: &str,
+) -> TransformOperation<'t> {
+    op.id(id).summary(summary).tag(OPS_TAG)
+}
+
+/// Metrics in the Prometheus text exposition format.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrometheusText(pub String);
+
+impl IntoResponse for PrometheusText {
+    fn into_response(self) -> Response {
+        (
+            [(
+                CONTENT_TYPE,
+                HeaderValue::from_static(PROMETHEUS_CONTENT_TYPE),
+            )],
+            self.0,
+        )
+            .into_response()
+    }
+}
+
+impl OperationOutput for PrometheusText {
+    type Inner = String;
+
+    fn operation_response(
+        ctx: &mut GenContext,
+        _operation: &mut Operation,
+    ) -> Option<openapi::Response> {
+        Some(media_response::<String>(
+            ctx,
+            PROMETHEUS_CONTENT_TYPE,
+            "the metrics in the Prometheus text format",
+        ))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, openapi::Response)> {
+        Self::operation_response(ctx, operation)
+            .map(|res| Vec::from([(Some(200), res)]))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::Request,
+    };
+    use tower_service::Service;
+
+    use super::{ops_routes, OPS_TAG, PROMETHEUS_CONTENT_TYPE};
+    use crate::openapi::OpenApi;
+
+    #[tokio::test]
+    async fn test_ops_routes() {
+        let mut api = OpenApi::default();
+        let mut app: axum::Router = ops_routes()
+            .readiness(|| async { false })
+            .metrics(|| async { String::from("up 1\n") })
+            .into_router()
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        assert_eq!(paths["/healthz"]["get"]["tags"][0], OPS_TAG);
+        assert!(paths["/readyz"]["get"]["responses"]["503"].is_object());
+        assert!(
+            paths["/metrics"]["get"]["responses"]["200"]["content"][PROMETHEUS_CONTENT_TYPE]
+                .is_object()
+        );
+
+        let res = app
+            .call(Request::get("/readyz").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), 503);
+
+        let res = app
+            .call(Request::get("/metrics").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.headers()["content-type"], PROMETHEUS_CONTENT_TYPE);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"up 1\n");
+    }
+}
@@ -0,0 +1,240 @@
+//! Pagination documentation helpers.
+//!
+//! Both offset-based (`page`, `limit`) and cursor-based
+//! (`cursor`, `limit`) pagination are supported, the response
+//! is documented as [`Paginated<T>`] in both cases.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::pagination::paginated, transform::TransformOperation};
+//! # #[derive(schemars::JsonSchema)]
+//! # struct Item;
+//!
+//! fn list_items_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("List all items.").with(paginated::<Item>)
+//! }
+//! ```
+//!
+//! The parameter types can also be used directly in extractors,
+//! e.g. `Query<OffsetParams>`, in which case the transforms will
+//! not document the parameters again.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error,
+    gen::in_context,
+    openapi::{ReferenceOr, StatusCode},
+    operation::{parameters_from_schema, ParamLocation},
+    transform::TransformOperation,
+};
+
+use super::{add_missing_parameters, json_response};
+
+/// Query parameters for offset-based pagination.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct OffsetParams {
+    /// The page to return, starting from 1.
+    pub page: Option<u64>,
+    /// The maximum amount of items in a page.
+    pub limit: Option<u64>,
+}
+
+/// Query parameters for cursor-based pagination.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct CursorParams {
+    /// An opaque cursor returned by a previous page,
+    /// the first page is returned if omitted.
+    pub cursor: Option<String>,
+    /// The maximum amount of items in a page.
+    pub limit: Option<u64>,
+}
+
+/// A single page of items.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct Paginated<T> {
+    /// The items in the page.
+    pub items: Vec<T>,
+    /// The cursor of the next page if there is one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+    /// The total amount of items if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<u64>,
+}
+
+impl<T> Paginated<T> {
+    /// Create a page from the given items.
+    #[must_use]
+    pub fn new(items: Vec<T>) -> Self {
+        Self {
+            items,
+            next_cursor: None,
+            total: None,
+        }
+    }
+
+    /// Set the cursor of the next page.
+    #[must_use]
+    pub fn with_next_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.next_cursor = Some(cursor.into());
+        self
+    }
+
+    /// Set the total amount of items.
+    #[must_use]
+    pub fn with_total(mut self, total: u64) -> Self {
+        self.total = Some(total);
+        self
+    }
+}
+
+/// Document offset-based pagination for an operation
+/// that returns items of type `T`.
+///
+/// This adds the [`OffsetParams`] query parameters and
+/// a `200` response of [`Paginated<T>`].
+pub fn paginated<T: JsonSchema>(op: TransformOperation) -> TransformOperation {
+    paginate_with::<OffsetParams, T>(op)
+}
+
+/// Document cursor-based pagination for an operation
+/// that returns items of type `T`.
+///
+/// This adds the [`CursorParams`] query parameters and
+/// a `200` response of [`Paginated<T>`].
+pub fn cursor_paginated<T: JsonSchema>(op: TransformOperation) -> TransformOperation {
+    paginate_with::<CursorParams, T>(op)
+}
+
+/// Document pagination with custom query parameters `P`
+/// for an operation that returns items of type `T`.
+///
+/// A different `200` response that is already documented, e.g. inferred
+/// from the return type of the handler, is replaced and reported as
+/// [`Error::ResponseExists`].
+pub fn paginate_with<P: JsonSchema, T: JsonSchema>(
+    mut op: TransformOperation,
+) -> TransformOperation {
+    let operation = op.inner_mut();
+
+    in_context(|ctx| {
+        let params_schema = ctx.schema.subschema_for::<P>().into_object();
+        let params = parameters_from_schema(ctx, params_schema, ParamLocation::Query);
+        add_missing_parameters(ctx, operation, params);
+
+        let res = json_response::<Paginated<T>>(ctx, "a page of results");
+        let content = serde_json::to_value(&res.content).ok();
+
+        let responses = operation.responses.get_or_insert_with(Default::default);
+        let status = StatusCode::Code(200);
+        match responses
+            .responses
+            .insert(status.clone(), ReferenceOr::Item(res))
+        {
+            // The response inferred from `Json<Paginated<T>>` is the same.
+            Some(ReferenceOr::Item(previous))
+                if serde_json::to_value(&previous.content).ok() == content => {}
+            Some(_) => ctx.error(Error::ResponseExists(status)),
+            None => {}
+        }
+    });
+
+    op
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{extract::Query, Json};
+    use schemars::JsonSchema;
+    use serde::Serialize;
+
+    use std::{cell::RefCell, rc::Rc};
+
+    use super::{cursor_paginated, paginated, CursorParams, Paginated};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        gen,
+        openapi::OpenApi,
+    };
+
+    #[derive(Serialize, JsonSchema)]
+    struct Item {
+        id: u64,
+    }
+
+    async fn list_items() -> Json<Paginated<Item>> {
+        Json(Paginated::new(Vec::new()))
+    }
+
+    async fn list_items_by_cursor(_params: Query<CursorParams>) -> Json<Paginated<Item>> {
+        Json(Paginated::new(Vec::new()))
+    }
+
+    async fn list_all_items() -> Json<Vec<Item>> {
+        Json(Vec::new())
+    }
+
+    #[test]
+    fn test_paginated() {
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_handler = errors.clone();
+        gen::on_error(move |error| errors_handler.borrow_mut().push(error.to_string()));
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/items", get_with(list_items, paginated::<Item>))
+            .api_route(
+                "/items/cursor",
+                get_with(list_items_by_cursor, cursor_paginated::<Item>),
+            )
+            .api_route("/items/all", get_with(list_all_items, paginated::<Item>))
+            .finish_api(&mut api);
+
+        let api = serde_json::to_value(api).unwrap();
+        let paths = &api["paths"];
+        let names = |path: &str| {
+            paths[path]["get"]["parameters"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|p| p["name"].as_str().unwrap().to_owned())
+                .collect::<Vec<_>>()
+        };
+        let schema = |path: &str| {
+            paths[path]["get"]["responses"]["200"]["content"]["application/json"]["schema"].clone()
+        };
+
+        assert_eq!(names("/items"), ["limit", "page"]);
+        assert_eq!(
+            schema("/items")["$ref"],
+            "#/components/schemas/Paginated_for_Item"
+        );
+
+        // The parameters of the extractor are not documented twice.
+        assert_eq!(names("/items/cursor"), ["cursor", "limit"]);
+        assert_eq!(
+            schema("/items/cursor")["$ref"],
+            "#/components/schemas/Paginated_for_Item"
+        );
+
+        // Only the response of the handler that differs is reported.
+        assert_eq!(
+            schema("/items/all")["$ref"],
+            "#/components/schemas/Paginated_for_Item"
+        );
+        assert_eq!(
+            *errors.borrow(),
+            [r#"the response for status "200" already exists for the operation"#]
+        );
+
+        let page = &api["components"]["schemas"]["Paginated_for_Item"];
+        assert_eq!(page["required"], serde_json::json!(["items"]));
+        assert_eq!(
+            page["properties"]["items"]["items"]["$ref"],
+            "#/components/schemas/Item"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+//! Protocol Buffers bodies.
+//!
+//! [`Protobuf<T>`] documents `application/x-protobuf` request
+//! and response bodies with the JSON schema of `T`, which is
+//! expected to describe the canonical JSON mapping of the message
+//! (e.g. a schema derived with `prost-reflect` or by deriving
+//! [`JsonSchema`] on the generated types).
+//!
+//! The message can also reference its `.proto` definition
+//! with [`protobuf_message`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::protobuf::{protobuf_message, Protobuf},
+//!     transform::TransformOperation,
+//! };
+//! # #[derive(schemars::JsonSchema)]
+//! # struct User { name: String }
+//!
+//! fn get_user_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, Protobuf<User>>().with(protobuf_message(
+//!         "example.v1.User",
+//!         Some("https://example.com/protos/user.proto"),
+//!     ))
+//! }
+//! ```
+
+use schemars::JsonSchema;
+
+use crate::{
+    gen::GenContext,
+    openapi::{ExternalDocumentation, Operation, Response},
+    operation::set_body,
+    transform::TransformOperation,
+    OperationInput, OperationOutput,
+};
+
+use super::{body_contents_mut, media_request_body, media_response};
+
+/// The Protocol Buffers media type.
+pub const MEDIA_TYPE: &str = "application/x-protobuf";
+
+/// The media type extension that holds the fully
+/// qualified name of the message.
+pub const MESSAGE_EXTENSION: &str = "x-protobuf-message";
+
+/// A Protocol Buffers request or response body of `T`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Protobuf<T>(pub T);
+
+impl<T> OperationInput for Protobuf<T>
+where
+    T: JsonSchema,
+{
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        let body = media_request_body::<T>(ctx, MEDIA_TYPE);
+        set_body(ctx, operation, body);
+    }
+}
+
+impl<T> OperationOutput for Protobuf<T>
+where
+    T: JsonSchema,
+{
+    type Inner = T;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(media_response::<T>(ctx, MEDIA_TYPE, ""))
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Reference the `.proto` definition of the message of all
+/// `application/x-protobuf` bodies of an operation.
+///
+/// The fully qualified message name is stored in the
+/// `x-protobuf-message` extension, the optional URL of the
+/// definition is set as the external documentation of the schema.
+pub fn protobuf_message(
+    name: &str,
+    definition_url: Option<&str>,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    let name = name.to_string();
+    let definition_url = definition_url.map(String::from);
+
+    move |mut op| {
+        for content in body_contents_mut(op.inner_mut()) {
+            let Some(media) = content.get_mut(MEDIA_TYPE) else {
+                continue;
+            };
+
+            media
+                .extensions
+                .insert(MESSAGE_EXTENSION.into(), name.clone().into());
+
+            if let (Some(url), Some(schema)) = (&definition_url, &mut media.schema) {
+                schema.external_docs = Some(ExternalDocumentation {
+                    description: Some(format!("The definition of `{name}`.")),
+                    url: url.clone(),
+                    extensions: Default::default(),
+                });
+            }
+        }
+
+        op
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::{
+        async_trait,
+        body::{Body, Bytes},
+        extract::FromRequest,
+        response::{IntoResponse, Response},
+    };
+    use http::{header, HeaderValue, Request, StatusCode};
+    use prost::Message;
+
+    use super::{Protobuf, MEDIA_TYPE};
+
+    impl<T> IntoResponse for Protobuf<T>
+    where
+        T: Message,
+    {
+        fn into_response(self) -> Response {
+            (
+                [(header::CONTENT_TYPE, HeaderValue::from_static(MEDIA_TYPE))],
+                self.0.encode_to_vec(),
+            )
+                .into_response()
+        }
+    }
+
+    #[async_trait]
+    impl<T, S> FromRequest<S> for Protobuf<T>
+    where
+        T: Message + Default,
+        S: Send + Sync,
+    {
+        type Rejection = Response;
+
+        async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+            let body = Bytes::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+
+            T::decode(body)
+                .map(Self)
+                .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()).into_response())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header::CONTENT_TYPE, Request, StatusCode},
+        routing::post,
+        Json,
+    };
+    use prost::Message;
+    use schemars::JsonSchema;
+    use serde_json::json;
+    use tower_service::Service;
+
+    use super::{protobuf_message, Protobuf, MEDIA_TYPE, MESSAGE_EXTENSION};
+    use crate::{
+        axum::{routing::post_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Clone, PartialEq, Message, JsonSchema)]
+    struct User {
+        #[prost(string, tag = "1")]
+        name: String,
+    }
+
+    async fn update_user(Protobuf(user): Protobuf<User>) -> Protobuf<User> {
+        Protobuf(user)
+    }
+
+    async fn create_user(Json(user): Json<String>) -> Json<String> {
+        Json(user)
+    }
+
+    #[test]
+    fn test_protobuf_docs() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users",
+                post_with(update_user, |op| {
+                    op.with(protobuf_message(
+                        "example.v1.User",
+                        Some("https://example.com/protos/user.proto"),
+                    ))
+                }),
+            )
+            .api_route(
+                "/users/json",
+                post_with(create_user, |op| {
+                    op.with(protobuf_message("example.v1.User", None))
+                }),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let op = &paths["/users"]["post"];
+
+        for content in [
+            &op["requestBody"]["content"],
+            &op["responses"]["200"]["content"],
+        ] {
+            assert_eq!(
+                content.as_object().unwrap().keys().collect::<Vec<_>>(),
+                [MEDIA_TYPE]
+            );
+            let media = &content[MEDIA_TYPE];
+            assert_eq!(media[MESSAGE_EXTENSION], "example.v1.User");
+            assert_eq!(
+                media["schema"]["externalDocs"],
+                json!({
+                    "description": "The definition of `example.v1.User`.",
+                    "url": "https://example.com/protos/user.proto",
+                })
+            );
+        }
+        assert_eq!(op["requestBody"]["required"], true);
+
+        // Other media types are left untouched.
+        let json = &paths["/users/json"]["post"]["requestBody"]["content"]["application/json"];
+        assert!(json.get(MESSAGE_EXTENSION).is_none());
+        assert!(json["schema"].get("externalDocs").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_protobuf_round_trip() {
+        let mut app = axum::Router::new().route("/users", post(update_user));
+
+        let user = User {
+            name: "Ferris".into(),
+        };
+        let res = app
+            .call(
+                Request::post("/users")
+                    .header(CONTENT_TYPE, MEDIA_TYPE)
+                    .body(Body::from(user.encode_to_vec()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[CONTENT_TYPE], MEDIA_TYPE);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(User::decode(body).unwrap(), user);
+
+        let res = app
+            .call(Request::post("/users").body(Body::from("{}")).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    }
+}
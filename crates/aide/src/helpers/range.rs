@@ -0,0 +1,182 @@
+//! Range requests (RFC 9110).
+//!
+//! [`ranged`] documents the `Range` and `If-Range` request headers,
+//! the `Accept-Ranges` header of successful responses, a
+//! `206 Partial Content` response with a `Content-Range` header and a
+//! `416 Range Not Satisfiable` response.
+//!
+//! Besides byte ranges, ranges can also be expressed in other units
+//! (such as items of a collection), see [`RangeUnit`].
+//!
+//! The content of the `206` response is copied from the
+//! `200` response, so these functions should be applied
+//! after the responses are documented (or inferred).
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::range::byte_ranges, transform::TransformOperation};
+//!
+//! fn download_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("Download the file.")
+//!         .response::<200, String>()
+//!         .with(byte_ranges)
+//! }
+//! ```
+
+use crate::{
+    gen::in_context,
+    openapi::{ReferenceOr, Response, StatusCode},
+    transform::TransformOperation,
+};
+
+use super::{
+    add_missing_parameters, add_response_if_missing, add_success_response_header, header_parameter,
+    response_header,
+};
+
+/// The unit of a range.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RangeUnit {
+    /// Byte ranges of the representation.
+    #[default]
+    Bytes,
+    /// Ranges of the items of a collection.
+    Items,
+    /// A custom unit.
+    Custom(String),
+}
+
+impl RangeUnit {
+    /// The name of the unit used in the headers.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            RangeUnit::Bytes => "bytes",
+            RangeUnit::Items => "items",
+            RangeUnit::Custom(unit) => unit,
+        }
+    }
+}
+
+/// Document byte range requests.
+pub fn byte_ranges(op: TransformOperation) -> TransformOperation {
+    ranged(RangeUnit::Bytes)(op)
+}
+
+/// Document range requests with the given unit.
+pub fn ranged(unit: RangeUnit) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    move |mut op| {
+        let operation = op.inner_mut();
+        let unit = unit.as_str();
+
+        in_context(|ctx| {
+            let params = [
+                header_parameter::<String>(
+                    ctx,
+                    "Range",
+                    &format!("The {unit} ranges to return, e.g. `{unit}=0-99`."),
+                    false,
+                ),
+                header_parameter::<String>(
+                    ctx,
+                    "If-Range",
+                    "Only return the requested ranges if the representation has not changed, otherwise return it in full.",
+                    false,
+                ),
+            ];
+            add_missing_parameters(ctx, operation, params);
+
+            let mut accept_ranges =
+                response_header::<String>(ctx, "The range unit supported by the resource.");
+            accept_ranges.example = Some(unit.into());
+            add_success_response_header(operation, "Accept-Ranges", &accept_ranges);
+
+            let full = operation
+                .responses
+                .as_ref()
+                .and_then(|responses| responses.responses.get(&StatusCode::Code(200)))
+                .and_then(ReferenceOr::as_item);
+
+            let mut partial = Response {
+                description: format!("the requested {unit} ranges"),
+                content: full.map(|res| res.content.clone()).unwrap_or_default(),
+                ..Default::default()
+            };
+            partial.headers.insert(
+                "Content-Range".into(),
+                ReferenceOr::Item(response_header::<String>(
+                    ctx,
+                    &format!("The range that is returned, e.g. `{unit} 0-99/1000`."),
+                )),
+            );
+            add_response_if_missing(operation, 206, partial);
+
+            let mut unsatisfiable = Response {
+                description: "the requested ranges cannot be satisfied".into(),
+                ..Default::default()
+            };
+            unsatisfiable.headers.insert(
+                "Content-Range".into(),
+                ReferenceOr::Item(response_header::<String>(
+                    ctx,
+                    &format!("The complete length of the representation, e.g. `{unit} */1000`."),
+                )),
+            );
+            add_response_if_missing(operation, 416, unsatisfiable);
+        });
+
+        op
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use super::{byte_ranges, ranged, RangeUnit};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    async fn download() -> String {
+        String::new()
+    }
+
+    #[test]
+    fn test_ranged() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/file", get_with(download, byte_ranges))
+            .api_route(
+                "/items",
+                get_with(download, ranged(RangeUnit::Custom("rows".into()))),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let operation = &paths["/file"]["get"];
+        let responses = &operation["responses"];
+
+        let params = operation["parameters"].as_array().unwrap();
+        assert_eq!(params.len(), 2);
+        assert!(params
+            .iter()
+            .all(|p| p["in"] == "header" && p["required"] != true));
+
+        assert_eq!(
+            responses["200"]["headers"]["Accept-Ranges"]["example"],
+            "bytes"
+        );
+        assert_eq!(responses["206"]["content"], responses["200"]["content"]);
+        assert!(responses["206"]["headers"]["Content-Range"].is_object());
+        assert!(responses["416"]["content"].is_null());
+        assert!(responses["416"]["headers"]["Content-Range"].is_object());
+
+        let responses = &paths["/items"]["get"]["responses"];
+        assert_eq!(
+            responses["200"]["headers"]["Accept-Ranges"]["example"],
+            "rows"
+        );
+        assert_eq!(responses["206"]["description"], "the requested rows ranges");
+    }
+}
@@ -0,0 +1,240 @@
+//! Rate limiting documentation.
+//!
+//! Documents the `X-RateLimit-Limit`, `X-RateLimit-Remaining` and
+//! `X-RateLimit-Reset` headers on successful responses and a
+//! `429 Too Many Requests` response with a `Retry-After` header.
+//!
+//! # Examples
+//!
+//! For a single operation:
+//!
+//! ```
+//! use std::time::Duration;
+//! use aide::{
+//!     helpers::rate_limit::{rate_limited, RateLimitPolicy},
+//!     transform::TransformOperation,
+//! };
+//!
+//! fn search_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, String>()
+//!         .with(rate_limited(RateLimitPolicy::new(100, Duration::from_secs(60))))
+//! }
+//! ```
+//!
+//! For all routes of a router, e.g. behind a governor layer:
+//!
+//! ```ignore
+//! let policy = RateLimitPolicy::new(100, Duration::from_secs(60));
+//!
+//! ApiRouter::new()
+//!     .api_route("/search", get(search))
+//!     .with_path_items(rate_limited_routes(policy))
+//!     .layer(governor_layer);
+//! ```
+
+use std::time::Duration;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Operation, ReferenceOr, Response},
+    operation::OperationOutput,
+    transform::{TransformOperation, TransformPathItem},
+    util::iter_operations_mut,
+};
+
+use super::{add_response_if_missing, add_success_response_header, json_response, response_header};
+
+/// The default body of a `429 Too Many Requests` response.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct RateLimitExceeded {
+    /// An error message.
+    pub error: String,
+    /// The amount of seconds to wait before retrying the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<u64>,
+}
+
+/// A rate limiting policy used for documentation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct RateLimitPolicy {
+    limit: u64,
+    window: Duration,
+    description: Option<String>,
+}
+
+impl RateLimitPolicy {
+    /// Create a policy that allows `limit` requests per `window`.
+    pub fn new(limit: u64, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            description: None,
+        }
+    }
+
+    /// Set the description of the `429` response.
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+
+    fn apply<R: OperationOutput>(&self, ctx: &mut GenContext, operation: &mut Operation) {
+        let window = self.window.as_secs();
+
+        let mut limit = response_header::<u64>(
+            ctx,
+            &format!("The maximum amount of requests allowed per {window} seconds."),
+        );
+        limit.example = Some(self.limit.into());
+
+        add_success_response_header(operation, "X-RateLimit-Limit", &limit);
+        add_success_response_header(
+            operation,
+            "X-RateLimit-Remaining",
+            &response_header::<u64>(
+                ctx,
+                "The amount of requests remaining in the current window.",
+            ),
+        );
+        add_success_response_header(
+            operation,
+            "X-RateLimit-Reset",
+            &response_header::<u64>(
+                ctx,
+                "The amount of seconds until the current window resets.",
+            ),
+        );
+
+        let Some(mut res) = R::operation_response(ctx, operation) else {
+            return;
+        };
+
+        res.description = self
+            .description
+            .clone()
+            .unwrap_or_else(|| "too many requests, the rate limit was exceeded".into());
+        res.headers.insert(
+            "Retry-After".into(),
+            ReferenceOr::Item(response_header::<u64>(
+                ctx,
+                "The amount of seconds to wait before retrying the request.",
+            )),
+        );
+
+        add_response_if_missing(operation, 429, res);
+    }
+}
+
+/// Document rate limiting for an operation with the given policy.
+///
+/// The `429` response body is documented as [`RateLimitExceeded`].
+pub fn rate_limited(
+    policy: RateLimitPolicy,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    move |mut op| {
+        in_context(|ctx| policy.apply::<RateLimitBody>(ctx, op.inner_mut()));
+        op
+    }
+}
+
+/// Document rate limiting for an operation with the given policy,
+/// the `429` response body is documented by `R`.
+pub fn rate_limited_with<R: OperationOutput>(
+    policy: RateLimitPolicy,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    move |mut op| {
+        in_context(|ctx| policy.apply::<R>(ctx, op.inner_mut()));
+        op
+    }
+}
+
+/// Document rate limiting for all operations of path items,
+/// meant to be used with
+/// [`ApiRouter::with_path_items`](crate::axum::ApiRouter::with_path_items).
+pub fn rate_limited_routes(
+    policy: RateLimitPolicy,
+) -> impl FnMut(TransformPathItem) -> TransformPathItem + Clone {
+    move |mut path| {
+        in_context(|ctx| {
+            for (_, op) in iter_operations_mut(path.inner_mut()) {
+                policy.apply::<RateLimitBody>(ctx, op);
+            }
+        });
+        path
+    }
+}
+
+/// Documents [`RateLimitExceeded`] as JSON without
+/// depending on a framework-specific JSON type.
+struct RateLimitBody;
+
+impl OperationOutput for RateLimitBody {
+    type Inner = RateLimitExceeded;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        Some(json_response::<RateLimitExceeded>(ctx, ""))
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use std::time::Duration;
+
+    use super::{rate_limited, rate_limited_routes, RateLimitPolicy};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    async fn search() -> String {
+        String::new()
+    }
+
+    #[test]
+    fn test_rate_limited() {
+        let policy = RateLimitPolicy::new(100, Duration::from_mins(1));
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/search",
+                get_with(
+                    search,
+                    rate_limited(policy.clone().with_description("slow down")),
+                ),
+            )
+            .api_route("/items", get_with(search, |op| op))
+            .with_path_items(rate_limited_routes(policy))
+            .finish_api(&mut api);
+
+        let api = serde_json::to_value(api).unwrap();
+        let search = &api["paths"]["/search"]["get"]["responses"];
+
+        let headers = &search["200"]["headers"];
+        assert_eq!(headers["X-RateLimit-Limit"]["example"], 100);
+        assert_eq!(
+            headers["X-RateLimit-Limit"]["description"],
+            "The maximum amount of requests allowed per 60 seconds."
+        );
+        assert!(headers["X-RateLimit-Remaining"].is_object());
+        assert!(headers["X-RateLimit-Reset"].is_object());
+
+        // The operation's own policy is kept.
+        let too_many = &search["429"];
+        assert_eq!(too_many["description"], "slow down");
+        assert!(too_many["headers"]["Retry-After"].is_object());
+        assert!(too_many["headers"].get("X-RateLimit-Limit").is_none());
+        assert!(too_many["content"]["application/json"]["schema"].is_object());
+
+        let items = &api["paths"]["/items"]["get"]["responses"];
+        assert!(items["200"]["headers"]["X-RateLimit-Reset"].is_object());
+        assert_eq!(
+            items["429"]["description"],
+            "too many requests, the rate limit was exceeded"
+        );
+    }
+}
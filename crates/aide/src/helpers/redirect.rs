@@ -0,0 +1,314 @@
+//! Redirect responses.
+//!
+//! axum's [`Redirect`](::axum::response::Redirect) does not know its
+//! status code until runtime, so it is documented without one. The
+//! types in this module have a fixed status code and are documented
+//! with it, along with the `Location` header.
+//!
+//! [`redirect_target`] describes where the redirect leads, with the
+//! URL template of the target and an `OpenAPI` link to the operation
+//! that serves it.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::redirect::{redirect_target, RedirectTarget, SeeOther},
+//!     transform::TransformOperation,
+//! };
+//!
+//! async fn create_todo() -> SeeOther {
+//!     SeeOther::new("/todos/1")
+//! }
+//!
+//! fn create_todo_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("Create a todo.").with(redirect_target(
+//!         RedirectTarget::new("/todos/{id}")
+//!             .operation("get_todo")
+//!             .parameter("id", "$response.header.X-Todo-Id"),
+//!     ))
+//! }
+//! ```
+
+use indexmap::IndexMap;
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Link, LinkOperation, Operation, ReferenceOr, Response, StatusCode},
+    transform::TransformOperation,
+    OperationOutput,
+};
+
+use super::response_header;
+
+/// The extension of the `Location` header that holds
+/// the URL template of the redirect target.
+pub const LOCATION_TEMPLATE_EXTENSION: &str = "x-location-template";
+
+macro_rules! redirect_types {
+    ($($(#[$meta:meta])* $name:ident => $status:literal, $description:literal;)*) => {
+        $(
+            $(#[$meta])*
+            #[derive(Debug, Clone, PartialEq, Eq)]
+            #[must_use]
+            pub struct $name {
+                location: String,
+            }
+
+            impl $name {
+                /// The status code of the redirect.
+                pub const STATUS: u16 = $status;
+
+                /// Redirect to the given URL.
+                pub fn new(location: impl Into<String>) -> Self {
+                    Self {
+                        location: location.into(),
+                    }
+                }
+
+                /// The URL of the redirect.
+                #[must_use]
+                pub fn location(&self) -> &str {
+                    &self.location
+                }
+            }
+
+            impl OperationOutput for $name {
+                type Inner = Self;
+
+                fn operation_response(
+                    ctx: &mut GenContext,
+                    _operation: &mut Operation,
+                ) -> Option<Response> {
+                    Some(redirect_response(ctx, $description))
+                }
+
+                fn inferred_responses(
+                    ctx: &mut GenContext,
+                    operation: &mut Operation,
+                ) -> Vec<(Option<u16>, Response)> {
+                    Self::operation_response(ctx, operation)
+                        .map(|res| Vec::from([(Some($status), res)]))
+                        .unwrap_or_default()
+                }
+            }
+
+            #[cfg(feature = "axum")]
+            impl ::axum::response::IntoResponse for $name {
+                fn into_response(self) -> ::axum::response::Response {
+                    self::axum::redirect(
+                        http::StatusCode::from_u16($status)
+                            .expect("redirect status codes are valid"),
+                        self.location,
+                    )
+                }
+            }
+        )*
+    };
+}
+
+redirect_types! {
+    /// A `301 Moved Permanently` redirect.
+    ///
+    /// Clients may change the method of the request to `GET`,
+    /// use [`PermanentRedirect`] to prevent that.
+    MovedPermanently => 301, "the resource has moved permanently";
+    /// A `302 Found` redirect.
+    ///
+    /// Clients may change the method of the request to `GET`,
+    /// use [`TemporaryRedirect`] to prevent that.
+    Found => 302, "the resource has moved temporarily";
+    /// A `303 See Other` redirect, e.g. to the
+    /// created resource after a `POST` request.
+    ///
+    /// The target is always requested with `GET`.
+    SeeOther => 303, "the result is at another location";
+    /// A `307 Temporary Redirect` that keeps the method
+    /// and body of the request.
+    TemporaryRedirect => 307, "the resource has moved temporarily";
+    /// A `308 Permanent Redirect` that keeps the method
+    /// and body of the request.
+    PermanentRedirect => 308, "the resource has moved permanently";
+}
+
+fn redirect_response(ctx: &mut GenContext, description: &str) -> Response {
+    let mut res = Response {
+        description: description.into(),
+        ..Default::default()
+    };
+    res.headers.insert(
+        "Location".into(),
+        ReferenceOr::Item(response_header::<String>(ctx, "The URL to redirect to.")),
+    );
+    res
+}
+
+/// The target of the redirect responses of an operation,
+/// see [`redirect_target`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct RedirectTarget {
+    template: String,
+    operation_id: Option<String>,
+    parameters: IndexMap<String, String>,
+}
+
+impl RedirectTarget {
+    /// A redirect to URLs following the template,
+    /// e.g. `/todos/{id}`.
+    pub fn new(template: &str) -> Self {
+        Self {
+            template: template.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Link the redirect to the operation with the given ID.
+    pub fn operation(mut self, operation_id: &str) -> Self {
+        self.operation_id = Some(operation_id.into());
+        self
+    }
+
+    /// Pass the value of the runtime expression, e.g. `$request.path.id`,
+    /// as the parameter `name` of the linked operation.
+    pub fn parameter(mut self, name: &str, expression: &str) -> Self {
+        self.parameters.insert(name.into(), expression.into());
+        self
+    }
+}
+
+/// Describe the target of all redirect (`3xx`) responses of an operation.
+///
+/// The `Location` header of the responses is documented with
+/// the URL template of the target, which is also stored in the
+/// [`LOCATION_TEMPLATE_EXTENSION`] extension of the header.
+/// If the target has an operation, the responses are linked to it.
+///
+/// Operations without redirect responses are left untouched,
+/// so this should be applied after the responses are documented.
+pub fn redirect_target(
+    target: RedirectTarget,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    move |mut op| {
+        let operation = op.inner_mut();
+        in_context(|ctx| document_target(ctx, operation, &target));
+        op
+    }
+}
+
+fn document_target(ctx: &mut GenContext, operation: &mut Operation, target: &RedirectTarget) {
+    let Some(responses) = &mut operation.responses else {
+        return;
+    };
+
+    let redirects = responses
+        .responses
+        .iter_mut()
+        .filter(|(status, _)| is_redirect(status))
+        .filter_map(|(_, res)| res.as_item_mut());
+
+    for res in redirects {
+        let header = res.headers.entry("Location".into()).or_insert_with(|| {
+            ReferenceOr::Item(response_header::<String>(ctx, "The URL to redirect to."))
+        });
+
+        if let ReferenceOr::Item(header) = header {
+            header.description = Some(format!(
+                "The URL to redirect to, following the template `{}`.",
+                target.template
+            ));
+            header.extensions.insert(
+                LOCATION_TEMPLATE_EXTENSION.into(),
+                target.template.clone().into(),
+            );
+        }
+
+        if let Some(operation_id) = &target.operation_id {
+            res.links.insert(
+                "target".into(),
+                ReferenceOr::Item(Link {
+                    description: Some("The target of the redirect.".into()),
+                    operation: LinkOperation::OperationId(operation_id.clone()),
+                    request_body: None,
+                    parameters: target
+                        .parameters
+                        .iter()
+                        .map(|(name, expression)| (name.clone(), expression.clone().into()))
+                        .collect(),
+                    server: None,
+                    extensions: IndexMap::default(),
+                }),
+            );
+        }
+    }
+}
+
+fn is_redirect(status: &StatusCode) -> bool {
+    match status {
+        StatusCode::Code(code) => (300..400).contains(code),
+        StatusCode::Range(range) => *range == 3,
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::response::{IntoResponse, Response};
+    use http::{header::LOCATION, HeaderValue, StatusCode};
+
+    /// A redirect response, or `500 Internal Server Error`
+    /// if the location is not a valid header value.
+    pub(super) fn redirect(status: StatusCode, location: String) -> Response {
+        match HeaderValue::try_from(location) {
+            Ok(location) => (status, [(LOCATION, location)]).into_response(),
+            Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::response::IntoResponse;
+
+    use super::{redirect_target, RedirectTarget, SeeOther, LOCATION_TEMPLATE_EXTENSION};
+    use crate::{
+        axum::{routing::post_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    async fn create_todo() -> SeeOther {
+        SeeOther::new("/todos/1")
+    }
+
+    #[test]
+    fn test_redirect() {
+        let res = SeeOther::new("/todos/1").into_response();
+        assert_eq!(res.status(), 303);
+        assert_eq!(res.headers()["location"], "/todos/1");
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos",
+                post_with(create_todo, |op| {
+                    op.with(redirect_target(
+                        RedirectTarget::new("/todos/{id}")
+                            .operation("get_todo")
+                            .parameter("id", "$response.header.X-Todo-Id"),
+                    ))
+                }),
+            )
+            .finish_api(&mut api);
+
+        let doc = serde_json::to_value(&api).unwrap();
+        let res = &doc["paths"]["/todos"]["post"]["responses"]["303"];
+        assert_eq!(
+            res["headers"]["Location"][LOCATION_TEMPLATE_EXTENSION],
+            "/todos/{id}"
+        );
+        assert_eq!(res["links"]["target"]["operationId"], "get_todo");
+        assert_eq!(
+            res["links"]["target"]["parameters"]["id"],
+            "$response.header.X-Todo-Id"
+        );
+    }
+}
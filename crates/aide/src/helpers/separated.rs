@@ -0,0 +1,73 @@
+//! Query parameters with separated values.
+//!
+//! Array-valued query parameters are documented as repeated parameters
+//! (`id=1&id=2`) by default, which can be changed for all of them with
+//! [`query_arrays`](crate::gen::query_arrays). Fields that are deserialized
+//! from a single separated value instead, e.g. with `serde_with`'s
+//! `StringWithSeparator`, can be documented with [`CommaSeparated`]
+//! or [`SpaceSeparated`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::helpers::separated::CommaSeparated;
+//! use schemars::JsonSchema;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, JsonSchema)]
+//! struct Search {
+//!     // #[serde_as(as = "StringWithSeparator::<CommaSeparator, u32>")]
+//!     #[schemars(with = "CommaSeparated<u32>")]
+//!     ids: Vec<u32>,
+//! }
+//! ```
+
+use std::marker::PhantomData;
+
+use schemars::{gen::SchemaGenerator, schema::Schema, JsonSchema};
+
+use crate::operation::{QueryArrays, QUERY_ARRAY};
+
+/// The schema of `Vec<T>`, documented as comma
+/// separated values in query parameters, e.g. `id=1,2`.
+pub struct CommaSeparated<T>(PhantomData<T>);
+
+impl<T: JsonSchema> JsonSchema for CommaSeparated<T> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("CommaSeparated_{}", T::schema_name())
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        separated::<T>(gen, QueryArrays::CommaSeparated)
+    }
+}
+
+/// The schema of `Vec<T>`, documented as space
+/// separated values in query parameters, e.g. `id=1%202`.
+pub struct SpaceSeparated<T>(PhantomData<T>);
+
+impl<T: JsonSchema> JsonSchema for SpaceSeparated<T> {
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn schema_name() -> String {
+        format!("SpaceSeparated_{}", T::schema_name())
+    }
+
+    fn json_schema(gen: &mut SchemaGenerator) -> Schema {
+        separated::<T>(gen, QueryArrays::SpaceDelimited)
+    }
+}
+
+fn separated<T: JsonSchema>(gen: &mut SchemaGenerator, format: QueryArrays) -> Schema {
+    let mut schema = gen.subschema_for::<Vec<T>>().into_object();
+    schema
+        .extensions
+        .insert(QUERY_ARRAY.into(), format.name().into());
+    schema.into()
+}
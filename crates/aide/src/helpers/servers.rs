@@ -0,0 +1,158 @@
+//! Servers resolved at generation time.
+//!
+//! The servers of the documentation usually differ between
+//! environments, a [`ServerResolver`] provides them from the
+//! environment or the configuration of the application instead
+//! of the code, see
+//! [`TransformOpenApi::servers_from`](crate::transform::TransformOpenApi::servers_from).
+//!
+//! [`EnvServers`] reads the servers from environment variables
+//! with a common prefix, for the prefix `API_SERVER`:
+//!
+//! ```text
+//! API_SERVER_URL=https://api.example.com
+//! API_SERVER_STAGING_URL=https://staging.example.com
+//! API_SERVER_STAGING_DESCRIPTION=Staging environment
+//! ```
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::servers::ServerResolver,
+//!     openapi::Server,
+//!     transform::TransformOpenApi,
+//! };
+//!
+//! struct Config {
+//!     public_url: String,
+//! }
+//!
+//! impl ServerResolver for Config {
+//!     fn servers(&self) -> Vec<Server> {
+//!         vec![Server {
+//!             url: self.public_url.clone(),
+//!             ..Default::default()
+//!         }]
+//!     }
+//! }
+//!
+//! fn api_docs<'t>(api: TransformOpenApi<'t>, config: &Config) -> TransformOpenApi<'t> {
+//!     api.title("Example API")
+//!         .servers_from(config)
+//!         .servers_from_env("API_SERVER")
+//! }
+//! ```
+
+use crate::openapi::Server;
+
+/// Provides the servers of the documentation.
+pub trait ServerResolver {
+    /// The resolved servers.
+    fn servers(&self) -> Vec<Server>;
+}
+
+impl<R: ServerResolver + ?Sized> ServerResolver for &R {
+    fn servers(&self) -> Vec<Server> {
+        (**self).servers()
+    }
+}
+
+impl ServerResolver for Vec<Server> {
+    fn servers(&self) -> Vec<Server> {
+        self.clone()
+    }
+}
+
+/// Resolves servers from environment variables.
+///
+/// Every `{PREFIX}_{NAME}_URL` variable is a server, described by
+/// the `{PREFIX}_{NAME}_DESCRIPTION` variable if it is set. The
+/// `{PREFIX}_URL` variable is a server without a name. The servers
+/// are ordered by name, with the unnamed one first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnvServers {
+    prefix: String,
+}
+
+impl EnvServers {
+    /// Resolve servers from the variables with the given prefix.
+    #[must_use]
+    pub fn new(prefix: &str) -> Self {
+        Self {
+            prefix: prefix.trim_end_matches('_').to_string(),
+        }
+    }
+
+    fn resolve(&self, vars: impl IntoIterator<Item = (String, String)>) -> Vec<Server> {
+        let vars = vars
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(&self.prefix))
+            .collect::<Vec<_>>();
+
+        let var = |name: &str| {
+            vars.iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, value)| value.clone())
+        };
+
+        let mut names = vars
+            .iter()
+            .filter_map(|(name, _)| {
+                let name = name.strip_prefix(&self.prefix)?.strip_suffix("_URL")?;
+                match name {
+                    "" => Some(String::new()),
+                    name => name.strip_prefix('_').map(Into::into),
+                }
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let var_name = match name.as_str() {
+                    "" => self.prefix.clone(),
+                    name => format!("{}_{name}", self.prefix),
+                };
+
+                Some(Server {
+                    url: var(&format!("{var_name}_URL")).filter(|url| !url.is_empty())?,
+                    description: var(&format!("{var_name}_DESCRIPTION")),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+}
+
+impl ServerResolver for EnvServers {
+    fn servers(&self) -> Vec<Server> {
+        self.resolve(std::env::vars())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EnvServers;
+
+    #[test]
+    fn test_env_servers() {
+        let vars = [
+            ("API_SERVER_STAGING_URL", "https://staging.example.com"),
+            ("API_SERVER_STAGING_DESCRIPTION", "Staging"),
+            ("API_SERVER_URL", "https://api.example.com"),
+            ("API_SERVER_EMPTY_URL", ""),
+            ("OTHER_URL", "https://other.example.com"),
+        ]
+        .map(|(name, value)| (name.to_string(), value.to_string()));
+
+        let servers = EnvServers::new("API_SERVER_").resolve(vars);
+
+        assert_eq!(servers.len(), 2);
+        assert_eq!(servers[0].url, "https://api.example.com");
+        assert_eq!(servers[0].description, None);
+        assert_eq!(servers[1].url, "https://staging.example.com");
+        assert_eq!(servers[1].description.as_deref(), Some("Staging"));
+    }
+}
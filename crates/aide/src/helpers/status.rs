@@ -0,0 +1,308 @@
+//! Responses with the status code as part of their type.
+//!
+//! Handlers returning `(StatusCode, T)` are documented with the
+//! status code of `T`, usually `200`, as the actual status code is
+//! only known at runtime. [`WithStatus`] moves the status code into
+//! the return type, so it is documented without repeating it in a
+//! [`response`](crate::transform::TransformOperation::response) transform.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::helpers::status::WithStatus;
+//!
+//! // Documented and returned with `201 Created`.
+//! async fn create_todo() -> WithStatus<201, String> {
+//!     WithStatus("todo created".into())
+//! }
+//! ```
+//!
+//! The most common status codes of REST APIs have their own types,
+//! [`Created`] (with a `Location` header), [`Accepted`] and [`NoContent`].
+
+use crate::{
+    gen::GenContext,
+    openapi::{Operation, ReferenceOr, Response},
+    transform::StatusConst,
+    OperationOutput,
+};
+
+use super::response_header;
+
+/// A response with the status code `N` instead of the
+/// successful status code of `T`.
+///
+/// The status code is checked at compile time,
+/// it must be in the range `100..=599`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WithStatus<const N: u16, T>(pub T);
+
+impl<const N: u16, T> OperationOutput for WithStatus<N, T>
+where
+    T: OperationOutput,
+{
+    type Inner = T::Inner;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<Response> {
+        T::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        T::inferred_responses(ctx, operation)
+            .into_iter()
+            .map(|(status, res)| match status {
+                Some(200..=299) => (Some(StatusConst::<N>::CODE), res),
+                status => (status, res),
+            })
+            .collect()
+    }
+}
+
+/// A `201 Created` response with the `Location` of
+/// the created resource and an optional body.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Created<T = ()> {
+    /// The URL of the created resource.
+    pub location: String,
+    /// The body of the response.
+    pub body: T,
+}
+
+impl<T> Created<T> {
+    /// A response for the resource created at `location`.
+    pub fn new(location: impl Into<String>, body: T) -> Self {
+        Self {
+            location: location.into(),
+            body,
+        }
+    }
+}
+
+impl<T> OperationOutput for Created<T>
+where
+    T: OperationOutput,
+{
+    type Inner = T::Inner;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<Response> {
+        let mut res = T::operation_response(ctx, operation)?;
+        add_location_header(ctx, &mut res);
+        Some(res)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        let mut responses = WithStatus::<201, T>::inferred_responses(ctx, operation);
+        for (_, res) in responses
+            .iter_mut()
+            .filter(|(status, _)| *status == Some(201))
+        {
+            add_location_header(ctx, res);
+        }
+        responses
+    }
+}
+
+fn add_location_header(ctx: &mut GenContext, res: &mut Response) {
+    res.headers.entry("Location".into()).or_insert_with(|| {
+        ReferenceOr::Item(response_header::<String>(
+            ctx,
+            "The URL of the created resource.",
+        ))
+    });
+}
+
+/// A `202 Accepted` response with an optional body,
+/// the same as [`WithStatus<202, T>`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Accepted<T = ()>(pub T);
+
+impl<T> OperationOutput for Accepted<T>
+where
+    T: OperationOutput,
+{
+    type Inner = T::Inner;
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<Response> {
+        T::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        WithStatus::<202, T>::inferred_responses(ctx, operation)
+    }
+}
+
+/// A `204 No Content` response.
+///
+/// Unlike `()`, it is always documented and
+/// returned as `204`, regardless of
+/// [`inferred_empty_response_status`](crate::gen::inferred_empty_response_status).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoContent;
+
+impl OperationOutput for NoContent {
+    type Inner = ();
+
+    fn operation_response(ctx: &mut GenContext, operation: &mut Operation) -> Option<Response> {
+        <()>::operation_response(ctx, operation)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        Self::operation_response(ctx, operation)
+            .map(|res| Vec::from([(Some(204), res)]))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::response::{IntoResponse, Response};
+    use http::{header::LOCATION, HeaderValue, StatusCode};
+
+    use super::{Accepted, Created, NoContent, WithStatus};
+    use crate::transform::StatusConst;
+
+    impl<const N: u16, T> IntoResponse for WithStatus<N, T>
+    where
+        T: IntoResponse,
+    {
+        fn into_response(self) -> Response {
+            let status = StatusCode::from_u16(StatusConst::<N>::CODE)
+                .expect("status codes in the range 100..=599 are valid");
+            (status, self.0).into_response()
+        }
+    }
+
+    impl<T> IntoResponse for Created<T>
+    where
+        T: IntoResponse,
+    {
+        fn into_response(self) -> Response {
+            match HeaderValue::try_from(self.location) {
+                Ok(location) => {
+                    (StatusCode::CREATED, [(LOCATION, location)], self.body).into_response()
+                }
+                Err(_) => StatusCode::INTERNAL_SERVER_ERROR.into_response(),
+            }
+        }
+    }
+
+    impl<T> IntoResponse for Accepted<T>
+    where
+        T: IntoResponse,
+    {
+        fn into_response(self) -> Response {
+            (StatusCode::ACCEPTED, self.0).into_response()
+        }
+    }
+
+    impl IntoResponse for NoContent {
+        fn into_response(self) -> Response {
+            StatusCode::NO_CONTENT.into_response()
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::{http::StatusCode, response::IntoResponse, Json};
+
+    use super::{Accepted, Created, NoContent, WithStatus};
+    use crate::{
+        axum::{
+            routing::{delete, post},
+            ApiRouter,
+        },
+        openapi::OpenApi,
+        transform::TransformOperation,
+    };
+
+    async fn create() -> WithStatus<201, Json<String>> {
+        WithStatus(Json(String::new()))
+    }
+
+    async fn accept() -> (StatusCode, Json<String>) {
+        (StatusCode::ACCEPTED, Json(String::new()))
+    }
+
+    async fn create_todo() -> Created<Json<String>> {
+        Created::new("/todos/1", Json(String::new()))
+    }
+
+    async fn start_job() -> Accepted {
+        Accepted(())
+    }
+
+    async fn delete_todo() -> NoContent {
+        NoContent
+    }
+
+    #[test]
+    fn test_with_status() {
+        assert_eq!(
+            WithStatus::<201, _>(Json("")).into_response().status(),
+            StatusCode::CREATED
+        );
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/created", post(create))
+            .api_route(
+                "/accepted",
+                post(accept).put_with(accept, |op: TransformOperation| {
+                    op.response_status::<Json<String>>(StatusCode::ACCEPTED)
+                }),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let responses = paths["/created"]["post"]["responses"].as_object().unwrap();
+        assert_eq!(responses.keys().collect::<Vec<_>>(), ["201"]);
+
+        let responses = paths["/accepted"]["put"]["responses"].as_object().unwrap();
+        assert!(responses.contains_key("202"));
+    }
+
+    #[test]
+    fn test_semantic_responses() {
+        let res = Created::new("/todos/1", ()).into_response();
+        assert_eq!(res.status(), StatusCode::CREATED);
+        assert_eq!(res.headers()["location"], "/todos/1");
+        assert_eq!(Accepted(()).into_response().status(), StatusCode::ACCEPTED);
+        assert_eq!(NoContent.into_response().status(), StatusCode::NO_CONTENT);
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route("/todos", post(create_todo))
+            .api_route("/jobs", post(start_job))
+            .api_route("/todos/:id", delete(delete_todo))
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let created = &paths["/todos"]["post"]["responses"]["201"];
+        assert!(created["headers"]["Location"].is_object());
+        assert_eq!(
+            created["content"]["application/json"]["schema"]["type"],
+            "string"
+        );
+
+        let responses = paths["/jobs"]["post"]["responses"].as_object().unwrap();
+        assert_eq!(responses.keys().collect::<Vec<_>>(), ["202"]);
+
+        let responses = paths["/todos/{id}"]["delete"]["responses"]
+            .as_object()
+            .unwrap();
+        assert_eq!(responses.keys().collect::<Vec<_>>(), ["204"]);
+    }
+}
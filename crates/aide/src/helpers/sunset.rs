@@ -0,0 +1,184 @@
+//! Deprecation and sunset documentation.
+//!
+//! [`sunset`] marks an operation as deprecated and documents the
+//! `Deprecation` (RFC 9745) and `Sunset` (RFC 8594) response headers
+//! along with a `Link` header with the `sunset` relation.
+//!
+//! The operation that replaces the retired one can be recorded
+//! with [`sunset_replaced_by`], which adds a response link to the
+//! successful responses.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{helpers::sunset::sunset_replaced_by, transform::TransformOperation};
+//!
+//! fn get_user_v1_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, String>().with(sunset_replaced_by(
+//!         "Wed, 31 Dec 2025 23:59:59 GMT",
+//!         "https://example.com/changelog/users-v2",
+//!         "get_user_v2",
+//!     ))
+//! }
+//! ```
+
+use schemars::schema::{InstanceType, SchemaObject as JsonSchemaObject, StringValidation};
+
+use crate::{
+    gen::in_context,
+    openapi::{Link, LinkOperation, ParameterSchemaOrContent, ReferenceOr, SchemaObject},
+    transform::TransformOperation,
+};
+
+use super::{add_success_response_header, is_success, response_header};
+
+/// The pattern of the `Deprecation` header, a structured field
+/// date (RFC 9651) such as `@1688169599`.
+const DEPRECATION_PATTERN: &str = "^@-?[0-9]{1,15}$";
+
+/// Deprecate an operation that will be removed at `date`,
+/// which is an HTTP date, with more information at `link`.
+pub fn sunset(
+    date: &str,
+    link: &str,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    document_sunset(date, link, None)
+}
+
+/// Same as [`sunset`], but also link the operation
+/// with the ID `operation_id` as the replacement.
+pub fn sunset_replaced_by(
+    date: &str,
+    link: &str,
+    operation_id: &str,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    document_sunset(date, link, Some(operation_id))
+}
+
+fn document_sunset(
+    date: &str,
+    link: &str,
+    replacement: Option<&str>,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    let date = date.to_string();
+    let link = link.to_string();
+    let replacement = replacement.map(|operation_id| Link {
+        description: Some("The operation that replaces this one.".into()),
+        operation: LinkOperation::OperationId(operation_id.into()),
+        request_body: None,
+        parameters: Default::default(),
+        server: None,
+        extensions: Default::default(),
+    });
+
+    move |mut op| {
+        let operation = op.inner_mut();
+        operation.deprecated = true;
+
+        in_context(|ctx| {
+            let mut deprecation = response_header::<String>(
+                ctx,
+                "The date at which the operation was deprecated, \
+                 as seconds since the Unix epoch prefixed with `@`.",
+            );
+            deprecation.format = ParameterSchemaOrContent::Schema(SchemaObject {
+                json_schema: JsonSchemaObject {
+                    instance_type: Some(InstanceType::String.into()),
+                    string: Some(Box::new(StringValidation {
+                        pattern: Some(DEPRECATION_PATTERN.into()),
+                        ..Default::default()
+                    })),
+                    ..Default::default()
+                }
+                .into(),
+                example: None,
+                external_docs: None,
+            });
+            deprecation.example = Some("@1688169599".into());
+
+            let mut sunset = response_header::<String>(
+                ctx,
+                "The HTTP date after which the operation is no longer available.",
+            );
+            sunset.example = Some(date.clone().into());
+
+            let mut sunset_link = response_header::<String>(
+                ctx,
+                "A link to information about the retirement of the operation.",
+            );
+            sunset_link.example = Some(format!(r#"<{link}>; rel="sunset""#).into());
+
+            add_success_response_header(operation, "Deprecation", &deprecation);
+            add_success_response_header(operation, "Sunset", &sunset);
+            add_success_response_header(operation, "Link", &sunset_link);
+        });
+
+        if let (Some(replacement), Some(responses)) = (replacement, &mut operation.responses) {
+            for (status, res) in &mut responses.responses {
+                if let (true, Some(res)) = (is_success(status), res.as_item_mut()) {
+                    res.links
+                        .entry("replacement".into())
+                        .or_insert_with(|| ReferenceOr::Item(replacement.clone()));
+                }
+            }
+        }
+
+        op
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use super::{sunset, sunset_replaced_by};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    async fn get_user() -> String {
+        String::new()
+    }
+
+    #[test]
+    fn test_sunset() {
+        let date = "Wed, 31 Dec 2025 23:59:59 GMT";
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/v1/users",
+                get_with(get_user, sunset(date, "https://example.com/changelog")),
+            )
+            .api_route(
+                "/v2/users",
+                get_with(get_user, |op| {
+                    op.response::<200, String>().with(sunset_replaced_by(
+                        date,
+                        "https://example.com/changelog",
+                        "get_user_v3",
+                    ))
+                }),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let operation = &paths["/v1/users"]["get"];
+        let headers = &operation["responses"]["200"]["headers"];
+
+        assert_eq!(operation["deprecated"], true);
+        assert_eq!(headers["Deprecation"]["example"], "@1688169599");
+        assert_eq!(
+            headers["Deprecation"]["schema"]["pattern"],
+            "^@-?[0-9]{1,15}$"
+        );
+        assert_eq!(headers["Sunset"]["example"], date);
+        assert_eq!(
+            headers["Link"]["example"],
+            r#"<https://example.com/changelog>; rel="sunset""#
+        );
+        assert!(operation["responses"]["200"].get("links").is_none());
+
+        let links = &paths["/v2/users"]["get"]["responses"]["200"]["links"];
+        assert_eq!(links["replacement"]["operationId"], "get_user_v3");
+    }
+}
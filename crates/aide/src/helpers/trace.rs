@@ -0,0 +1,198 @@
+//! Trace and correlation headers.
+//!
+//! [`trace_context`] documents the W3C Trace Context headers
+//! (`traceparent` and `tracestate`) and [`request_id`] documents
+//! the `X-Request-Id` header. The headers are optional request
+//! parameters, and are echoed in all documented responses.
+//!
+//! The functions are usually applied to all operations with
+//! [`TransformOpenApi::all_operations`](crate::transform::TransformOpenApi::all_operations).
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::trace::{request_id, trace_context},
+//!     transform::TransformOpenApi,
+//! };
+//!
+//! fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+//!     api.title("Example API")
+//!         .all_operations(trace_context)
+//!         .all_operations(request_id)
+//! }
+//! ```
+
+use crate::{gen::in_context, transform::TransformOperation};
+
+use super::{add_missing_parameters, add_response_header, header_parameter, response_header};
+
+/// Document the `traceparent` and `tracestate` headers.
+pub fn trace_context(mut op: TransformOperation) -> TransformOperation {
+    let operation = op.inner_mut();
+
+    in_context(|ctx| {
+        let mut traceparent = header_parameter::<String>(
+            ctx,
+            "traceparent",
+            "The trace context of the request.",
+            false,
+        );
+        traceparent.parameter_data_mut().example =
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".into());
+
+        let params = [
+            traceparent,
+            header_parameter::<String>(
+                ctx,
+                "tracestate",
+                "Vendor-specific trace information.",
+                false,
+            ),
+        ];
+        add_missing_parameters(ctx, operation, params);
+
+        let traceparent = response_header::<String>(ctx, "The trace context of the response.");
+        let tracestate = response_header::<String>(ctx, "Vendor-specific trace information.");
+
+        add_response_header(operation, "traceparent", &traceparent, |_| true);
+        add_response_header(operation, "tracestate", &tracestate, |_| true);
+    });
+
+    op
+}
+
+/// Document the `X-Request-Id` header.
+pub fn request_id(op: TransformOperation) -> TransformOperation {
+    request_id_header("X-Request-Id")(op)
+}
+
+/// Same as [`request_id`], but with a custom header name.
+pub fn request_id_header(
+    name: &str,
+) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    let name = name.to_string();
+
+    move |mut op| {
+        let operation = op.inner_mut();
+
+        in_context(|ctx| {
+            let param = header_parameter::<String>(
+                ctx,
+                &name,
+                "An identifier of the request used for correlation, generated if missing.",
+                false,
+            );
+            add_missing_parameters(ctx, operation, [param]);
+
+            let header = response_header::<String>(ctx, "The identifier of the request.");
+            add_response_header(operation, &name, &header, |_| true);
+        });
+
+        op
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use serde_json::Value;
+
+    use super::{request_id, trace_context};
+    use crate::{
+        axum::{
+            routing::{get_with, post_with},
+            ApiRouter,
+        },
+        openapi::OpenApi,
+    };
+
+    async fn handler() -> axum::Json<u64> {
+        axum::Json(0)
+    }
+
+    fn names(value: &Value, key: &str) -> Vec<String> {
+        match value {
+            Value::Array(params) => params
+                .iter()
+                .map(|p| p[key].as_str().unwrap().to_string())
+                .collect(),
+            Value::Object(headers) => headers.keys().cloned().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    #[test]
+    // The methods themselves are not general over the lifetime of the transforms.
+    #[allow(clippy::redundant_closure_for_method_calls)]
+    fn test_trace_headers() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users",
+                get_with(handler, |op| op.response::<404, ()>()).post_with(handler, |op| {
+                    op.header_parameter::<String, _>("x-request-id", |p| {
+                        p.description("Declared by the route.")
+                    })
+                }),
+            )
+            .api_route("/users/:id", post_with(handler, |op| op))
+            .finish_api_with(&mut api, |api| {
+                api.response_header_all::<String>("x-request-id", "Declared by the API.")
+                    .all_operations(trace_context)
+                    .all_operations(request_id)
+                    // Applying them again changes nothing.
+                    .all_operations(trace_context)
+                    .all_operations(request_id)
+            });
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        let mut operations = 0;
+
+        for (path, method) in [
+            ("/users", "get"),
+            ("/users", "post"),
+            ("/users/{id}", "post"),
+        ] {
+            let op = &paths[path][method];
+            let mut params = names(&op["parameters"], "name");
+            params.retain(|name| name != "id");
+            params.sort_by_key(|name| name.to_ascii_lowercase());
+            assert_eq!(
+                params
+                    .iter()
+                    .map(|name| name.to_ascii_lowercase())
+                    .collect::<Vec<_>>(),
+                ["traceparent", "tracestate", "x-request-id"]
+            );
+
+            for res in op["responses"].as_object().unwrap().values() {
+                let mut headers = names(&res["headers"], "");
+                headers.sort();
+                assert_eq!(headers, ["traceparent", "tracestate", "x-request-id"]);
+                assert_eq!(
+                    res["headers"]["x-request-id"]["description"],
+                    "Declared by the API."
+                );
+            }
+
+            operations += 1;
+        }
+        assert_eq!(operations, 3);
+
+        // The parameter declared by the route is kept as is.
+        let params = &paths["/users"]["post"]["parameters"];
+        assert_eq!(
+            names(params, "name"),
+            ["x-request-id", "traceparent", "tracestate"]
+        );
+        assert_eq!(params[0]["description"], "Declared by the route.");
+        assert_eq!(
+            params[1]["example"],
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01"
+        );
+        assert_eq!(
+            names(&paths["/users"]["get"]["parameters"], "name"),
+            ["traceparent", "tracestate", "X-Request-Id"]
+        );
+    }
+}
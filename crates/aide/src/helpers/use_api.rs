@@ -1,3 +1,5 @@
+//! Override the documentation of a type with [`UseApi`].
+
 use std::{
     marker::PhantomData,
     ops::{Deref, DerefMut},
@@ -11,6 +13,7 @@ use crate::{OperationInput, OperationOutput};
 
 /// helper trait to allow simplified use of [`UseApi`] in responses
 pub trait IntoApi {
+    /// Wrap the value into [`UseApi`] with the documentation of `A`.
     fn into_api<A>(self) -> UseApi<Self, A>
     where
         Self: Sized;
@@ -1,3 +1,5 @@
+//! Override the documentation of a type via [`ApiOverride`] and [`WithApi`].
+
 use std::{
     marker::PhantomData,
     ops::{Deref, DerefMut},
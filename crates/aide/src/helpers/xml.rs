@@ -0,0 +1,452 @@
+//! XML bodies.
+//!
+//! [`Xml<T>`] documents `application/xml` request and response
+//! bodies with the schema of `T`. The name of the root element
+//! is derived from the schema name of `T` (which respects
+//! `#[serde(rename)]`), and arrays are documented as wrapped.
+//!
+//! Additional hints (namespaces, attributes and wrapped
+//! properties) can be provided with [`xml_hints`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     helpers::xml::{xml_hints, Xml, XmlHints},
+//!     transform::TransformOperation,
+//! };
+//! # #[derive(schemars::JsonSchema)]
+//! # struct User { id: u64, tags: Vec<String> }
+//!
+//! fn get_user_docs(op: TransformOperation) -> TransformOperation {
+//!     op.response::<200, Xml<User>>().with(xml_hints(
+//!         XmlHints::new()
+//!             .namespace("https://example.com/schema/user")
+//!             .attribute("id")
+//!             .wrapped("tags"),
+//!     ))
+//! }
+//! ```
+
+use indexmap::IndexMap;
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec, SubschemaValidation},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gen::{in_context, GenContext},
+    openapi::{Operation, Response},
+    operation::set_body,
+    transform::TransformOperation,
+    OperationInput, OperationOutput,
+};
+
+use super::{body_contents_mut, media_request_body, media_response};
+
+/// The XML media type.
+pub const MEDIA_TYPE: &str = "application/xml";
+
+/// The schema keyword that holds the [`XmlObject`].
+pub const XML_KEYWORD: &str = "xml";
+
+/// An XML request or response body of `T`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Xml<T>(pub T);
+
+/// Describes the XML representation of a schema.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct XmlObject {
+    /// The name of the element or attribute.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// The URI of the namespace definition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    /// The prefix used for the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+    /// Whether the property is an attribute instead of an element.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub attribute: bool,
+    /// Whether an array is wrapped in an element.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wrapped: bool,
+}
+
+impl XmlObject {
+    fn merge_with(&mut self, other: XmlObject) {
+        if other.name.is_some() {
+            self.name = other.name;
+        }
+        if other.namespace.is_some() {
+            self.namespace = other.namespace;
+        }
+        if other.prefix.is_some() {
+            self.prefix = other.prefix;
+        }
+        self.attribute |= other.attribute;
+        self.wrapped |= other.wrapped;
+    }
+}
+
+/// XML hints of a body, see [`xml_hints`].
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct XmlHints {
+    root: XmlObject,
+    properties: IndexMap<String, XmlObject>,
+}
+
+impl XmlHints {
+    /// Create empty hints.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the name of the root element.
+    pub fn name(mut self, name: &str) -> Self {
+        self.root.name = Some(name.into());
+        self
+    }
+
+    /// Set the namespace of the root element.
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.root.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Set the namespace prefix of the root element.
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.root.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Represent the given property as an attribute.
+    pub fn attribute(self, property: &str) -> Self {
+        self.property(
+            property,
+            XmlObject {
+                attribute: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Wrap the given array property in an element.
+    pub fn wrapped(self, property: &str) -> Self {
+        self.property(
+            property,
+            XmlObject {
+                wrapped: true,
+                ..Default::default()
+            },
+        )
+    }
+
+    /// Set the XML representation of the given property.
+    pub fn property(mut self, property: &str, xml: XmlObject) -> Self {
+        self.properties
+            .entry(property.into())
+            .or_default()
+            .merge_with(xml);
+        self
+    }
+}
+
+impl<T> OperationInput for Xml<T>
+where
+    T: JsonSchema,
+{
+    fn operation_input(ctx: &mut GenContext, operation: &mut Operation) {
+        let mut body = media_request_body::<T>(ctx, MEDIA_TYPE);
+        if let Some(schema) = body
+            .content
+            .get_mut(MEDIA_TYPE)
+            .and_then(|media| media.schema.as_mut())
+        {
+            document_root::<T>(ctx, &mut schema.json_schema);
+        }
+        set_body(ctx, operation, body);
+    }
+}
+
+impl<T> OperationOutput for Xml<T>
+where
+    T: JsonSchema,
+{
+    type Inner = T;
+
+    fn operation_response(ctx: &mut GenContext, _operation: &mut Operation) -> Option<Response> {
+        let mut res = media_response::<T>(ctx, MEDIA_TYPE, "");
+        if let Some(schema) = res
+            .content
+            .get_mut(MEDIA_TYPE)
+            .and_then(|media| media.schema.as_mut())
+        {
+            document_root::<T>(ctx, &mut schema.json_schema);
+        }
+        Some(res)
+    }
+
+    fn inferred_responses(
+        ctx: &mut GenContext,
+        operation: &mut Operation,
+    ) -> Vec<(Option<u16>, Response)> {
+        if let Some(res) = Self::operation_response(ctx, operation) {
+            Vec::from([(Some(200), res)])
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+/// Apply XML hints to all `application/xml` bodies of an operation.
+///
+/// Property hints modify the schema of the body type, so they
+/// also apply everywhere else the same type is used in XML.
+pub fn xml_hints(hints: XmlHints) -> impl FnOnce(TransformOperation) -> TransformOperation + Clone {
+    move |mut op| {
+        in_context(|ctx| {
+            for content in body_contents_mut(op.inner_mut()) {
+                let Some(Schema::Object(schema)) = content
+                    .get_mut(MEDIA_TYPE)
+                    .and_then(|media| media.schema.as_mut())
+                    .map(|schema| &mut schema.json_schema)
+                else {
+                    continue;
+                };
+
+                set_xml(schema, &hints.root);
+
+                if hints.properties.is_empty() {
+                    continue;
+                }
+
+                let target = match reference_of(schema) {
+                    Some(r) => {
+                        let name = r.strip_prefix("#/components/schemas/").unwrap_or(&r);
+                        match ctx.schema.definitions_mut().get_mut(name) {
+                            Some(Schema::Object(target)) => target,
+                            _ => continue,
+                        }
+                    }
+                    None => schema,
+                };
+
+                for (name, xml) in &hints.properties {
+                    if let Some(Schema::Object(property)) = target.object().properties.get_mut(name)
+                    {
+                        set_xml(property, xml);
+                    }
+                }
+            }
+        });
+
+        op
+    }
+}
+
+/// Document the name of the root element of `T`, and
+/// whether it is wrapped if it is an array.
+fn document_root<T: JsonSchema>(ctx: &GenContext, schema: &mut Schema) {
+    let Schema::Object(schema) = schema else {
+        return;
+    };
+
+    let is_array = matches!(
+        &ctx.resolve_schema(schema).instance_type,
+        Some(SingleOrVec::Single(ty)) if **ty == InstanceType::Array
+    );
+
+    let root = XmlObject {
+        name: T::is_referenceable().then(T::schema_name),
+        wrapped: is_array,
+        ..Default::default()
+    };
+
+    set_xml(schema, &root);
+}
+
+/// The reference of a schema, or of the only `allOf` subschema
+/// of a schema that has been wrapped by [`set_xml`].
+fn reference_of(schema: &SchemaObject) -> Option<String> {
+    if let Some(reference) = &schema.reference {
+        return Some(reference.clone());
+    }
+
+    match schema.subschemas.as_ref()?.all_of.as_deref()? {
+        [Schema::Object(inner)] => inner.reference.clone(),
+        _ => None,
+    }
+}
+
+/// Set the XML object of a schema.
+///
+/// Keywords next to a `$ref` are ignored, so a reference
+/// is wrapped in an `allOf` that holds the XML object.
+fn set_xml(schema: &mut SchemaObject, xml: &XmlObject) {
+    let mut current = schema
+        .extensions
+        .get(XML_KEYWORD)
+        .and_then(|value| serde_json::from_value::<XmlObject>(value.clone()).ok())
+        .unwrap_or_default();
+
+    current.merge_with(xml.clone());
+
+    if current == XmlObject::default() {
+        return;
+    }
+
+    let Ok(value) = serde_json::to_value(current) else {
+        return;
+    };
+
+    if schema.reference.is_some() {
+        let reference = std::mem::take(schema);
+        schema.subschemas = Some(Box::new(SubschemaValidation {
+            all_of: Some(vec![reference.into()]),
+            ..Default::default()
+        }));
+    }
+
+    schema.extensions.insert(XML_KEYWORD.into(), value);
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use axum::{
+        async_trait,
+        body::Body,
+        extract::FromRequest,
+        response::{IntoResponse, Response},
+    };
+    use http::{header, HeaderValue, Request, StatusCode};
+    use serde::{de::DeserializeOwned, Serialize};
+
+    use super::{Xml, MEDIA_TYPE};
+
+    impl<T> IntoResponse for Xml<T>
+    where
+        T: Serialize,
+    {
+        fn into_response(self) -> Response {
+            match quick_xml::se::to_string(&self.0) {
+                Ok(body) => (
+                    [(header::CONTENT_TYPE, HeaderValue::from_static(MEDIA_TYPE))],
+                    body,
+                )
+                    .into_response(),
+                Err(err) => (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response(),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl<T, S> FromRequest<S> for Xml<T>
+    where
+        T: DeserializeOwned,
+        S: Send + Sync,
+    {
+        type Rejection = Response;
+
+        async fn from_request(req: Request<Body>, state: &S) -> Result<Self, Self::Rejection> {
+            let body = String::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+
+            quick_xml::de::from_str(&body)
+                .map(Self)
+                .map_err(|err| (StatusCode::BAD_REQUEST, err.to_string()).into_response())
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use axum::response::IntoResponse;
+    use schemars::JsonSchema;
+    use serde::Serialize;
+    use serde_json::json;
+
+    use super::{xml_hints, Xml, XmlHints, MEDIA_TYPE};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Serialize, JsonSchema)]
+    struct User {
+        id: u64,
+        tags: Vec<String>,
+    }
+
+    async fn get_user() -> Xml<User> {
+        Xml(User {
+            id: 1,
+            tags: Vec::new(),
+        })
+    }
+
+    async fn list_users() -> Xml<Vec<User>> {
+        Xml(Vec::new())
+    }
+
+    #[test]
+    fn test_xml_hints() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users/:id",
+                get_with(get_user, |op| {
+                    op.with(xml_hints(
+                        XmlHints::new()
+                            .namespace("https://example.com/schema/user")
+                            .attribute("id")
+                            .wrapped("tags"),
+                    ))
+                }),
+            )
+            .api_route("/users", get_with(list_users, |op| op))
+            .finish_api(&mut api);
+
+        let value = serde_json::to_value(&api).unwrap();
+        let schema = |path: &str| {
+            value["paths"][path]["get"]["responses"]["200"]["content"][MEDIA_TYPE]["schema"].clone()
+        };
+
+        // The root element is documented next to the reference in an `allOf`.
+        let user = schema("/users/{id}");
+        assert!(user.get("$ref").is_none());
+        assert_eq!(
+            user["allOf"],
+            json!([{ "$ref": "#/components/schemas/User" }])
+        );
+        assert_eq!(
+            user["xml"],
+            json!({ "name": "User", "namespace": "https://example.com/schema/user" })
+        );
+
+        let properties = &value["components"]["schemas"]["User"]["properties"];
+        assert_eq!(properties["id"]["xml"], json!({ "attribute": true }));
+        assert_eq!(properties["tags"]["xml"], json!({ "wrapped": true }));
+
+        let users = schema("/users");
+        assert_eq!(users["type"], "array");
+        assert_eq!(users["xml"], json!({ "wrapped": true }));
+    }
+
+    #[test]
+    fn test_xml_response() {
+        let res = Xml(User {
+            id: 1,
+            tags: Vec::new(),
+        })
+        .into_response();
+
+        assert_eq!(res.status(), 200);
+        assert_eq!(res.headers()["content-type"], MEDIA_TYPE);
+    }
+}
@@ -0,0 +1,242 @@
+//! Descriptions in multiple languages.
+//!
+//! Descriptions, summaries and titles can be given as translation keys,
+//! created with [`key`], that are resolved against the locale bundles of
+//! a [`Localization`] when the documentation is generated:
+//!
+//! - [`Localization::apply`] resolves the keys in the default locale and
+//!   adds all translations to the [`DESCRIPTIONS_I18N`] extension of the
+//!   object, for documentation UIs that can switch the language,
+//! - [`Localization::localize`] produces a copy of the documentation
+//!   in a single locale, e.g. to publish a document per language.
+//!
+//! Missing translations fall back to the default locale, keys without
+//! any translation are left as they are and reported as
+//! [`Error::MissingTranslation`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     i18n::{self, Localization},
+//!     openapi::OpenApi,
+//!     transform::TransformOperation,
+//! };
+//!
+//! fn create_todo_docs(op: TransformOperation) -> TransformOperation {
+//!     op.summary(&i18n::key("todos.create"))
+//! }
+//!
+//! let localization = Localization::new("en")
+//!     .bundle("en", [("todos.create", "Create a todo.")])
+//!     .bundle("de", [("todos.create", "Ein Todo anlegen.")]);
+//!
+//! // Resolve the keys in the generated documentation.
+//! aide::gen::localization(localization.clone());
+//!
+//! // Publish a German copy.
+//! let api = OpenApi::default();
+//! let german_api = localization.localize(&api, "de");
+//! ```
+
+use indexmap::IndexMap;
+use serde_json::{Map, Value};
+
+use crate::{error::Error, gen::in_context, openapi::OpenApi, util::transform_objects};
+
+/// The prefix of translation keys.
+pub const KEY_PREFIX: &str = "i18n:";
+
+/// The extension of objects with translated fields, mapping the
+/// names of the fields to their text in every locale.
+pub const DESCRIPTIONS_I18N: &str = "x-descriptions-i18n";
+
+/// The fields that can be translated.
+const FIELDS: [&str; 3] = ["description", "summary", "title"];
+
+/// A translation key to use as a description, summary or title.
+#[must_use]
+pub fn key(key: &str) -> String {
+    format!("{KEY_PREFIX}{key}")
+}
+
+/// The locale bundles of the documentation.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct Localization {
+    default_locale: String,
+    bundles: IndexMap<String, IndexMap<String, String>>,
+}
+
+impl Localization {
+    /// Localization without bundles, with the locale that
+    /// is used if a translation is missing.
+    pub fn new(default_locale: &str) -> Self {
+        Self {
+            default_locale: default_locale.into(),
+            bundles: IndexMap::new(),
+        }
+    }
+
+    /// Add translations to the bundle of the locale.
+    pub fn bundle<I, K, V>(mut self, locale: &str, translations: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.bundles.entry(locale.into()).or_default().extend(
+            translations
+                .into_iter()
+                .map(|(key, text)| (key.into(), text.into())),
+        );
+        self
+    }
+
+    /// The locales with a bundle.
+    pub fn locales(&self) -> impl Iterator<Item = &str> {
+        self.bundles.keys().map(String::as_str)
+    }
+
+    /// Resolve the translation keys of the document in the default
+    /// locale, and document all translations in the
+    /// [`DESCRIPTIONS_I18N`] extension.
+    pub fn apply(&self, api: &mut OpenApi) {
+        transform_objects(api, |object| {
+            let mut translations = Map::new();
+
+            for field in FIELDS {
+                let Some(key) = translation_key(object, field) else {
+                    continue;
+                };
+
+                let texts = self
+                    .bundles
+                    .iter()
+                    .filter_map(|(locale, bundle)| {
+                        Some((locale.clone(), Value::from(bundle.get(&key)?.clone())))
+                    })
+                    .collect::<Map<_, _>>();
+
+                if let Some(text) = self.translate(&key, &self.default_locale) {
+                    object.insert(field.into(), text.into());
+                }
+                if !texts.is_empty() {
+                    translations.insert(field.into(), texts.into());
+                }
+            }
+
+            if !translations.is_empty() && !object.contains_key("$ref") {
+                object.insert(DESCRIPTIONS_I18N.into(), translations.into());
+            }
+        });
+    }
+
+    /// A copy of the document in the given locale.
+    ///
+    /// Both documents with translation keys and documents that
+    /// were already [resolved](Self::apply) can be localized.
+    #[must_use]
+    pub fn localize(&self, api: &OpenApi, locale: &str) -> OpenApi {
+        let mut api = api.clone();
+        transform_objects(&mut api, |object| {
+            let translations = object.remove(DESCRIPTIONS_I18N);
+
+            for field in FIELDS {
+                let translated = translations
+                    .as_ref()
+                    .and_then(|t| t.get(field))
+                    .and_then(|texts| {
+                        texts
+                            .get(locale)
+                            .or_else(|| texts.get(&self.default_locale))
+                    })
+                    .and_then(Value::as_str)
+                    .map(String::from);
+
+                let text = match translation_key(object, field) {
+                    Some(key) => self.translate(&key, locale),
+                    None => translated,
+                };
+                if let Some(text) = text {
+                    object.insert(field.into(), text.into());
+                }
+            }
+        });
+        api
+    }
+
+    /// The translation of the key in the locale or the default locale.
+    fn translate(&self, key: &str, locale: &str) -> Option<String> {
+        let text = [locale, &self.default_locale]
+            .into_iter()
+            .find_map(|locale| self.bundles.get(locale)?.get(key))
+            .cloned();
+
+        if text.is_none() {
+            in_context(|ctx| ctx.error(Error::MissingTranslation(key.into(), locale.into())));
+        }
+        text
+    }
+}
+
+fn translation_key(object: &Map<String, Value>, field: &str) -> Option<String> {
+    object
+        .get(field)?
+        .as_str()?
+        .strip_prefix(KEY_PREFIX)
+        .map(String::from)
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use super::{key, Localization, DESCRIPTIONS_I18N};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        gen,
+        openapi::OpenApi,
+    };
+
+    async fn handler() {}
+
+    #[test]
+    fn test_localization() {
+        let localization = Localization::new("en")
+            .bundle("en", [("todos.list", "List todos."), ("api", "Todos")])
+            .bundle("de", [("todos.list", "Todos auflisten.")]);
+
+        gen::reset_context();
+        let missing = std::rc::Rc::new(std::cell::Cell::new(0));
+        gen::on_error({
+            let missing = missing.clone();
+            move |_| missing.set(missing.get() + 1)
+        });
+        gen::localization(localization.clone());
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos",
+                get_with(handler, |op| {
+                    op.summary(&key("todos.list"))
+                        .description(&key("todos.unknown"))
+                }),
+            )
+            .finish_api_with(&mut api, |api| api.title(&key("api")));
+
+        assert_eq!(api.info.title, "Todos");
+        assert_eq!(missing.get(), 1);
+
+        let op =
+            serde_json::to_value(api.paths.as_ref().unwrap()).unwrap()["/todos"]["get"].clone();
+        assert_eq!(op["summary"], "List todos.");
+        assert_eq!(op["description"], "i18n:todos.unknown");
+        assert_eq!(op[DESCRIPTIONS_I18N]["summary"]["de"], "Todos auflisten.");
+
+        let german = localization.localize(&api, "de");
+        let op = serde_json::to_value(german.paths.unwrap()).unwrap()["/todos"]["get"].clone();
+        assert_eq!(op["summary"], "Todos auflisten.");
+        assert!(op.get(DESCRIPTIONS_I18N).is_none());
+        assert_eq!(german.info.title, "Todos");
+    }
+}
@@ -48,16 +48,45 @@ where
         T::operation_response(ctx, operation)
     }
 
+    /// The responses of `T` and `E`.
+    ///
+    /// Successful responses of `E` are documented as the default
+    /// response, as an error is never returned with a successful status.
     fn inferred_responses(
         ctx: &mut crate::gen::GenContext,
         operation: &mut Operation,
     ) -> Vec<(Option<u16>, Response)> {
-        let mut responses = T::inferred_responses(ctx, operation);
-        responses.extend(E::inferred_responses(ctx, operation));
+        let mut responses = responses_of::<T>(ctx, operation, Some(200));
+        responses.extend(responses_of::<E>(ctx, operation, None).into_iter().map(
+            |(status, res)| match status {
+                Some(200..=299) => (None, res),
+                status => (status, res),
+            },
+        ));
         responses
     }
 }
 
+/// The inferred responses of `T`, or its response with
+/// the given status if it does not infer any responses,
+/// e.g. for error types that only implement
+/// [`OperationOutput::operation_response`].
+pub(crate) fn responses_of<T: OperationOutput>(
+    ctx: &mut crate::gen::GenContext,
+    operation: &mut Operation,
+    status: Option<u16>,
+) -> Vec<(Option<u16>, Response)> {
+    let responses = T::inferred_responses(ctx, operation);
+    if !responses.is_empty() {
+        return responses;
+    }
+
+    T::operation_response(ctx, operation)
+        .map(|res| (status, res))
+        .into_iter()
+        .collect()
+}
+
 impl<T> OperationInput for Option<T>
 where
     T: OperationInput,
@@ -447,7 +476,7 @@ where
         ctx: &mut crate::gen::GenContext,
         operation: &mut Operation,
     ) -> Vec<(Option<u16>, Response)> {
-        let mut responses = T::inferred_responses(ctx, operation);
+        let mut responses = responses_of::<T>(ctx, operation, Some(200));
         responses.extend(S::inferred_responses(ctx, operation));
         responses
     }
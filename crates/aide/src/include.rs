@@ -0,0 +1,171 @@
+//! Long descriptions from Markdown files.
+//!
+//! Long-form descriptions are easier to write and review in Markdown
+//! files than in Rust string literals. They can be included when the
+//! code is compiled with [`description_file!`](crate::description_file),
+//! which fails to compile if the file does not exist:
+//!
+//! ```ignore
+//! op.description(aide::description_file!("docs/get_user.md"))
+//! ```
+//!
+//! Or they can be referenced with [`file`] and loaded from a directory
+//! by [`DescriptionFiles`] when the documentation is generated, e.g. to
+//! update the documentation without recompiling:
+//!
+//! ```
+//! use aide::{include::{self, DescriptionFiles}, transform::TransformOperation};
+//!
+//! fn get_user_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description(&include::file("get_user.md"))
+//! }
+//!
+//! aide::gen::description_files(DescriptionFiles::new("docs"));
+//! ```
+//!
+//! Files are read once and cached. Files that cannot be read are
+//! reported as [`Error::DescriptionFile`] and the reference is left
+//! in the description.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use serde_json::Value;
+
+use crate::{error::Error, gen::in_context, openapi::OpenApi, util::transform_objects};
+
+/// The prefix of descriptions that reference a file.
+pub const INCLUDE_PREFIX: &str = "include:";
+
+/// Include a file relative to the manifest directory of the crate
+/// as a string when the code is compiled, see [`include`](crate::include).
+#[macro_export]
+macro_rules! description_file {
+    ($path:literal) => {
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path))
+    };
+}
+
+/// A reference to a file to use as a description, relative
+/// to the directory of the [`DescriptionFiles`].
+#[must_use]
+pub fn file(path: &str) -> String {
+    format!("{INCLUDE_PREFIX}{path}")
+}
+
+/// Loads the files referenced in descriptions from a directory.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct DescriptionFiles {
+    root: PathBuf,
+    cache: Rc<RefCell<HashMap<PathBuf, Result<String, String>>>>,
+}
+
+impl DescriptionFiles {
+    /// Load the files relative to the directory `root`.
+    pub fn new(root: impl AsRef<Path>) -> Self {
+        Self {
+            root: root.as_ref().into(),
+            cache: Rc::default(),
+        }
+    }
+
+    /// Replace the file references in the descriptions
+    /// of the document with the contents of the files.
+    pub fn apply(&self, api: &mut OpenApi) {
+        transform_objects(api, |object| {
+            let Some(Value::String(description)) = object.get_mut("description") else {
+                return;
+            };
+            let Some(path) = description.strip_prefix(INCLUDE_PREFIX) else {
+                return;
+            };
+
+            if let Some(contents) = self.load(path) {
+                *description = contents;
+            }
+        });
+    }
+
+    /// The contents of the file, or `None` if it cannot be read.
+    fn load(&self, path: &str) -> Option<String> {
+        let path = self.root.join(path);
+        let mut cache = self.cache.borrow_mut();
+        let contents = cache.entry(path.clone()).or_insert_with(|| {
+            fs::read_to_string(&path)
+                .map(|contents| contents.trim_end().into())
+                .map_err(|error| error.to_string())
+        });
+
+        match contents {
+            Ok(contents) => Some(contents.clone()),
+            Err(error) => {
+                in_context(|ctx| {
+                    ctx.error(Error::DescriptionFile(
+                        path.display().to_string(),
+                        error.clone(),
+                    ));
+                });
+                None
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "axum"))]
+mod tests {
+    use std::{cell::Cell, fs, rc::Rc};
+
+    use super::{file, DescriptionFiles};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        gen,
+        openapi::OpenApi,
+    };
+
+    async fn handler() {}
+
+    #[test]
+    fn test_description_files() {
+        let root = std::env::temp_dir().join(format!("aide-include-{}", std::process::id()));
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("list.md"), "# Todos\n\nList the todos.\n").unwrap();
+
+        gen::reset_context();
+        let errors = Rc::new(Cell::new(0));
+        gen::on_error({
+            let errors = errors.clone();
+            move |_| errors.set(errors.get() + 1)
+        });
+        gen::description_files(DescriptionFiles::new(&root));
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos",
+                get_with(handler, |op| op.description(&file("list.md"))),
+            )
+            .api_route(
+                "/missing",
+                get_with(handler, |op| op.description(&file("missing.md"))),
+            )
+            .finish_api(&mut api);
+        fs::remove_dir_all(&root).unwrap();
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        assert_eq!(
+            paths["/todos"]["get"]["description"],
+            "# Todos\n\nList the todos."
+        );
+        assert_eq!(
+            paths["/missing"]["get"]["description"],
+            "include:missing.md"
+        );
+        assert_eq!(errors.get(), 1);
+    }
+}
@@ -0,0 +1,406 @@
+//! A compact representation of large documents.
+//!
+//! The strings of a generated document repeat a lot: keys like
+//! `"description"` or `"$ref"`, media types, reference paths, tags
+//! and shared descriptions appear in every operation. An
+//! [`InternedOpenApi`] is an immutable copy of a document in which
+//! every distinct string is stored once behind an [`Arc<str>`], and
+//! only the fields that are present are stored at all.
+//!
+//! This cuts the memory of documents that are kept around to be
+//! served by an order of magnitude (see the `intern` benchmark),
+//! serializing the copy is about as fast as serializing the document.
+//! The copy serializes to exactly the same JSON as the original
+//! document, including the order of the fields.
+//!
+//! The model types in [`openapi`](crate::openapi) are not affected,
+//! documents are still built and changed as usual and interned once
+//! they are finished.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{intern::InternedOpenApi, openapi::OpenApi};
+//!
+//! let mut api = OpenApi {
+//!     openapi: "3.1.0".into(),
+//!     ..Default::default()
+//! };
+//! api.info.title = "Example".into();
+//!
+//! let interned = InternedOpenApi::new(&api).unwrap();
+//! assert_eq!(
+//!     serde_json::to_string(&interned).unwrap(),
+//!     serde_json::to_string(&api).unwrap()
+//! );
+//! assert_eq!(interned.to_openapi().unwrap(), api);
+//! ```
+
+use std::{collections::HashSet, fmt, io::Read, sync::Arc};
+
+use serde::{
+    de::{DeserializeSeed, MapAccess, SeqAccess, Visitor},
+    ser::{SerializeMap, SerializeSeq},
+    Deserializer, Serialize, Serializer,
+};
+use serde_json::Number;
+
+use crate::openapi::OpenApi;
+
+/// An immutable document with deduplicated strings.
+///
+/// See the [module documentation](self) for details.
+#[derive(Debug, Clone)]
+pub struct InternedOpenApi {
+    root: Node,
+    strings: usize,
+}
+
+impl InternedOpenApi {
+    /// Create an interned copy of the document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the document cannot be serialized.
+    pub fn new(api: &OpenApi) -> serde_json::Result<Self> {
+        Self::from_slice(&serde_json::to_vec(api)?)
+    }
+
+    /// Read an interned document from JSON.
+    ///
+    /// The JSON is not checked to be a valid document until it is
+    /// converted with [`to_openapi`](Self::to_openapi).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is not valid JSON.
+    pub fn from_slice(json: &[u8]) -> serde_json::Result<Self> {
+        Self::read(&mut serde_json::Deserializer::from_slice(json))
+    }
+
+    /// Read an interned document from a JSON reader, e.g. a file,
+    /// without reading the whole input into memory first.
+    ///
+    /// The JSON is not checked to be a valid document until it is
+    /// converted with [`to_openapi`](Self::to_openapi).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if reading fails or the input is not valid JSON.
+    pub fn from_reader(reader: impl Read) -> serde_json::Result<Self> {
+        Self::read(&mut serde_json::Deserializer::from_reader(reader))
+    }
+
+    fn read<'de, R>(de: &mut serde_json::Deserializer<R>) -> serde_json::Result<Self>
+    where
+        R: serde_json::de::Read<'de>,
+    {
+        let mut interner = Interner::default();
+        let root = NodeSeed(&mut interner).deserialize(&mut *de)?;
+        de.end()?;
+
+        Ok(Self {
+            root,
+            strings: interner.0.len(),
+        })
+    }
+
+    /// Convert the interned copy back to a document.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interned JSON is not a valid document.
+    pub fn to_openapi(&self) -> serde_json::Result<OpenApi> {
+        serde_json::from_slice(&serde_json::to_vec(self)?)
+    }
+
+    /// The number of distinct strings stored.
+    #[must_use]
+    pub fn unique_strings(&self) -> usize {
+        self.strings
+    }
+}
+
+impl Serialize for InternedOpenApi {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.root.serialize(serializer)
+    }
+}
+
+/// A JSON value that only stores interned strings.
+#[derive(Debug, Clone)]
+enum Node {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(Arc<str>),
+    Array(Box<[Node]>),
+    Object(Box<[(Arc<str>, Node)]>),
+}
+
+impl Serialize for Node {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Node::Null => serializer.serialize_unit(),
+            Node::Bool(b) => serializer.serialize_bool(*b),
+            Node::Number(n) => n.serialize(serializer),
+            Node::String(s) => serializer.serialize_str(s),
+            Node::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in &**items {
+                    seq.serialize_element(item)?;
+                }
+                seq.end()
+            }
+            Node::Object(entries) => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, value) in &**entries {
+                    map.serialize_entry(&**key, value)?;
+                }
+                map.end()
+            }
+        }
+    }
+}
+
+#[derive(Default)]
+struct Interner(HashSet<Arc<str>>);
+
+impl Interner {
+    fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.0.get(s) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.0.insert(interned.clone());
+        interned
+    }
+}
+
+/// Reads a value in the order of the input, interning all strings.
+struct NodeSeed<'a>(&'a mut Interner);
+
+impl<'de> DeserializeSeed<'de> for NodeSeed<'_> {
+    type Value = Node;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Node, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for NodeSeed<'_> {
+    type Value = Node;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_unit<E>(self) -> Result<Node, E> {
+        Ok(Node::Null)
+    }
+
+    fn visit_none<E>(self) -> Result<Node, E> {
+        Ok(Node::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Node, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_bool<E>(self, b: bool) -> Result<Node, E> {
+        Ok(Node::Bool(b))
+    }
+
+    fn visit_i64<E>(self, n: i64) -> Result<Node, E> {
+        Ok(Node::Number(n.into()))
+    }
+
+    fn visit_u64<E>(self, n: u64) -> Result<Node, E> {
+        Ok(Node::Number(n.into()))
+    }
+
+    fn visit_f64<E>(self, n: f64) -> Result<Node, E> {
+        Ok(Number::from_f64(n).map_or(Node::Null, Node::Number))
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Node, E> {
+        Ok(Node::String(self.0.intern(s)))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Node, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut items = Vec::with_capacity(seq.size_hint().unwrap_or_default());
+        while let Some(item) = seq.next_element_seed(NodeSeed(self.0))? {
+            items.push(item);
+        }
+        Ok(Node::Array(items.into_boxed_slice()))
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Node, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        let mut entries = Vec::with_capacity(map.size_hint().unwrap_or_default());
+        while let Some(key) = map.next_key_seed(KeySeed(self.0))? {
+            let value = map.next_value_seed(NodeSeed(self.0))?;
+            entries.push((key, value));
+        }
+        Ok(Node::Object(entries.into_boxed_slice()))
+    }
+}
+
+struct KeySeed<'a>(&'a mut Interner);
+
+impl<'de> DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = Arc<str>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Arc<str>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl Visitor<'_> for KeySeed<'_> {
+    type Value = Arc<str>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a string key")
+    }
+
+    fn visit_str<E>(self, s: &str) -> Result<Arc<str>, E> {
+        Ok(self.0.intern(s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::openapi::{Info, Operation, PathItem, Paths, ReferenceOr};
+
+    fn api() -> OpenApi {
+        let op = |id: &str| Operation {
+            operation_id: Some(id.into()),
+            summary: Some("Get a resource".into()),
+            tags: vec!["resources".into()],
+            ..Default::default()
+        };
+
+        // Not in sorted order, the order must be kept.
+        let paths = ["/zebras", "/apples", "/mangos"]
+            .into_iter()
+            .enumerate()
+            .map(|(i, path)| {
+                let item = PathItem {
+                    get: Some(op(&format!("get{i}"))),
+                    ..Default::default()
+                };
+                (path.to_string(), ReferenceOr::Item(item))
+            })
+            .collect::<IndexMap<_, _>>();
+
+        OpenApi {
+            openapi: "3.1.0".into(),
+            info: Info {
+                title: "Example".into(),
+                version: "1.2.3".into(),
+                ..Default::default()
+            },
+            paths: Some(Paths {
+                paths,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_serializes_identically() {
+        let api = api();
+        let interned = InternedOpenApi::new(&api).unwrap();
+
+        assert_eq!(
+            serde_json::to_string(&interned).unwrap(),
+            serde_json::to_string(&api).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string_pretty(&interned).unwrap(),
+            serde_json::to_string_pretty(&api).unwrap()
+        );
+        assert_eq!(interned.to_openapi().unwrap(), api);
+    }
+
+    #[test]
+    fn test_strings_are_shared() {
+        let interned = InternedOpenApi::new(&api()).unwrap();
+
+        let mut summaries = Vec::new();
+        collect(&interned.root, "summary", &mut summaries);
+        assert_eq!(summaries.len(), 3);
+        assert!(summaries.iter().all(|s| Arc::ptr_eq(s, &summaries[0])));
+
+        // "get0".."get2" and the paths are distinct, everything else is shared.
+        let json = serde_json::to_string(&interned).unwrap();
+        assert!(interned.unique_strings() < json.matches('"').count() / 2);
+    }
+
+    fn collect(node: &Node, name: &str, found: &mut Vec<Arc<str>>) {
+        match node {
+            Node::Array(items) => {
+                for item in &**items {
+                    collect(item, name, found);
+                }
+            }
+            Node::Object(entries) => {
+                for (key, value) in &**entries {
+                    match value {
+                        Node::String(s) if &**key == name => found.push(s.clone()),
+                        _ => collect(value, name, found),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[test]
+    fn test_from_reader() {
+        let api = api();
+        let json = serde_json::to_vec(&api).unwrap();
+
+        let interned = InternedOpenApi::from_reader(&json[..]).unwrap();
+        assert_eq!(serde_json::to_vec(&interned).unwrap(), json);
+        assert_eq!(interned.to_openapi().unwrap(), api);
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(InternedOpenApi::from_slice(b"{\"openapi\":").is_err());
+        assert!(InternedOpenApi::from_slice(b"{} {}").is_err());
+
+        // Valid JSON, but not a document.
+        let interned = InternedOpenApi::from_slice(b"[1, -2, 3.5, null, true]").unwrap();
+        assert_eq!(
+            serde_json::to_string(&interned).unwrap(),
+            "[1,-2,3.5,null,true]"
+        );
+        assert!(interned.to_openapi().is_err());
+    }
+}
@@ -0,0 +1,148 @@
+//! Placeholders in descriptions.
+//!
+//! Descriptions, summaries and titles can contain placeholders like
+//! `{{base_url}}` or `{{rate_limit}}` for values that depend on the
+//! environment or the plan of the customer, which are replaced with
+//! the variables of an [`Interpolation`] when the documentation is
+//! generated.
+//!
+//! Placeholders without a variable are left as they are and
+//! reported as [`Error::UnknownPlaceholder`].
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{interpolation::Interpolation, transform::TransformOperation};
+//!
+//! fn list_todos_docs(op: TransformOperation) -> TransformOperation {
+//!     op.description("At most {{rate_limit}} requests per minute are allowed.")
+//! }
+//!
+//! aide::gen::interpolation(
+//!     Interpolation::new()
+//!         .var("base_url", "https://api.example.com")
+//!         .var("rate_limit", "600"),
+//! );
+//! ```
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::{error::Error, gen::in_context, openapi::OpenApi, util::transform_objects};
+
+/// The fields that can contain placeholders.
+const FIELDS: [&str; 3] = ["description", "summary", "title"];
+
+/// The variables of the placeholders in descriptions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[must_use]
+pub struct Interpolation {
+    vars: IndexMap<String, String>,
+}
+
+impl Interpolation {
+    /// No variables.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace the placeholder `{{name}}` with the value.
+    pub fn var(mut self, name: &str, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Replace the placeholders in the descriptions,
+    /// summaries and titles of the document.
+    pub fn apply(&self, api: &mut OpenApi) {
+        transform_objects(api, |object| {
+            for field in FIELDS {
+                if let Some(Value::String(text)) = object.get_mut(field) {
+                    if text.contains("{{") {
+                        *text = self.interpolate(text);
+                    }
+                }
+            }
+        });
+    }
+
+    /// Replace the placeholders in the text.
+    #[must_use]
+    pub fn interpolate(&self, text: &str) -> String {
+        let mut interpolated = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(start) = rest.find("{{") {
+            let Some(end) = rest[start..].find("}}").map(|end| start + end) else {
+                break;
+            };
+
+            let placeholder = &rest[start..end + 2];
+            let name = rest[start + 2..end].trim();
+            interpolated.push_str(&rest[..start]);
+            match self.vars.get(name) {
+                Some(value) => interpolated.push_str(value),
+                None => {
+                    in_context(|ctx| ctx.error(Error::UnknownPlaceholder(name.into())));
+                    interpolated.push_str(placeholder);
+                }
+            }
+            rest = &rest[end + 2..];
+        }
+
+        interpolated.push_str(rest);
+        interpolated
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Interpolation;
+
+    #[test]
+    fn test_interpolate() {
+        let interpolation = Interpolation::new()
+            .var("base_url", "https://api.example.com")
+            .var("rate_limit", "600");
+
+        assert_eq!(
+            interpolation.interpolate("See {{base_url}}/docs, {{ rate_limit }} per minute."),
+            "See https://api.example.com/docs, 600 per minute."
+        );
+        assert_eq!(
+            interpolation.interpolate("{{unknown}} and {{base_url"),
+            "{{unknown}} and {{base_url"
+        );
+    }
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn test_interpolation() {
+        use crate::{
+            axum::{routing::get_with, ApiRouter},
+            gen,
+            openapi::OpenApi,
+        };
+
+        async fn handler() {}
+
+        gen::reset_context();
+        gen::interpolation(Interpolation::new().var("rate_limit", "600"));
+
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos",
+                get_with(handler, |op| {
+                    op.description("At most {{rate_limit}} requests per minute.")
+                }),
+            )
+            .finish_api(&mut api);
+
+        let paths = serde_json::to_value(api.paths.unwrap()).unwrap();
+        assert_eq!(
+            paths["/todos"]["get"]["description"],
+            "At most 600 requests per minute."
+        );
+    }
+}
@@ -0,0 +1,222 @@
+//! Deferred generation of the documentation.
+//!
+//! Generating the documentation of a large API at startup delays
+//! serving requests, even though the documentation itself is rarely
+//! requested. A [`LazyOpenApi`] defers the generation until the
+//! document is first needed and caches the result, it can also
+//! generate the document on a background thread right after startup.
+//!
+//! With the `axum` feature the handle can be returned from handlers,
+//! the document is serialized as JSON directly into the response body.
+//!
+//! The generation context is thread-local, so all settings (e.g.
+//! [`gen::extract_schemas`](crate::gen::extract_schemas)) must be
+//! applied by the generator function, which runs on the thread that
+//! first requests the document.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{gen, lazy::LazyOpenApi, openapi::OpenApi};
+//!
+//! let api = LazyOpenApi::new(|| {
+//!     gen::infer_responses(true);
+//!
+//!     let mut api = OpenApi::default();
+//!     api.info.title = "Example".into();
+//!     // e.g. `routes().finish_api(&mut api)`
+//!     api
+//! });
+//! assert!(!api.is_generated());
+//!
+//! // Clones share the same document.
+//! let docs = api.clone();
+//! assert_eq!(docs.get().info.title, "Example");
+//! assert!(api.is_generated());
+//! ```
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex, OnceLock, PoisonError},
+    thread::{self, JoinHandle},
+};
+
+use crate::openapi::OpenApi;
+
+type Generator = Box<dyn FnOnce() -> OpenApi + Send>;
+
+/// A handle to a document that is generated on first use.
+///
+/// The handle is cheap to clone, all clones share the same document
+/// which is generated at most once.
+#[derive(Clone)]
+pub struct LazyOpenApi {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    api: OnceLock<Arc<OpenApi>>,
+    generator: Mutex<Option<Generator>>,
+}
+
+impl LazyOpenApi {
+    /// Defer the generation of the document to the given function.
+    pub fn new<F>(generator: F) -> Self
+    where
+        F: FnOnce() -> OpenApi + Send + 'static,
+    {
+        Self {
+            inner: Arc::new(Inner {
+                api: OnceLock::new(),
+                generator: Mutex::new(Some(Box::new(generator))),
+            }),
+        }
+    }
+
+    /// A handle to an already generated document.
+    #[must_use]
+    pub fn ready(api: OpenApi) -> Self {
+        Self {
+            inner: Arc::new(Inner {
+                api: OnceLock::from(Arc::new(api)),
+                generator: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// The document, generated on the first call.
+    ///
+    /// Concurrent callers wait for the generation in progress.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the generator function panicked in a previous call.
+    #[must_use]
+    pub fn get(&self) -> Arc<OpenApi> {
+        self.inner
+            .api
+            .get_or_init(|| {
+                let generator = self
+                    .inner
+                    .generator
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .take()
+                    .expect("the generation of the document panicked");
+
+                Arc::new(generator())
+            })
+            .clone()
+    }
+
+    /// Whether the document has been generated already.
+    #[must_use]
+    pub fn is_generated(&self) -> bool {
+        self.inner.api.get().is_some()
+    }
+
+    /// Generate the document on a new thread, so that it is
+    /// ready by the time it is first requested.
+    ///
+    /// Callers of [`get`](Self::get) wait for the generation
+    /// to finish instead of generating the document again.
+    #[must_use = "the handle can be used to wait for the generation"]
+    pub fn generate_in_background(&self) -> JoinHandle<()> {
+        let this = self.clone();
+        thread::spawn(move || {
+            let _ = this.get();
+        })
+    }
+}
+
+impl From<OpenApi> for LazyOpenApi {
+    fn from(api: OpenApi) -> Self {
+        Self::ready(api)
+    }
+}
+
+#[cfg(feature = "axum")]
+impl axum::response::IntoResponse for LazyOpenApi {
+    fn into_response(self) -> axum::response::Response {
+        use axum::http::{header::CONTENT_TYPE, HeaderValue, StatusCode};
+        use bytes::{BufMut, BytesMut};
+
+        let api = self.get();
+        let mut body = BytesMut::new().writer();
+
+        match serde_json::to_writer(&mut body, &*api) {
+            Ok(()) => (
+                [(CONTENT_TYPE, HeaderValue::from_static("application/json"))],
+                body.into_inner().freeze(),
+            )
+                .into_response(),
+            Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response(),
+        }
+    }
+}
+
+impl fmt::Debug for LazyOpenApi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyOpenApi")
+            .field("api", &self.inner.api.get())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn test_generated_once() {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        let api = LazyOpenApi::new(|| {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            let mut api = OpenApi::default();
+            api.info.title = "Lazy".into();
+            api
+        });
+        assert_eq!(CALLS.load(Ordering::SeqCst), 0);
+
+        let handles = (0..4)
+            .map(|_| {
+                let api = api.clone();
+                thread::spawn(move || api.get().info.title.clone())
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), "Lazy");
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+        assert!(Arc::ptr_eq(&api.get(), &api.get()));
+    }
+
+    #[cfg(feature = "axum")]
+    #[tokio::test]
+    async fn test_into_response() {
+        use axum::{body::to_bytes, response::IntoResponse};
+
+        let mut api = OpenApi::default();
+        api.info.title = "Served".into();
+
+        let response = LazyOpenApi::ready(api).into_response();
+        assert_eq!(response.headers()["content-type"], "application/json");
+
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let api: OpenApi = serde_json::from_slice(&body).unwrap();
+        assert_eq!(api.info.title, "Served");
+    }
+
+    #[test]
+    fn test_generate_in_background() {
+        let api = LazyOpenApi::new(OpenApi::default);
+        api.generate_in_background().join().unwrap();
+        assert!(api.is_generated());
+
+        assert!(LazyOpenApi::ready(OpenApi::default()).is_generated());
+    }
+}
@@ -78,6 +78,13 @@
 //! No features are enabled by default.
 //!
 //! - `macros`: additional helper macros
+//! - `garde`: documenting `garde` validation rules in schemas
+//! - `digest`: integrity digests of the generated documentation
+//! - `client`: generating typed clients
+//! - `emit`: writing the documentation to files, `emit-yaml` adds YAML support
+//! - `intern`: a compact copy of large documents with deduplicated strings
+//! - `export`: exporting the documentation as Postman and Insomnia collections
+//! - `render`: rendering the documentation as static Markdown pages
 //!
 //! ### Third-party trait implementations
 //!
@@ -101,6 +108,35 @@
 //! - `axum-extra-cookie-private`
 //! - `axum-extra-form`
 //! - `axum-extra-query`
+//! - `validate`: request and response validation against the documentation,
+//!   and validation of the documentation against the `OpenAPI` specification
+//! - `testing`: contract tests of handlers against the documentation
+//! - `mock`: mock servers generated from the documentation
+//!
+//! ### Disabling the documentation
+//!
+//! Builds that do not serve the documentation, e.g. latency-sensitive
+//! production builds, can compile it out with the `aide_docs_disabled`
+//! configuration flag:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg aide_docs_disabled" cargo build --release
+//! ```
+//!
+//! The routes of [`axum::ApiRouter`] are routed as usual, and the code
+//! that uses it compiles unchanged. The documentation code of the router
+//! is compiled out: the operations of the handlers are not generated,
+//! the path items are not built, nested or merged, security requirements,
+//! default error responses and the fallback response are not applied,
+//! none of the transform functions are called and `finish_api` leaves
+//! the document untouched. The `docs_disabled` benchmark compares the
+//! time to build a router with many routes with and without the flag.
+//!
+//! Documents built or changed directly, e.g. with [`transform`] or
+//! [`openapi`], are not affected.
+//!
+//! It is a configuration flag rather than a feature, as features are
+//! enabled for all crates that depend on `aide` in a build.
 //!
 //! ## MSRV
 //!
@@ -122,24 +158,65 @@
 mod macros;
 mod impls;
 
+pub mod embed;
 pub mod error;
+pub mod example;
 pub mod gen;
+pub mod i18n;
+pub mod include;
+pub mod interpolation;
+pub mod lazy;
 pub mod operation;
 
 pub mod openapi;
+pub mod overlay;
+pub mod patch;
+pub mod redaction;
+pub mod refs;
+pub mod schema;
+pub mod sdk_hints;
 pub mod transform;
 pub mod util;
+pub mod visibility;
 
 #[cfg(feature = "axum")]
 pub mod axum;
 
-mod helpers;
+#[cfg(feature = "client")]
+pub mod client;
+
+#[cfg(feature = "digest")]
+pub mod digest;
+
+#[cfg(feature = "emit")]
+pub mod emit;
+
+#[cfg(feature = "export")]
+pub mod export;
+
+#[cfg(feature = "intern")]
+pub mod intern;
+
+pub mod helpers;
+
+#[cfg(feature = "mock")]
+pub mod mock;
+
 #[cfg(feature = "redoc")]
 pub mod redoc;
 
+#[cfg(feature = "render")]
+pub mod render;
+
 #[cfg(feature = "scalar")]
 pub mod scalar;
 
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "validate")]
+pub mod validate;
+
 pub use helpers::{no_api::NoApi, with_api::ApiOverride, with_api::WithApi, use_api::UseApi};
 
 pub use error::Error;
@@ -147,3 +224,6 @@ pub use operation::{OperationInput, OperationOutput};
 
 #[cfg(feature = "macros")]
 pub use aide_macros::OperationIo;
+
+#[cfg(feature = "garde")]
+pub use aide_macros::garde_schema;
@@ -0,0 +1,413 @@
+//! Mock servers generated from the documentation.
+//!
+//! [`mock_router`] builds a router that responds to every documented
+//! operation with a canned response, using the documented examples or
+//! example values generated from the response schemas. This allows
+//! running clients against the documentation before the real handlers
+//! exist.
+//!
+//! The documented successful response is returned by default, see
+//! [`StatusSelection`] for other options. Clients can also request a
+//! documented status code with the `Prefer` header:
+//!
+//! ```text
+//! Prefer: code=404
+//! ```
+//!
+//! # Examples
+//!
+//! ```no_run
+//! # async fn run(api: aide::openapi::OpenApi) {
+//! use std::time::Duration;
+//!
+//! use aide::mock::MockRouter;
+//!
+//! let app = MockRouter::new(&api)
+//!     .latency(Duration::from_millis(200))
+//!     .operation_status("create_todo", 201)
+//!     .into_router();
+//!
+//! let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
+//! axum::serve(listener, app).await.unwrap();
+//! # }
+//! ```
+
+use std::{sync::Arc, time::Duration};
+
+use ::axum::{
+    body::Body,
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{MethodFilter, MethodRouter},
+    Router,
+};
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::{
+    example,
+    helpers::is_json_media_type,
+    openapi::{self, OpenApi, ReferenceOr},
+    util::WILDCARD,
+};
+
+/// Build a mock router with the default configuration,
+/// see [`MockRouter`].
+pub fn mock_router(api: &OpenApi) -> Router {
+    MockRouter::new(api).into_router()
+}
+
+/// The status code of the responses of the mock server.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StatusSelection {
+    /// The lowest documented successful status code.
+    ///
+    /// If there is none, the default response with `200 OK`, or
+    /// the lowest documented status code otherwise.
+    #[default]
+    Success,
+    /// The given status code if it is documented
+    /// or there is a default response, otherwise
+    /// the same as [`StatusSelection::Success`].
+    Code(u16),
+}
+
+/// A builder of mock routers.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct MockRouter {
+    operations: Vec<MockOperation>,
+    status: StatusSelection,
+    operation_status: IndexMap<String, u16>,
+    latency: Duration,
+}
+
+impl MockRouter {
+    /// Create a mock router for the finished documentation.
+    pub fn new(api: &OpenApi) -> Self {
+        let schemas = example::component_schemas(api);
+
+        let operations = api
+            .paths
+            .iter()
+            .flat_map(|paths| paths.iter())
+            .filter_map(|(path, item)| Some((path, item.as_item()?)))
+            .flat_map(|(path, item)| {
+                let wildcard = item.extensions.get(WILDCARD).and_then(Value::as_str);
+                let path = axum_path(path, wildcard);
+                item.iter()
+                    .map(|(method, operation)| {
+                        MockOperation::new(&path, method, operation, &schemas)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        Self {
+            operations,
+            status: StatusSelection::default(),
+            operation_status: IndexMap::new(),
+            latency: Duration::ZERO,
+        }
+    }
+
+    /// The status code of the responses of all operations.
+    pub fn status(mut self, status: StatusSelection) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// The status code of the responses of the operation with the given ID.
+    pub fn operation_status(mut self, operation_id: &str, status: u16) -> Self {
+        self.operation_status.insert(operation_id.into(), status);
+        self
+    }
+
+    /// Delay all responses by the given duration.
+    pub fn latency(mut self, latency: Duration) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    /// Build the router.
+    ///
+    /// # Panics
+    ///
+    /// If the documented paths conflict in axum,
+    /// see [`Router::route`].
+    pub fn into_router(self) -> Router {
+        let mut routes: IndexMap<String, MethodRouter> = IndexMap::new();
+
+        for mut operation in self.operations {
+            let Some(filter) = method_filter(&operation.method) else {
+                continue;
+            };
+
+            if let Some(status) = operation
+                .operation_id
+                .as_ref()
+                .and_then(|id| self.operation_status.get(id))
+            {
+                operation.status = StatusSelection::Code(*status);
+            } else {
+                operation.status = self.status;
+            }
+
+            let operation = Arc::new(operation);
+            let latency = self.latency;
+
+            let route = routes.entry(operation.path.clone()).or_default();
+            *route = std::mem::take(route).on(filter, move |headers: HeaderMap| async move {
+                if !latency.is_zero() {
+                    tokio::time::sleep(latency).await;
+                }
+                operation.respond(&headers)
+            });
+        }
+
+        routes
+            .into_iter()
+            .fold(Router::new(), |router, (path, route)| {
+                router.route(&path, route)
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct MockOperation {
+    method: String,
+    path: String,
+    operation_id: Option<String>,
+    status: StatusSelection,
+    responses: IndexMap<u16, MockResponse>,
+    default: Option<MockResponse>,
+}
+
+#[derive(Debug, Clone)]
+struct MockResponse {
+    content_type: Option<String>,
+    body: Vec<u8>,
+}
+
+impl MockOperation {
+    fn new(path: &str, method: &str, operation: &openapi::Operation, schemas: &Value) -> Self {
+        let mut responses = IndexMap::new();
+        let mut default = None;
+
+        if let Some(res) = &operation.responses {
+            for (status, response) in &res.responses {
+                if let (openapi::StatusCode::Code(code), Some(response)) =
+                    (status, response.as_item())
+                {
+                    responses.insert(*code, MockResponse::new(response, schemas));
+                }
+            }
+
+            default = res
+                .default
+                .as_ref()
+                .and_then(ReferenceOr::as_item)
+                .map(|response| MockResponse::new(response, schemas));
+        }
+
+        responses.sort_keys();
+
+        Self {
+            method: method.into(),
+            path: path.into(),
+            operation_id: operation.operation_id.clone(),
+            status: StatusSelection::default(),
+            responses,
+            default,
+        }
+    }
+
+    fn respond(&self, headers: &HeaderMap) -> Response {
+        let status = preferred_code(headers).map_or(self.status, StatusSelection::Code);
+
+        let Some((status, response)) = self.select(status) else {
+            return StatusCode::NOT_IMPLEMENTED.into_response();
+        };
+
+        let mut res = Response::new(Body::from(response.body.clone()));
+        *res.status_mut() = StatusCode::from_u16(status).unwrap_or(StatusCode::OK);
+
+        if let Some(content_type) = response
+            .content_type
+            .as_deref()
+            .and_then(|ct| HeaderValue::from_str(ct).ok())
+        {
+            res.headers_mut().insert(CONTENT_TYPE, content_type);
+        }
+
+        res
+    }
+
+    fn select(&self, status: StatusSelection) -> Option<(u16, &MockResponse)> {
+        if let StatusSelection::Code(code) = status {
+            if let Some(response) = self.responses.get(&code).or(self.default.as_ref()) {
+                return Some((code, response));
+            }
+        }
+
+        self.responses
+            .iter()
+            .find(|(code, _)| (200..300).contains(*code))
+            .map(|(code, response)| (*code, response))
+            .or_else(|| self.default.as_ref().map(|response| (200, response)))
+            .or_else(|| {
+                self.responses
+                    .first()
+                    .map(|(code, response)| (*code, response))
+            })
+    }
+}
+
+impl MockResponse {
+    fn new(response: &openapi::Response, schemas: &Value) -> Self {
+        let Some((media_type, media)) = response.content.first() else {
+            return Self {
+                content_type: None,
+                body: Vec::new(),
+            };
+        };
+
+        let example = example::media_example(media, schemas);
+
+        let body = match example {
+            Value::String(s) if !is_json_media_type(media_type) => s.into_bytes(),
+            example => serde_json::to_vec(&example).unwrap_or_default(),
+        };
+
+        Self {
+            content_type: Some(media_type.clone()),
+            body,
+        }
+    }
+}
+
+/// The status code requested with the `Prefer: code=404` header.
+fn preferred_code(headers: &HeaderMap) -> Option<u16> {
+    headers
+        .get_all("prefer")
+        .iter()
+        .filter_map(|h| h.to_str().ok())
+        .flat_map(|h| h.split([',', ';']))
+        .find_map(|pref| pref.trim().strip_prefix("code=")?.trim().parse().ok())
+}
+
+fn method_filter(method: &str) -> Option<MethodFilter> {
+    Some(match method {
+        "delete" => MethodFilter::DELETE,
+        "get" => MethodFilter::GET,
+        "head" => MethodFilter::HEAD,
+        "options" => MethodFilter::OPTIONS,
+        "patch" => MethodFilter::PATCH,
+        "post" => MethodFilter::POST,
+        "put" => MethodFilter::PUT,
+        "trace" => MethodFilter::TRACE,
+        _ => return None,
+    })
+}
+
+/// Transform the `OpenApi` path notation to the one used by axum,
+/// the reverse of [`path_colon_params`](crate::util::path_colon_params).
+///
+/// The parameter named by the [`WILDCARD`] extension of
+/// the path item becomes a wildcard.
+fn axum_path(path: &str, wildcard: Option<&str>) -> String {
+    path.split('/')
+        .map(
+            |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                Some(param) if Some(param) == wildcard => format!("*{param}"),
+                Some(param) => format!(":{param}"),
+                None => segment.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        Json,
+    };
+    use schemars::JsonSchema;
+    use serde::Serialize;
+    use tower_service::Service;
+
+    use super::{axum_path, MockRouter};
+    use crate::{
+        axum::{routing::get_with, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Serialize, JsonSchema)]
+    struct Todo {
+        id: u32,
+        title: String,
+    }
+
+    async fn get_todo() -> Json<Todo> {
+        Json(Todo {
+            id: 1,
+            title: "Write the docs".into(),
+        })
+    }
+
+    #[test]
+    fn test_axum_path() {
+        assert_eq!(axum_path("/users/{id}", None), "/users/:id");
+        assert_eq!(
+            axum_path("/{id}/{repo}/{tree}", Some("tree")),
+            "/:id/:repo/*tree"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_mock_router() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/todos/:id",
+                get_with(get_todo, |op| {
+                    op.id("get_todo").response::<404, Json<String>>()
+                }),
+            )
+            .finish_api(&mut api);
+
+        let mut mock = MockRouter::new(&api).into_router();
+
+        let res = mock
+            .call(Request::get("/todos/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], br#"{"id":0,"title":"string"}"#);
+
+        let res = mock
+            .call(
+                Request::get("/todos/1")
+                    .header("prefer", "code=404")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+        let mut mock = MockRouter::new(&api)
+            .operation_status("get_todo", 404)
+            .into_router();
+        let res = mock
+            .call(Request::get("/todos/1").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND);
+    }
+}
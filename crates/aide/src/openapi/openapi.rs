@@ -96,7 +96,7 @@ mod serde_version {
     }
 
     pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Cow<'static, str>, D::Error> {
-        <&'de str>::deserialize(de).and_then(|s| match s == "3.1.0" {
+        String::deserialize(de).and_then(|s| match s == "3.1.0" {
             true => Ok(Cow::Owned("3.1.0".to_owned())),
             false => Err(serde::de::Error::custom("expected 3.1.0")),
         })
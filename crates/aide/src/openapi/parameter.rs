@@ -193,6 +193,93 @@ impl Parameter {
             } => parameter_data,
         }
     }
+
+    /// The serialization style of the parameter.
+    pub fn style(&self) -> ParameterStyle {
+        match self {
+            Parameter::Query { style, .. } => match style {
+                QueryStyle::Form => ParameterStyle::Form,
+                QueryStyle::SpaceDelimited => ParameterStyle::SpaceDelimited,
+                QueryStyle::PipeDelimited => ParameterStyle::PipeDelimited,
+                QueryStyle::DeepObject => ParameterStyle::DeepObject,
+            },
+            Parameter::Header { .. } => ParameterStyle::Simple,
+            Parameter::Path { style, .. } => match style {
+                PathStyle::Matrix => ParameterStyle::Matrix,
+                PathStyle::Label => ParameterStyle::Label,
+                PathStyle::Simple => ParameterStyle::Simple,
+            },
+            Parameter::Cookie { .. } => ParameterStyle::Form,
+        }
+    }
+
+    /// Sets the serialization style of the parameter, returns `false`
+    /// if the style is not supported for the location of the parameter.
+    pub fn set_style(&mut self, style: ParameterStyle) -> bool {
+        match (self, style) {
+            (Parameter::Query { style, .. }, ParameterStyle::Form) => *style = QueryStyle::Form,
+            (Parameter::Query { style, .. }, ParameterStyle::SpaceDelimited) => {
+                *style = QueryStyle::SpaceDelimited
+            }
+            (Parameter::Query { style, .. }, ParameterStyle::PipeDelimited) => {
+                *style = QueryStyle::PipeDelimited
+            }
+            (Parameter::Query { style, .. }, ParameterStyle::DeepObject) => {
+                *style = QueryStyle::DeepObject
+            }
+            (Parameter::Path { style, .. }, ParameterStyle::Matrix) => *style = PathStyle::Matrix,
+            (Parameter::Path { style, .. }, ParameterStyle::Label) => *style = PathStyle::Label,
+            (Parameter::Path { style, .. }, ParameterStyle::Simple) => *style = PathStyle::Simple,
+            (Parameter::Header { .. }, ParameterStyle::Simple)
+            | (Parameter::Cookie { .. }, ParameterStyle::Form) => {}
+            _ => return false,
+        }
+        true
+    }
+
+    /// Whether array and object values generate separate parameters,
+    /// defaults to `true` for the `form` style and `false` otherwise.
+    pub fn explode(&self) -> bool {
+        self.parameter_data_ref()
+            .explode
+            .unwrap_or(self.style() == ParameterStyle::Form)
+    }
+}
+
+/// The serialization style of a [`Parameter`] in any location.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+#[derive(schemars::JsonSchema)]
+pub enum ParameterStyle {
+    /// `;name=value`, path parameters only.
+    Matrix,
+    /// `.value`, path parameters only.
+    Label,
+    /// `name=value`, query and cookie parameters.
+    Form,
+    /// `value,value`, path and header parameters.
+    Simple,
+    /// Space separated arrays, query parameters only.
+    SpaceDelimited,
+    /// Pipe separated arrays, query parameters only.
+    PipeDelimited,
+    /// `name[key]=value` for objects, query parameters only.
+    DeepObject,
+}
+
+impl std::fmt::Display for ParameterStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let style = match self {
+            ParameterStyle::Matrix => "matrix",
+            ParameterStyle::Label => "label",
+            ParameterStyle::Form => "form",
+            ParameterStyle::Simple => "simple",
+            ParameterStyle::SpaceDelimited => "spaceDelimited",
+            ParameterStyle::PipeDelimited => "pipeDelimited",
+            ParameterStyle::DeepObject => "deepObject",
+        };
+        f.write_str(style)
+    }
 }
 
 struct SkipSerializeIfDefault;
@@ -260,3 +347,35 @@ impl Default for HeaderStyle {
         HeaderStyle::Simple
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parameter(location: &str) -> Parameter {
+        serde_json::from_value(serde_json::json!({ "name": "ids", "in": location, "schema": {} }))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parameter_style() {
+        let mut query = parameter("query");
+        assert_eq!(query.style(), ParameterStyle::Form);
+        assert!(query.explode());
+
+        assert!(query.set_style(ParameterStyle::PipeDelimited));
+        assert_eq!(query.style(), ParameterStyle::PipeDelimited);
+        assert!(!query.explode());
+        assert!(!query.set_style(ParameterStyle::Matrix));
+
+        let mut path = parameter("path");
+        assert_eq!(path.style(), ParameterStyle::Simple);
+        assert!(!path.explode());
+        assert!(path.set_style(ParameterStyle::Label));
+        assert!(!path.set_style(ParameterStyle::Form));
+
+        let mut header = parameter("header");
+        assert!(header.set_style(ParameterStyle::Simple));
+        assert!(!header.set_style(ParameterStyle::DeepObject));
+    }
+}
@@ -1,7 +1,10 @@
 //! Traits and utilities for schema generation for operations (handlers).
 
 use indexmap::IndexMap;
-use schemars::schema::SchemaObject;
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject, SingleOrVec},
+    JsonSchema,
+};
 
 use crate::gen::GenContext;
 use crate::openapi::{
@@ -183,9 +186,85 @@ pub enum ParamLocation {
     Cookie,
 }
 
+/// How object-valued query parameters, e.g. nested structs or
+/// maps of a `Query<T>`, are documented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueryObjects {
+    /// A single parameter with the `deepObject` style,
+    /// e.g. `filter[name]=value`.
+    #[default]
+    DeepObject,
+    /// A parameter for each property, named after the
+    /// `deepObject` style, e.g. `filter[name]`.
+    ///
+    /// Maps are still documented as a single parameter.
+    Flatten,
+}
+
+/// How array-valued query parameters, e.g. `Vec` fields
+/// of a `Query<T>`, are serialized.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum QueryArrays {
+    /// The parameter is repeated for every value, e.g. `id=1&id=2`,
+    /// as supported by `axum_extra::extract::Query`.
+    #[default]
+    Repeated,
+    /// Comma separated values, e.g. `id=1,2`.
+    CommaSeparated,
+    /// Space separated values, e.g. `id=1%202`.
+    SpaceDelimited,
+    /// Pipe separated values, e.g. `id=1|2`.
+    PipeDelimited,
+}
+
+/// The extension that overrides [`QueryArrays`] for a schema,
+/// see [`separated`](crate::helpers::separated).
+pub(crate) const QUERY_ARRAY: &str = "x-aide-query-array";
+
+impl QueryArrays {
+    pub(crate) fn name(self) -> &'static str {
+        match self {
+            Self::Repeated => "repeated",
+            Self::CommaSeparated => "commaSeparated",
+            Self::SpaceDelimited => "spaceDelimited",
+            Self::PipeDelimited => "pipeDelimited",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [
+            Self::Repeated,
+            Self::CommaSeparated,
+            Self::SpaceDelimited,
+            Self::PipeDelimited,
+        ]
+        .into_iter()
+        .find(|format| format.name() == name)
+    }
+
+    fn style(self) -> (QueryStyle, Option<bool>) {
+        match self {
+            Self::Repeated => (QueryStyle::Form, None),
+            Self::CommaSeparated => (QueryStyle::Form, Some(false)),
+            Self::SpaceDelimited => (QueryStyle::SpaceDelimited, Some(false)),
+            Self::PipeDelimited => (QueryStyle::PipeDelimited, Some(false)),
+        }
+    }
+}
+
 /// Generate operation parameters from a JSON schema
 /// where the schema is an object, and each
 /// property is a parameter.
+///
+/// The properties of flattened enums are included as
+/// parameters that are not required, and object-valued and
+/// array-valued query parameters are documented according to
+/// [`query_objects`](crate::gen::query_objects) and
+/// [`query_arrays`](crate::gen::query_arrays).
+///
+/// Path parameters that are not named, e.g. of `Path<(u32, String)>`,
+/// are marked with their position and named after the parameters
+/// of the route by the generators provided by this library.
 #[tracing::instrument(skip_all)]
 pub fn parameters_from_schema(
     ctx: &mut GenContext,
@@ -194,102 +273,53 @@ pub fn parameters_from_schema(
 ) -> Vec<Parameter> {
     let schema = ctx.resolve_schema(&schema);
 
-    let mut params = Vec::new();
-    if let Some(obj) = &schema.object {
-        for (name, schema) in &obj.properties {
-            let s = schema.clone().into_object();
-
-            match location {
-                ParamLocation::Query => {
-                    params.push(Parameter::Query {
-                        parameter_data: ParameterData {
-                            name: name.clone(),
-                            description: s.metadata.as_ref().and_then(|m| m.description.clone()),
-                            required: obj.required.contains(name),
-                            format: crate::openapi::ParameterSchemaOrContent::Schema(
-                                openapi::SchemaObject {
-                                    json_schema: s.into(),
-                                    example: None,
-                                    external_docs: None,
-                                },
-                            ),
-                            extensions: Default::default(),
-                            deprecated: None,
-                            example: None,
-                            examples: IndexMap::default(),
-                            explode: None,
-                        },
-                        allow_reserved: false,
-                        style: QueryStyle::Form,
-                        allow_empty_value: None,
-                    });
-                }
-                ParamLocation::Path => {
-                    params.push(Parameter::Path {
-                        parameter_data: ParameterData {
-                            name: name.clone(),
-                            description: s.metadata.as_ref().and_then(|m| m.description.clone()),
-                            required: obj.required.contains(name),
-                            format: crate::openapi::ParameterSchemaOrContent::Schema(
-                                openapi::SchemaObject {
-                                    json_schema: s.into(),
-                                    example: None,
-                                    external_docs: None,
-                                },
-                            ),
-                            extensions: Default::default(),
-                            deprecated: None,
-                            example: None,
-                            examples: IndexMap::default(),
-                            explode: None,
-                        },
+    if location == ParamLocation::Path {
+        if let Some(schemas) = positional_schemas(ctx, schema) {
+            return schemas
+                .into_iter()
+                .enumerate()
+                .map(|(position, s)| {
+                    let mut parameter_data = parameter_data(position.to_string(), s, true);
+                    parameter_data
+                        .extensions
+                        .insert(PATH_POSITION.into(), position.into());
+                    Parameter::Path {
+                        parameter_data,
                         style: openapi::PathStyle::Simple,
-                    });
-                }
-                ParamLocation::Header => {
-                    params.push(Parameter::Header {
-                        parameter_data: ParameterData {
-                            name: name.clone(),
-                            description: s.metadata.as_ref().and_then(|m| m.description.clone()),
-                            required: obj.required.contains(name),
-                            format: crate::openapi::ParameterSchemaOrContent::Schema(
-                                openapi::SchemaObject {
-                                    json_schema: s.into(),
-                                    example: None,
-                                    external_docs: None,
-                                },
-                            ),
-                            extensions: Default::default(),
-                            deprecated: None,
-                            example: None,
-                            examples: IndexMap::default(),
-                            explode: None,
-                        },
-                        style: openapi::HeaderStyle::Simple,
-                    });
-                }
-                ParamLocation::Cookie => {
-                    params.push(Parameter::Cookie {
-                        parameter_data: ParameterData {
-                            name: name.clone(),
-                            description: s.metadata.as_ref().and_then(|m| m.description.clone()),
-                            required: obj.required.contains(name),
-                            format: crate::openapi::ParameterSchemaOrContent::Schema(
-                                openapi::SchemaObject {
-                                    json_schema: s.into(),
-                                    example: None,
-                                    external_docs: None,
-                                },
-                            ),
-                            extensions: Default::default(),
-                            deprecated: None,
-                            example: None,
-                            examples: IndexMap::default(),
-                            explode: None,
-                        },
-                        style: openapi::CookieStyle::Form,
-                    });
-                }
+                    }
+                })
+                .collect();
+        }
+    }
+
+    let mut params = Vec::new();
+    for (name, mut s, required) in schema_properties(ctx, schema) {
+        let separated = s
+            .extensions
+            .remove(QUERY_ARRAY)
+            .and_then(|format| QueryArrays::from_name(format.as_str()?));
+
+        match location {
+            ParamLocation::Query => {
+                query_parameters(ctx, name, s, required, separated, &mut params);
+            }
+            ParamLocation::Path => {
+                params.push(Parameter::Path {
+                    parameter_data: parameter_data(name, s, required),
+                    style: openapi::PathStyle::Simple,
+                });
+            }
+            ParamLocation::Header => {
+                params.push(Parameter::Header {
+                    parameter_data: parameter_data(name, s, required),
+                    style: openapi::HeaderStyle::Simple,
+                });
+            }
+            ParamLocation::Cookie => {
+                params.push(Parameter::Cookie {
+                    parameter_data: parameter_data(name, s, required),
+                    style: openapi::CookieStyle::Form,
+                });
             }
         }
     }
@@ -297,6 +327,194 @@ pub fn parameters_from_schema(
     params
 }
 
+/// A header parameter with the schema of `T`,
+/// required unless the schema is nullable.
+pub(crate) fn header_parameter<T: JsonSchema>(ctx: &mut GenContext, name: &str) -> Parameter {
+    let schema = ctx.schema.subschema_for::<T>().into_object();
+    let required = !is_nullable(ctx.resolve_schema(&schema));
+
+    Parameter::Header {
+        parameter_data: parameter_data(name.into(), schema, required),
+        style: openapi::HeaderStyle::Simple,
+    }
+}
+
+/// The extension with the position of a path parameter that is not named.
+pub(crate) const PATH_POSITION: &str = "x-aide-path-position";
+
+/// The schemas of the values of a tuple or of a single value.
+fn positional_schemas(ctx: &GenContext, schema: &SchemaObject) -> Option<Vec<SchemaObject>> {
+    match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) if **ty == InstanceType::Array => {
+            let Some(SingleOrVec::Vec(items)) = schema.array.as_ref()?.items.as_ref() else {
+                return None;
+            };
+            Some(items.iter().cloned().map(Schema::into_object).collect())
+        }
+        Some(SingleOrVec::Single(ty)) if **ty == InstanceType::Object => None,
+        Some(_) => Some(vec![schema.clone()]),
+        None => object_schema(ctx, schema)
+            .is_none()
+            .then(|| vec![schema.clone()]),
+    }
+}
+
+fn parameter_data(name: String, schema: SchemaObject, required: bool) -> ParameterData {
+    ParameterData {
+        name,
+        description: schema.metadata.as_ref().and_then(|m| m.description.clone()),
+        required,
+        format: crate::openapi::ParameterSchemaOrContent::Schema(openapi::SchemaObject {
+            json_schema: schema.into(),
+            example: None,
+            external_docs: None,
+        }),
+        extensions: Default::default(),
+        deprecated: None,
+        example: None,
+        examples: IndexMap::default(),
+        explode: None,
+    }
+}
+
+fn query_parameters(
+    ctx: &GenContext,
+    name: String,
+    schema: SchemaObject,
+    required: bool,
+    separated: Option<QueryArrays>,
+    params: &mut Vec<Parameter>,
+) {
+    let Some(object) = object_schema(ctx, &schema) else {
+        let (style, explode) = separated
+            .or_else(|| is_array(ctx, &schema).then_some(ctx.query_arrays))
+            .map_or((QueryStyle::Form, None), QueryArrays::style);
+
+        let mut parameter_data = parameter_data(name, schema, required);
+        parameter_data.explode = explode;
+        params.push(Parameter::Query {
+            parameter_data,
+            allow_reserved: false,
+            style,
+            allow_empty_value: None,
+        });
+        return;
+    };
+
+    let properties = schema_properties(ctx, object);
+    if ctx.query_objects == QueryObjects::Flatten && !properties.is_empty() {
+        for (property, s, property_required) in properties {
+            let property_name = format!("{name}[{property}]");
+            query_parameters(
+                ctx,
+                property_name,
+                s,
+                required && property_required,
+                None,
+                params,
+            );
+        }
+        return;
+    }
+
+    let mut parameter_data = parameter_data(name, schema, required);
+    parameter_data.explode = Some(true);
+    params.push(Parameter::Query {
+        parameter_data,
+        allow_reserved: false,
+        style: QueryStyle::DeepObject,
+        allow_empty_value: None,
+    });
+}
+
+/// The properties of an object schema with whether they are required,
+/// including the properties of the variants of flattened enums.
+fn schema_properties(ctx: &GenContext, schema: &SchemaObject) -> Vec<(String, SchemaObject, bool)> {
+    let mut properties = Vec::new();
+    let mut add = |schema: &SchemaObject, variant: bool| {
+        let Some(obj) = &ctx.resolve_schema(schema).object else {
+            return;
+        };
+
+        for (name, s) in &obj.properties {
+            if properties.iter().any(|(n, _, _)| n == name) {
+                continue;
+            }
+            let required = !variant && obj.required.contains(name);
+            properties.push((name.clone(), s.clone().into_object(), required));
+        }
+    };
+
+    add(schema, false);
+
+    if let Some(subschemas) = &schema.subschemas {
+        let all_of = subschemas.all_of.iter().flatten();
+        let variants = subschemas.one_of.iter().chain(&subschemas.any_of).flatten();
+
+        for (s, variant) in all_of
+            .map(|s| (s, false))
+            .chain(variants.map(|s| (s, true)))
+        {
+            if let Schema::Object(s) = s {
+                add(s, variant);
+            }
+        }
+    }
+
+    properties
+}
+
+/// The object schema of a parameter, if it is an object.
+fn object_schema<'s>(ctx: &'s GenContext, schema: &'s SchemaObject) -> Option<&'s SchemaObject> {
+    let schema = ctx.resolve_schema(schema);
+
+    // `Option` of a referenced schema.
+    if let Some(any_of) = schema.subschemas.as_ref().and_then(|s| s.any_of.as_ref()) {
+        let mut not_null = any_of.iter().filter(|s| !is_null(s));
+        if let (Some(Schema::Object(s)), None) = (not_null.next(), not_null.next()) {
+            return object_schema(ctx, s);
+        }
+    }
+
+    let is_object = match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) => **ty == InstanceType::Object,
+        Some(SingleOrVec::Vec(types)) => types.contains(&InstanceType::Object),
+        None => schema.object.is_some(),
+    };
+
+    is_object.then_some(schema)
+}
+
+fn is_array(ctx: &GenContext, schema: &SchemaObject) -> bool {
+    match &ctx.resolve_schema(schema).instance_type {
+        Some(SingleOrVec::Single(ty)) => **ty == InstanceType::Array,
+        Some(SingleOrVec::Vec(types)) => types.contains(&InstanceType::Array),
+        None => false,
+    }
+}
+
+fn is_nullable(schema: &SchemaObject) -> bool {
+    let null_type = match &schema.instance_type {
+        Some(SingleOrVec::Single(ty)) => **ty == InstanceType::Null,
+        Some(SingleOrVec::Vec(types)) => types.contains(&InstanceType::Null),
+        None => false,
+    };
+    let null_variant = schema
+        .subschemas
+        .as_ref()
+        .and_then(|s| s.any_of.as_ref())
+        .is_some_and(|any_of| any_of.iter().any(is_null));
+
+    null_type || null_variant
+}
+
+fn is_null(schema: &Schema) -> bool {
+    matches!(schema, Schema::Object(SchemaObject {
+        instance_type: Some(SingleOrVec::Single(ty)),
+        ..
+    }) if **ty == InstanceType::Null)
+}
+
 /// Set the body of an operation while
 /// reporting errors.
 pub fn set_body(ctx: &mut GenContext, operation: &mut Operation, body: RequestBody) {
@@ -325,3 +543,84 @@ pub fn add_parameters(
         operation.parameters.push(ReferenceOr::Item(param));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    use super::*;
+    use crate::{gen::in_context, openapi::ParameterStyle};
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Filter {
+        name: Option<String>,
+        min_age: u8,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    #[serde(tag = "sort")]
+    enum Sort {
+        Name,
+        Age { descending: bool },
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    #[allow(dead_code)]
+    struct Search {
+        q: String,
+        filter: Filter,
+        tags: Option<HashMap<String, String>>,
+        #[serde(flatten)]
+        sort: Sort,
+    }
+
+    fn query_parameters(objects: QueryObjects) -> Vec<(String, bool, ParameterStyle)> {
+        crate::gen::query_objects(objects);
+        let params = in_context(|ctx| {
+            let schema = ctx.schema.subschema_for::<Search>().into_object();
+            parameters_from_schema(ctx, schema, ParamLocation::Query)
+        });
+        crate::gen::reset_context();
+
+        params
+            .iter()
+            .map(|p| {
+                let data = p.parameter_data_ref();
+                (data.name.clone(), data.required, p.style())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_query_objects() {
+        let param = |name: &str, required, style| (name.to_string(), required, style);
+
+        assert_eq!(
+            query_parameters(QueryObjects::DeepObject),
+            [
+                param("filter", true, ParameterStyle::DeepObject),
+                param("q", true, ParameterStyle::Form),
+                param("tags", false, ParameterStyle::DeepObject),
+                param("sort", false, ParameterStyle::Form),
+                param("descending", false, ParameterStyle::Form),
+            ]
+        );
+
+        assert_eq!(
+            query_parameters(QueryObjects::Flatten),
+            [
+                param("filter[min_age]", true, ParameterStyle::Form),
+                param("filter[name]", false, ParameterStyle::Form),
+                param("q", true, ParameterStyle::Form),
+                param("tags", false, ParameterStyle::DeepObject),
+                param("sort", false, ParameterStyle::Form),
+                param("descending", false, ParameterStyle::Form),
+            ]
+        );
+    }
+}
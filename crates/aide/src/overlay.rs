@@ -0,0 +1,582 @@
+//! Support for the [`OpenAPI` Overlay Specification](https://spec.openapis.org/overlay/v1.0.0.html).
+//!
+//! An [`Overlay`] is a list of actions that update or remove parts of
+//! an API document selected by `JSONPath` expressions. Overlays can be
+//! maintained outside of the code (e.g. per environment) and applied to
+//! the generated documentation with [`apply`].
+//!
+//! Only a subset of `JSONPath` is supported for targets:
+//!
+//! - the root `$`
+//! - child names, either `.name` or `['name']`
+//! - array indices, including negative ones (`[0]`, `[-1]`)
+//! - wildcards (`.*` and `[*]`)
+//! - recursive descent (`..name`, `..*`)
+//! - filters that test the existence or equality of
+//!   a relative path (`[?(@.name)]`, `[?(@.name == 'value')]`)
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{openapi::OpenApi, overlay::Overlay};
+//!
+//! let overlay: Overlay = serde_json::from_value(serde_json::json!({
+//!     "overlay": "1.0.0",
+//!     "info": { "title": "Production", "version": "1.0.0" },
+//!     "actions": [
+//!         {
+//!             "target": "$.info",
+//!             "update": { "contact": { "email": "ops@example.com" } }
+//!         },
+//!         {
+//!             "target": "$.servers[?(@.description == 'staging')]",
+//!             "remove": true
+//!         }
+//!     ]
+//! }))
+//! .unwrap();
+//!
+//! let mut api = OpenApi::default();
+//! aide::overlay::apply(&mut api, &overlay).unwrap();
+//!
+//! assert_eq!(api.info.contact.unwrap().email.unwrap(), "ops@example.com");
+//! ```
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::openapi::OpenApi;
+
+/// An overlay document.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct Overlay {
+    /// REQUIRED. The version of the Overlay Specification.
+    pub overlay: String,
+    /// REQUIRED. Metadata about the overlay.
+    pub info: OverlayInfo,
+    /// A URL to the document the overlay is meant to be applied to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub extends: Option<String>,
+    /// REQUIRED. The actions in the order they are applied.
+    pub actions: Vec<OverlayAction>,
+    /// Inline extensions to this object.
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
+    pub extensions: IndexMap<String, Value>,
+}
+
+/// Metadata about an overlay.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct OverlayInfo {
+    /// REQUIRED. The title of the overlay.
+    pub title: String,
+    /// REQUIRED. The version of the overlay.
+    pub version: String,
+    /// Inline extensions to this object.
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
+    pub extensions: IndexMap<String, Value>,
+}
+
+/// An action that updates or removes the nodes selected by the target.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct OverlayAction {
+    /// REQUIRED. A `JSONPath` expression selecting the nodes.
+    pub target: String,
+    /// A description of the action.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The value that is merged into selected objects,
+    /// or appended to selected arrays.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update: Option<Value>,
+    /// Whether the selected nodes are removed.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub remove: bool,
+    /// Inline extensions to this object.
+    #[serde(flatten, deserialize_with = "crate::util::deserialize_extensions")]
+    pub extensions: IndexMap<String, Value>,
+}
+
+/// Errors during the application of an overlay.
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum OverlayError {
+    #[error(r#"invalid target "{target}": {reason}"#)]
+    InvalidTarget { target: String, reason: String },
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Apply the overlay to the API document.
+///
+/// Targets that select no nodes are ignored.
+///
+/// # Errors
+///
+/// Returns an error if a target is not a supported `JSONPath`
+/// expression, or the result is not a valid API document.
+pub fn apply(api: &mut OpenApi, overlay: &Overlay) -> Result<(), OverlayError> {
+    let mut value = serde_json::to_value(&*api)?;
+    apply_to_value(&mut value, overlay)?;
+    *api = serde_json::from_value(value)?;
+    Ok(())
+}
+
+/// Apply the overlay to an arbitrary JSON document.
+///
+/// # Errors
+///
+/// Returns an error if a target is not a supported `JSONPath` expression.
+pub fn apply_to_value(document: &mut Value, overlay: &Overlay) -> Result<(), OverlayError> {
+    for action in &overlay.actions {
+        let steps = parse_path(&action.target).map_err(|reason| OverlayError::InvalidTarget {
+            target: action.target.clone(),
+            reason,
+        })?;
+
+        let mut locations = select(document, &steps);
+
+        if action.remove {
+            // Remove later array items first so that
+            // the indices of the remaining ones stay valid.
+            locations.sort_by(|a, b| b.cmp(a));
+            for location in locations {
+                remove(document, &location);
+            }
+        } else if let Some(update) = &action.update {
+            for location in locations {
+                if let Some(node) = get_mut(document, &location) {
+                    match node {
+                        Value::Array(items) => items.push(update.clone()),
+                        node => merge(node, update),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge `update` into `target`, objects are merged
+/// recursively, all other values are replaced.
+fn merge(target: &mut Value, update: &Value) {
+    match (target, update) {
+        (Value::Object(target), Value::Object(update)) => {
+            for (key, value) in update {
+                match target.get_mut(key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        target.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (target, update) => *target = update.clone(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Selector {
+    Name(String),
+    Index(i64),
+    Wildcard,
+    Filter(Filter),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Filter {
+    path: Vec<String>,
+    comparison: Option<(bool, Value)>,
+}
+
+impl Filter {
+    fn matches(&self, node: &Value) -> bool {
+        let value = self
+            .path
+            .iter()
+            .try_fold(node, |node, name| node.as_object()?.get(name));
+
+        match (&self.comparison, value) {
+            (None, value) => value.is_some(),
+            (Some((equal, expected)), Some(value)) => (value == expected) == *equal,
+            (Some((equal, _)), None) => !equal,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Step {
+    recursive: bool,
+    selector: Selector,
+}
+
+fn select(document: &Value, steps: &[Step]) -> Vec<Vec<Segment>> {
+    let mut locations = vec![Vec::new()];
+
+    for step in steps {
+        let mut parents = Vec::new();
+        for location in locations {
+            if step.recursive {
+                if let Some(node) = get(document, &location) {
+                    descendants(node, location, &mut parents);
+                }
+            } else {
+                parents.push(location);
+            }
+        }
+
+        locations = Vec::new();
+        for parent in parents {
+            if let Some(node) = get(document, &parent) {
+                children(node, &parent, &step.selector, &mut locations);
+            }
+        }
+    }
+
+    locations
+}
+
+/// Collect the location of the node and all of its descendants.
+fn descendants(node: &Value, location: Vec<Segment>, out: &mut Vec<Vec<Segment>>) {
+    match node {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let mut child_location = location.clone();
+                child_location.push(Segment::Key(key.clone()));
+                descendants(child, child_location, out);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                let mut child_location = location.clone();
+                child_location.push(Segment::Index(index));
+                descendants(child, child_location, out);
+            }
+        }
+        _ => {}
+    }
+    out.push(location);
+}
+
+fn children(node: &Value, location: &[Segment], selector: &Selector, out: &mut Vec<Vec<Segment>>) {
+    let child = |segment| {
+        let mut child = location.to_vec();
+        child.push(segment);
+        child
+    };
+
+    match (node, selector) {
+        (Value::Object(map), Selector::Name(name)) if map.contains_key(name) => {
+            out.push(child(Segment::Key(name.clone())));
+        }
+        (Value::Array(items), Selector::Index(index)) => {
+            let len = i64::try_from(items.len()).unwrap_or(i64::MAX);
+            let index = if *index < 0 { len + index } else { *index };
+            if (0..len).contains(&index) {
+                out.push(child(Segment::Index(
+                    usize::try_from(index).unwrap_or_default(),
+                )));
+            }
+        }
+        (Value::Object(map), Selector::Wildcard) => {
+            out.extend(map.keys().map(|key| child(Segment::Key(key.clone()))));
+        }
+        (Value::Array(items), Selector::Wildcard) => {
+            out.extend((0..items.len()).map(|index| child(Segment::Index(index))));
+        }
+        (Value::Object(map), Selector::Filter(filter)) => {
+            out.extend(
+                map.iter()
+                    .filter(|(_, value)| filter.matches(value))
+                    .map(|(key, _)| child(Segment::Key(key.clone()))),
+            );
+        }
+        (Value::Array(items), Selector::Filter(filter)) => {
+            out.extend(
+                items
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, value)| filter.matches(value))
+                    .map(|(index, _)| child(Segment::Index(index))),
+            );
+        }
+        _ => {}
+    }
+}
+
+fn get<'v>(document: &'v Value, location: &[Segment]) -> Option<&'v Value> {
+    location
+        .iter()
+        .try_fold(document, |node, segment| match segment {
+            Segment::Key(key) => node.as_object()?.get(key),
+            Segment::Index(index) => node.as_array()?.get(*index),
+        })
+}
+
+fn get_mut<'v>(document: &'v mut Value, location: &[Segment]) -> Option<&'v mut Value> {
+    location
+        .iter()
+        .try_fold(document, |node, segment| match segment {
+            Segment::Key(key) => node.as_object_mut()?.get_mut(key),
+            Segment::Index(index) => node.as_array_mut()?.get_mut(*index),
+        })
+}
+
+fn remove(document: &mut Value, location: &[Segment]) {
+    let Some((last, parent)) = location.split_last() else {
+        // Removing the root is not supported.
+        return;
+    };
+
+    match (get_mut(document, parent), last) {
+        (Some(Value::Object(map)), Segment::Key(key)) => {
+            map.remove(key);
+        }
+        (Some(Value::Array(items)), Segment::Index(index)) if *index < items.len() => {
+            items.remove(*index);
+        }
+        _ => {}
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Step>, String> {
+    let mut rest = path
+        .trim()
+        .strip_prefix('$')
+        .ok_or_else(|| String::from("the path must start with `$`"))?;
+    let mut steps = Vec::new();
+
+    while !rest.is_empty() {
+        let recursive = if let Some(r) = rest.strip_prefix("..") {
+            rest = r;
+            true
+        } else if let Some(r) = rest.strip_prefix('.') {
+            rest = r;
+            false
+        } else if rest.starts_with('[') {
+            false
+        } else {
+            return Err(format!("unexpected `{rest}`"));
+        };
+
+        let selector;
+        (selector, rest) = if let Some(r) = rest.strip_prefix('[') {
+            parse_bracket(r)?
+        } else if let Some(r) = rest.strip_prefix('*') {
+            (Selector::Wildcard, r)
+        } else {
+            let (name, r) = parse_name(rest);
+            if name.is_empty() {
+                return Err(String::from("expected a name"));
+            }
+            (Selector::Name(name.into()), r)
+        };
+
+        steps.push(Step {
+            recursive,
+            selector,
+        });
+    }
+
+    Ok(steps)
+}
+
+fn parse_name(s: &str) -> (&str, &str) {
+    let end = s
+        .find(['.', '[', ']', ' ', '=', '!', ')'])
+        .unwrap_or(s.len());
+    s.split_at(end)
+}
+
+/// Parse the contents of a bracket up to and including the closing `]`.
+fn parse_bracket(s: &str) -> Result<(Selector, &str), String> {
+    let s = s.trim_start();
+
+    let (selector, rest) = if let Some(r) = s.strip_prefix('*') {
+        (Selector::Wildcard, r)
+    } else if let Some(r) = s.strip_prefix('?') {
+        let (filter, r) = parse_filter(r.trim_start())?;
+        (Selector::Filter(filter), r)
+    } else if s.starts_with(['\'', '"']) {
+        let (name, r) = parse_string(s)?;
+        (Selector::Name(name), r)
+    } else {
+        let end = s.find(']').ok_or_else(|| String::from("missing `]`"))?;
+        let index = s[..end]
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid index `{}`", s[..end].trim()))?;
+        (Selector::Index(index), &s[end..])
+    };
+
+    let rest = rest
+        .trim_start()
+        .strip_prefix(']')
+        .ok_or_else(|| String::from("missing `]`"))?;
+
+    Ok((selector, rest))
+}
+
+fn parse_filter(s: &str) -> Result<(Filter, &str), String> {
+    let (parenthesized, s) = match s.strip_prefix('(') {
+        Some(s) => (true, s.trim_start()),
+        None => (false, s),
+    };
+
+    let mut rest = s
+        .strip_prefix('@')
+        .ok_or_else(|| String::from("filters must start with `@`"))?;
+
+    let mut path = Vec::new();
+    loop {
+        if let Some(r) = rest.strip_prefix('.') {
+            let (name, r) = parse_name(r);
+            path.push(name.to_string());
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix('[') {
+            let (name, r) = parse_string(r.trim_start())?;
+            path.push(name);
+            rest = r
+                .trim_start()
+                .strip_prefix(']')
+                .ok_or_else(|| String::from("missing `]`"))?;
+        } else {
+            break;
+        }
+    }
+
+    rest = rest.trim_start();
+    let comparison = if let Some(r) = rest.strip_prefix("==") {
+        let (value, r) = parse_literal(r.trim_start())?;
+        rest = r;
+        Some((true, value))
+    } else if let Some(r) = rest.strip_prefix("!=") {
+        let (value, r) = parse_literal(r.trim_start())?;
+        rest = r;
+        Some((false, value))
+    } else {
+        None
+    };
+
+    if parenthesized {
+        rest = rest
+            .trim_start()
+            .strip_prefix(')')
+            .ok_or_else(|| String::from("missing `)`"))?;
+    }
+
+    Ok((Filter { path, comparison }, rest))
+}
+
+fn parse_literal(s: &str) -> Result<(Value, &str), String> {
+    if s.starts_with(['\'', '"']) {
+        let (value, rest) = parse_string(s)?;
+        return Ok((Value::String(value), rest));
+    }
+
+    let end = s
+        .find(|c: char| matches!(c, ')' | ']') || c.is_whitespace())
+        .unwrap_or(s.len());
+    let value =
+        serde_json::from_str(&s[..end]).map_err(|_| format!("invalid literal `{}`", &s[..end]))?;
+
+    Ok((value, &s[end..]))
+}
+
+/// Parse a single or double quoted string.
+fn parse_string(s: &str) -> Result<(String, &str), String> {
+    let mut chars = s.char_indices();
+    let quote = match chars.next() {
+        Some((_, c @ ('\'' | '"'))) => c,
+        _ => return Err(String::from("expected a string")),
+    };
+
+    let mut value = String::new();
+    let mut escaped = false;
+    for (i, c) in chars {
+        if escaped {
+            value.push(c);
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Ok((value, &s[i + c.len_utf8()..]));
+        } else {
+            value.push(c);
+        }
+    }
+
+    Err(String::from("unterminated string"))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn overlay(actions: Value) -> Overlay {
+        Overlay {
+            overlay: "1.0.0".into(),
+            actions: serde_json::from_value(actions).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_update_and_remove() {
+        let mut document = json!({
+            "servers": [
+                { "url": "https://staging.example.com", "description": "staging" },
+                { "url": "https://example.com", "description": "production" }
+            ],
+            "paths": {
+                "/users": {
+                    "get": { "tags": ["users"], "x-internal": true },
+                    "post": { "tags": ["users"] }
+                }
+            }
+        });
+
+        let overlay = overlay(json!([
+            { "target": "$.servers[?(@.description != 'production')]", "remove": true },
+            { "target": "$.paths['/users'].*", "update": { "summary": "Users" } },
+            { "target": "$..tags", "update": "public" },
+            { "target": "$..[?@['x-internal'] == true]", "remove": true }
+        ]));
+
+        apply_to_value(&mut document, &overlay).unwrap();
+
+        assert_eq!(
+            document,
+            json!({
+                "servers": [
+                    { "url": "https://example.com", "description": "production" }
+                ],
+                "paths": {
+                    "/users": {
+                        "post": { "tags": ["users", "public"], "summary": "Users" }
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_invalid_target() {
+        let overlay = overlay(json!([{ "target": "servers", "remove": true }]));
+        assert!(matches!(
+            apply_to_value(&mut json!({}), &overlay),
+            Err(OverlayError::InvalidTarget { .. })
+        ));
+    }
+}
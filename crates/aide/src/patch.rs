@@ -0,0 +1,448 @@
+//! JSON Patch ([RFC 6902](https://www.rfc-editor.org/rfc/rfc6902))
+//! and JSON Merge Patch ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)).
+//!
+//! Patches are applied to the serialized document, they are useful
+//! for last-minute changes that cannot be expressed with the transform
+//! functions.
+//!
+//! The patches are usually applied with
+//! [`TransformOpenApi::apply_json_patch`](crate::transform::TransformOpenApi::apply_json_patch)
+//! and [`TransformOpenApi::apply_merge_patch`](crate::transform::TransformOpenApi::apply_merge_patch).
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::patch::{apply_json_patch, JsonPatch, PatchError};
+//! use serde_json::json;
+//!
+//! let mut document = json!({ "info": { "title": "Example", "version": "1.0.0" } });
+//!
+//! let patch: JsonPatch = serde_json::from_value(json!([
+//!     { "op": "test", "path": "/info/version", "value": "1.0.0" },
+//!     { "op": "replace", "path": "/info/version", "value": "1.1.0" }
+//! ]))
+//! .unwrap();
+//!
+//! apply_json_patch(&mut document, &patch).unwrap();
+//! assert_eq!(document["info"]["version"], "1.1.0");
+//!
+//! // The document is unchanged if any of the operations fail.
+//! assert!(matches!(
+//!     apply_json_patch(&mut document, &patch),
+//!     Err(PatchError::TestFailed { .. })
+//! ));
+//! assert_eq!(document["info"]["version"], "1.1.0");
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use thiserror::Error;
+
+/// A JSON Patch document.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(transparent)]
+pub struct JsonPatch(pub Vec<PatchOperation>);
+
+/// A single operation of a [`JsonPatch`].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// Errors during the application of a patch.
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PatchError {
+    #[error(r#"invalid JSON pointer "{0}""#)]
+    InvalidPointer(String),
+    #[error(r#"the path "{0}" does not exist"#)]
+    PathNotExists(String),
+    #[error(r#"cannot move "{0}" into one of its children"#)]
+    MoveIntoChild(String),
+    #[error(r#"test failed for "{path}", expected {expected} but found {actual}"#)]
+    TestFailed {
+        path: String,
+        expected: Value,
+        actual: Value,
+    },
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Apply a JSON Patch to the document.
+///
+/// The patch is atomic, the document is only changed
+/// if all of the operations succeed.
+///
+/// # Errors
+///
+/// Returns an error if any of the operations fail.
+pub fn apply_json_patch(document: &mut Value, patch: &JsonPatch) -> Result<(), PatchError> {
+    let mut patched = document.clone();
+
+    for operation in &patch.0 {
+        apply_operation(&mut patched, operation)?;
+    }
+
+    *document = patched;
+    Ok(())
+}
+
+/// Apply a JSON Merge Patch to the document.
+pub fn apply_merge_patch(document: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *document = patch.clone();
+        return;
+    };
+
+    if !document.is_object() {
+        *document = Value::Object(serde_json::Map::default());
+    }
+
+    if let Value::Object(document) = document {
+        for (key, value) in patch {
+            if value.is_null() {
+                document.remove(key);
+            } else {
+                apply_merge_patch(document.entry(key.clone()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn apply_operation(document: &mut Value, operation: &PatchOperation) -> Result<(), PatchError> {
+    match operation {
+        PatchOperation::Add { path, value } => add(document, path, value.clone()),
+        PatchOperation::Remove { path } => remove(document, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            *pointer_mut(document, path)? = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(PatchError::MoveIntoChild(from.clone()));
+            }
+            let value = remove(document, from)?;
+            add(document, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = pointer_mut(document, from)?.clone();
+            add(document, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            let actual = pointer_mut(document, path)?;
+            if actual == value {
+                Ok(())
+            } else {
+                Err(PatchError::TestFailed {
+                    path: path.clone(),
+                    expected: value.clone(),
+                    actual: actual.clone(),
+                })
+            }
+        }
+    }
+}
+
+fn add(document: &mut Value, path: &str, value: Value) -> Result<(), PatchError> {
+    let Some((parent, token)) = split_pointer(path)? else {
+        *document = value;
+        return Ok(());
+    };
+
+    match pointer_mut(document, parent)? {
+        Value::Object(map) => {
+            map.insert(token, value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            let index = if token == "-" {
+                items.len()
+            } else {
+                array_index(&token, items.len() + 1, path)?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(PatchError::PathNotExists(path.into())),
+    }
+}
+
+fn remove(document: &mut Value, path: &str) -> Result<Value, PatchError> {
+    let Some((parent, token)) = split_pointer(path)? else {
+        return Ok(std::mem::take(document));
+    };
+
+    match pointer_mut(document, parent)? {
+        Value::Object(map) => map
+            .remove(&token)
+            .ok_or_else(|| PatchError::PathNotExists(path.into())),
+        Value::Array(items) => {
+            let index = array_index(&token, items.len(), path)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(PatchError::PathNotExists(path.into())),
+    }
+}
+
+fn pointer_mut<'v>(document: &'v mut Value, path: &str) -> Result<&'v mut Value, PatchError> {
+    if !path.is_empty() && !path.starts_with('/') {
+        return Err(PatchError::InvalidPointer(path.into()));
+    }
+
+    document
+        .pointer_mut(path)
+        .ok_or_else(|| PatchError::PathNotExists(path.into()))
+}
+
+/// Split a pointer into the pointer of the parent and the last
+/// unescaped reference token, or `None` for the whole document.
+fn split_pointer(path: &str) -> Result<Option<(&str, String)>, PatchError> {
+    if path.is_empty() {
+        return Ok(None);
+    }
+
+    let (parent, token) = path
+        .rsplit_once('/')
+        .ok_or_else(|| PatchError::InvalidPointer(path.into()))?;
+
+    if !parent.is_empty() && !parent.starts_with('/') {
+        return Err(PatchError::InvalidPointer(path.into()));
+    }
+
+    Ok(Some((parent, token.replace("~1", "/").replace("~0", "~"))))
+}
+
+/// Parse an array index that must be less than `len`.
+fn array_index(token: &str, len: usize, path: &str) -> Result<usize, PatchError> {
+    if token.len() > 1 && token.starts_with('0') {
+        return Err(PatchError::InvalidPointer(path.into()));
+    }
+
+    match token.parse() {
+        Ok(index) if index < len => Ok(index),
+        Ok(_) => Err(PatchError::PathNotExists(path.into())),
+        Err(_) => Err(PatchError::InvalidPointer(path.into())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn patch(operations: Value) -> JsonPatch {
+        serde_json::from_value(operations).unwrap()
+    }
+
+    fn patched(mut document: Value, operations: Value) -> Result<Value, PatchError> {
+        apply_json_patch(&mut document, &patch(operations))?;
+        Ok(document)
+    }
+
+    #[test]
+    fn test_add() {
+        let document = json!({ "tags": ["a", "c"] });
+
+        assert_eq!(
+            patched(
+                document.clone(),
+                json!([
+                    { "op": "add", "path": "/title", "value": "Example" },
+                    { "op": "add", "path": "/tags/1", "value": "b" },
+                    { "op": "add", "path": "/tags/-", "value": "d" }
+                ])
+            )
+            .unwrap(),
+            json!({ "tags": ["a", "b", "c", "d"], "title": "Example" })
+        );
+
+        assert!(matches!(
+            patched(
+                document,
+                json!([{ "op": "add", "path": "/tags/3", "value": "d" }])
+            ),
+            Err(PatchError::PathNotExists(path)) if path == "/tags/3"
+        ));
+    }
+
+    #[test]
+    fn test_remove() {
+        let document = json!({ "title": "Example", "tags": ["a", "b"] });
+
+        assert_eq!(
+            patched(
+                document.clone(),
+                json!([
+                    { "op": "remove", "path": "/title" },
+                    { "op": "remove", "path": "/tags/0" }
+                ])
+            )
+            .unwrap(),
+            json!({ "tags": ["b"] })
+        );
+
+        assert!(matches!(
+            patched(
+                document.clone(),
+                json!([{ "op": "remove", "path": "/version" }])
+            ),
+            Err(PatchError::PathNotExists(path)) if path == "/version"
+        ));
+
+        // `-` refers to the element after the last one, which does not exist.
+        assert!(matches!(
+            patched(document, json!([{ "op": "remove", "path": "/tags/-" }])),
+            Err(PatchError::InvalidPointer(path)) if path == "/tags/-"
+        ));
+    }
+
+    #[test]
+    fn test_replace() {
+        let document = json!({ "info": { "version": "1.0.0" } });
+
+        assert_eq!(
+            patched(
+                document.clone(),
+                json!([{ "op": "replace", "path": "/info/version", "value": "1.1.0" }])
+            )
+            .unwrap(),
+            json!({ "info": { "version": "1.1.0" } })
+        );
+
+        assert!(matches!(
+            patched(
+                document,
+                json!([{ "op": "replace", "path": "/info/title", "value": "Example" }])
+            ),
+            Err(PatchError::PathNotExists(path)) if path == "/info/title"
+        ));
+    }
+
+    #[test]
+    fn test_move() {
+        let document = json!({ "a": { "b": 1 }, "c": [] });
+
+        assert_eq!(
+            patched(
+                document.clone(),
+                json!([
+                    { "op": "move", "from": "/a/b", "path": "/c/-" },
+                    { "op": "move", "from": "/a", "path": "/d" }
+                ])
+            )
+            .unwrap(),
+            json!({ "c": [1], "d": {} })
+        );
+
+        assert!(matches!(
+            patched(
+                document,
+                json!([{ "op": "move", "from": "/a", "path": "/a/b/c" }])
+            ),
+            Err(PatchError::MoveIntoChild(from)) if from == "/a"
+        ));
+    }
+
+    #[test]
+    fn test_copy() {
+        assert_eq!(
+            patched(
+                json!({ "a": { "b": 1 } }),
+                json!([{ "op": "copy", "from": "/a", "path": "/c" }])
+            )
+            .unwrap(),
+            json!({ "a": { "b": 1 }, "c": { "b": 1 } })
+        );
+    }
+
+    #[test]
+    fn test_test() {
+        let mut document = json!({ "info": { "version": "1.0.0" } });
+
+        let operations = json!([
+            { "op": "test", "path": "/info/version", "value": "1.0.0" },
+            { "op": "remove", "path": "/info/version" }
+        ]);
+        assert_eq!(
+            patched(document.clone(), operations).unwrap(),
+            json!({ "info": {} })
+        );
+
+        // The failed test leaves the document untouched,
+        // including the changes of earlier operations.
+        let err = apply_json_patch(
+            &mut document,
+            &patch(json!([
+                { "op": "add", "path": "/info/title", "value": "Example" },
+                { "op": "test", "path": "/info/version", "value": "2.0.0" }
+            ])),
+        )
+        .unwrap_err();
+
+        assert!(matches!(
+            err,
+            PatchError::TestFailed { path, expected, actual }
+                if path == "/info/version" && expected == "2.0.0" && actual == "1.0.0"
+        ));
+        assert_eq!(document, json!({ "info": { "version": "1.0.0" } }));
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            patched(
+                json!({ "paths": { "/users": {} } }),
+                json!([
+                    { "op": "add", "path": "/paths/~1users/get", "value": {} },
+                    { "op": "add", "path": "/paths/a~0b~01", "value": 1 }
+                ])
+            )
+            .unwrap(),
+            json!({ "paths": { "/users": { "get": {} }, "a~b~1": 1 } })
+        );
+    }
+
+    #[test]
+    fn test_invalid_pointer() {
+        assert!(matches!(
+            patched(json!({}), json!([{ "op": "add", "path": "title", "value": 1 }])),
+            Err(PatchError::InvalidPointer(path)) if path == "title"
+        ));
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut document = json!({
+            "info": { "title": "Example", "version": "1.0.0" },
+            "tags": ["a"]
+        });
+
+        apply_merge_patch(
+            &mut document,
+            &json!({
+                "info": { "version": null, "summary": "An example" },
+                "tags": { "name": "b" }
+            }),
+        );
+        assert_eq!(
+            document,
+            json!({
+                "info": { "title": "Example", "summary": "An example" },
+                "tags": { "name": "b" }
+            })
+        );
+
+        apply_merge_patch(&mut document, &json!(["replaced"]));
+        assert_eq!(document, json!(["replaced"]));
+    }
+}
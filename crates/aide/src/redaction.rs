@@ -0,0 +1,323 @@
+//! Redaction of sensitive properties, e.g. passwords, tokens or
+//! personal data.
+//!
+//! Properties are sensitive if they are marked with the [`SENSITIVE`]
+//! extension, e.g. with
+//! [`TransformSchema::sensitive`](crate::transform::TransformSchema::sensitive),
+//! or if their name is registered with [`Redaction::property`].
+//!
+//! [`Redaction`] documents sensitive properties as `writeOnly` and masks
+//! their values in the examples and defaults of the schemas and in the
+//! examples of request and response bodies. It can also remove the
+//! properties entirely, which allows publishing a sanitized copy of the
+//! documentation while keeping the complete one for internal use.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{openapi::OpenApi, redaction::Redaction};
+//!
+//! let redaction = Redaction::new().property("password").property("api_key");
+//!
+//! // Mask the sensitive properties in the generated documentation.
+//! aide::gen::redaction(redaction.clone());
+//!
+//! // Remove them from the published copy.
+//! let api = OpenApi::default();
+//! let mut public_api = api.clone();
+//! redaction.strip(true).apply(&mut public_api);
+//! ```
+
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+
+use crate::{
+    openapi::{OpenApi, ReferenceOr},
+    schema::visit_schemas_mut,
+    util::iter_operations_mut,
+};
+
+/// The extension that marks a property as sensitive.
+pub const SENSITIVE: &str = "x-sensitive";
+
+/// The default mask of sensitive values.
+pub const MASK: &str = "********";
+
+/// The redaction of sensitive properties.
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct Redaction {
+    properties: Vec<String>,
+    mask: String,
+    strip: bool,
+}
+
+impl Default for Redaction {
+    fn default() -> Self {
+        Self {
+            properties: Vec::new(),
+            mask: MASK.into(),
+            strip: false,
+        }
+    }
+}
+
+impl Redaction {
+    /// Redact the properties marked with [`SENSITIVE`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also redact all properties with the given name.
+    pub fn property(mut self, name: impl Into<String>) -> Self {
+        self.properties.push(name.into());
+        self
+    }
+
+    /// Replace sensitive values with `mask` instead of [`MASK`].
+    pub fn mask(mut self, mask: impl Into<String>) -> Self {
+        self.mask = mask.into();
+        self
+    }
+
+    /// Remove the sensitive properties and their values instead of
+    /// masking them.
+    pub fn strip(mut self, strip: bool) -> Self {
+        self.strip = strip;
+        self
+    }
+
+    /// Redact the sensitive properties of the document.
+    pub fn apply(&self, api: &mut OpenApi) {
+        let mut names = self.properties.iter().cloned().collect::<HashSet<_>>();
+        visit_schemas_mut(api, &mut |schema, _| {
+            names.extend(self.sensitive_properties(schema));
+        });
+
+        if names.is_empty() {
+            return;
+        }
+
+        visit_schemas_mut(api, &mut |schema, _| {
+            for keyword in ["example", "default"] {
+                if let Some(value) = schema.get_mut(keyword) {
+                    self.redact_value(value, &names);
+                }
+            }
+            if let Some(Value::Array(examples)) = schema.get_mut("examples") {
+                for example in examples {
+                    self.redact_value(example, &names);
+                }
+            }
+
+            self.redact_properties(schema);
+        });
+
+        let Some(paths) = &mut api.paths else {
+            return;
+        };
+
+        for (_, path) in &mut paths.paths {
+            let ReferenceOr::Item(path) = path else {
+                continue;
+            };
+
+            for (_, operation) in iter_operations_mut(path) {
+                let request = operation
+                    .request_body
+                    .as_mut()
+                    .and_then(ReferenceOr::as_item_mut)
+                    .into_iter()
+                    .flat_map(|body| body.content.values_mut());
+
+                let responses = operation
+                    .responses
+                    .iter_mut()
+                    .flat_map(|res| res.default.iter_mut().chain(res.responses.values_mut()))
+                    .filter_map(ReferenceOr::as_item_mut)
+                    .flat_map(|res| res.content.values_mut());
+
+                for media in request.chain(responses) {
+                    if let Some(example) = &mut media.example {
+                        self.redact_value(example, &names);
+                    }
+                    for example in media
+                        .examples
+                        .values_mut()
+                        .filter_map(ReferenceOr::as_item_mut)
+                    {
+                        if let Some(value) = &mut example.value {
+                            self.redact_value(value, &names);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// The names of the sensitive properties of an object schema.
+    fn sensitive_properties(&self, schema: &Map<String, Value>) -> Vec<String> {
+        let Some(Value::Object(properties)) = schema.get("properties") else {
+            return Vec::new();
+        };
+
+        properties
+            .iter()
+            .filter(|(name, property)| {
+                property.get(SENSITIVE) == Some(&Value::Bool(true))
+                    || self.properties.contains(name)
+            })
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    fn redact_properties(&self, schema: &mut Map<String, Value>) {
+        let sensitive = self.sensitive_properties(schema);
+        if sensitive.is_empty() {
+            return;
+        }
+
+        let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+            return;
+        };
+
+        if self.strip {
+            properties.retain(|name, _| !sensitive.contains(name));
+            if let Some(Value::Array(required)) = schema.get_mut("required") {
+                required.retain(|name| !sensitive.iter().any(|s| name == s));
+            }
+            return;
+        }
+
+        for name in &sensitive {
+            let Some(Value::Object(property)) = properties.get_mut(name) else {
+                continue;
+            };
+
+            property.insert("writeOnly".into(), true.into());
+            for keyword in ["example", "default"] {
+                if let Some(value) = property.get_mut(keyword) {
+                    *value = self.mask.clone().into();
+                }
+            }
+            if let Some(Value::Array(examples)) = property.get_mut("examples") {
+                for example in examples {
+                    *example = self.mask.clone().into();
+                }
+            }
+        }
+    }
+
+    /// Mask or remove the values of sensitive properties in an example.
+    fn redact_value(&self, value: &mut Value, names: &HashSet<String>) {
+        match value {
+            Value::Object(object) => {
+                if self.strip {
+                    object.retain(|key, _| !names.contains(key));
+                }
+                for (key, value) in object {
+                    if names.contains(key) {
+                        *value = self.mask.clone().into();
+                    } else {
+                        self.redact_value(value, names);
+                    }
+                }
+            }
+            Value::Array(values) => {
+                for value in values {
+                    self.redact_value(value, names);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn api() -> OpenApi {
+        serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Users", "version": "1" },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/NewUser" },
+                                    "example": { "name": "alice", "password": "hunter2" }
+                                }
+                            }
+                        },
+                        "responses": {}
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "NewUser": {
+                        "type": "object",
+                        "required": ["name", "password", "token"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "password": { "type": "string", "examples": ["hunter2"] },
+                            "token": { "type": "string", "x-sensitive": true }
+                        },
+                        "examples": [{ "name": "bob", "token": "abc" }]
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mask() {
+        let mut api = api();
+        Redaction::new().property("password").apply(&mut api);
+        let api = serde_json::to_value(&api).unwrap();
+
+        let schema = &api["components"]["schemas"]["NewUser"];
+        assert_eq!(
+            schema["properties"]["password"],
+            json!({ "type": "string", "writeOnly": true, "examples": [MASK] })
+        );
+        assert_eq!(schema["properties"]["token"]["writeOnly"], true);
+        assert_eq!(
+            schema["examples"],
+            json!([{ "name": "bob", "token": MASK }])
+        );
+        assert_eq!(
+            api["paths"]["/users"]["post"]["requestBody"]["content"]["application/json"]["example"],
+            json!({ "name": "alice", "password": MASK })
+        );
+    }
+
+    #[test]
+    fn test_strip() {
+        let mut api = api();
+        Redaction::new()
+            .property("password")
+            .strip(true)
+            .apply(&mut api);
+        let api = serde_json::to_value(&api).unwrap();
+
+        let schema = &api["components"]["schemas"]["NewUser"];
+        assert_eq!(
+            schema["properties"],
+            json!({ "name": { "type": "string" } })
+        );
+        assert_eq!(schema["required"], json!(["name"]));
+        assert_eq!(schema["examples"], json!([{ "name": "bob" }]));
+        assert_eq!(
+            api["paths"]["/users"]["post"]["requestBody"]["content"]["application/json"]["example"],
+            json!({ "name": "alice" })
+        );
+    }
+}
@@ -0,0 +1,596 @@
+//! Utilities for working with references (`$ref`) in the documentation.
+//!
+//! - [`resolve`] follows a [`ReferenceOr`] to the component it points to.
+//! - [`bundle_external_refs`] pulls external (file or URL) references
+//!   of merged hand-written fragments into the components of the document.
+//! - [`dangling_refs`] finds local references that do not point to anything.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     openapi::{Components, OpenApi, Parameter, ReferenceOr},
+//!     refs,
+//! };
+//! use serde_json::json;
+//!
+//! let mut api: OpenApi = serde_json::from_value(json!({
+//!     "openapi": "3.1.0",
+//!     "info": { "title": "Example", "version": "1.0.0" },
+//!     "paths": {
+//!         "/todos": {
+//!             "get": {
+//!                 "parameters": [{ "$ref": "common.json#/parameters/limit" }],
+//!                 "responses": { "200": { "$ref": "#/components/responses/Todos" } }
+//!             }
+//!         }
+//!     }
+//! }))
+//! .unwrap();
+//!
+//! assert_eq!(refs::dangling_refs(&api).len(), 1);
+//!
+//! refs::bundle_external_refs(&mut api, |uri| match uri {
+//!     "common.json" => Ok(json!({
+//!         "parameters": {
+//!             "limit": { "name": "limit", "in": "query", "schema": { "type": "integer" } }
+//!         }
+//!     })),
+//!     _ => Err(format!("unknown document {uri}")),
+//! })
+//! .unwrap();
+//!
+//! let op = api.paths.as_ref().unwrap().paths["/todos"]
+//!     .as_item()
+//!     .unwrap()
+//!     .get
+//!     .as_ref()
+//!     .unwrap();
+//! let limit = refs::resolve(&api, &op.parameters[0]).unwrap();
+//! assert_eq!(limit.parameter_data_ref().name, "limit");
+//! ```
+
+use std::error::Error as StdError;
+
+use indexmap::{IndexMap, IndexSet};
+use serde::Serialize;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::openapi::{
+    Callback, Components, Example, Header, Link, OpenApi, Parameter, PathItem, ReferenceOr,
+    RequestBody, Response, SecurityScheme,
+};
+
+/// A type that can be defined in the [`Components`] of the documentation.
+pub trait Component: Sized {
+    /// The name of the components, e.g. `parameters`
+    /// for `#/components/parameters/...`.
+    const KIND: &'static str;
+
+    /// The defined components of this type.
+    fn components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>>;
+}
+
+macro_rules! impl_component {
+    ($($ty:ty => $kind:literal, $field:ident;)*) => {
+        $(
+            impl Component for $ty {
+                const KIND: &'static str = $kind;
+
+                fn components(components: &Components) -> &IndexMap<String, ReferenceOr<Self>> {
+                    &components.$field
+                }
+            }
+        )*
+    };
+}
+
+impl_component! {
+    SecurityScheme => "securitySchemes", security_schemes;
+    Response => "responses", responses;
+    Parameter => "parameters", parameters;
+    Example => "examples", examples;
+    RequestBody => "requestBodies", request_bodies;
+    Header => "headers", headers;
+    Link => "links", links;
+    Callback => "callbacks", callbacks;
+    PathItem => "pathItems", path_items;
+}
+
+/// Resolve an item that might be a reference to a component
+/// of the documentation, references between components are followed.
+///
+/// Returns `None` for external references, references that
+/// point to a different kind of component and dangling references.
+#[must_use]
+pub fn resolve<'a, T: Component>(api: &'a OpenApi, item: &'a ReferenceOr<T>) -> Option<&'a T> {
+    let components = api.components.as_ref();
+    let mut item = item;
+
+    // Every component is visited at most once, anything more is a cycle.
+    for _ in 0..=components.map_or(0, |c| T::components(c).len()) {
+        match item {
+            ReferenceOr::Item(item) => return Some(item),
+            ReferenceOr::Reference { reference, .. } => {
+                let name = reference
+                    .strip_prefix("#/components/")?
+                    .strip_prefix(T::KIND)?
+                    .strip_prefix('/')?;
+                item = T::components(components?).get(&*unescape_pointer(name))?;
+            }
+        }
+    }
+
+    None
+}
+
+/// A local reference that does not point to anything in the documentation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DanglingRef {
+    /// A JSON pointer to the `$ref`.
+    pub pointer: String,
+    /// The value of the `$ref`.
+    pub reference: String,
+}
+
+/// Find all local references (`#/...`) in the documentation,
+/// including the ones in schemas, that cannot be resolved.
+///
+/// External references are not checked, see [`bundle_external_refs`].
+#[must_use]
+pub fn dangling_refs(api: &OpenApi) -> Vec<DanglingRef> {
+    let Ok(doc) = serde_json::to_value(api) else {
+        return Vec::new();
+    };
+
+    let mut dangling = Vec::new();
+    visit_refs(&doc, &mut Vec::new(), &mut |path, reference| {
+        let resolved = reference
+            .strip_prefix('#')
+            .map(|target| target.is_empty() || doc.pointer(target).is_some());
+
+        if resolved == Some(false) {
+            dangling.push(DanglingRef {
+                pointer: pointer(path.iter().map(String::as_str).chain(["$ref"])),
+                reference: reference.into(),
+            });
+        }
+    });
+    dangling
+}
+
+/// Errors during the bundling of external references.
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum RefError {
+    #[error(r#"failed to load "{uri}": {source}"#)]
+    Load {
+        uri: String,
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    #[error(r#"the reference "{0}" does not exist"#)]
+    NotFound(String),
+    #[error(transparent)]
+    Serde(#[from] serde_json::Error),
+}
+
+/// Pull all external references of the documentation into its components,
+/// e.g. the ones of hand-written fragments that were merged into it.
+///
+/// The `loader` returns the document for a URI without the fragment,
+/// the URI is resolved relative to the document containing the reference.
+/// Every document is only loaded once.
+///
+/// Each referenced value is added to the components, named after the
+/// last segment of the fragment or the file name, and the reference is
+/// replaced with a local one. The kind of component is inferred from
+/// where it is referenced, references within schemas become schemas.
+///
+/// # Errors
+///
+/// Returns an error if a document cannot be loaded or a referenced
+/// value does not exist, the documentation is unchanged in this case.
+pub fn bundle_external_refs<F, E>(api: &mut OpenApi, loader: F) -> Result<(), RefError>
+where
+    F: FnMut(&str) -> Result<Value, E>,
+    E: Into<Box<dyn StdError + Send + Sync>>,
+{
+    let mut doc = serde_json::to_value(&*api)?;
+
+    let mut bundler = Bundler {
+        loader,
+        documents: IndexMap::new(),
+        bundled: IndexMap::new(),
+        names: IndexSet::new(),
+        components: Vec::new(),
+    };
+    if let Some(Value::Object(components)) = doc.get("components") {
+        for (kind, items) in components {
+            if let Value::Object(items) = items {
+                bundler
+                    .names
+                    .extend(items.keys().map(|name| (kind.clone(), name.clone())));
+            }
+        }
+    }
+
+    bundler.bundle(&mut doc, None, &mut Vec::new())?;
+
+    if let Some(doc) = doc.as_object_mut() {
+        for (kind, name, value) in bundler.components {
+            doc.entry("components")
+                .or_insert_with(|| Value::Object(Default::default()))[kind][name] = value;
+        }
+    }
+
+    *api = serde_json::from_value(doc)?;
+    Ok(())
+}
+
+struct Bundler<F> {
+    loader: F,
+    documents: IndexMap<String, Value>,
+    /// The local references of the already bundled external references.
+    bundled: IndexMap<String, String>,
+    /// The names of the components by kind.
+    names: IndexSet<(String, String)>,
+    components: Vec<(&'static str, String, Value)>,
+}
+
+impl<F, E> Bundler<F>
+where
+    F: FnMut(&str) -> Result<Value, E>,
+    E: Into<Box<dyn StdError + Send + Sync>>,
+{
+    /// Bundle the references in `value` that is part of
+    /// the document at `base`, or the documentation itself.
+    fn bundle(
+        &mut self,
+        value: &mut Value,
+        base: Option<&str>,
+        path: &mut Vec<String>,
+    ) -> Result<(), RefError> {
+        match value {
+            Value::Object(object) => {
+                if let Some(Value::String(reference)) = object.get_mut("$ref") {
+                    let external = match (reference.strip_prefix('#'), base) {
+                        (Some(_), None) => None,
+                        (Some(_), Some(base)) => Some(format!("{base}{reference}")),
+                        (None, base) => Some(join_uri(base, reference)),
+                    };
+                    if let Some(external) = external {
+                        *reference = self.bundle_ref(&external, component_kind(path))?;
+                    }
+                }
+
+                for (key, value) in object.iter_mut() {
+                    path.push(key.clone());
+                    let result = self.bundle(value, base, path);
+                    path.pop();
+                    result?;
+                }
+            }
+            Value::Array(items) => {
+                for (i, value) in items.iter_mut().enumerate() {
+                    path.push(i.to_string());
+                    let result = self.bundle(value, base, path);
+                    path.pop();
+                    result?;
+                }
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Add the value of an external reference to the components,
+    /// returning the local reference to it.
+    fn bundle_ref(&mut self, reference: &str, kind: &'static str) -> Result<String, RefError> {
+        if let Some(local) = self.bundled.get(reference) {
+            return Ok(local.clone());
+        }
+
+        let (uri, fragment) = reference.split_once('#').unwrap_or((reference, ""));
+
+        if !self.documents.contains_key(uri) {
+            let document = (self.loader)(uri).map_err(|source| RefError::Load {
+                uri: uri.into(),
+                source: source.into(),
+            })?;
+            self.documents.insert(uri.into(), document);
+        }
+
+        let mut value = self.documents[uri]
+            .pointer(fragment)
+            .cloned()
+            .ok_or_else(|| RefError::NotFound(reference.into()))?;
+
+        let name = self.component_name(kind, uri, fragment);
+        let local = format!("#/components/{kind}/{}", escape_pointer(&name));
+        self.bundled.insert(reference.into(), local.clone());
+
+        let uri = uri.to_string();
+        let mut path = vec!["components".into(), kind.into(), name.clone()];
+        self.bundle(&mut value, Some(&uri), &mut path)?;
+        self.components.push((kind, name, value));
+
+        Ok(local)
+    }
+
+    /// A unique name for a component from the reference.
+    fn component_name(&mut self, kind: &str, uri: &str, fragment: &str) -> String {
+        let base = fragment
+            .rsplit('/')
+            .next()
+            .filter(|s| !s.is_empty())
+            .map_or_else(
+                || {
+                    let file = uri.rsplit('/').next().unwrap_or(uri);
+                    let stem = file.split_once('.').map_or(file, |(stem, _)| stem);
+                    stem.into()
+                },
+                unescape_pointer,
+            );
+        let base: String = base
+            .chars()
+            .map(|c| match c {
+                'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+                _ => '_',
+            })
+            .collect();
+
+        let mut name = base.clone();
+        let mut i = 1;
+        while self.names.contains(&(kind.to_string(), name.clone())) {
+            i += 1;
+            name = format!("{base}{i}");
+        }
+        self.names.insert((kind.into(), name.clone()));
+        name
+    }
+}
+
+/// The kind of component that is referenced at the path.
+fn component_kind(path: &[String]) -> &'static str {
+    const KINDS: [&str; 10] = [
+        "schemas",
+        "responses",
+        "parameters",
+        "examples",
+        "requestBodies",
+        "headers",
+        "securitySchemes",
+        "links",
+        "callbacks",
+        "pathItems",
+    ];
+
+    let segments: Vec<&str> = path.iter().map(String::as_str).collect();
+    match segments.as_slice() {
+        ["components", "schemas", ..] => return "schemas",
+        ["components", kind, _] => {
+            if let Some(kind) = KINDS.iter().find(|k| *k == kind) {
+                return kind;
+            }
+        }
+        _ => {}
+    }
+    if segments.contains(&"schema") {
+        return "schemas";
+    }
+
+    match segments.as_slice() {
+        ["paths", _] | [.., "callbacks", _, _] => "pathItems",
+        [.., "parameters", _] => "parameters",
+        [.., "responses", _] => "responses",
+        [.., "requestBody"] => "requestBodies",
+        [.., "headers", _] => "headers",
+        [.., "examples", _] => "examples",
+        [.., "links", _] => "links",
+        [.., "callbacks", _] => "callbacks",
+        _ => "schemas",
+    }
+}
+
+/// Resolve a reference relative to the URI of the document containing it.
+fn join_uri(base: Option<&str>, reference: &str) -> String {
+    match base {
+        Some(base) if !reference.contains("://") && !reference.starts_with('/') => {
+            match base.rsplit_once('/') {
+                Some((dir, _)) => format!("{dir}/{reference}"),
+                None => reference.into(),
+            }
+        }
+        _ => reference.into(),
+    }
+}
+
+fn visit_refs(value: &Value, path: &mut Vec<String>, f: &mut impl FnMut(&[String], &str)) {
+    match value {
+        Value::Object(object) => {
+            if let Some(Value::String(reference)) = object.get("$ref") {
+                f(path, reference);
+            }
+            for (key, value) in object {
+                path.push(key.clone());
+                visit_refs(value, path, f);
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (i, value) in items.iter().enumerate() {
+                path.push(i.to_string());
+                visit_refs(value, path, f);
+                path.pop();
+            }
+        }
+        _ => {}
+    }
+}
+
+fn pointer<'a>(segments: impl IntoIterator<Item = &'a str>) -> String {
+    segments
+        .into_iter()
+        .fold(String::new(), |mut pointer, segment| {
+            pointer.push('/');
+            pointer.push_str(&escape_pointer(segment));
+            pointer
+        })
+}
+
+fn escape_pointer(segment: &str) -> String {
+    segment.replace('~', "~0").replace('/', "~1")
+}
+
+fn unescape_pointer(segment: &str) -> String {
+    segment.replace("~1", "/").replace("~0", "~")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn document(paths: &Value) -> OpenApi {
+        serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Test", "version": "1" },
+            "paths": paths,
+            "components": {
+                "schemas": { "Todo": { "type": "object" } },
+                "parameters": {
+                    "Id": { "$ref": "#/components/parameters/TodoId" },
+                    "TodoId": {
+                        "name": "id",
+                        "in": "path",
+                        "required": true,
+                        "schema": { "type": "string" }
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_resolve() {
+        let api = document(&json!({}));
+
+        let param: ReferenceOr<Parameter> = ReferenceOr::ref_("#/components/parameters/Id");
+        assert_eq!(
+            resolve(&api, &param).unwrap().parameter_data_ref().name,
+            "id"
+        );
+
+        let param: ReferenceOr<Parameter> = ReferenceOr::ref_("#/components/parameters/Missing");
+        assert!(resolve(&api, &param).is_none());
+
+        let body: ReferenceOr<RequestBody> = ReferenceOr::ref_("#/components/parameters/Id");
+        assert!(resolve(&api, &body).is_none());
+    }
+
+    #[test]
+    fn test_dangling_refs() {
+        let api = document(&json!({
+            "/todos": {
+                "get": {
+                    "responses": {
+                        "200": {
+                            "description": "",
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/Todos" }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }));
+
+        assert_eq!(
+            dangling_refs(&api),
+            [DanglingRef {
+                pointer: "/paths/~1todos/get/responses/200/content/application~1json/schema/$ref"
+                    .into(),
+                reference: "#/components/schemas/Todos".into(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bundle_external_refs() {
+        let mut api = document(&json!({
+            "/todos": {
+                "post": {
+                    "requestBody": { "$ref": "bodies/todo.json" },
+                    "responses": {
+                        "200": { "$ref": "https://example.com/common.json#/responses/Ok" }
+                    }
+                }
+            }
+        }));
+
+        let mut loaded = Vec::new();
+        bundle_external_refs(&mut api, |uri| {
+            loaded.push(uri.to_string());
+            match uri {
+                "bodies/todo.json" => Ok(json!({
+                    "content": {
+                        "application/json": { "schema": { "$ref": "schemas.json#/Todo" } }
+                    }
+                })),
+                "bodies/schemas.json" => Ok(json!({
+                    "Todo": {
+                        "type": "object",
+                        "properties": { "next": { "$ref": "#/Todo" } }
+                    }
+                })),
+                "https://example.com/common.json" => Ok(json!({
+                    "responses": { "Ok": { "description": "Ok" } }
+                })),
+                _ => Err(format!("unknown document {uri}")),
+            }
+        })
+        .unwrap();
+
+        assert_eq!(
+            loaded,
+            [
+                "bodies/todo.json",
+                "bodies/schemas.json",
+                "https://example.com/common.json"
+            ]
+        );
+        assert!(dangling_refs(&api).is_empty());
+
+        let doc = serde_json::to_value(&api).unwrap();
+        let op = &doc["paths"]["/todos"]["post"];
+        assert_eq!(op["requestBody"]["$ref"], "#/components/requestBodies/todo");
+        assert_eq!(op["responses"]["200"]["$ref"], "#/components/responses/Ok");
+
+        let schemas = &doc["components"]["schemas"];
+        assert!(schemas.get("Todo").is_some());
+        assert_eq!(
+            schemas["Todo2"]["properties"]["next"]["$ref"],
+            "#/components/schemas/Todo2"
+        );
+        assert_eq!(
+            doc["components"]["requestBodies"]["todo"]["content"]["application/json"]["schema"]
+                ["$ref"],
+            "#/components/schemas/Todo2"
+        );
+
+        let mut api = document(&json!({
+            "/todos": { "get": { "parameters": [{ "$ref": "missing.json" }] } }
+        }));
+        let before = serde_json::to_value(&api).unwrap();
+        let result = bundle_external_refs(&mut api, |_| Err("not found"));
+        assert!(matches!(result, Err(RefError::Load { uri, .. }) if uri == "missing.json"));
+        assert_eq!(serde_json::to_value(&api).unwrap(), before);
+    }
+}
@@ -0,0 +1,516 @@
+//! Rendering the documentation as static reference pages.
+//!
+//! [`markdown`] renders a Markdown page per tag for teams that publish
+//! their documentation with a static site generator rather than with
+//! an interactive UI such as Redoc or Scalar:
+//!
+//! - `index.md` with the description of the API, its servers and the pages,
+//! - a page per tag with its operations, their parameters and responses
+//!   as tables and their example bodies as fenced JSON,
+//! - `schemas.md` with the properties of the component schemas as tables.
+//!
+//! Operations without tags are rendered on `default.md`, and
+//! operations with several tags on the page of their first tag.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use aide::{openapi::OpenApi, render};
+//!
+//! let api = OpenApi::default();
+//!
+//! for page in render::markdown(&api) {
+//!     std::fs::write(format!("docs/{}", page.file_name), page.content).unwrap();
+//! }
+//! ```
+
+use std::fmt::Write;
+
+use indexmap::IndexMap;
+use serde_json::Value;
+
+use crate::{
+    example,
+    openapi::{
+        MediaType, OpenApi, Operation, Parameter, ParameterSchemaOrContent, ReferenceOr, Response,
+    },
+};
+
+/// The page of the component schemas.
+const SCHEMAS_PAGE: &str = "schemas.md";
+
+/// A rendered page.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page {
+    /// The title of the page, the name of its tag for operation pages.
+    pub title: String,
+    /// The file name of the page, relative links between
+    /// the pages assume that they are in the same directory.
+    pub file_name: String,
+    /// The rendered content.
+    pub content: String,
+}
+
+/// Render the documentation as Markdown pages.
+#[must_use]
+pub fn markdown(api: &OpenApi) -> Vec<Page> {
+    let schemas = example::component_schemas(api);
+
+    let mut tags: IndexMap<&str, Vec<(&str, &str, &Operation)>> = api
+        .tags
+        .iter()
+        .map(|tag| (tag.name.as_str(), Vec::new()))
+        .collect();
+
+    for (path, item) in api.paths.iter().flat_map(|paths| paths.iter()) {
+        let Some(item) = item.as_item() else {
+            continue;
+        };
+
+        for (method, operation) in item.iter() {
+            let tag = operation.tags.first().map_or("default", String::as_str);
+            tags.entry(tag)
+                .or_default()
+                .push((method, path.as_str(), operation));
+        }
+    }
+    tags.retain(|_, operations| !operations.is_empty());
+
+    let mut pages = vec![index_page(api, tags.keys().copied())];
+
+    for (tag, operations) in tags {
+        let mut content = format!("# {tag}\n\n");
+
+        if let Some(description) = api
+            .tags
+            .iter()
+            .find(|t| t.name == tag)
+            .and_then(|t| t.description.as_ref())
+        {
+            let _ = write!(content, "{description}\n\n");
+        }
+
+        for (method, path, operation) in operations {
+            render_operation(&mut content, method, path, operation, &schemas);
+        }
+
+        pages.push(Page {
+            title: tag.into(),
+            file_name: format!("{}.md", slug(tag)),
+            content,
+        });
+    }
+
+    if let Some(page) = schemas_page(api) {
+        pages.push(page);
+    }
+
+    pages
+}
+
+fn index_page<'a>(api: &OpenApi, tags: impl Iterator<Item = &'a str>) -> Page {
+    let mut content = format!("# {}\n\nVersion: {}\n\n", api.info.title, api.info.version);
+
+    if let Some(description) = &api.info.description {
+        let _ = write!(content, "{description}\n\n");
+    }
+
+    if !api.servers.is_empty() {
+        content.push_str("## Servers\n\n");
+        for server in &api.servers {
+            let _ = write!(content, "- `{}`", server.url);
+            if let Some(description) = &server.description {
+                let _ = write!(content, ": {description}");
+            }
+            content.push('\n');
+        }
+        content.push('\n');
+    }
+
+    content.push_str("## Operations\n\n");
+    for tag in tags {
+        let _ = writeln!(content, "- [{tag}]({}.md)", slug(tag));
+    }
+
+    Page {
+        title: api.info.title.clone(),
+        file_name: "index.md".into(),
+        content,
+    }
+}
+
+fn render_operation(
+    content: &mut String,
+    method: &str,
+    path: &str,
+    operation: &Operation,
+    schemas: &Value,
+) {
+    let method = method.to_ascii_uppercase();
+    let title = operation
+        .summary
+        .clone()
+        .or_else(|| operation.operation_id.clone())
+        .unwrap_or_else(|| format!("{method} {path}"));
+
+    let _ = write!(content, "## {title}\n\n`{method} {path}`\n\n");
+
+    if operation.deprecated {
+        content.push_str("**Deprecated**\n\n");
+    }
+
+    if let Some(description) = &operation.description {
+        let _ = write!(content, "{description}\n\n");
+    }
+
+    let parameters = operation
+        .parameters
+        .iter()
+        .filter_map(ReferenceOr::as_item)
+        .collect::<Vec<_>>();
+
+    if !parameters.is_empty() {
+        content.push_str("### Parameters\n\n");
+        content.push_str("| Name | In | Type | Required | Description |\n");
+        content.push_str("| --- | --- | --- | --- | --- |\n");
+
+        for param in parameters {
+            let location = match param {
+                Parameter::Query { .. } => "query",
+                Parameter::Header { .. } => "header",
+                Parameter::Path { .. } => "path",
+                Parameter::Cookie { .. } => "cookie",
+            };
+            let data = param.parameter_data_ref();
+            let ty = match &data.format {
+                ParameterSchemaOrContent::Schema(schema) => {
+                    serde_json::to_value(&schema.json_schema)
+                        .map(|schema| type_name(&schema))
+                        .unwrap_or_default()
+                }
+                ParameterSchemaOrContent::Content(content) => {
+                    content.keys().cloned().collect::<Vec<_>>().join(", ")
+                }
+            };
+
+            let _ = writeln!(
+                content,
+                "| `{}` | {location} | {} | {} | {} |",
+                data.name,
+                cell(&ty),
+                if data.required { "yes" } else { "no" },
+                cell(data.description.as_deref().unwrap_or_default()),
+            );
+        }
+        content.push('\n');
+    }
+
+    if let Some(body) = operation
+        .request_body
+        .as_ref()
+        .and_then(ReferenceOr::as_item)
+    {
+        content.push_str("### Request body\n\n");
+        if let Some(description) = &body.description {
+            let _ = write!(content, "{description}\n\n");
+        }
+        for (content_type, media) in &body.content {
+            render_media(content, content_type, media, schemas);
+        }
+    }
+
+    let responses = operation
+        .responses
+        .iter()
+        .flat_map(|res| {
+            res.responses
+                .iter()
+                .map(|(status, res)| (status.to_string(), res))
+                .chain(res.default.iter().map(|res| ("default".to_string(), res)))
+        })
+        .filter_map(|(status, res)| Some((status, res.as_item()?)))
+        .collect::<Vec<_>>();
+
+    if !responses.is_empty() {
+        content.push_str("### Responses\n\n");
+        content.push_str("| Status | Description | Content |\n");
+        content.push_str("| --- | --- | --- |\n");
+
+        for (status, res) in &responses {
+            let _ = writeln!(
+                content,
+                "| {status} | {} | {} |",
+                cell(&res.description),
+                cell(&response_content(res)),
+            );
+        }
+        content.push('\n');
+
+        for (status, res) in responses {
+            for (content_type, media) in &res.content {
+                let _ = write!(content, "#### {status}\n\n");
+                render_media(content, content_type, media, schemas);
+            }
+        }
+    }
+}
+
+fn response_content(res: &Response) -> String {
+    res.content
+        .iter()
+        .map(|(content_type, media)| match media_type(media) {
+            Some(ty) => format!("`{content_type}`: {ty}"),
+            None => format!("`{content_type}`"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn render_media(content: &mut String, content_type: &str, media: &MediaType, schemas: &Value) {
+    let _ = write!(content, "`{content_type}`");
+    if let Some(ty) = media_type(media) {
+        let _ = write!(content, ": {ty}");
+    }
+    content.push_str("\n\n");
+
+    let example = example::media_example(media, schemas);
+    if example.is_null() {
+        return;
+    }
+
+    if content_type.contains("json") {
+        let json = serde_json::to_string_pretty(&example).unwrap_or_default();
+        let _ = write!(content, "```json\n{json}\n```\n\n");
+    } else if let Value::String(text) = example {
+        let _ = write!(content, "```\n{text}\n```\n\n");
+    }
+}
+
+fn media_type(media: &MediaType) -> Option<String> {
+    let schema = media.schema.as_ref()?;
+    serde_json::to_value(&schema.json_schema)
+        .ok()
+        .map(|schema| type_name(&schema))
+}
+
+fn schemas_page(api: &OpenApi) -> Option<Page> {
+    let schemas = &api.components.as_ref()?.schemas;
+    if schemas.is_empty() {
+        return None;
+    }
+
+    let mut content = String::from("# Schemas\n\n");
+
+    for (name, schema) in schemas {
+        let Ok(schema) = serde_json::to_value(&schema.json_schema) else {
+            continue;
+        };
+
+        let _ = write!(content, "## {name}\n\n");
+
+        if let Some(description) = schema.get("description").and_then(Value::as_str) {
+            let _ = write!(content, "{description}\n\n");
+        }
+
+        let Some(properties) = schema.get("properties").and_then(Value::as_object) else {
+            let _ = write!(content, "Type: {}\n\n", type_name(&schema));
+            continue;
+        };
+
+        let required = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+
+        content.push_str("| Property | Type | Required | Description |\n");
+        content.push_str("| --- | --- | --- | --- |\n");
+
+        for (property, schema) in properties {
+            let _ = writeln!(
+                content,
+                "| `{property}` | {} | {} | {} |",
+                cell(&type_name(schema)),
+                if required.iter().any(|r| r == property) {
+                    "yes"
+                } else {
+                    "no"
+                },
+                cell(
+                    schema
+                        .get("description")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                ),
+            );
+        }
+        content.push('\n');
+    }
+
+    Some(Page {
+        title: "Schemas".into(),
+        file_name: SCHEMAS_PAGE.into(),
+        content,
+    })
+}
+
+/// A short description of the type of a schema,
+/// with links to the referenced component schemas.
+fn type_name(schema: &Value) -> String {
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.rsplit('/').next().unwrap_or(reference);
+        return format!("[{name}]({SCHEMAS_PAGE}#{})", slug(name));
+    }
+
+    if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+        return values
+            .iter()
+            .map(|value| format!("`{value}`"))
+            .collect::<Vec<_>>()
+            .join(" or ");
+    }
+
+    if let Some(value) = schema.get("const") {
+        return format!("`{value}`");
+    }
+
+    for keyword in ["allOf", "oneOf", "anyOf"] {
+        if let Some(schemas) = schema.get(keyword).and_then(Value::as_array) {
+            let separator = if keyword == "allOf" { " and " } else { " or " };
+            return schemas
+                .iter()
+                .map(type_name)
+                .collect::<Vec<_>>()
+                .join(separator);
+        }
+    }
+
+    let types = match schema.get("type") {
+        Some(Value::String(ty)) => vec![ty.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => return "any".into(),
+    };
+
+    types
+        .into_iter()
+        .map(|ty| match ty {
+            "array" => match schema.get("items") {
+                Some(items) => format!("array of {}", type_name(items)),
+                None => "array".into(),
+            },
+            "object" => match schema.get("additionalProperties") {
+                Some(values) if values.is_object() => {
+                    format!("map of {}", type_name(values))
+                }
+                _ => "object".into(),
+            },
+            ty => match schema.get("format").and_then(Value::as_str) {
+                Some(format) => format!("{ty} ({format})"),
+                None => ty.into(),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join(" or ")
+}
+
+/// The anchor or file name of a heading, as generated by most renderers.
+fn slug(name: &str) -> String {
+    name.chars()
+        .filter_map(|c| match c {
+            ' ' | '-' => Some('-'),
+            '_' => Some('_'),
+            c if c.is_alphanumeric() => Some(c.to_ascii_lowercase()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Escape the content of a table cell.
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_markdown() {
+        let api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Todos", "version": "1.0.0" },
+            "tags": [{ "name": "Todo items", "description": "Manage todos." }],
+            "paths": {
+                "/todos/{id}": {
+                    "get": {
+                        "summary": "Get a todo",
+                        "tags": ["Todo items"],
+                        "parameters": [{
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "description": "The ID | of the todo.",
+                            "schema": { "type": "integer", "format": "uint32" }
+                        }],
+                        "responses": {
+                            "200": {
+                                "description": "The todo.",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/Todo" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/health": {
+                    "get": { "responses": { "204": { "description": "Healthy." } } }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "Todo": {
+                        "type": "object",
+                        "required": ["title"],
+                        "properties": {
+                            "title": { "type": "string", "example": "milk" },
+                            "tags": { "type": "array", "items": { "type": "string" } }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let pages = markdown(&api);
+        let file_names = pages
+            .iter()
+            .map(|p| p.file_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            file_names,
+            ["index.md", "todo-items.md", "default.md", "schemas.md"]
+        );
+
+        assert!(pages[0].content.contains("- [Todo items](todo-items.md)"));
+
+        let todos = &pages[1].content;
+        assert!(todos.contains("# Todo items\n\nManage todos."));
+        assert!(todos.contains("## Get a todo\n\n`GET /todos/{id}`"));
+        assert!(
+            todos.contains("| `id` | path | integer (uint32) | yes | The ID \\| of the todo. |")
+        );
+        assert!(todos.contains("| 200 | The todo. | `application/json`: [Todo](schemas.md#todo) |"));
+        assert!(todos.contains("```json\n{\n"));
+        assert!(todos.contains("\"title\": \"milk\""));
+
+        assert!(pages[2].content.contains("## GET /health"));
+
+        let schemas = &pages[3].content;
+        assert!(schemas.contains("| `title` | string | yes |  |"));
+        assert!(schemas.contains("| `tags` | array of string | no |  |"));
+    }
+}
@@ -0,0 +1,2052 @@
+//! Post-processing of the generated JSON schemas.
+//!
+//! The schemas generated by `schemars` are adjusted for
+//! `OpenAPI` and for client generators after they are merged into the
+//! documentation, the adjustments are configured in [`gen`](crate::gen).
+//!
+//! # Enum Descriptions
+//!
+//! The doc comments of unit enum variants are kept by `schemars` as a `oneOf`
+//! of single value `enum` schemas, with the undocumented variants grouped
+//! together. [`gen::enum_descriptions`](crate::gen::enum_descriptions)
+//! selects a representation that is better supported by tools, see
+//! [`EnumDescriptions`]. Descriptions can also be provided for variants
+//! with [`TransformSchema::variant_description`](crate::transform::TransformSchema::variant_description).
+//!
+//! # Discriminators
+//!
+//! Internally and adjacently tagged enums are documented by `schemars` as
+//! a `oneOf` of object schemas with a tag property. Client generators rely
+//! on a `discriminator` to deserialize such schemas, which requires the
+//! variants to be component schemas.
+//!
+//! With [`gen::discriminator_mapping`](crate::gen::discriminator_mapping)
+//! the variants of tagged enums in the components are moved to their own
+//! components, named by [`DiscriminatorMapping`], and a `discriminator`
+//! that maps the tag values to these components is added to the enum:
+//!
+//! ```json
+//! {
+//!   "oneOf": [
+//!     { "$ref": "#/components/schemas/PetCat" },
+//!     { "$ref": "#/components/schemas/PetDog" }
+//!   ],
+//!   "discriminator": {
+//!     "propertyName": "kind",
+//!     "mapping": {
+//!       "cat": "#/components/schemas/PetCat",
+//!       "dog": "#/components/schemas/PetDog"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! # Newtypes
+//!
+//! Newtype structs such as `struct UserId(u64)` are documented by
+//! `schemars` as component schemas that alias their inner schema, with
+//! the doc comment of the newtype as the description, which names domain
+//! primitives in the documentation.
+//! [`gen::newtype_schemas`](crate::gen::newtype_schemas) changes how these
+//! aliases are documented, see [`NewtypeSchemas`].
+//!
+//! Wrappers with `#[serde(transparent)]` are always inlined by `schemars`,
+//! so their names are not available. Remove the attribute, which does not
+//! change the serialized form of newtype structs, to name them.
+//!
+//! # Optional Fields
+//!
+//! `Option` fields are documented by `schemars` as not required and with
+//! `null` in the `type`, or in an `anyOf` for references. Client generators
+//! disagree on which of the two makes a field optional in the generated
+//! models, [`gen::option_fields`](crate::gen::option_fields) documents
+//! nullable fields only with one of them, see [`OptionFields`].
+//!
+//! # Schema Docs
+//!
+//! The doc comments of types and fields are only documented for types that
+//! derive `JsonSchema`. For types that implement it manually, or that are
+//! documented with a remote derive, the descriptions of component schemas
+//! and of their properties can be registered in [`SchemaDocs`], which is
+//! applied with [`gen::schema_docs`](crate::gen::schema_docs) or
+//! [`SchemaDocs::apply`]:
+//!
+//! ```
+//! use aide::schema::SchemaDocs;
+//! # #[derive(schemars::JsonSchema)]
+//! # struct Money { amount: String, currency: String }
+//!
+//! aide::gen::schema_docs(
+//!     SchemaDocs::new()
+//!         .describe::<Money>("An amount of money.")
+//!         .property::<Money>("currency", "The ISO 4217 currency code."),
+//! );
+//! ```
+//!
+//! Descriptions that are already documented are kept, use
+//! [`TransformOpenApi::describe_schema`](crate::transform::TransformOpenApi::describe_schema)
+//! to override them. Schemas that are not components, i.e. types that
+//! are not referenceable, cannot be described this way.
+//!
+//! # Maps and Strict Objects
+//!
+//! Maps such as `HashMap<K, V>` are documented by `schemars` as objects
+//! with the schema of the values in `additionalProperties`. The keys and
+//! the size of map fields can be documented with
+//! [`TransformSchema::property_names`](crate::transform::TransformSchema::property_names)
+//! and [`TransformSchema::max_properties`](crate::transform::TransformSchema::max_properties).
+//!
+//! Other objects allow unknown properties, unless the type uses
+//! `#[serde(deny_unknown_fields)]`. [`gen::strict_objects`](crate::gen::strict_objects)
+//! documents all object schemas that do not document additional properties
+//! with `additionalProperties: false`, except for schemas composed with
+//! `allOf`, `anyOf` or `oneOf`, where it would reject the properties of
+//! the other schemas.
+//!
+//! # Read and Write Schemas
+//!
+//! Properties marked with `#[schemars(read_only)]` or
+//! `#[schemars(write_only)]`, or with
+//! [`TransformSchema::read_only`](crate::transform::TransformSchema::read_only) and
+//! [`TransformSchema::write_only`](crate::transform::TransformSchema::write_only),
+//! are documented with `readOnly` and `writeOnly`, which many tools ignore.
+//!
+//! With [`gen::read_write_schemas`](crate::gen::read_write_schemas) the
+//! component schemas with such properties are instead split into a
+//! `{Name}Create` schema without the read-only properties, used by
+//! parameters and request bodies, and a `{Name}Read` schema without the
+//! write-only properties, used by responses, see [`ReadWriteSchemas`].
+//! Schemas that reference split schemas are split as well.
+//!
+//! # Formats
+//!
+//! The `type` and `format` of well-known types, e.g. dates, UUIDs
+//! or decimals, are decided by their `JsonSchema` implementations.
+//! A [`FormatMap`] set with [`gen::format_map`](crate::gen::format_map)
+//! overrides them in every schema of the documentation:
+//!
+//! ```ignore
+//! use aide::schema::FormatMap;
+//! use schemars::schema::InstanceType;
+//!
+//! aide::gen::format_map(
+//!     FormatMap::new()
+//!         .map::<rust_decimal::Decimal>(InstanceType::String, "decimal")
+//!         .map::<time::Date>(InstanceType::String, "date"),
+//! );
+//! ```
+//!
+//! The schemas of a type are recognized by the keywords its `JsonSchema`
+//! implementation generates, ignoring annotations such as descriptions,
+//! so the overrides also apply to the types in fields and nullable
+//! `Option`s. Types with identical schemas, e.g. the `DateTime` types
+//! of all time zones, share their overrides.
+//!
+//! # Deduplication
+//!
+//! Generic types and remote derives often generate identical component
+//! schemas under different names, e.g. `Page_for_User` and `UserPage`.
+//! With [`gen::deduplicate_schemas`](crate::gen::deduplicate_schemas)
+//! such schemas are merged into the first one of them, the references to
+//! the others are replaced and their names are listed in [`SCHEMA_ALIASES`].
+//!
+//! Schemas are compared without their titles, and schemas that only
+//! differ by references to merged schemas are merged as well.
+
+use std::collections::HashMap;
+
+use indexmap::IndexMap;
+use schemars::{
+    gen::{SchemaGenerator, SchemaSettings},
+    schema::{InstanceType, Schema},
+    JsonSchema,
+};
+use serde_json::{Map, Value};
+
+use crate::{
+    openapi::{MediaType, OpenApi, Parameter, ParameterSchemaOrContent, ReferenceOr, SchemaObject},
+    util::{iter_operations_mut, pascal_case},
+};
+
+/// The extension with the descriptions of the values of an enum,
+/// in the same order as the values.
+pub const ENUM_DESCRIPTIONS: &str = "x-enum-descriptions";
+
+/// The extension with the names of the component schemas
+/// that were merged into a schema, see [`schema`](self#deduplication).
+pub const SCHEMA_ALIASES: &str = "x-aliases";
+
+/// The representation of enums with documented variants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum EnumDescriptions {
+    /// Keep the representation generated by `schemars`, a `oneOf`
+    /// of `enum` schemas that groups the undocumented values.
+    #[default]
+    Generated,
+    /// A `oneOf` with a `const` schema and its description per value.
+    OneOfConst,
+    /// A flat `enum` with the descriptions in [`ENUM_DESCRIPTIONS`],
+    /// an empty string for undocumented values.
+    Extension,
+}
+
+/// How component schemas that alias another schema,
+/// usually generated for newtypes, are documented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NewtypeSchemas {
+    /// Keep the alias components and reference them,
+    /// with the keywords of the references, e.g. descriptions,
+    /// next to `$ref`.
+    #[default]
+    Component,
+    /// Replace the references with the aliased schemas,
+    /// and remove the alias components.
+    Inline,
+    /// Keep the alias components, and wrap references that have
+    /// other keywords in an `allOf`, for tools that ignore the
+    /// keywords next to `$ref`.
+    AllOf,
+}
+
+/// How nullable properties, usually `Option` fields, are documented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OptionFields {
+    /// Not required and nullable, as generated by `schemars`.
+    #[default]
+    Both,
+    /// Required and nullable.
+    Nullable,
+    /// Not required and not nullable.
+    Optional,
+}
+
+/// How component schemas with read-only or write-only properties
+/// are documented.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ReadWriteSchemas {
+    /// A single schema with `readOnly` and `writeOnly` properties.
+    #[default]
+    Shared,
+    /// A `{Name}Create` schema for requests and a
+    /// `{Name}Read` schema for responses.
+    Split,
+}
+
+/// The names of the component schemas of tagged enum
+/// variants, which are referenced by discriminators.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum DiscriminatorMapping {
+    /// Do not add discriminators.
+    #[default]
+    Disabled,
+    /// The name of the enum followed by the tag value, e.g. `PetCat`.
+    Prefixed,
+    /// The tag value, e.g. `Cat`, or the prefixed name
+    /// if another schema already has the name.
+    Variant,
+    /// A name from the name of the enum and the tag value.
+    Custom(fn(&str, &str) -> String),
+}
+
+impl DiscriminatorMapping {
+    fn name(
+        self,
+        schemas: &IndexMap<String, SchemaObject>,
+        name: &str,
+        value: &str,
+    ) -> Option<String> {
+        let prefixed = || format!("{name}{}", pascal_case(value));
+
+        let name = match self {
+            Self::Disabled => return None,
+            Self::Prefixed => prefixed(),
+            Self::Variant => {
+                let variant = pascal_case(value);
+                if schemas.contains_key(&variant) {
+                    prefixed()
+                } else {
+                    variant
+                }
+            }
+            Self::Custom(name_fn) => name_fn(name, value),
+        };
+
+        Some(name)
+    }
+}
+
+/// Overrides of the `type` and `format` of the schemas of types,
+/// see [`schema`](crate::schema#formats).
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct FormatMap {
+    formats: Vec<FormatOverride>,
+}
+
+/// The schema of a type, without annotations, and the keywords replacing it.
+type ResolvedFormat = (Map<String, Value>, Map<String, Value>);
+
+#[derive(Debug, Clone)]
+struct FormatOverride {
+    json_schema: fn(&mut SchemaGenerator) -> Schema,
+    instance_type: InstanceType,
+    format: String,
+}
+
+impl FormatMap {
+    /// A map without overrides.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Document the schemas of `T` with the given `type` and `format`,
+    /// replacing the other keywords of the schemas such as `pattern`.
+    pub fn map<T: JsonSchema>(
+        mut self,
+        instance_type: InstanceType,
+        format: impl Into<String>,
+    ) -> Self {
+        self.formats.push(FormatOverride {
+            json_schema: T::json_schema,
+            instance_type,
+            format: format.into(),
+        });
+        self
+    }
+
+    /// The schemas of the types generated with the settings
+    /// of the context, paired with their overrides.
+    pub(crate) fn resolve(&self, settings: &SchemaSettings) -> Vec<ResolvedFormat> {
+        self.formats
+            .iter()
+            .filter_map(|format| {
+                let mut gen = SchemaGenerator::new(settings.clone());
+                let Ok(Value::Object(mut schema)) =
+                    serde_json::to_value((format.json_schema)(&mut gen))
+                else {
+                    return None;
+                };
+                schema.retain(|keyword, _| !is_annotation(keyword));
+
+                let mut replacement = Map::new();
+                replacement.insert(
+                    "type".into(),
+                    serde_json::to_value(format.instance_type).ok()?,
+                );
+                replacement.insert("format".into(), format.format.clone().into());
+
+                Some((schema, replacement))
+            })
+            .collect()
+    }
+}
+
+/// Descriptions of component schemas and their properties,
+/// see [`schema`](crate::schema#schema-docs).
+#[derive(Debug, Clone, Default)]
+#[must_use]
+pub struct SchemaDocs {
+    schemas: IndexMap<String, SchemaDoc>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct SchemaDoc {
+    description: Option<String>,
+    properties: IndexMap<String, String>,
+}
+
+impl SchemaDocs {
+    /// A registry without descriptions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Describe the schema of `T`.
+    pub fn describe<T: JsonSchema>(mut self, description: impl Into<String>) -> Self {
+        self.schemas
+            .entry(T::schema_name())
+            .or_default()
+            .description = Some(description.into());
+        self
+    }
+
+    /// Describe a property of the schema of `T`.
+    pub fn property<T: JsonSchema>(
+        mut self,
+        property: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        self.schemas
+            .entry(T::schema_name())
+            .or_default()
+            .properties
+            .insert(property.into(), description.into());
+        self
+    }
+
+    /// Add the descriptions to the component schemas
+    /// of the document that are not described yet.
+    pub fn apply(&self, api: &mut OpenApi) {
+        if self.schemas.is_empty() {
+            return;
+        }
+
+        visit_schemas_mut(api, &mut |schema, name| {
+            let Some(doc) = name.and_then(|name| self.schemas.get(name)) else {
+                return;
+            };
+
+            if let Some(description) = &doc.description {
+                schema
+                    .entry("description")
+                    .or_insert_with(|| description.clone().into());
+            }
+
+            if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+                for (property, description) in &doc.properties {
+                    if let Some(Value::Object(property)) = properties.get_mut(property) {
+                        property
+                            .entry("description")
+                            .or_insert_with(|| description.clone().into());
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// The settings of the post-processing, taken from the current context.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PostProcess {
+    pub(crate) formats: Vec<ResolvedFormat>,
+    pub(crate) option_fields: OptionFields,
+    pub(crate) enum_descriptions: EnumDescriptions,
+    pub(crate) discriminator_mapping: DiscriminatorMapping,
+    pub(crate) newtype_schemas: NewtypeSchemas,
+    pub(crate) read_write_schemas: ReadWriteSchemas,
+    pub(crate) strict_objects: bool,
+    pub(crate) deduplicate_schemas: bool,
+    pub(crate) schema_docs: SchemaDocs,
+}
+
+impl PostProcess {
+    /// Returns the number of duplicate schemas that were merged.
+    #[cfg_attr(any(not(feature = "axum"), aide_docs_disabled), allow(dead_code))]
+    pub(crate) fn apply(&self, api: &mut OpenApi) -> usize {
+        self.schema_docs.apply(api);
+
+        if !self.formats.is_empty() {
+            visit_schemas_mut(api, &mut |schema, _| override_format(schema, &self.formats));
+        }
+
+        if self.option_fields != OptionFields::Both {
+            visit_schemas_mut(api, &mut |schema, _| {
+                document_options(schema, self.option_fields);
+            });
+        }
+
+        match self.newtype_schemas {
+            NewtypeSchemas::Component => {}
+            NewtypeSchemas::Inline => inline_aliases(api),
+            NewtypeSchemas::AllOf => wrap_alias_refs(api),
+        }
+
+        if self.read_write_schemas == ReadWriteSchemas::Split {
+            split_read_write(api);
+        }
+
+        if !matches!(self.discriminator_mapping, DiscriminatorMapping::Disabled) {
+            add_discriminators(api, self.discriminator_mapping);
+        }
+
+        if self.enum_descriptions != EnumDescriptions::Generated {
+            visit_schemas_mut(api, &mut |schema, _| {
+                document_enum(schema, self.enum_descriptions);
+            });
+        }
+
+        if self.strict_objects {
+            visit_schemas_mut(api, &mut |schema, _| deny_additional_properties(schema));
+        }
+
+        if self.deduplicate_schemas {
+            deduplicate_schemas(api)
+        } else {
+            0
+        }
+    }
+}
+
+type Visitor<'v> = dyn FnMut(&mut Map<String, Value>, Option<&str>) + 'v;
+
+/// Call `visit` for every schema object in the document, including
+/// nested schemas, with the name of the top-level component schemas.
+///
+/// Schemas are visited before their nested schemas.
+pub(crate) fn visit_schemas_mut(api: &mut OpenApi, visit: &mut Visitor<'_>) {
+    if let Some(components) = &mut api.components {
+        for (name, schema) in &mut components.schemas {
+            visit_schema_object_mut(schema, Some(name), visit);
+        }
+    }
+
+    visit_operation_schemas_mut(api, &mut |schema, _| visit(schema, None));
+}
+
+/// Whether a schema of an operation describes a request or a response.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Direction {
+    Request,
+    Response,
+}
+
+/// Call `visit` for every schema object of the parameters,
+/// request bodies and responses of the operations.
+fn visit_operation_schemas_mut(
+    api: &mut OpenApi,
+    visit: &mut dyn FnMut(&mut Map<String, Value>, Direction),
+) {
+    let Some(paths) = &mut api.paths else {
+        return;
+    };
+
+    let mut request = |schema: &mut Map<String, Value>, _: Option<&str>| {
+        visit(schema, Direction::Request);
+    };
+
+    for (_, path) in &mut paths.paths {
+        let ReferenceOr::Item(path) = path else {
+            continue;
+        };
+
+        for param in path
+            .parameters
+            .iter_mut()
+            .filter_map(ReferenceOr::as_item_mut)
+        {
+            visit_parameter_mut(param, &mut request);
+        }
+
+        for (_, operation) in iter_operations_mut(path) {
+            for param in operation
+                .parameters
+                .iter_mut()
+                .filter_map(ReferenceOr::as_item_mut)
+            {
+                visit_parameter_mut(param, &mut request);
+            }
+
+            for media in operation
+                .request_body
+                .as_mut()
+                .and_then(ReferenceOr::as_item_mut)
+                .into_iter()
+                .flat_map(|body| body.content.values_mut())
+            {
+                visit_media_type_mut(media, &mut request);
+            }
+        }
+    }
+
+    let mut response = |schema: &mut Map<String, Value>, _: Option<&str>| {
+        visit(schema, Direction::Response);
+    };
+
+    for (_, path) in &mut paths.paths {
+        let ReferenceOr::Item(path) = path else {
+            continue;
+        };
+
+        for (_, operation) in iter_operations_mut(path) {
+            for media in operation
+                .responses
+                .iter_mut()
+                .flat_map(|res| res.default.iter_mut().chain(res.responses.values_mut()))
+                .filter_map(ReferenceOr::as_item_mut)
+                .flat_map(|res| res.content.values_mut())
+            {
+                visit_media_type_mut(media, &mut response);
+            }
+        }
+    }
+}
+
+fn visit_parameter_mut(param: &mut Parameter, visit: &mut Visitor<'_>) {
+    match &mut param.parameter_data_mut().format {
+        ParameterSchemaOrContent::Schema(schema) => visit_schema_object_mut(schema, None, visit),
+        ParameterSchemaOrContent::Content(content) => {
+            for media in content.values_mut() {
+                visit_media_type_mut(media, visit);
+            }
+        }
+    }
+}
+
+fn visit_media_type_mut(media: &mut MediaType, visit: &mut Visitor<'_>) {
+    if let Some(schema) = &mut media.schema {
+        visit_schema_object_mut(schema, None, visit);
+    }
+}
+
+fn visit_schema_object_mut(schema: &mut SchemaObject, name: Option<&str>, visit: &mut Visitor<'_>) {
+    let Ok(mut value) = serde_json::to_value(&schema.json_schema) else {
+        return;
+    };
+
+    if let Value::Object(object) = &mut value {
+        visit(object, name);
+    }
+    visit_subschemas_mut(&mut value, visit);
+
+    if let Ok(json_schema) = serde_json::from_value(value) {
+        schema.json_schema = json_schema;
+    }
+}
+
+/// Call `visit` for the nested schemas of a schema.
+fn visit_subschemas_mut(schema: &mut Value, visit: &mut Visitor<'_>) {
+    let Value::Object(object) = schema else {
+        return;
+    };
+
+    let mut subschemas: Vec<&mut Value> = Vec::new();
+
+    for (keyword, value) in object.iter_mut() {
+        match (keyword.as_str(), value) {
+            ("items" | "additionalProperties" | "not" | "if" | "then" | "else", value) => {
+                subschemas.push(value);
+            }
+            ("allOf" | "anyOf" | "oneOf" | "prefixItems", Value::Array(schemas)) => {
+                subschemas.extend(schemas);
+            }
+            (
+                "properties" | "patternProperties" | "definitions" | "$defs",
+                Value::Object(schemas),
+            ) => {
+                subschemas.extend(schemas.values_mut());
+            }
+            _ => {}
+        }
+    }
+
+    for schema in subschemas {
+        if let Value::Object(object) = schema {
+            visit(object, None);
+        }
+        visit_subschemas_mut(schema, visit);
+    }
+}
+
+/// The component schemas that alias another schema, i.e. schemas that are
+/// neither objects nor enums, or references with additional keywords.
+fn alias_schemas(api: &OpenApi) -> IndexMap<String, Value> {
+    let Some(components) = &api.components else {
+        return IndexMap::new();
+    };
+
+    components
+        .schemas
+        .iter()
+        .filter_map(|(name, schema)| {
+            let schema = serde_json::to_value(&schema.json_schema).ok()?;
+            let object = schema.as_object()?;
+
+            let is_object = object.get("type").is_some_and(|ty| ty == "object");
+            let alias = object.contains_key("$ref")
+                || (!is_object
+                    && ["properties", "enum", "const", "oneOf", "anyOf", "allOf"]
+                        .iter()
+                        .all(|keyword| !object.contains_key(*keyword)));
+
+            alias.then(|| (name.clone(), schema))
+        })
+        .collect()
+}
+
+/// The name of the referenced component schema.
+fn component_ref(schema: &Map<String, Value>) -> Option<&str> {
+    schema
+        .get("$ref")?
+        .as_str()?
+        .strip_prefix("#/components/schemas/")
+}
+
+/// Replace the references to alias components with their schemas,
+/// the keywords next to the references take precedence.
+fn inline_aliases(api: &mut OpenApi) {
+    let aliases = alias_schemas(api);
+    if aliases.is_empty() {
+        return;
+    }
+
+    visit_schemas_mut(api, &mut |schema, _| {
+        // Aliases can reference other aliases, the depth is
+        // limited in case of recursive aliases.
+        for _ in 0..16 {
+            let Some(Value::Object(alias)) = component_ref(schema).and_then(|r| aliases.get(r))
+            else {
+                break;
+            };
+
+            schema.remove("$ref");
+            for (keyword, value) in alias {
+                if !schema.contains_key(keyword) {
+                    schema.insert(keyword.clone(), value.clone());
+                }
+            }
+        }
+    });
+
+    if let Some(components) = &mut api.components {
+        components
+            .schemas
+            .retain(|name, _| !aliases.contains_key(name));
+    }
+}
+
+/// Wrap the references to alias components and the references of alias
+/// components that have other keywords next to them in an `allOf`.
+fn wrap_alias_refs(api: &mut OpenApi) {
+    let aliases = alias_schemas(api);
+    if aliases.is_empty() {
+        return;
+    }
+
+    visit_schemas_mut(api, &mut |schema, name| {
+        let is_alias = name.is_some_and(|name| aliases.contains_key(name));
+        let refs_alias = component_ref(schema).is_some_and(|r| aliases.contains_key(r));
+
+        if schema.len() < 2 || !(is_alias || refs_alias) {
+            return;
+        }
+
+        if let Some(reference) = schema.remove("$ref") {
+            schema.insert(
+                "allOf".into(),
+                vec![serde_json::json!({ "$ref": reference })].into(),
+            );
+        }
+    });
+}
+
+/// Merge the component schemas that are identical except for their titles,
+/// until no more schemas are merged as the references are replaced.
+///
+/// Returns the number of merged schemas.
+fn deduplicate_schemas(api: &mut OpenApi) -> usize {
+    let mut count = 0;
+    loop {
+        let Some(components) = &mut api.components else {
+            return count;
+        };
+
+        let mut canonical: HashMap<String, String> = HashMap::new();
+        let mut merged: IndexMap<String, String> = IndexMap::new();
+
+        for (name, schema) in &components.schemas {
+            let Ok(Value::Object(mut schema)) = serde_json::to_value(&schema.json_schema) else {
+                continue;
+            };
+            schema.remove("title");
+            schema.remove(SCHEMA_ALIASES);
+
+            let key = Value::Object(schema).to_string();
+            match canonical.get(&key) {
+                Some(target) => {
+                    merged.insert(name.clone(), target.clone());
+                }
+                None => {
+                    canonical.insert(key, name.clone());
+                }
+            }
+        }
+
+        if merged.is_empty() {
+            return count;
+        }
+        count += merged.len();
+
+        let mut aliases: IndexMap<String, Vec<Value>> = IndexMap::new();
+        for (name, schema) in &components.schemas {
+            let Some(target) = merged.get(name) else {
+                continue;
+            };
+
+            let names = aliases.entry(target.clone()).or_default();
+            names.push(name.clone().into());
+            if let Schema::Object(object) = &schema.json_schema {
+                if let Some(Value::Array(previous)) = object.extensions.get(SCHEMA_ALIASES) {
+                    names.extend(previous.iter().cloned());
+                }
+            }
+        }
+
+        components
+            .schemas
+            .retain(|name, _| !merged.contains_key(name));
+
+        visit_schemas_mut(api, &mut |schema, name| {
+            if let Some(names) = name.and_then(|name| aliases.get(name)) {
+                let Value::Array(existing) = schema
+                    .entry(SCHEMA_ALIASES)
+                    .or_insert_with(|| Value::Array(Vec::new()))
+                else {
+                    return;
+                };
+                existing.extend(names.iter().cloned());
+            }
+
+            if let Some(target) = component_ref(schema).and_then(|r| merged.get(r)) {
+                schema.insert(
+                    "$ref".into(),
+                    format!("#/components/schemas/{target}").into(),
+                );
+            }
+
+            if let Some(Value::Object(mapping)) = schema
+                .get_mut("discriminator")
+                .and_then(|d| d.get_mut("mapping"))
+            {
+                for reference in mapping.values_mut() {
+                    let target = reference
+                        .as_str()
+                        .and_then(|r| r.strip_prefix("#/components/schemas/"))
+                        .and_then(|r| merged.get(r));
+                    if let Some(target) = target {
+                        *reference = format!("#/components/schemas/{target}").into();
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Whether `null` is in the type or in an `anyOf` of a schema.
+fn is_nullable(schema: &Map<String, Value>) -> bool {
+    let null_type = |ty: &Value| ty == "null";
+
+    match (schema.get("type"), schema.get("anyOf")) {
+        (Some(Value::Array(types)), _) => types.len() > 1 && types.iter().any(null_type),
+        (_, Some(Value::Array(schemas))) => schemas
+            .iter()
+            .any(|schema| schema.get("type").is_some_and(null_type)),
+        _ => false,
+    }
+}
+
+/// Remove `null` from the type, the values and the `anyOf` of a schema.
+fn remove_null(schema: &mut Map<String, Value>) {
+    if let Some(Value::Array(types)) = schema.get_mut("type") {
+        types.retain(|ty| ty != "null");
+        if let [ty] = types.as_slice() {
+            let ty = ty.clone();
+            schema.insert("type".into(), ty);
+        }
+        if let Some(Value::Array(values)) = schema.get_mut("enum") {
+            values.retain(|value| !value.is_null());
+        }
+    }
+
+    if let Some(Value::Array(schemas)) = schema.get_mut("anyOf") {
+        schemas.retain(|schema| !schema.get("type").is_some_and(|ty| ty == "null"));
+        if let [Value::Object(_)] = schemas.as_slice() {
+            if let Some(Value::Array(mut schemas)) = schema.remove("anyOf") {
+                if let Some(Value::Object(inner)) = schemas.pop() {
+                    for (keyword, value) in inner {
+                        schema.entry(keyword).or_insert(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn document_options(schema: &mut Map<String, Value>, style: OptionFields) {
+    let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+        return;
+    };
+
+    let mut nullable = Vec::new();
+    for (name, property) in properties.iter_mut() {
+        let Value::Object(property) = property else {
+            continue;
+        };
+        if is_nullable(property) {
+            nullable.push(name.clone());
+            if style == OptionFields::Optional {
+                remove_null(property);
+            }
+        }
+    }
+
+    if style == OptionFields::Nullable && !nullable.is_empty() {
+        let required = schema
+            .entry("required")
+            .or_insert_with(|| Value::Array(Vec::new()));
+        if let Value::Array(required) = required {
+            for name in nullable {
+                if !required.iter().any(|r| *r == name) {
+                    required.push(name.into());
+                }
+            }
+        }
+    }
+}
+
+fn deny_additional_properties(schema: &mut Map<String, Value>) {
+    let is_object =
+        schema.get("type").is_some_and(|ty| ty == "object") || schema.contains_key("properties");
+    let is_composed = ["allOf", "anyOf", "oneOf", "$ref"]
+        .iter()
+        .any(|keyword| schema.contains_key(*keyword));
+
+    if is_object && !is_composed {
+        schema
+            .entry("additionalProperties")
+            .or_insert(Value::Bool(false));
+    }
+}
+
+/// Whether a schema has properties marked with `marker`,
+/// `readOnly` or `writeOnly`, not following references.
+fn has_marked_properties(schema: &Value, marker: &str) -> bool {
+    let mut found = false;
+    let mut schema = schema.clone();
+
+    if let Value::Object(object) = &mut schema {
+        let mut visit = |schema: &mut Map<String, Value>, _: Option<&str>| {
+            found |= marked_properties(schema, marker).next().is_some();
+        };
+        visit(object, None);
+        visit_subschemas_mut(&mut schema, &mut visit);
+    }
+
+    found
+}
+
+fn marked_properties<'s>(
+    schema: &'s Map<String, Value>,
+    marker: &'s str,
+) -> impl Iterator<Item = &'s String> + 's {
+    schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .filter(move |(_, property)| property.get(marker) == Some(&Value::Bool(true)))
+        .map(|(name, _)| name)
+}
+
+/// Whether a schema references any of the component schemas.
+fn references_any(schema: &Value, names: &IndexMap<String, Value>) -> bool {
+    match schema {
+        Value::Object(object) => {
+            component_ref(object).is_some_and(|r| names.contains_key(r))
+                || object.values().any(|value| references_any(value, names))
+        }
+        Value::Array(values) => values.iter().any(|value| references_any(value, names)),
+        _ => false,
+    }
+}
+
+/// Point the references to the `split` components to their variant.
+fn reference_variant(
+    schema: &mut Map<String, Value>,
+    split: &IndexMap<String, Value>,
+    suffix: &str,
+) {
+    if let Some(name) = component_ref(schema).filter(|r| split.contains_key(*r)) {
+        let reference = format!("#/components/schemas/{name}{suffix}");
+        schema.insert("$ref".into(), reference.into());
+    }
+}
+
+/// The request or response variant of a split component schema,
+/// without the properties marked with `marker`.
+fn schema_variant(
+    schema: &Value,
+    split: &IndexMap<String, Value>,
+    name: &str,
+    marker: &str,
+    suffix: &str,
+) -> Value {
+    let mut variant = schema.clone();
+
+    let mut visit = |schema: &mut Map<String, Value>, _: Option<&str>| {
+        let removed = marked_properties(schema, marker)
+            .cloned()
+            .collect::<Vec<_>>();
+
+        if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+            properties.retain(|property, _| !removed.contains(property));
+        }
+        if let Some(Value::Array(required)) = schema.get_mut("required") {
+            required.retain(|property| !removed.iter().any(|r| property == r));
+        }
+
+        reference_variant(schema, split, suffix);
+    };
+
+    if let Value::Object(object) = &mut variant {
+        visit(object, None);
+        if object.contains_key("title") {
+            object.insert("title".into(), format!("{name}{suffix}").into());
+        }
+    }
+    visit_subschemas_mut(&mut variant, &mut visit);
+
+    variant
+}
+
+/// Split the component schemas with read-only or write-only properties,
+/// and the component schemas that reference them, into request and
+/// response schemas.
+fn split_read_write(api: &mut OpenApi) {
+    const CREATE: &str = "Create";
+    const READ: &str = "Read";
+
+    let Some(components) = &api.components else {
+        return;
+    };
+
+    let schemas = components
+        .schemas
+        .iter()
+        .filter_map(|(name, schema)| {
+            Some((
+                name.clone(),
+                serde_json::to_value(&schema.json_schema).ok()?,
+            ))
+        })
+        .collect::<IndexMap<_, _>>();
+
+    let mut split = schemas
+        .iter()
+        .filter(|(_, schema)| {
+            has_marked_properties(schema, "readOnly") || has_marked_properties(schema, "writeOnly")
+        })
+        .map(|(name, schema)| (name.clone(), schema.clone()))
+        .collect::<IndexMap<_, _>>();
+
+    if split.is_empty() {
+        return;
+    }
+
+    loop {
+        let referencing = schemas
+            .iter()
+            .filter(|(name, schema)| !split.contains_key(*name) && references_any(schema, &split))
+            .map(|(name, schema)| (name.clone(), schema.clone()))
+            .collect::<Vec<_>>();
+
+        if referencing.is_empty() {
+            break;
+        }
+        split.extend(referencing);
+    }
+
+    let mut new_schemas = IndexMap::new();
+
+    for (name, schema) in &schemas {
+        if split.contains_key(name) {
+            for (marker, suffix) in [("readOnly", CREATE), ("writeOnly", READ)] {
+                let variant = schema_variant(schema, &split, name, marker, suffix);
+                if let Ok(variant) = serde_json::from_value(variant) {
+                    new_schemas.insert(format!("{name}{suffix}"), variant);
+                }
+            }
+        }
+
+        if let Some(schema) = api
+            .components
+            .as_mut()
+            .and_then(|c| c.schemas.swap_remove(name))
+        {
+            new_schemas.insert(name.clone(), schema);
+        }
+    }
+
+    if let Some(components) = &mut api.components {
+        components.schemas = new_schemas;
+    }
+
+    visit_operation_schemas_mut(api, &mut |schema, direction| {
+        let suffix = match direction {
+            Direction::Request => CREATE,
+            Direction::Response => READ,
+        };
+        reference_variant(schema, &split, suffix);
+    });
+
+    // The original schemas are kept if they are still referenced
+    // outside of the split schemas, e.g. by callbacks.
+    let Some(components) = &mut api.components else {
+        return;
+    };
+    let originals = split
+        .keys()
+        .filter_map(|name| Some((name, components.schemas.shift_remove(name)?)))
+        .collect::<Vec<_>>();
+
+    let document = serde_json::to_string(&*api).unwrap_or_default();
+    if let Some(components) = &mut api.components {
+        components.schemas.extend(
+            originals
+                .into_iter()
+                .filter(|(name, _)| document.contains(&format!("\"#/components/schemas/{name}\"")))
+                .map(|(name, schema)| (name.clone(), schema)),
+        );
+    }
+}
+
+/// Move the variants of tagged enums in the components
+/// to their own components and add discriminators.
+fn add_discriminators(api: &mut OpenApi, mapping: DiscriminatorMapping) {
+    let Some(components) = &mut api.components else {
+        return;
+    };
+
+    let names = components.schemas.keys().cloned().collect::<Vec<_>>();
+
+    for name in names {
+        let Ok(Value::Object(mut schema)) =
+            serde_json::to_value(&components.schemas[&name].json_schema)
+        else {
+            continue;
+        };
+
+        if schema.contains_key("discriminator") {
+            continue;
+        }
+
+        let Some((property, values)) = tagged_variants(&schema) else {
+            continue;
+        };
+
+        let Some(targets) = values
+            .iter()
+            .map(|value| mapping.name(&components.schemas, &name, value))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        // Existing schemas are never replaced.
+        if targets
+            .iter()
+            .any(|target| components.schemas.contains_key(target))
+        {
+            continue;
+        }
+
+        let Some(Value::Array(variants)) = schema.remove("oneOf") else {
+            continue;
+        };
+
+        let mut refs = Vec::new();
+        let mut discriminator_mapping = Map::new();
+
+        for ((variant, value), target) in variants.into_iter().zip(values).zip(targets) {
+            let Ok(json_schema) = serde_json::from_value(variant) else {
+                continue;
+            };
+            components.schemas.insert(
+                target.clone(),
+                SchemaObject {
+                    json_schema,
+                    example: None,
+                    external_docs: None,
+                },
+            );
+
+            let reference = format!("#/components/schemas/{target}");
+            refs.push(serde_json::json!({ "$ref": reference }));
+            discriminator_mapping.insert(value, reference.into());
+        }
+
+        schema.insert("oneOf".into(), refs.into());
+        schema.insert(
+            "discriminator".into(),
+            serde_json::json!({
+                "propertyName": property,
+                "mapping": discriminator_mapping,
+            }),
+        );
+
+        if let Ok(json_schema) = serde_json::from_value(schema.into()) {
+            components.schemas[&name].json_schema = json_schema;
+        }
+    }
+}
+
+/// The tag property and values of the variants of a tagged enum.
+///
+/// The tag is a required property with a single string
+/// value in every variant, and the values are unique.
+fn tagged_variants(schema: &Map<String, Value>) -> Option<(String, Vec<String>)> {
+    let Some(Value::Array(variants)) = schema.get("oneOf") else {
+        return None;
+    };
+
+    let tag_value = |variant: &Value, property: &str| -> Option<String> {
+        let required = variant.get("required")?.as_array()?;
+        if !required.iter().any(|r| r == property) {
+            return None;
+        }
+
+        let tag = variant.get("properties")?.get(property)?;
+        match (tag.get("enum"), tag.get("const")) {
+            (Some(Value::Array(values)), None) if values.len() == 1 => {
+                values[0].as_str().map(Into::into)
+            }
+            (None, Some(Value::String(value))) => Some(value.clone()),
+            _ => None,
+        }
+    };
+
+    let candidates = variants.first()?.get("properties")?.as_object()?.keys();
+
+    for property in candidates {
+        let Some(values) = variants
+            .iter()
+            .map(|variant| tag_value(variant, property))
+            .collect::<Option<Vec<_>>>()
+        else {
+            continue;
+        };
+
+        let unique = values
+            .iter()
+            .enumerate()
+            .all(|(i, value)| !values[..i].contains(value));
+
+        if unique {
+            return Some((property.clone(), values));
+        }
+    }
+
+    None
+}
+
+/// The values of an enum schema with their descriptions.
+///
+/// Both flat enums and a `oneOf` of single value schemas
+/// (either `enum` or `const`) are recognized.
+fn enum_variants(schema: &Map<String, Value>) -> Option<Vec<(Value, Option<Value>)>> {
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        if schema.contains_key("oneOf") {
+            return None;
+        }
+        return Some(values.iter().map(|value| (value.clone(), None)).collect());
+    }
+
+    let Some(Value::Array(schemas)) = schema.get("oneOf") else {
+        return None;
+    };
+
+    let mut variants = Vec::new();
+    for schema in schemas {
+        let schema = schema.as_object()?;
+        if schema
+            .keys()
+            .any(|key| !matches!(key.as_str(), "type" | "enum" | "const" | "description"))
+        {
+            return None;
+        }
+
+        let description = schema.get("description").cloned();
+        match (schema.get("enum"), schema.get("const")) {
+            (Some(Value::Array(values)), None) => {
+                variants.extend(
+                    values
+                        .iter()
+                        .map(|value| (value.clone(), description.clone())),
+                );
+            }
+            (None, Some(value)) => variants.push((value.clone(), description)),
+            _ => return None,
+        }
+    }
+
+    Some(variants)
+}
+
+/// The JSON type of every value, if it is the same for all values.
+fn enum_type(variants: &[(Value, Option<Value>)]) -> Option<&'static str> {
+    let mut types = variants.iter().map(|(value, _)| match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    });
+
+    let first = types.next()?;
+    types.all(|ty| ty == first).then_some(first)
+}
+
+/// Rewrite an enum schema with documented values in the given representation.
+fn document_enum(schema: &mut Map<String, Value>, style: EnumDescriptions) {
+    let Some(variants) = enum_variants(schema) else {
+        return;
+    };
+
+    if variants
+        .iter()
+        .all(|(_, description)| description.is_none())
+    {
+        return;
+    }
+
+    write_enum(schema, variants, style);
+}
+
+fn write_enum(
+    schema: &mut Map<String, Value>,
+    variants: Vec<(Value, Option<Value>)>,
+    style: EnumDescriptions,
+) {
+    let ty = enum_type(&variants);
+    schema.remove("enum");
+    schema.remove("oneOf");
+    schema.remove(ENUM_DESCRIPTIONS);
+
+    match style {
+        EnumDescriptions::Generated => {
+            schema.remove("type");
+            let schemas = variants.into_iter().map(|(value, description)| {
+                let mut variant = Map::new();
+                if let Some(description) = description {
+                    variant.insert("description".into(), description);
+                }
+                if let Some(ty) = ty {
+                    variant.insert("type".into(), ty.into());
+                }
+                variant.insert("enum".into(), vec![value].into());
+                Value::Object(variant)
+            });
+            schema.insert("oneOf".into(), schemas.collect());
+        }
+        EnumDescriptions::OneOfConst => {
+            if let Some(ty) = ty {
+                schema.insert("type".into(), ty.into());
+            }
+            let schemas = variants.into_iter().map(|(value, description)| {
+                let mut variant = Map::new();
+                variant.insert("const".into(), value);
+                if let Some(description) = description {
+                    variant.insert("description".into(), description);
+                }
+                Value::Object(variant)
+            });
+            schema.insert("oneOf".into(), schemas.collect());
+        }
+        EnumDescriptions::Extension => {
+            if let Some(ty) = ty {
+                schema.insert("type".into(), ty.into());
+            }
+            let (values, descriptions): (Vec<_>, Vec<_>) = variants
+                .into_iter()
+                .map(|(value, description)| (value, description.unwrap_or_else(|| "".into())))
+                .unzip();
+            schema.insert("enum".into(), values.into());
+            schema.insert(ENUM_DESCRIPTIONS.into(), descriptions.into());
+        }
+    }
+}
+
+/// Set the description of a value of an enum schema,
+/// returns whether the value exists.
+pub(crate) fn set_variant_description(
+    schema: &mut Map<String, Value>,
+    value: &str,
+    description: &str,
+) -> bool {
+    let Some(mut variants) = enum_variants(schema) else {
+        return false;
+    };
+
+    let Some(variant) = variants.iter_mut().find(|(v, _)| match v.as_str() {
+        Some(v) => v == value,
+        None => serde_json::to_string(v).is_ok_and(|v| v == value),
+    }) else {
+        return false;
+    };
+    variant.1 = Some(description.into());
+
+    write_enum(schema, variants, EnumDescriptions::Generated);
+    true
+}
+
+/// Keywords that do not constrain the instances of a schema.
+fn is_annotation(keyword: &str) -> bool {
+    matches!(
+        keyword,
+        "$schema"
+            | "$comment"
+            | "title"
+            | "description"
+            | "default"
+            | "examples"
+            | "example"
+            | "deprecated"
+            | "readOnly"
+            | "writeOnly"
+    ) || keyword.starts_with("x-")
+}
+
+fn override_format(schema: &mut Map<String, Value>, formats: &[ResolvedFormat]) {
+    // `Option`s are documented with `null` in the type.
+    let nullable = match schema.get("type") {
+        Some(Value::Array(types)) if types.len() == 2 && types.contains(&"null".into()) => {
+            types.iter().find(|t| *t != "null").cloned()
+        }
+        _ => None,
+    };
+
+    let Some((_, replacement)) = formats.iter().find(|(type_schema, _)| {
+        let mut keywords = schema.iter().filter(|(keyword, _)| !is_annotation(keyword));
+        keywords.clone().count() == type_schema.len()
+            && keywords.all(|(keyword, value)| match (keyword.as_str(), &nullable) {
+                ("type", Some(non_null)) => type_schema.get("type") == Some(non_null),
+                _ => type_schema.get(keyword) == Some(value),
+            })
+    }) else {
+        return;
+    };
+
+    schema.retain(|keyword, _| is_annotation(keyword));
+    schema.extend(replacement.clone());
+
+    if nullable.is_some() {
+        schema.insert(
+            "type".into(),
+            Value::Array(vec![replacement["type"].clone(), "null".into()]),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn status_schema() -> Map<String, Value> {
+        json!({
+            "title": "Status",
+            "oneOf": [
+                { "type": "string", "enum": ["inactive", "pending"] },
+                { "description": "It is active.", "type": "string", "enum": ["active"] }
+            ]
+        })
+        .as_object()
+        .unwrap()
+        .clone()
+    }
+
+    #[test]
+    fn test_enum_descriptions() {
+        let mut schema = status_schema();
+        document_enum(&mut schema, EnumDescriptions::OneOfConst);
+        assert_eq!(
+            Value::Object(schema),
+            json!({
+                "title": "Status",
+                "type": "string",
+                "oneOf": [
+                    { "const": "inactive" },
+                    { "const": "pending" },
+                    { "const": "active", "description": "It is active." }
+                ]
+            })
+        );
+
+        let mut schema = status_schema();
+        document_enum(&mut schema, EnumDescriptions::Extension);
+        assert_eq!(
+            Value::Object(schema),
+            json!({
+                "title": "Status",
+                "type": "string",
+                "enum": ["inactive", "pending", "active"],
+                "x-enum-descriptions": ["", "", "It is active."]
+            })
+        );
+
+        let mut schema = json!({ "type": "string", "enum": ["a", "b"] })
+            .as_object()
+            .unwrap()
+            .clone();
+        document_enum(&mut schema, EnumDescriptions::OneOfConst);
+        assert_eq!(
+            Value::Object(schema),
+            json!({ "type": "string", "enum": ["a", "b"] })
+        );
+    }
+
+    #[test]
+    fn test_variant_description() {
+        let mut schema = json!({ "type": "string", "enum": ["a", "b"] })
+            .as_object()
+            .unwrap()
+            .clone();
+
+        assert!(set_variant_description(&mut schema, "b", "The second."));
+        assert!(!set_variant_description(&mut schema, "c", "Missing."));
+
+        document_enum(&mut schema, EnumDescriptions::Extension);
+        assert_eq!(
+            Value::Object(schema),
+            json!({
+                "type": "string",
+                "enum": ["a", "b"],
+                "x-enum-descriptions": ["", "The second."]
+            })
+        );
+    }
+
+    #[test]
+    fn test_discriminators() {
+        let api = || -> OpenApi {
+            serde_json::from_value(json!({
+                "openapi": "3.1.0",
+                "info": { "title": "Pets", "version": "1" },
+                "components": {
+                    "schemas": {
+                        "Cat": { "type": "object" },
+                        "Pet": {
+                            "oneOf": [
+                                {
+                                    "type": "object",
+                                    "required": ["kind", "lives"],
+                                    "properties": {
+                                        "kind": { "type": "string", "enum": ["cat"] },
+                                        "lives": { "type": "integer" }
+                                    }
+                                },
+                                {
+                                    "type": "object",
+                                    "required": ["kind"],
+                                    "properties": {
+                                        "kind": { "type": "string", "enum": ["dog"] }
+                                    }
+                                }
+                            ]
+                        },
+                        "Shape": {
+                            "oneOf": [{ "type": "string" }, { "type": "integer" }]
+                        }
+                    }
+                }
+            }))
+            .unwrap()
+        };
+
+        let mut prefixed = api();
+        add_discriminators(&mut prefixed, DiscriminatorMapping::Prefixed);
+        let schemas = serde_json::to_value(&prefixed.components.unwrap().schemas).unwrap();
+
+        assert_eq!(
+            schemas["Pet"],
+            json!({
+                "oneOf": [
+                    { "$ref": "#/components/schemas/PetCat" },
+                    { "$ref": "#/components/schemas/PetDog" }
+                ],
+                "discriminator": {
+                    "propertyName": "kind",
+                    "mapping": {
+                        "cat": "#/components/schemas/PetCat",
+                        "dog": "#/components/schemas/PetDog"
+                    }
+                }
+            })
+        );
+        assert_eq!(
+            schemas["PetDog"]["properties"]["kind"]["enum"],
+            json!(["dog"])
+        );
+        assert!(schemas["Shape"].get("discriminator").is_none());
+
+        let mut variant = api();
+        add_discriminators(&mut variant, DiscriminatorMapping::Variant);
+        let schemas = serde_json::to_value(&variant.components.unwrap().schemas).unwrap();
+
+        assert_eq!(
+            schemas["Pet"]["discriminator"]["mapping"],
+            json!({
+                "cat": "#/components/schemas/PetCat",
+                "dog": "#/components/schemas/Dog"
+            })
+        );
+        assert_eq!(schemas["Cat"], json!({ "type": "object" }));
+    }
+
+    #[test]
+    fn test_newtype_schemas() {
+        let api = || -> OpenApi {
+            serde_json::from_value(json!({
+                "openapi": "3.1.0",
+                "info": { "title": "Users", "version": "1" },
+                "components": {
+                    "schemas": {
+                        "Cat": { "type": "object", "properties": {} },
+                        "CatRef": {
+                            "description": "A cat wrapper.",
+                            "$ref": "#/components/schemas/Cat"
+                        },
+                        "UserId": { "description": "A user ID.", "type": "integer" },
+                        "User": {
+                            "type": "object",
+                            "properties": {
+                                "id": { "$ref": "#/components/schemas/UserId" },
+                                "parent": {
+                                    "description": "The parent.",
+                                    "$ref": "#/components/schemas/UserId"
+                                },
+                                "cat": { "$ref": "#/components/schemas/CatRef" }
+                            }
+                        }
+                    }
+                }
+            }))
+            .unwrap()
+        };
+
+        let mut inline = api();
+        inline_aliases(&mut inline);
+        let schemas = serde_json::to_value(&inline.components.unwrap().schemas).unwrap();
+
+        assert!(schemas.get("UserId").is_none());
+        assert!(schemas.get("CatRef").is_none());
+        assert_eq!(
+            schemas["User"]["properties"],
+            json!({
+                "id": { "description": "A user ID.", "type": "integer" },
+                "parent": { "description": "The parent.", "type": "integer" },
+                "cat": {
+                    "description": "A cat wrapper.",
+                    "$ref": "#/components/schemas/Cat"
+                }
+            })
+        );
+
+        let mut all_of = api();
+        wrap_alias_refs(&mut all_of);
+        let schemas = serde_json::to_value(&all_of.components.unwrap().schemas).unwrap();
+
+        assert_eq!(
+            schemas["CatRef"],
+            json!({
+                "description": "A cat wrapper.",
+                "allOf": [{ "$ref": "#/components/schemas/Cat" }]
+            })
+        );
+        assert_eq!(
+            schemas["User"]["properties"]["id"],
+            json!({ "$ref": "#/components/schemas/UserId" })
+        );
+        assert_eq!(
+            schemas["User"]["properties"]["parent"],
+            json!({
+                "description": "The parent.",
+                "allOf": [{ "$ref": "#/components/schemas/UserId" }]
+            })
+        );
+    }
+
+    #[test]
+    fn test_format_map() {
+        struct Decimal;
+
+        impl JsonSchema for Decimal {
+            fn schema_name() -> String {
+                "Decimal".into()
+            }
+
+            fn json_schema(_: &mut SchemaGenerator) -> Schema {
+                serde_json::from_value(json!({
+                    "type": "string",
+                    "pattern": "^-?[0-9]+(\\.[0-9]+)?$"
+                }))
+                .unwrap()
+            }
+
+            fn is_referenceable() -> bool {
+                false
+            }
+        }
+
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Order {
+            /// The total.
+            total: Decimal,
+            discount: Option<Decimal>,
+            note: String,
+        }
+
+        let settings = SchemaSettings::draft07();
+        let order = SchemaGenerator::new(settings.clone()).into_root_schema_for::<Order>();
+
+        let mut api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Orders", "version": "1" },
+            "components": { "schemas": { "Order": order.schema } }
+        }))
+        .unwrap();
+
+        PostProcess {
+            formats: FormatMap::new()
+                .map::<Decimal>(InstanceType::String, "decimal")
+                .resolve(&settings),
+            ..Default::default()
+        }
+        .apply(&mut api);
+
+        let schemas = serde_json::to_value(&api.components.unwrap().schemas).unwrap();
+        assert_eq!(
+            schemas["Order"]["properties"],
+            json!({
+                "total": {
+                    "description": "The total.",
+                    "type": "string",
+                    "format": "decimal"
+                },
+                "discount": { "type": ["string", "null"], "format": "decimal" },
+                "note": { "type": "string" }
+            })
+        );
+    }
+
+    #[test]
+    fn test_read_write_schemas() {
+        let mut api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Users", "version": "1" },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/User" }
+                                }
+                            }
+                        },
+                        "responses": {
+                            "200": {
+                                "description": "",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+                "/teams": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "",
+                                "content": {
+                                    "application/json": {
+                                        "schema": {
+                                            "type": "array",
+                                            "items": { "$ref": "#/components/schemas/Team" }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "title": "User",
+                        "type": "object",
+                        "required": ["id", "name", "password"],
+                        "properties": {
+                            "id": { "type": "integer", "readOnly": true },
+                            "name": { "type": "string" },
+                            "password": { "type": "string", "writeOnly": true }
+                        }
+                    },
+                    "Team": {
+                        "type": "object",
+                        "properties": {
+                            "members": {
+                                "type": "array",
+                                "items": { "$ref": "#/components/schemas/User" }
+                            }
+                        }
+                    },
+                    "Name": { "type": "string" }
+                }
+            }
+        }))
+        .unwrap();
+
+        split_read_write(&mut api);
+
+        let api = serde_json::to_value(&api).unwrap();
+        let schemas = &api["components"]["schemas"];
+
+        let mut names = schemas.as_object().unwrap().keys().collect::<Vec<_>>();
+        names.sort();
+        assert_eq!(
+            names,
+            ["Name", "TeamCreate", "TeamRead", "UserCreate", "UserRead"]
+        );
+        assert_eq!(
+            schemas["UserCreate"],
+            json!({
+                "title": "UserCreate",
+                "type": "object",
+                "required": ["name", "password"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "password": { "type": "string", "writeOnly": true }
+                }
+            })
+        );
+        assert_eq!(schemas["UserRead"]["required"], json!(["id", "name"]));
+        assert_eq!(
+            schemas["TeamRead"]["properties"]["members"]["items"],
+            json!({ "$ref": "#/components/schemas/UserRead" })
+        );
+
+        let users = &api["paths"]["/users"]["post"];
+        assert_eq!(
+            users["requestBody"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/UserCreate" })
+        );
+        assert_eq!(
+            users["responses"]["200"]["content"]["application/json"]["schema"],
+            json!({ "$ref": "#/components/schemas/UserRead" })
+        );
+        assert_eq!(
+            api["paths"]["/teams"]["get"]["responses"]["200"]["content"]["application/json"]
+                ["schema"]["items"],
+            json!({ "$ref": "#/components/schemas/TeamRead" })
+        );
+    }
+
+    #[test]
+    fn test_option_fields() {
+        let schema = || {
+            json!({
+                "type": "object",
+                "required": ["id"],
+                "properties": {
+                    "id": { "type": "integer" },
+                    "name": { "type": ["string", "null"] },
+                    "status": { "type": ["string", "null"], "enum": ["active", null] },
+                    "parent": {
+                        "description": "The parent.",
+                        "anyOf": [{ "$ref": "#/components/schemas/User" }, { "type": "null" }]
+                    }
+                }
+            })
+            .as_object()
+            .unwrap()
+            .clone()
+        };
+
+        let mut nullable = schema();
+        document_options(&mut nullable, OptionFields::Nullable);
+        assert_eq!(
+            nullable["required"],
+            json!(["id", "name", "parent", "status"])
+        );
+        assert_eq!(nullable["properties"], schema()["properties"]);
+
+        let mut optional = schema();
+        document_options(&mut optional, OptionFields::Optional);
+        assert_eq!(optional["required"], json!(["id"]));
+        assert_eq!(
+            optional["properties"],
+            json!({
+                "id": { "type": "integer" },
+                "name": { "type": "string" },
+                "status": { "type": "string", "enum": ["active"] },
+                "parent": {
+                    "description": "The parent.",
+                    "$ref": "#/components/schemas/User"
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_deduplicate_schemas() {
+        let page = |title: &str, item: &str| {
+            json!({
+                "title": title,
+                "type": "object",
+                "properties": {
+                    "items": {
+                        "type": "array",
+                        "items": { "$ref": format!("#/components/schemas/{item}") }
+                    }
+                }
+            })
+        };
+        let user = |title: &str| {
+            json!({
+                "title": title,
+                "type": "object",
+                "properties": { "name": { "type": "string" } }
+            })
+        };
+
+        let mut api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Users", "version": "1" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/UserPage" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": user("User"),
+                    "Customer": user("Customer"),
+                    "Team": { "type": "object" },
+                    "UserPage": page("UserPage", "User"),
+                    "CustomerPage": page("CustomerPage", "Customer")
+                }
+            }
+        }))
+        .unwrap();
+
+        // The components are sorted by the JSON object above.
+        assert_eq!(deduplicate_schemas(&mut api), 2);
+        let api = serde_json::to_value(api).unwrap();
+        let schemas = &api["components"]["schemas"];
+
+        assert_eq!(
+            schemas.as_object().unwrap().keys().collect::<Vec<_>>(),
+            ["Customer", "CustomerPage", "Team"]
+        );
+        assert_eq!(schemas["Customer"]["title"], "Customer");
+        assert_eq!(schemas["Customer"][SCHEMA_ALIASES], json!(["User"]));
+        assert_eq!(schemas["CustomerPage"][SCHEMA_ALIASES], json!(["UserPage"]));
+        assert_eq!(
+            schemas["CustomerPage"]["properties"]["items"]["items"]["$ref"],
+            "#/components/schemas/Customer"
+        );
+        assert_eq!(
+            api["paths"]["/users"]["get"]["responses"]["200"]["content"]["application/json"]
+                ["schema"]["$ref"],
+            "#/components/schemas/CustomerPage"
+        );
+    }
+
+    #[test]
+    fn test_strict_objects() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "labels": {
+                    "type": "object",
+                    "additionalProperties": { "type": "string" }
+                },
+                "address": {
+                    "type": "object",
+                    "properties": { "city": { "type": "string" } }
+                },
+                "pet": {
+                    "oneOf": [
+                        { "type": "object", "properties": { "name": { "type": "string" } } }
+                    ]
+                }
+            }
+        });
+
+        let mut visit = |schema: &mut Map<String, Value>, _: Option<&str>| {
+            deny_additional_properties(schema);
+        };
+        visit(schema.as_object_mut().unwrap(), None);
+        visit_subschemas_mut(&mut schema, &mut visit);
+
+        assert_eq!(schema["additionalProperties"], false);
+        assert_eq!(
+            schema["properties"]["labels"]["additionalProperties"],
+            json!({ "type": "string" })
+        );
+        assert_eq!(
+            schema["properties"]["address"]["additionalProperties"],
+            false
+        );
+        assert!(schema["properties"]["pet"]
+            .get("additionalProperties")
+            .is_none());
+        assert_eq!(
+            schema["properties"]["pet"]["oneOf"][0]["additionalProperties"],
+            false
+        );
+    }
+
+    #[test]
+    fn test_schema_docs() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Money {
+            amount: String,
+            /// The currency.
+            currency: String,
+        }
+
+        let mut api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Payments", "version": "1" },
+            "components": {
+                "schemas": {
+                    "Money": {
+                        "type": "object",
+                        "properties": {
+                            "amount": { "type": "string" },
+                            "currency": { "description": "The currency.", "type": "string" }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        SchemaDocs::new()
+            .describe::<Money>("An amount of money.")
+            .property::<Money>("amount", "The decimal amount.")
+            .property::<Money>("currency", "The ISO 4217 currency code.")
+            .apply(&mut api);
+
+        let schemas = serde_json::to_value(&api.components.unwrap().schemas).unwrap();
+        assert_eq!(schemas["Money"]["description"], "An amount of money.");
+        assert_eq!(
+            schemas["Money"]["properties"],
+            json!({
+                "amount": { "description": "The decimal amount.", "type": "string" },
+                "currency": { "description": "The currency.", "type": "string" }
+            })
+        );
+    }
+}
@@ -0,0 +1,327 @@
+//! Vendor extensions with hints for client generators.
+//!
+//! Client generators such as [openapi-generator](https://openapi-generator.tech)
+//! read vendor extensions to name the generated parameters, enum variants
+//! and fields. [`SdkHints`] derives these extensions from the names in the
+//! documentation, which are the names of the Rust types and fields unless
+//! they are renamed with `serde` or `schemars`.
+//!
+//! The hints are opt-in, either enabled for the generated documentation
+//! with [`gen::sdk_hints`](crate::gen::sdk_hints) or applied to any
+//! document with [`SdkHints::apply`]. Extensions that already exist
+//! in the documentation are not overwritten.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{openapi::OpenApi, sdk_hints::SdkHints};
+//!
+//! let hints = SdkHints::common()
+//!     // Name the generated Java models after the Rust types.
+//!     .schema_extension("x-class-name", |name| name.to_string());
+//!
+//! aide::gen::sdk_hints(hints.clone());
+//!
+//! // Or for an existing document.
+//! let mut api = OpenApi::default();
+//! hints.apply(&mut api);
+//! ```
+
+use std::{fmt, sync::Arc};
+
+use serde_json::{Map, Value};
+
+use crate::{
+    openapi::{OpenApi, Operation, ReferenceOr, SchemaObject},
+    schema::visit_schemas_mut,
+    util::{iter_operations_mut, pascal_case},
+};
+
+/// The extension with the name of the request body parameter.
+pub const REQUEST_BODY_NAME: &str = "x-codegen-request-body-name";
+
+/// The extension with the names of the variants of an enum.
+pub const ENUM_VARNAMES: &str = "x-enum-varnames";
+
+/// The extension with the names of the fields in Go clients.
+pub const GO_NAME: &str = "x-go-name";
+
+type Mapping = Arc<dyn Fn(&str) -> String + Send + Sync>;
+
+/// A configurable set of vendor extensions for client generators.
+///
+/// Every hint is a mapping from a name in the documentation
+/// to the value of the extension.
+#[derive(Clone, Default)]
+#[must_use]
+pub struct SdkHints {
+    request_body_name: Option<Mapping>,
+    enum_varnames: Option<Mapping>,
+    schema_extensions: Vec<(String, Mapping)>,
+    property_extensions: Vec<(String, Mapping)>,
+}
+
+impl SdkHints {
+    /// Hints without any extensions.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The commonly supported hints:
+    ///
+    /// - [`REQUEST_BODY_NAME`] with the body schema in `camelCase`,
+    /// - [`ENUM_VARNAMES`] with the variants in `PascalCase`,
+    /// - [`GO_NAME`] with the properties in `PascalCase`.
+    pub fn common() -> Self {
+        Self::new()
+            .request_body_name(camel_case)
+            .enum_varnames(pascal_case)
+            .property_extension(GO_NAME, pascal_case)
+    }
+
+    /// Add [`REQUEST_BODY_NAME`] to operations with a request body,
+    /// the mapping receives the name of the body schema, or `body`
+    /// if the schema is not a component.
+    pub fn request_body_name(
+        mut self,
+        mapping: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.request_body_name = Some(Arc::new(mapping));
+        self
+    }
+
+    /// Add [`ENUM_VARNAMES`] to enum schemas, the mapping
+    /// receives every enum value as a string.
+    pub fn enum_varnames(
+        mut self,
+        mapping: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.enum_varnames = Some(Arc::new(mapping));
+        self
+    }
+
+    /// Add an extension to every component schema,
+    /// the mapping receives the name of the schema.
+    pub fn schema_extension(
+        mut self,
+        extension: impl Into<String>,
+        mapping: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.schema_extensions
+            .push((extension.into(), Arc::new(mapping)));
+        self
+    }
+
+    /// Add an extension to every property of object schemas,
+    /// the mapping receives the name of the property.
+    pub fn property_extension(
+        mut self,
+        extension: impl Into<String>,
+        mapping: impl Fn(&str) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.property_extensions
+            .push((extension.into(), Arc::new(mapping)));
+        self
+    }
+
+    /// Add the extensions to the document.
+    pub fn apply(&self, api: &mut OpenApi) {
+        if let Some(mapping) = &self.request_body_name {
+            for (_, path) in api
+                .paths
+                .iter_mut()
+                .flat_map(|paths| paths.paths.iter_mut())
+            {
+                let ReferenceOr::Item(path) = path else {
+                    continue;
+                };
+
+                for (_, operation) in iter_operations_mut(path) {
+                    Self::add_request_body_name(operation, mapping);
+                }
+            }
+        }
+
+        visit_schemas_mut(api, &mut |schema, name| self.visit_schema(schema, name));
+    }
+
+    fn add_request_body_name(operation: &mut Operation, mapping: &Mapping) {
+        let Some(body) = operation
+            .request_body
+            .as_ref()
+            .and_then(ReferenceOr::as_item)
+        else {
+            return;
+        };
+
+        if operation.extensions.contains_key(REQUEST_BODY_NAME) {
+            return;
+        }
+
+        let name = body
+            .content
+            .values()
+            .find_map(|media| schema_name(media.schema.as_ref()?))
+            .unwrap_or("body");
+        operation
+            .extensions
+            .insert(REQUEST_BODY_NAME.into(), mapping(name).into());
+    }
+
+    fn visit_schema(&self, schema: &mut Map<String, Value>, name: Option<&str>) {
+        if let Some(name) = name {
+            for (extension, mapping) in &self.schema_extensions {
+                if !schema.contains_key(extension) {
+                    schema.insert(extension.clone(), mapping(name).into());
+                }
+            }
+        }
+
+        if let (Some(mapping), Some(Value::Array(values))) =
+            (&self.enum_varnames, schema.get("enum"))
+        {
+            if !schema.contains_key(ENUM_VARNAMES) {
+                let names = values
+                    .iter()
+                    .map(|value| match value {
+                        Value::String(value) => mapping(value),
+                        value => mapping(&value.to_string()),
+                    })
+                    .collect::<Vec<_>>();
+                schema.insert(ENUM_VARNAMES.into(), names.into());
+            }
+        }
+
+        if let Some(Value::Object(properties)) = schema.get_mut("properties") {
+            for (property, schema) in properties {
+                let Value::Object(schema) = schema else {
+                    continue;
+                };
+                for (extension, mapping) in &self.property_extensions {
+                    if !schema.contains_key(extension) {
+                        schema.insert(extension.clone(), mapping(property).into());
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SdkHints {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SdkHints")
+            .field("request_body_name", &self.request_body_name.is_some())
+            .field("enum_varnames", &self.enum_varnames.is_some())
+            .field(
+                "schema_extensions",
+                &self
+                    .schema_extensions
+                    .iter()
+                    .map(|(e, _)| e)
+                    .collect::<Vec<_>>(),
+            )
+            .field(
+                "property_extensions",
+                &self
+                    .property_extensions
+                    .iter()
+                    .map(|(e, _)| e)
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// The name of a referenced component schema.
+fn schema_name(schema: &SchemaObject) -> Option<&str> {
+    match &schema.json_schema {
+        schemars::schema::Schema::Object(object) => object
+            .reference
+            .as_deref()?
+            .strip_prefix("#/components/schemas/"),
+        schemars::schema::Schema::Bool(_) => None,
+    }
+}
+
+fn camel_case(s: &str) -> String {
+    let mut name = pascal_case(s);
+    if let Some(first) = name.get_mut(..1) {
+        first.make_ascii_lowercase();
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_common_hints() {
+        let mut api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Todos", "version": "1" },
+            "paths": {
+                "/todos": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "$ref": "#/components/schemas/NewTodo" }
+                                }
+                            }
+                        },
+                        "responses": {}
+                    },
+                    "put": {
+                        "x-codegen-request-body-name": "todos",
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": { "type": "array", "items": { "type": "string" } }
+                                }
+                            }
+                        },
+                        "responses": {}
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "NewTodo": {
+                        "type": "object",
+                        "properties": {
+                            "title": { "type": "string" },
+                            "due_date": { "type": "string", "x-go-name": "Due" },
+                            "state": {
+                                "type": "string",
+                                "enum": ["not_started", "in_progress", "done"]
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        SdkHints::common()
+            .schema_extension("x-class-name", str::to_string)
+            .apply(&mut api);
+
+        let api = serde_json::to_value(&api).unwrap();
+
+        let paths = &api["paths"]["/todos"];
+        assert_eq!(paths["post"][REQUEST_BODY_NAME], "newTodo");
+        assert_eq!(paths["put"][REQUEST_BODY_NAME], "todos");
+
+        let schema = &api["components"]["schemas"]["NewTodo"];
+        assert_eq!(schema["x-class-name"], "NewTodo");
+        assert_eq!(schema["properties"]["title"][GO_NAME], "Title");
+        assert_eq!(schema["properties"]["due_date"][GO_NAME], "Due");
+        assert_eq!(
+            schema["properties"]["state"][ENUM_VARNAMES],
+            json!(["NotStarted", "InProgress", "Done"])
+        );
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    http::{header::CONTENT_TYPE, HeaderMap, Request, StatusCode as HttpStatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    helpers::is_json_media_type,
+    openapi::{Example, MediaType, OpenApi, ReferenceOr, StatusCode},
+    util::iter_operations_mut,
+    validate::{buffer_body, Buffered, SpecValidator},
+};
+
+/// Records the requests and responses of documented
+/// operations to be merged into the documentation as examples.
+///
+/// The recorder is cheap to clone, all clones share the recorded examples.
+#[derive(Debug, Clone)]
+pub struct ExampleRecorder {
+    validator: Arc<SpecValidator>,
+    examples: Arc<Mutex<Vec<CapturedExample>>>,
+    body_limit: usize,
+}
+
+impl ExampleRecorder {
+    /// Create a recorder for the finished documentation.
+    #[must_use]
+    pub fn new(api: &OpenApi) -> Self {
+        Self {
+            validator: Arc::new(SpecValidator::new(api)),
+            examples: Arc::default(),
+            body_limit: 2 * 1024 * 1024,
+        }
+    }
+
+    /// The maximum size of the recorded bodies in bytes, 2 MB by default.
+    ///
+    /// Larger bodies are passed on unchanged without being recorded.
+    #[must_use]
+    pub fn body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = limit;
+        self
+    }
+
+    /// A layer that records the examples with this recorder.
+    #[must_use]
+    pub fn layer(&self) -> ExampleCaptureLayer {
+        ExampleCaptureLayer {
+            recorder: self.clone(),
+        }
+    }
+
+    /// The examples recorded so far.
+    ///
+    /// # Panics
+    ///
+    /// If a thread panicked while recording an example.
+    #[must_use]
+    pub fn examples(&self) -> Vec<CapturedExample> {
+        self.examples.lock().unwrap().clone()
+    }
+
+    /// Merge the examples recorded so far into the documentation,
+    /// see [`merge_examples`].
+    pub fn merge_into(&self, api: &mut OpenApi) {
+        merge_examples(api, &self.examples());
+    }
+
+    fn record(&self, example: CapturedExample) {
+        if let Ok(mut examples) = self.examples.lock() {
+            examples.push(example);
+        }
+    }
+}
+
+/// A request and response recorded by an [`ExampleRecorder`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedExample {
+    /// The lowercase method of the operation.
+    pub method: String,
+    /// The documented path of the operation.
+    pub path: String,
+    /// The ID of the operation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// The status code of the response.
+    pub status: u16,
+    /// The content type and JSON body of the request.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<(String, Value)>,
+    /// The content type and JSON body of the response.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<(String, Value)>,
+}
+
+impl CapturedExample {
+    /// The name of the example in the documentation.
+    #[must_use]
+    pub fn name(&self) -> String {
+        match &self.operation_id {
+            Some(id) => format!("{id}_{}", self.status),
+            None => format!("{}_{}", self.method, self.status),
+        }
+    }
+}
+
+/// Merge captured examples into the documentation.
+///
+/// The examples are added to the request bodies and the responses
+/// of the documented status codes (or the default response) of
+/// the operations, named by the operation and the status code.
+/// Existing examples with the same name are not replaced, so the
+/// first captured example for an operation and status is used.
+pub fn merge_examples(api: &mut OpenApi, examples: &[CapturedExample]) {
+    let Some(paths) = &mut api.paths else {
+        return;
+    };
+
+    for captured in examples {
+        let Some(ReferenceOr::Item(path)) = paths.paths.get_mut(&captured.path) else {
+            continue;
+        };
+        let Some((_, operation)) =
+            iter_operations_mut(path).find(|(method, _)| *method == captured.method)
+        else {
+            continue;
+        };
+
+        if let Some((content_type, value)) = &captured.request {
+            if let Some(media) = operation
+                .request_body
+                .as_mut()
+                .and_then(ReferenceOr::as_item_mut)
+                .and_then(|body| body.content.get_mut(content_type))
+            {
+                add_example(media, captured, value);
+            }
+        }
+
+        if let Some((content_type, value)) = &captured.response {
+            let Some(responses) = &mut operation.responses else {
+                continue;
+            };
+
+            let response = match responses
+                .responses
+                .get_mut(&StatusCode::Code(captured.status))
+            {
+                Some(response) => Some(response),
+                None => responses.default.as_mut(),
+            };
+
+            if let Some(media) = response
+                .and_then(ReferenceOr::as_item_mut)
+                .and_then(|res| res.content.get_mut(content_type))
+            {
+                add_example(media, captured, value);
+            }
+        }
+    }
+}
+
+fn add_example(media: &mut MediaType, captured: &CapturedExample, value: &Value) {
+    // Examples are mutually exclusive with the example field.
+    if media.example.is_some() {
+        return;
+    }
+
+    media.examples.entry(captured.name()).or_insert_with(|| {
+        ReferenceOr::Item(Example {
+            summary: Some(format!(
+                "{} {} ({})",
+                captured.method.to_ascii_uppercase(),
+                captured.path,
+                captured.status
+            )),
+            value: Some(value.clone()),
+            ..Default::default()
+        })
+    });
+}
+
+/// A layer that records the requests and responses
+/// of documented operations, see [`ExampleRecorder`].
+#[derive(Debug, Clone)]
+pub struct ExampleCaptureLayer {
+    recorder: ExampleRecorder,
+}
+
+impl<S> Layer<S> for ExampleCaptureLayer {
+    type Service = ExampleCapture<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ExampleCapture {
+            inner,
+            recorder: self.recorder.clone(),
+        }
+    }
+}
+
+/// The service created by [`ExampleCaptureLayer`].
+#[derive(Debug, Clone)]
+pub struct ExampleCapture<S> {
+    inner: S,
+    recorder: ExampleRecorder,
+}
+
+impl<S> Service<Request<Body>> for ExampleCapture<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        // The clone might not be ready, use the one that was polled.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let recorder = self.recorder.clone();
+
+        Box::pin(async move {
+            let method = req.method().as_str().to_ascii_lowercase();
+
+            let Some((operation, _)) = recorder.validator.operation(&method, req.uri().path())
+            else {
+                return inner.call(req).await;
+            };
+
+            let mut captured = CapturedExample {
+                method,
+                path: operation.path().into(),
+                operation_id: operation.operation_id().map(Into::into),
+                status: 0,
+                request: None,
+                response: None,
+            };
+
+            let (parts, body) = req.into_parts();
+            let body = match json_content_type(&parts.headers) {
+                Some(content_type) => {
+                    match buffer_body(&parts.headers, body, recorder.body_limit).await {
+                        Ok(Buffered::Complete(bytes)) => {
+                            captured.request = serde_json::from_slice(&bytes)
+                                .ok()
+                                .map(|value| (content_type, value));
+                            Body::from(bytes)
+                        }
+                        // Larger bodies are passed on without being recorded.
+                        Ok(Buffered::TooLarge(body)) => body,
+                        Err(error) => {
+                            tracing::warn!(%error, "failed to read the request body for an example");
+                            return Ok((
+                                HttpStatusCode::BAD_REQUEST,
+                                "failed to read the request body",
+                            )
+                                .into_response());
+                        }
+                    }
+                }
+                None => body,
+            };
+
+            let res = inner.call(Request::from_parts(parts, body)).await?;
+            captured.status = res.status().as_u16();
+
+            let Some(content_type) = json_content_type(res.headers()) else {
+                recorder.record(captured);
+                return Ok(res);
+            };
+
+            let (parts, body) = res.into_parts();
+            let body = match buffer_body(&parts.headers, body, recorder.body_limit).await {
+                Ok(Buffered::Complete(bytes)) => {
+                    captured.response = serde_json::from_slice(&bytes)
+                        .ok()
+                        .map(|value| (content_type, value));
+                    Body::from(bytes)
+                }
+                Ok(Buffered::TooLarge(body)) => body,
+                Err(error) => {
+                    // The body is partially consumed, so it cannot be returned.
+                    tracing::warn!(%error, "failed to read the response body for an example");
+                    return Ok((
+                        HttpStatusCode::INTERNAL_SERVER_ERROR,
+                        "failed to read the response body",
+                    )
+                        .into_response());
+                }
+            };
+            recorder.record(captured);
+
+            Ok(Response::from_parts(parts, body))
+        })
+    }
+}
+
+/// The essence of JSON content types without parameters,
+/// as used in the documentation.
+fn json_content_type(headers: &HeaderMap) -> Option<String> {
+    let content_type = headers.get(CONTENT_TYPE)?.to_str().ok()?;
+    let essence = content_type.split(';').next()?.trim();
+    is_json_media_type(essence).then(|| essence.to_string())
+}
@@ -0,0 +1,282 @@
+use std::{
+    fmt::{self, Write},
+    marker::PhantomData,
+};
+
+use axum::{
+    body::{to_bytes, Body, Bytes},
+    http::{header::CONTENT_TYPE, HeaderMap, Request, StatusCode},
+    response::Response,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use thiserror::Error;
+use tower_service::Service;
+
+use crate::axum::typed::TypedOperation;
+
+/// Calls [`TypedOperation`]s of a service in-process.
+///
+/// The parameters and bodies are checked by the compiler
+/// against the declared types of the operations.
+///
+/// # Examples
+///
+/// ```ignore
+/// let mut client = TypedClient::new(app);
+///
+/// let todo = client
+///     .call::<GetTodo>(&TodoPath { id: 1 }, &())
+///     .await
+///     .unwrap()
+///     .into_success();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TypedClient<S> {
+    service: S,
+}
+
+impl<S> TypedClient<S>
+where
+    S: Service<Request<Body>, Response = Response>,
+    S::Error: fmt::Display,
+{
+    /// Create a client for the service, usually an [`axum::Router`].
+    pub fn new(service: S) -> Self {
+        Self { service }
+    }
+
+    /// Call the operation with the given parameters and body.
+    ///
+    /// # Errors
+    ///
+    /// If the request cannot be built from the parameters
+    /// and the body, or the service fails.
+    pub async fn call<Op: TypedOperation>(
+        &mut self,
+        params: &Op::Params,
+        body: &Op::Body,
+    ) -> Result<TypedResponse<Op::Response>, TypedClientError> {
+        let uri = operation_uri(Op::PATH, serde_json::to_value(params)?)?;
+
+        let mut request = Request::builder().method(Op::METHOD).uri(uri);
+
+        let body = match serde_json::to_value(body)? {
+            Value::Null => Body::empty(),
+            body => {
+                request = request.header(CONTENT_TYPE, "application/json");
+                Body::from(body.to_string())
+            }
+        };
+
+        let request = request
+            .body(body)
+            .map_err(|error| TypedClientError::Request(error.to_string()))?;
+
+        std::future::poll_fn(|cx| self.service.poll_ready(cx))
+            .await
+            .map_err(|error| TypedClientError::Service(error.to_string()))?;
+
+        let res = self
+            .service
+            .call(request)
+            .await
+            .map_err(|error| TypedClientError::Service(error.to_string()))?;
+
+        let (parts, body) = res.into_parts();
+        let body = to_bytes(body, usize::MAX)
+            .await
+            .map_err(|error| TypedClientError::Service(error.to_string()))?;
+
+        Ok(TypedResponse {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+            response: PhantomData,
+        })
+    }
+}
+
+/// A response of a [`TypedOperation`].
+#[derive(Debug, Clone)]
+pub struct TypedResponse<T> {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    response: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedResponse<T> {
+    /// The status code of the response.
+    #[must_use]
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// The headers of the response.
+    #[must_use]
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// The raw body of the response.
+    #[must_use]
+    pub fn bytes(&self) -> &Bytes {
+        &self.body
+    }
+}
+
+impl<T: DeserializeOwned> TypedResponse<T> {
+    /// Deserialize the body of the response as the declared response type.
+    ///
+    /// # Errors
+    ///
+    /// If the body is not a valid JSON value of the declared type.
+    pub fn json(&self) -> Result<T, TypedClientError> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+
+    /// The deserialized body of a successful response.
+    ///
+    /// # Panics
+    ///
+    /// If the response is not successful or the body
+    /// is not a valid JSON value of the declared type.
+    #[track_caller]
+    pub fn into_success(self) -> T {
+        assert!(
+            self.status.is_success(),
+            "the operation responded with {}: {}",
+            self.status,
+            String::from_utf8_lossy(&self.body)
+        );
+
+        match self.json() {
+            Ok(value) => value,
+            Err(error) => panic!("invalid response body: {error}"),
+        }
+    }
+}
+
+/// An error of a [`TypedClient`].
+#[allow(missing_docs)]
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum TypedClientError {
+    #[error("the parameters must serialize to a struct or map")]
+    InvalidParams,
+    #[error(r#"the path parameter "{0}" is missing"#)]
+    MissingPathParam(String),
+    #[error("invalid request: {0}")]
+    Request(String),
+    #[error("the service failed: {0}")]
+    Service(String),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error(transparent)]
+    Query(#[from] serde_urlencoded::ser::Error),
+}
+
+/// Interpolate the parameters into an axum path, the
+/// remaining parameters are added to the query.
+fn operation_uri(path: &str, params: Value) -> Result<String, TypedClientError> {
+    let mut params = match params {
+        Value::Object(params) => params,
+        Value::Null => serde_json::Map::new(),
+        _ => return Err(TypedClientError::InvalidParams),
+    };
+
+    let mut uri = path
+        .split('/')
+        .map(|segment| match segment.strip_prefix([':', '*']) {
+            Some(name) => params
+                .remove(name)
+                .map(|value| encode_path(&param_string(&value), segment.starts_with('*')))
+                .ok_or_else(|| TypedClientError::MissingPathParam(name.into())),
+            None => Ok(segment.to_string()),
+        })
+        .collect::<Result<Vec<_>, _>>()?
+        .join("/");
+
+    let mut query = Vec::new();
+    for (name, value) in params {
+        match value {
+            Value::Null => {}
+            Value::Array(values) => {
+                query.extend(values.iter().map(|v| (name.clone(), param_string(v))));
+            }
+            value => query.push((name, param_string(&value))),
+        }
+    }
+
+    if !query.is_empty() {
+        uri.push('?');
+        uri.push_str(&serde_urlencoded::to_string(&query)?);
+    }
+
+    Ok(uri)
+}
+
+fn param_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Percent-encode a path parameter, wildcards can contain slashes.
+fn encode_path(value: &str, wildcard: bool) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(char::from(byte));
+            }
+            b'/' if wildcard => encoded.push('/'),
+            byte => {
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{encode_path, operation_uri, TypedClientError};
+
+    #[test]
+    fn test_operation_uri() {
+        assert_eq!(
+            operation_uri("/users/:id/todos", json!({ "id": 3, "done": true })).unwrap(),
+            "/users/3/todos?done=true"
+        );
+        assert_eq!(
+            operation_uri("/files/*path", json!({ "path": "docs/read me.md" })).unwrap(),
+            "/files/docs/read%20me.md"
+        );
+        assert_eq!(
+            operation_uri("/todos", json!({ "tag": ["a", "b c"], "page": null })).unwrap(),
+            "/todos?tag=a&tag=b+c"
+        );
+        assert_eq!(operation_uri("/todos", json!(null)).unwrap(), "/todos");
+
+        assert!(matches!(
+            operation_uri("/users/:id", json!({})),
+            Err(TypedClientError::MissingPathParam(name)) if name == "id"
+        ));
+        assert!(matches!(
+            operation_uri("/users/:id", json!([1])),
+            Err(TypedClientError::InvalidParams)
+        ));
+    }
+
+    #[test]
+    fn test_encode_path() {
+        assert_eq!(encode_path("a/b?c#d", false), "a%2Fb%3Fc%23d");
+        assert_eq!(encode_path("a/b?c#d", true), "a/b%3Fc%23d");
+        assert_eq!(encode_path("ünï-code_~.", false), "%C3%BCn%C3%AF-code_~.");
+    }
+}
@@ -0,0 +1,663 @@
+//! Contract tests for documented operations.
+//!
+//! [`ContractTester`] sends a request to every documented operation of
+//! a service, using the documented examples or example values generated
+//! from the schemas, and checks that the responses match the documented
+//! status codes and schemas.
+//!
+//! The result is a [`ContractReport`] with the outcome of every operation,
+//! which can be printed or asserted on in CI.
+//!
+//! # Examples
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn handlers_match_documentation() {
+//!     let mut api = OpenApi::default();
+//!     let app = app().finish_api(&mut api).with_state(AppState::default());
+//!
+//!     ContractTester::new(&api, app)
+//!         .path_param("id", "00000000-0000-0000-0000-000000000000")
+//!         .skip("delete_everything")
+//!         .run()
+//!         .await
+//!         .assert_success();
+//! }
+//! ```
+//!
+//! # Captured examples
+//!
+//! An [`ExampleRecorder`] records the requests and responses of the
+//! documented operations during integration tests, which can then be
+//! merged into the documentation as named examples, so that it shows
+//! realistic payloads:
+//!
+//! ```ignore
+//! #[tokio::test]
+//! async fn record_examples() {
+//!     let mut api = OpenApi::default();
+//!     let app = app().finish_api(&mut api).with_state(AppState::default());
+//!
+//!     let recorder = ExampleRecorder::new(&api);
+//!     let app = app.layer(recorder.layer());
+//!
+//!     // Run the integration tests against `app`...
+//!
+//!     recorder.merge_into(&mut api);
+//!     std::fs::write("openapi.json", serde_json::to_string_pretty(&api).unwrap()).unwrap();
+//! }
+//! ```
+//!
+//! The [`CapturedExample`]s can also be serialized to
+//! collect them from multiple tests and merged later
+//! with [`merge_examples`].
+//!
+//! # Typed clients
+//!
+//! A [`TypedClient`] calls operations declared as
+//! [`TypedOperation`](crate::axum::typed::TypedOperation)s
+//! in-process, with the parameter, body and response
+//! types checked by the compiler.
+
+use std::fmt;
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, Request},
+    response::Response,
+};
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::Value;
+use tower_service::Service;
+
+use crate::{
+    example,
+    helpers::is_json_media_type,
+    openapi::{OpenApi, Operation, Parameter, ReferenceOr},
+    validate::{SpecValidator, Violation, ViolationLocation},
+};
+
+mod capture;
+mod client;
+
+pub use capture::{
+    merge_examples, CapturedExample, ExampleCapture, ExampleCaptureLayer, ExampleRecorder,
+};
+pub use client::{TypedClient, TypedClientError, TypedResponse};
+
+/// Tests a service against its documentation.
+pub struct ContractTester<S> {
+    service: S,
+    api: OpenApi,
+    validator: SpecValidator,
+    path_params: IndexMap<String, String>,
+    headers: HeaderMap,
+    skipped: Vec<String>,
+}
+
+impl<S> fmt::Debug for ContractTester<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ContractTester")
+            .field("path_params", &self.path_params)
+            .field("headers", &self.headers)
+            .field("skipped", &self.skipped)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S> ContractTester<S>
+where
+    S: Service<Request<Body>, Response = Response>,
+    S::Error: fmt::Display,
+{
+    /// Create a tester for the service with the finished documentation.
+    #[must_use]
+    pub fn new(api: &OpenApi, service: S) -> Self {
+        Self {
+            service,
+            api: api.clone(),
+            validator: SpecValidator::new(api),
+            path_params: IndexMap::new(),
+            headers: HeaderMap::new(),
+            skipped: Vec::new(),
+        }
+    }
+
+    /// Use the given value for a path parameter in all
+    /// operations instead of the example value.
+    #[must_use]
+    pub fn path_param(mut self, name: &str, value: &str) -> Self {
+        self.path_params.insert(name.into(), value.into());
+        self
+    }
+
+    /// Send the header with every request, e.g. for authentication.
+    #[must_use]
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.insert(name, value);
+        self
+    }
+
+    /// Skip the operation with the given ID.
+    #[must_use]
+    pub fn skip(mut self, operation_id: &str) -> Self {
+        self.skipped.push(operation_id.into());
+        self
+    }
+
+    /// Test all documented operations.
+    pub async fn run(&mut self) -> ContractReport {
+        let schemas = example::component_schemas(&self.api);
+
+        let mut requests = Vec::new();
+
+        for (path, item) in self.api.paths.iter().flat_map(|paths| paths.iter()) {
+            let Some(item) = item.as_item() else {
+                continue;
+            };
+
+            for (method, operation) in item.iter() {
+                if operation
+                    .operation_id
+                    .as_ref()
+                    .is_some_and(|id| self.skipped.contains(id))
+                {
+                    continue;
+                }
+
+                let request = build_request(
+                    method,
+                    path,
+                    item.parameters.iter().chain(&operation.parameters),
+                    operation,
+                    &self.path_params,
+                    &self.headers,
+                    &schemas,
+                );
+
+                requests.push((
+                    OperationReport {
+                        method: method.into(),
+                        path: path.clone(),
+                        operation_id: operation.operation_id.clone(),
+                        status: None,
+                        violations: Vec::new(),
+                        error: None,
+                    },
+                    request,
+                ));
+            }
+        }
+
+        let mut operations = Vec::with_capacity(requests.len());
+
+        for (mut report, request) in requests {
+            match request {
+                Ok(request) => self.test(&mut report, request).await,
+                Err(error) => report.error = Some(error),
+            }
+            operations.push(report);
+        }
+
+        ContractReport { operations }
+    }
+
+    async fn test(&mut self, report: &mut OperationReport, request: Request<Body>) {
+        let path = request.uri().path().to_string();
+
+        if let Err(error) = std::future::poll_fn(|cx| self.service.poll_ready(cx)).await {
+            report.error = Some(error.to_string());
+            return;
+        }
+
+        let res = match self.service.call(request).await {
+            Ok(res) => res,
+            Err(error) => {
+                report.error = Some(error.to_string());
+                return;
+            }
+        };
+
+        let status = res.status().as_u16();
+        report.status = Some(status);
+
+        let content_type = res
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|h| h.to_str().ok())
+            .map(ToString::to_string);
+
+        let body = match to_bytes(res.into_body(), usize::MAX).await {
+            Ok(body) => body,
+            Err(error) => {
+                report.error = Some(error.to_string());
+                return;
+            }
+        };
+
+        if status >= 500 {
+            report.violations.push(Violation::new(
+                ViolationLocation::Response,
+                format!("the server responded with {status}"),
+            ));
+        }
+
+        if let Some((operation, _)) = self.validator.operation(&report.method, &path) {
+            report.violations.extend(operation.validate_response(
+                status,
+                content_type.as_deref(),
+                &body,
+            ));
+        }
+    }
+}
+
+/// The result of a contract test.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ContractReport {
+    /// The results of the tested operations.
+    pub operations: Vec<OperationReport>,
+}
+
+impl ContractReport {
+    /// Whether all operations passed.
+    #[must_use]
+    pub fn is_success(&self) -> bool {
+        self.operations.iter().all(OperationReport::passed)
+    }
+
+    /// The operations that failed.
+    pub fn failures(&self) -> impl Iterator<Item = &OperationReport> {
+        self.operations.iter().filter(|op| !op.passed())
+    }
+
+    /// Panic with the report if any of the operations failed.
+    ///
+    /// # Panics
+    ///
+    /// If any of the operations failed.
+    #[track_caller]
+    pub fn assert_success(&self) {
+        assert!(self.is_success(), "contract test failed:\n{self}");
+    }
+}
+
+impl fmt::Display for ContractReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for op in &self.operations {
+            writeln!(f, "{op}")?;
+        }
+
+        let failed = self.failures().count();
+        write!(
+            f,
+            "{} passed, {failed} failed",
+            self.operations.len() - failed
+        )
+    }
+}
+
+/// The result of a contract test of a single operation.
+#[derive(Debug, Clone, Serialize)]
+pub struct OperationReport {
+    /// The lowercase method of the operation.
+    pub method: String,
+    /// The documented path of the operation.
+    pub path: String,
+    /// The ID of the operation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation_id: Option<String>,
+    /// The status code of the response.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// The violations of the documentation.
+    pub violations: Vec<Violation>,
+    /// An error that prevented the test.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl OperationReport {
+    /// Whether the operation passed.
+    #[must_use]
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.violations.is_empty()
+    }
+}
+
+impl fmt::Display for OperationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outcome = if self.passed() { "PASS" } else { "FAIL" };
+        write!(
+            f,
+            "{outcome} {} {}",
+            self.method.to_ascii_uppercase(),
+            self.path
+        )?;
+        if let Some(id) = &self.operation_id {
+            write!(f, " ({id})")?;
+        }
+        if let Some(status) = self.status {
+            write!(f, " -> {status}")?;
+        }
+        if let Some(error) = &self.error {
+            write!(f, "\n    error: {error}")?;
+        }
+        for violation in &self.violations {
+            write!(f, "\n    {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+fn build_request<'p>(
+    method: &str,
+    path: &str,
+    parameters: impl Iterator<Item = &'p ReferenceOr<Parameter>>,
+    operation: &Operation,
+    path_params: &IndexMap<String, String>,
+    headers: &HeaderMap,
+    schemas: &Value,
+) -> Result<Request<Body>, String> {
+    let mut uri = path.to_string();
+    let mut query = Vec::new();
+    let mut request = Request::builder().method(method.to_ascii_uppercase().as_str());
+
+    for param in parameters.filter_map(ReferenceOr::as_item) {
+        let data = param.parameter_data_ref();
+
+        match param {
+            Parameter::Path { .. } => {
+                let value = match path_params.get(&data.name) {
+                    Some(value) => value.clone(),
+                    None => to_param_string(&example::parameter_example(data, schemas)),
+                };
+                uri = uri
+                    .replace(&format!("{{{}}}", data.name), &value)
+                    .replace(&format!("{{{}+}}", data.name), &value);
+            }
+            Parameter::Query { .. } if data.required => {
+                match example::parameter_example(data, schemas) {
+                    Value::Array(items) => {
+                        query.extend(
+                            items
+                                .iter()
+                                .map(|v| (data.name.clone(), to_param_string(v))),
+                        );
+                    }
+                    value => query.push((data.name.clone(), to_param_string(&value))),
+                }
+            }
+            Parameter::Header { .. } if data.required && !headers.contains_key(&data.name) => {
+                request = request.header(
+                    data.name.as_str(),
+                    to_param_string(&example::parameter_example(data, schemas)),
+                );
+            }
+            Parameter::Cookie { .. } if data.required => {
+                request = request.header(
+                    "cookie",
+                    format!(
+                        "{}={}",
+                        data.name,
+                        to_param_string(&example::parameter_example(data, schemas))
+                    ),
+                );
+            }
+            _ => {}
+        }
+    }
+
+    if !query.is_empty() {
+        uri.push('?');
+        uri.push_str(&serde_urlencoded::to_string(&query).map_err(|e| e.to_string())?);
+    }
+
+    for (name, value) in headers {
+        request = request.header(name, value);
+    }
+
+    let body = operation
+        .request_body
+        .as_ref()
+        .and_then(ReferenceOr::as_item)
+        .and_then(|body| {
+            body.content
+                .iter()
+                .find(|(media_type, _)| is_json_media_type(media_type))
+        });
+
+    let body = match body {
+        Some((media_type, media)) => {
+            request = request.header(CONTENT_TYPE, media_type.as_str());
+            Body::from(example::media_example(media, schemas).to_string())
+        }
+        None => Body::empty(),
+    };
+
+    request.uri(uri).body(body).map_err(|e| e.to_string())
+}
+
+fn to_param_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items
+            .iter()
+            .map(to_param_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::{Path, Query},
+        http::{Request, StatusCode},
+        Json,
+    };
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use tower_service::Service;
+
+    use super::{ContractTester, ExampleRecorder, TypedClient};
+    use crate::{
+        axum::{
+            routing::{delete_with, get, post, post_with},
+            ApiRouter,
+        },
+        openapi::OpenApi,
+    };
+
+    #[derive(Deserialize, Serialize, JsonSchema)]
+    struct Todo {
+        id: u32,
+        title: String,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct TodoPath {
+        id: u32,
+    }
+
+    async fn create_todo(Json(todo): Json<Todo>) -> Json<Todo> {
+        Json(todo)
+    }
+
+    async fn get_todo(Path(path): Path<TodoPath>) -> Json<Todo> {
+        Json(Todo {
+            id: path.id,
+            title: String::new(),
+        })
+    }
+
+    async fn delete_todo() -> Result<Json<Todo>, StatusCode> {
+        Err(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+
+    #[tokio::test]
+    async fn test_contract() {
+        let mut api = OpenApi::default();
+        let app: axum::Router = ApiRouter::new()
+            .api_route("/todos", post(create_todo))
+            .api_route("/todos/:id", get(get_todo))
+            .api_route(
+                "/todos/:id/delete",
+                delete_with(delete_todo, |op| op.id("delete_todo")),
+            )
+            .finish_api(&mut api);
+
+        let report = ContractTester::new(&api, app.clone())
+            .path_param("id", "3")
+            .run()
+            .await;
+
+        assert_eq!(report.operations.len(), 3);
+        assert!(report.operations[0].passed(), "{report}");
+        assert!(report.operations[1].passed(), "{report}");
+        assert_eq!(report.failures().count(), 1);
+        assert_eq!(report.operations[2].status, Some(500));
+
+        ContractTester::new(&api, app)
+            .skip("delete_todo")
+            .run()
+            .await
+            .assert_success();
+    }
+
+    #[tokio::test]
+    async fn test_capture_examples() {
+        let mut api = OpenApi::default();
+        let app: axum::Router = ApiRouter::new()
+            .api_route("/todos", post_with(create_todo, |op| op.id("create_todo")))
+            .finish_api(&mut api);
+
+        let recorder = ExampleRecorder::new(&api);
+        let mut app = app.layer(recorder.layer());
+
+        for title in ["first", "second"] {
+            let res = app
+                .call(
+                    Request::post("/todos")
+                        .header("content-type", "application/json")
+                        .body(Body::from(format!(r#"{{"id":1,"title":"{title}"}}"#)))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(res.status(), StatusCode::OK);
+        }
+
+        assert_eq!(recorder.examples().len(), 2);
+        recorder.merge_into(&mut api);
+
+        let operation = api.paths.as_ref().unwrap().paths["/todos"]
+            .as_item()
+            .unwrap()
+            .post
+            .as_ref()
+            .unwrap();
+        let request = &operation
+            .request_body
+            .as_ref()
+            .unwrap()
+            .as_item()
+            .unwrap()
+            .content["application/json"];
+        let example = request.examples["create_todo_200"].as_item().unwrap();
+        assert_eq!(example.value.as_ref().unwrap()["title"], "first");
+    }
+
+    #[tokio::test]
+    async fn test_capture_body_limit() {
+        let mut api = OpenApi::default();
+        let app: axum::Router = ApiRouter::new()
+            .api_route("/todos", post_with(create_todo, |op| op.id("create_todo")))
+            .finish_api(&mut api);
+
+        let recorder = ExampleRecorder::new(&api).body_limit(32);
+        let mut app = app.layer(recorder.layer());
+
+        let body = format!(r#"{{"id":1,"title":"{}"}}"#, "a".repeat(64));
+        let res = app
+            .call(
+                Request::post("/todos")
+                    .header("content-type", "application/json")
+                    .header("content-length", body.len())
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        // The oversize bodies are passed on, but not recorded.
+        assert_eq!(res.status(), StatusCode::OK);
+        let res = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Todo>(&res).unwrap().title.len(),
+            64
+        );
+
+        let examples = recorder.examples();
+        assert_eq!(examples.len(), 1);
+        assert_eq!(examples[0].status, 200);
+        assert!(examples[0].request.is_none());
+        assert!(examples[0].response.is_none());
+    }
+
+    #[derive(Deserialize, Serialize, JsonSchema)]
+    struct GetTodoParams {
+        id: u32,
+        #[serde(default)]
+        prefix: Option<String>,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct GetTodoQuery {
+        prefix: Option<String>,
+    }
+
+    crate::typed_operation! {
+        struct GetTodo: GET "/todos/:id" {
+            params: GetTodoParams,
+            body: (),
+            response: Todo,
+        }
+    }
+
+    async fn get_prefixed_todo(
+        Path(id): Path<u32>,
+        Query(query): Query<GetTodoQuery>,
+    ) -> Json<Todo> {
+        Json(Todo {
+            id,
+            title: query.prefix.unwrap_or_default(),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_typed_client() {
+        let app: axum::Router = ApiRouter::new()
+            .typed_api_route::<GetTodo, _, _, _, _>(get_prefixed_todo)
+            .into();
+        let mut client = TypedClient::new(app);
+
+        let todo = client
+            .call::<GetTodo>(
+                &GetTodoParams {
+                    id: 3,
+                    prefix: Some("a b".into()),
+                },
+                &(),
+            )
+            .await
+            .unwrap()
+            .into_success();
+
+        assert_eq!(todo.id, 3);
+        assert_eq!(todo.title, "a b");
+    }
+}
@@ -46,20 +46,60 @@
 //! ```
 //!
 
-use std::{any::type_name, marker::PhantomData};
+use std::{any::type_name, marker::PhantomData, time::Duration};
 
 use crate::{
     gen::GenContext,
     openapi::{
-        Components, Contact, Info, License, OpenApi, Operation, Parameter, PathItem, ReferenceOr,
-        Response, SecurityScheme, Server, StatusCode, Tag,
+        Components, Contact, Example, Info, License, OpenApi, Operation, Parameter, ParameterStyle,
+        PathItem, ReferenceOr, Response, Responses, SecurityRequirement, SecurityScheme, Server,
+        StatusCode, Tag,
     },
     OperationInput,
 };
 use indexmap::IndexMap;
+use schemars::{
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
 use serde::Serialize;
 
-use crate::{error::Error, gen::in_context, operation::OperationOutput, util::iter_operations_mut};
+use crate::{
+    error::Error,
+    gen::in_context,
+    helpers::{
+        add_response_header, add_response_if_missing,
+        code_samples::{add_code_sample, add_request_samples, CURL},
+        dictionary::ParameterDictionary,
+        envelope, is_success,
+        layers::{body_limit_response, timeout_response},
+        response_header,
+        servers::{EnvServers, ServerResolver},
+    },
+    operation::{header_parameter, OperationOutput},
+    patch::{JsonPatch, PatchError},
+    util::iter_operations_mut,
+    visibility::{is_hidden, set_hidden, ApiVisibility},
+};
+
+/// The extension of operations that holds their
+/// timeout in milliseconds, see [`TransformOperation::timeout`].
+pub const TIMEOUT_MS: &str = "x-timeout-ms";
+
+/// The extension of operations and request bodies that holds the
+/// maximum size of the request body in bytes,
+/// see [`TransformOperation::max_body_size`].
+pub const MAX_BODY_SIZE: &str = "x-max-body-size";
+
+/// The extension of the document that groups tags in the navigation
+/// of documentation UIs, see [`TransformOpenApi::tag_group`].
+pub const TAG_GROUPS: &str = "x-tagGroups";
+
+/// Whether the operation can be called without authentication,
+/// i.e. it has an empty security requirement.
+pub(crate) fn allows_anonymous(operation: &Operation) -> bool {
+    operation.security.iter().any(IndexMap::is_empty)
+}
 
 /// A transform helper that wraps [`OpenApi`].
 #[must_use]
@@ -136,6 +176,68 @@ impl<'t> TransformOpenApi<'t> {
         self
     }
 
+    /// Group tags under a heading in the navigation of documentation UIs,
+    /// with the `x-tagGroups` extension read by Redoc and Scalar.
+    ///
+    /// Groups are listed in the order they are first added, adding tags
+    /// to an existing group appends them. Tags that are not declared
+    /// yet are added to the documentation, as tags that are not part
+    /// of any group are hidden once groups are used.
+    ///
+    /// ```
+    /// # use aide::transform::TransformOpenApi;
+    /// fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+    ///     api.tag_group("Accounts", ["users", "orgs"])
+    ///         .tag_group("Content", ["todos"])
+    /// }
+    /// ```
+    #[tracing::instrument(skip_all)]
+    pub fn tag_group<'a, I>(self, name: &str, tags: I) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let tags = tags.into_iter().collect::<Vec<_>>();
+
+        for tag in &tags {
+            if !self.api.tags.iter().any(|t| t.name == *tag) {
+                self.api.tags.push(Tag {
+                    name: (*tag).into(),
+                    ..Default::default()
+                });
+            }
+        }
+
+        let groups = self
+            .api
+            .extensions
+            .entry(TAG_GROUPS.into())
+            .or_insert_with(|| serde_json::Value::Array(Vec::new()));
+        let Some(groups) = groups.as_array_mut() else {
+            return self;
+        };
+
+        let index = groups
+            .iter()
+            .position(|g| g["name"] == name)
+            .unwrap_or_else(|| {
+                groups.push(serde_json::json!({ "name": name, "tags": [] }));
+                groups.len() - 1
+            });
+        if let Some(group_tags) = groups[index]
+            .as_object_mut()
+            .map(|g| g.entry("tags").or_insert_with(|| serde_json::json!([])))
+            .and_then(serde_json::Value::as_array_mut)
+        {
+            for tag in tags {
+                if !group_tags.iter().any(|t| t == tag) {
+                    group_tags.push(tag.into());
+                }
+            }
+        }
+
+        self
+    }
+
     /// Add a server to the documentation.
     #[tracing::instrument(skip_all)]
     pub fn server(self, server: Server) -> Self {
@@ -143,6 +245,28 @@ impl<'t> TransformOpenApi<'t> {
         self
     }
 
+    /// Add the servers provided by the resolver to the documentation,
+    /// servers with URLs that are already documented are skipped.
+    ///
+    /// See [`helpers::servers`](crate::helpers::servers) for more details.
+    #[tracing::instrument(skip_all)]
+    pub fn servers_from(self, resolver: impl ServerResolver) -> Self {
+        for server in resolver.servers() {
+            if !self.api.servers.iter().any(|s| s.url == server.url) {
+                self.api.servers.push(server);
+            }
+        }
+        self
+    }
+
+    /// Add the servers defined in environment variables with the given prefix.
+    ///
+    /// See [`EnvServers`](crate::helpers::servers::EnvServers) for the naming of the variables.
+    #[tracing::instrument(skip_all)]
+    pub fn servers_from_env(self, prefix: &str) -> Self {
+        self.servers_from(EnvServers::new(prefix))
+    }
+
     /// Set a default response for all operations
     /// that do not already have one.
     #[tracing::instrument(skip_all)]
@@ -192,6 +316,217 @@ impl<'t> TransformOpenApi<'t> {
         self
     }
 
+    /// Wrap the successful JSON responses of all operations
+    /// in an [`Envelope`](crate::helpers::Envelope) with the metadata type `M`.
+    ///
+    /// Use `()` as `M` for envelopes without metadata.
+    ///
+    /// See [`helpers::envelope`](crate::helpers::envelope) for more details.
+    #[tracing::instrument(skip_all)]
+    // The method itself is not general over the lifetime of the operation.
+    #[allow(clippy::redundant_closure_for_method_calls)]
+    pub fn envelope<M>(self) -> Self
+    where
+        M: JsonSchema,
+    {
+        self.all_operations(|op| op.envelope::<M>())
+    }
+
+    /// Apply a transform to all operations of the API.
+    ///
+    /// The transform is applied to the operations that
+    /// are already documented at the time of the call.
+    #[tracing::instrument(skip_all)]
+    pub fn all_operations<F>(self, mut transform: F) -> Self
+    where
+        F: FnMut(TransformOperation) -> TransformOperation,
+    {
+        if let Some(p) = &mut self.api.paths {
+            for (_, p) in &mut p.paths {
+                let p = match p {
+                    ReferenceOr::Reference { .. } => continue,
+                    ReferenceOr::Item(p) => p,
+                };
+
+                for (_, op) in iter_operations_mut(p) {
+                    let _ = transform(TransformOperation::new(op));
+                }
+            }
+        }
+
+        self
+    }
+
+    /// Apply a hook to every response of all operations, e.g. to link
+    /// the pages of an error catalog or runbooks in the descriptions of
+    /// error responses without documenting them on every route.
+    ///
+    /// The hook receives the status code of the response, which is
+    /// `None` for the default response, and the operation. It is applied
+    /// to the responses that are already documented at the time of the
+    /// call, references to response components are skipped.
+    #[tracing::instrument(skip_all)]
+    pub fn annotate_responses<F>(self, mut hook: F) -> Self
+    where
+        F: FnMut(Option<&StatusCode>, &Operation, &mut Response),
+    {
+        self.all_operations(|mut op| {
+            let operation = op.inner_mut();
+            let Some(mut responses) = operation.responses.take() else {
+                return op;
+            };
+
+            if let Some(ReferenceOr::Item(res)) = &mut responses.default {
+                hook(None, operation, res);
+            }
+            for (status, res) in &mut responses.responses {
+                if let ReferenceOr::Item(res) = res {
+                    hook(Some(status), operation, res);
+                }
+            }
+
+            operation.responses = Some(responses);
+            op
+        })
+    }
+
+    /// Document a response header, e.g. `X-Request-Id` or `Server-Timing`,
+    /// on every response of all operations.
+    ///
+    /// The header is added to the responses that are already documented
+    /// at the time of the call, headers with the same name are left
+    /// untouched.
+    pub fn response_header_all<T: JsonSchema>(self, name: &str, description: &str) -> Self {
+        self.response_header_all_with::<T, _>(name, description, |_| true)
+    }
+
+    /// Same as [`response_header_all`](Self::response_header_all), but
+    /// only for responses with a status code that matches the filter,
+    /// the status code of the default response is `None`.
+    #[tracing::instrument(skip_all)]
+    pub fn response_header_all_with<T, F>(self, name: &str, description: &str, filter: F) -> Self
+    where
+        T: JsonSchema,
+        F: Fn(Option<&StatusCode>) -> bool,
+    {
+        let header = in_context(|ctx| response_header::<T>(ctx, description));
+
+        self.all_operations(|mut op| {
+            add_response_header(op.inner_mut(), name, &header, &filter);
+            op
+        })
+    }
+
+    /// Add generated samples in the given languages to all operations
+    /// without a sample in the language, the supported languages are
+    /// [`CURL`](crate::helpers::code_samples::CURL) and
+    /// [`HTTPIE`](crate::helpers::code_samples::HTTPIE).
+    ///
+    /// See [`helpers::code_samples`](crate::helpers::code_samples) for more details.
+    #[tracing::instrument(skip_all)]
+    pub fn generate_request_samples(self, langs: &[&str]) -> Self {
+        add_request_samples(self.api, langs);
+        self
+    }
+
+    /// Add a generated `curl` sample to all operations without one.
+    ///
+    /// See [`helpers::code_samples`](crate::helpers::code_samples) for more details.
+    pub fn curl_samples(self) -> Self {
+        self.generate_request_samples(&[CURL])
+    }
+
+    /// Fill in the missing descriptions, examples and formats of
+    /// parameters from the dictionary.
+    ///
+    /// The dictionary is applied to the parameters of all operations, path
+    /// items and components that are already documented at the time of the call.
+    ///
+    /// See [`helpers::dictionary`](crate::helpers::dictionary) for more details.
+    #[tracing::instrument(skip_all)]
+    pub fn parameter_dictionary(self, dictionary: &ParameterDictionary) -> Self {
+        let apply = |params: &mut Vec<ReferenceOr<Parameter>>| {
+            for param in params.iter_mut().filter_map(ReferenceOr::as_item_mut) {
+                dictionary.apply(param);
+            }
+        };
+
+        if let Some(p) = &mut self.api.paths {
+            for p in p.paths.values_mut().filter_map(ReferenceOr::as_item_mut) {
+                apply(&mut p.parameters);
+            }
+        }
+
+        if let Some(components) = &mut self.api.components {
+            for param in components
+                .parameters
+                .values_mut()
+                .filter_map(ReferenceOr::as_item_mut)
+            {
+                dictionary.apply(param);
+            }
+        }
+
+        self.all_operations(|mut op| {
+            apply(&mut op.inner_mut().parameters);
+            op
+        })
+    }
+
+    /// Apply a JSON Patch to the document.
+    ///
+    /// The document is left unchanged if any of the operations
+    /// fail, and the error is reported in the current context.
+    ///
+    /// See [`patch`](crate::patch) for more details.
+    #[tracing::instrument(skip_all)]
+    pub fn apply_json_patch(mut self, patch: &JsonPatch) -> Self {
+        self.patch_document(|document| crate::patch::apply_json_patch(document, patch));
+        self
+    }
+
+    /// Apply a JSON Merge Patch to the document.
+    ///
+    /// See [`patch`](crate::patch) for more details.
+    #[tracing::instrument(skip_all)]
+    pub fn apply_merge_patch(mut self, patch: &serde_json::Value) -> Self {
+        self.patch_document(|document| {
+            crate::patch::apply_merge_patch(document, patch);
+            Ok(())
+        });
+        self
+    }
+
+    /// Remove the hidden items from the document.
+    ///
+    /// [`ApiRouter`](crate::axum::ApiRouter) does this after the
+    /// transform of the document, documentation that is built or
+    /// changed in other ways has to be pruned explicitly.
+    ///
+    /// See [`prune_hidden`](crate::visibility::prune_hidden) for more details.
+    #[tracing::instrument(skip_all)]
+    pub fn prune_hidden(self) -> Self {
+        crate::visibility::prune_hidden(self.api);
+        self
+    }
+
+    fn patch_document(
+        &mut self,
+        patch: impl FnOnce(&mut serde_json::Value) -> Result<(), PatchError>,
+    ) {
+        let patched = serde_json::to_value(&*self.api)
+            .map_err(PatchError::from)
+            .and_then(|mut document| {
+                patch(&mut document)?;
+                Ok(serde_json::from_value(document)?)
+            });
+
+        match patched {
+            Ok(api) => *self.api = api,
+            Err(error) => in_context(|ctx| ctx.error(Error::Patch(error))),
+        }
+    }
+
     /// Add a security scheme.
     #[allow(clippy::missing_panics_doc)]
     pub fn security_scheme(mut self, name: &str, scheme: SecurityScheme) -> Self {
@@ -210,6 +545,26 @@ impl<'t> TransformOpenApi<'t> {
         self
     }
 
+    /// Add a `mutualTLS` security scheme, for APIs that
+    /// authenticate clients with their TLS certificates.
+    ///
+    /// ```
+    /// # use aide::transform::TransformOpenApi;
+    /// fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+    ///     api.security_scheme_mtls("client_certificate")
+    ///         .security_requirement("client_certificate")
+    /// }
+    /// ```
+    pub fn security_scheme_mtls(self, name: &str) -> Self {
+        self.security_scheme(
+            name,
+            SecurityScheme::MutualTls {
+                description: None,
+                extensions: IndexMap::default(),
+            },
+        )
+    }
+
     /// Add a global security requirement.
     #[tracing::instrument(skip_all)]
     pub fn security_requirement(self, security_scheme: &str) -> Self {
@@ -297,6 +652,111 @@ impl<'t> TransformOpenApi<'t> {
         self
     }
 
+    /// Transform the schema of `T`.
+    ///
+    /// The schema is looked up in the components of the document,
+    /// or in the schemas generated in the current context that are
+    /// not yet merged into the document, in which case it is generated
+    /// if needed. This requires [`extract_schemas`](crate::gen::extract_schemas).
+    #[tracing::instrument(skip_all)]
+    pub fn schema<T: JsonSchema>(
+        self,
+        transform: impl FnOnce(TransformSchema) -> TransformSchema,
+    ) -> Self {
+        let name = T::schema_name();
+
+        if let Some(schema) = self
+            .api
+            .components
+            .as_mut()
+            .and_then(|components| components.schemas.get_mut(&name))
+        {
+            let _ = transform(TransformSchema::new(&mut schema.json_schema));
+            return self;
+        }
+
+        // The schema is taken out of the context, so that
+        // the transform can access the context as well.
+        let schema = in_context(|ctx| {
+            let _ = ctx.schema.subschema_for::<T>();
+            ctx.schema.definitions_mut().remove(&name)
+        });
+
+        match schema {
+            Some(mut schema) => {
+                let _ = transform(TransformSchema::new(&mut schema));
+                in_context(|ctx| ctx.schema.definitions_mut().insert(name, schema));
+            }
+            None => in_context(|ctx| ctx.error(Error::SchemaNotExists(name))),
+        }
+
+        self
+    }
+
+    /// Provide or override the description of the schema of `T`,
+    /// e.g. for types that implement [`JsonSchema`] without doc comments,
+    /// see also [`SchemaDocs`](crate::schema::SchemaDocs).
+    ///
+    /// The schema is looked up like in [`schema`](Self::schema).
+    #[tracing::instrument(skip_all)]
+    pub fn describe_schema<T: JsonSchema>(self, description: &str) -> Self {
+        self.schema::<T>(|schema| schema.description(description))
+    }
+
+    /// Transform the component schema with the given name, the schema
+    /// is added if it does not exist, e.g. to compose schemas that are
+    /// defined in other crates with [`TransformSchema::one_of`]:
+    ///
+    /// ```
+    /// use aide::transform::TransformOpenApi;
+    ///
+    /// fn pet_docs(api: TransformOpenApi) -> TransformOpenApi {
+    ///     api.component_schema("Pet", |schema| {
+    ///         schema
+    ///             .description("A cat or a dog.")
+    ///             .one_of(["Cat", "Dog"])
+    ///             .discriminator("kind")
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// The schema is looked up in the components of the document, or in
+    /// the schemas generated in the current context that are not yet
+    /// merged into the document.
+    #[tracing::instrument(skip_all)]
+    pub fn component_schema(
+        self,
+        name: &str,
+        transform: impl FnOnce(TransformSchema) -> TransformSchema,
+    ) -> Self {
+        let components = self.api.components.get_or_insert_with(Default::default);
+        if let Some(schema) = components.schemas.get_mut(name) {
+            let _ = transform(TransformSchema::new(&mut schema.json_schema));
+            return self;
+        }
+
+        match in_context(|ctx| ctx.schema.definitions_mut().remove(name)) {
+            Some(mut schema) => {
+                let _ = transform(TransformSchema::new(&mut schema));
+                in_context(|ctx| ctx.schema.definitions_mut().insert(name.into(), schema));
+            }
+            None => {
+                let mut schema = Schema::Object(SchemaObject::default());
+                let _ = transform(TransformSchema::new(&mut schema));
+                components.schemas.insert(
+                    name.into(),
+                    crate::openapi::SchemaObject {
+                        json_schema: schema,
+                        example: None,
+                        external_docs: None,
+                    },
+                );
+            }
+        }
+
+        self
+    }
+
     /// Apply an another transform function.
     pub fn with(self, transform: impl FnOnce(Self) -> Self) -> Self {
         transform(self)
@@ -312,29 +772,40 @@ impl<'t> TransformOpenApi<'t> {
 /// A transform helper that wraps [`TransformPathItem`].
 #[must_use]
 pub struct TransformPathItem<'t> {
-    pub(crate) hidden: bool,
     pub(crate) path: &'t mut PathItem,
 }
 
 impl<'t> TransformPathItem<'t> {
     /// Create a new transform helper.
     pub fn new(path: &'t mut PathItem) -> Self {
-        Self {
-            hidden: false,
-            path,
-        }
+        Self { path }
     }
 
     /// Hide the path from the documentation.
     ///
-    /// This is taken into account by generators provided
-    /// by this library.
-    ///
-    /// Hiding an item causes it to be ignored
-    /// completely, there is no way to restore or "unhide" it afterwards.
+    /// Hidden items are only marked with the [`HIDDEN`](crate::visibility::HIDDEN)
+    /// extension, and can be shown again with `hidden(false)` until they are
+    /// removed with [`prune_hidden`](crate::visibility::prune_hidden), which
+    /// [`ApiRouter`](crate::axum::ApiRouter) does when the documentation is
+    /// finished. Documentation that is built in other ways still contains the
+    /// hidden items until [`TransformOpenApi::prune_hidden`] is called.
+    #[tracing::instrument(skip_all)]
+    pub fn hidden(self, hidden: bool) -> Self {
+        set_hidden(&mut self.path.extensions, hidden);
+        self
+    }
+
+    /// Whether the path is hidden from the documentation.
+    #[must_use]
+    pub fn is_hidden(&self) -> bool {
+        is_hidden(&self.path.extensions)
+    }
+
+    /// Set the visibility of the path, see [`visibility`](crate::visibility).
     #[tracing::instrument(skip_all)]
-    pub fn hidden(mut self, hidden: bool) -> Self {
-        self.hidden = hidden;
+    pub fn visibility(self, visibility: ApiVisibility) -> Self {
+        let (key, value) = visibility.extension();
+        self.path.extensions.insert(key, value);
         self
     }
 
@@ -476,20 +947,37 @@ impl<'t> TransformPathItem<'t> {
     }
 }
 
+/// Compile-time validation of the status codes given as const generics.
+pub(crate) struct StatusConst<const N: u16>;
+
+impl<const N: u16> StatusConst<N> {
+    pub(crate) const CODE: u16 = {
+        assert!(
+            100 <= N && N <= 599,
+            "status codes must be in the range 100..=599"
+        );
+        N
+    };
+
+    pub(crate) const RANGE: u16 = {
+        assert!(
+            1 <= N && N <= 5,
+            "status code ranges must be in the range 1..=5"
+        );
+        N
+    };
+}
+
 /// A transform helper that wraps [`Operation`].
 #[must_use]
 pub struct TransformOperation<'t> {
-    pub(crate) hidden: bool,
     pub(crate) operation: &'t mut Operation,
 }
 
 impl<'t> TransformOperation<'t> {
     /// Create a new transform helper.
     pub fn new(operation: &'t mut Operation) -> Self {
-        Self {
-            hidden: false,
-            operation,
-        }
+        Self { operation }
     }
 
     /// Specify the operation ID.
@@ -525,14 +1013,29 @@ impl<'t> TransformOperation<'t> {
 
     /// Hide the operation from the documentation.
     ///
-    /// This is taken into account by generators provided
-    /// by this library.
-    ///
-    /// Hiding an item causes it to be ignored
-    /// completely, there is no way to restore or "unhide" it afterwards.
+    /// Hidden items are only marked with the [`HIDDEN`](crate::visibility::HIDDEN)
+    /// extension, and can be shown again with `hidden(false)` until they are
+    /// removed with [`prune_hidden`](crate::visibility::prune_hidden), which
+    /// [`ApiRouter`](crate::axum::ApiRouter) does when the documentation is
+    /// finished. Documentation that is built in other ways still contains the
+    /// hidden items until [`TransformOpenApi::prune_hidden`] is called.
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn hidden(self, hidden: bool) -> Self {
+        set_hidden(&mut self.operation.extensions, hidden);
+        self
+    }
+
+    /// Whether the operation is hidden from the documentation.
+    #[must_use]
+    pub fn is_hidden(&self) -> bool {
+        is_hidden(&self.operation.extensions)
+    }
+
+    /// Set the visibility of the operation, see [`visibility`](crate::visibility).
     #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
-    pub fn hidden(mut self, hidden: bool) -> Self {
-        self.hidden = hidden;
+    pub fn visibility(self, visibility: ApiVisibility) -> Self {
+        let (key, value) = visibility.extension();
+        self.operation.extensions.insert(key, value);
         self
     }
 
@@ -551,6 +1054,79 @@ impl<'t> TransformOperation<'t> {
         self
     }
 
+    /// Add a named example of the request body to all of its media
+    /// types, documentation UIs usually offer a selection of the
+    /// examples, e.g. of a minimal and a full request.
+    ///
+    /// An example with the same name is replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aide::transform::TransformOperation;
+    /// use serde::Serialize;
+    ///
+    /// #[derive(Serialize)]
+    /// struct NewUser {
+    ///     name: String,
+    ///     email: Option<String>,
+    /// }
+    ///
+    /// fn create_user_docs(op: TransformOperation) -> TransformOperation {
+    ///     op.input_example_named::<NewUser>(
+    ///         "minimal",
+    ///         "Only the required fields",
+    ///         NewUser {
+    ///             name: "alice".into(),
+    ///             email: None,
+    ///         },
+    ///     )
+    ///     .input_example_named::<NewUser>(
+    ///         "full",
+    ///         "All fields",
+    ///         NewUser {
+    ///             name: "alice".into(),
+    ///             email: Some("alice@example.com".into()),
+    ///         },
+    ///     )
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the example cannot be serialized to JSON.
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn input_example_named<T>(self, name: &str, summary: &str, example: impl Into<T>) -> Self
+    where
+        T: Serialize,
+    {
+        let body = match &mut self.operation.request_body {
+            Some(ReferenceOr::Item(body)) => body,
+            Some(ReferenceOr::Reference { .. }) => {
+                in_context(|ctx| ctx.error(Error::UnexpectedReference));
+                return self;
+            }
+            None => {
+                in_context(|ctx| ctx.error(Error::RequestBodyNotExists));
+                return self;
+            }
+        };
+
+        let value = serde_json::to_value(example.into()).unwrap();
+        for media in body.content.values_mut() {
+            media.examples.insert(
+                name.into(),
+                ReferenceOr::Item(Example {
+                    summary: Some(summary.into()),
+                    value: Some(value.clone()),
+                    ..Default::default()
+                }),
+            );
+        }
+
+        self
+    }
+
     /// Modify a parameter of the operation.
     #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
     pub fn parameter<T, F>(self, name: &str, transform: F) -> Self
@@ -558,17 +1134,12 @@ impl<'t> TransformOperation<'t> {
         T: Serialize,
         F: FnOnce(TransformParameter<T>) -> TransformParameter<T>,
     {
-        let (idx, param) = match self
-            .operation
-            .parameters
-            .iter_mut()
-            .enumerate()
-            .find(|(_, p)| match p {
-                ReferenceOr::Item(p) => p.parameter_data_ref().name == name,
-                ReferenceOr::Reference { .. } => false,
-            }) {
-            Some((idx, p)) => match p {
-                ReferenceOr::Item(p) => (idx, p),
+        let param = match self.operation.parameters.iter_mut().find(|p| match p {
+            ReferenceOr::Item(p) => p.parameter_data_ref().name == name,
+            ReferenceOr::Reference { .. } => false,
+        }) {
+            Some(p) => match p {
+                ReferenceOr::Item(p) => p,
                 ReferenceOr::Reference { .. } => {
                     in_context(|ctx| {
                         ctx.error(Error::UnexpectedReference);
@@ -584,11 +1155,7 @@ impl<'t> TransformOperation<'t> {
             }
         };
 
-        let t = transform(TransformParameter::new(param));
-
-        if t.hidden {
-            self.operation.parameters.swap_remove(idx);
-        }
+        let _ = transform(TransformParameter::new(param));
 
         self
     }
@@ -604,6 +1171,53 @@ impl<'t> TransformOperation<'t> {
         self.parameter(name, transform)
     }
 
+    /// Document a header that is not documented by an extractor,
+    /// e.g. a header that is read from a `HeaderMap`, with the schema
+    /// of `T`, or modify it if it is already documented.
+    ///
+    /// The header is required unless `T` is nullable, e.g. an `Option`,
+    /// and it is also required if it is the header of an API key security
+    /// scheme that is required by the operation when the documentation
+    /// is finished.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aide::transform::TransformOperation;
+    ///
+    /// fn tenant_docs(op: TransformOperation) -> TransformOperation {
+    ///     op.header_parameter::<Option<String>, _>("X-Tenant-Id", |p| {
+    ///         p.description("The tenant of the request.").required(true)
+    ///     })
+    /// }
+    /// ```
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn header_parameter<T, F>(self, name: &str, transform: F) -> Self
+    where
+        T: JsonSchema,
+        F: FnOnce(TransformParameter<T>) -> TransformParameter<T>,
+    {
+        let existing = self.operation.parameters.iter_mut().find_map(|p| match p {
+            ReferenceOr::Item(p @ Parameter::Header { .. })
+                if p.parameter_data_ref().name.eq_ignore_ascii_case(name) =>
+            {
+                Some(p)
+            }
+            _ => None,
+        });
+
+        if let Some(param) = existing {
+            let _ = transform(TransformParameter::new(param));
+            return self;
+        }
+
+        let mut param = in_context(|ctx| header_parameter::<T>(ctx, name));
+        let _ = transform(TransformParameter::new(&mut param));
+        self.operation.parameters.push(ReferenceOr::Item(param));
+
+        self
+    }
+
     /// Set a default response for the operation if
     /// it does not already have one.
     #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
@@ -619,11 +1233,7 @@ impl<'t> TransformOperation<'t> {
         in_context(|ctx| {
             if let Some(res) = R::operation_response(ctx, self.operation) {
                 let responses = self.operation.responses.as_mut().unwrap();
-                if responses.default.is_none() {
-                    responses.default = Some(ReferenceOr::Item(res));
-                } else {
-                    ctx.error(Error::DefaultResponseExists);
-                }
+                insert_default_response(ctx, responses, res);
             } else {
                 tracing::debug!(type_name = type_name::<R>(), "no response info of type");
             }
@@ -646,16 +1256,12 @@ impl<'t> TransformOperation<'t> {
     {
         in_context(|ctx| {
             if let Some(mut res) = R::operation_response(ctx, self.operation) {
-                let responses = self.operation.responses.get_or_insert_with(Default::default);
-                if responses.default.is_none() {
-                    let t = transform(TransformResponse::new(&mut res));
-
-                    if !t.hidden {
-                        responses.default = Some(ReferenceOr::Item(res));
-                    }
-                } else {
-                    ctx.error(Error::DefaultResponseExists);
-                }
+                let _ = transform(TransformResponse::new(&mut res));
+                let responses = self
+                    .operation
+                    .responses
+                    .get_or_insert_with(Default::default);
+                insert_default_response(ctx, responses, res);
             } else {
                 tracing::debug!(type_name = type_name::<R>(), "no response info of type");
             }
@@ -665,12 +1271,29 @@ impl<'t> TransformOperation<'t> {
     }
 
     /// Add a response to the operation with the given status code.
+    ///
+    /// The status code is checked at compile time,
+    /// it must be in the range `100..=599`:
+    ///
+    /// ```
+    /// # use aide::{openapi::Operation, transform::TransformOperation};
+    /// # let mut operation = Operation::default();
+    /// TransformOperation::new(&mut operation).response::<201, String>();
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use aide::{openapi::Operation, transform::TransformOperation};
+    /// # let mut operation = Operation::default();
+    /// TransformOperation::new(&mut operation).response::<20, String>();
+    /// ```
     #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
     #[allow(clippy::missing_panics_doc)]
     pub fn response<const N: u16, R>(self) -> Self
     where
         R: OperationOutput,
     {
+        let status = StatusCode::Code(StatusConst::<N>::CODE);
+
         if self.operation.responses.is_none() {
             self.operation.responses = Some(Default::default());
         }
@@ -678,13 +1301,7 @@ impl<'t> TransformOperation<'t> {
         in_context(|ctx| {
             if let Some(res) = R::operation_response(ctx, self.operation) {
                 let responses = self.operation.responses.as_mut().unwrap();
-                if responses
-                    .responses
-                    .insert(StatusCode::Code(N), ReferenceOr::Item(res))
-                    .is_some()
-                {
-                    ctx.error(Error::ResponseExists(StatusCode::Code(N)));
-                };
+                insert_response(ctx, responses, status, res);
             } else {
                 tracing::debug!(type_name = type_name::<R>(), "no response info of type");
             }
@@ -695,6 +1312,9 @@ impl<'t> TransformOperation<'t> {
 
     /// Add a response to the operation with the given status code.
     ///
+    /// The status code is checked at compile time,
+    /// it must be in the range `100..=599`.
+    ///
     /// This method additionally accepts a transform function
     /// to modify the generated documentation.
     #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
@@ -704,24 +1324,62 @@ impl<'t> TransformOperation<'t> {
         R: OperationOutput,
         F: FnOnce(TransformResponse<R::Inner>) -> TransformResponse<R::Inner>,
     {
+        let status = StatusCode::Code(StatusConst::<N>::CODE);
+
         if self.operation.responses.is_none() {
             self.operation.responses = Some(Default::default());
         }
 
         in_context(|ctx| {
             if let Some(mut res) = R::operation_response(ctx, self.operation) {
-                let t = transform(TransformResponse::new(&mut res));
+                let _ = transform(TransformResponse::new(&mut res));
 
                 let responses = self.operation.responses.as_mut().unwrap();
-                if !t.hidden {
-                    let existing = responses
-                        .responses
-                        .insert(StatusCode::Code(N), ReferenceOr::Item(res))
-                        .is_some();
-                    if existing {
-                        ctx.error(Error::ResponseExists(StatusCode::Code(N)));
-                    };
-                }
+                insert_response(ctx, responses, status, res);
+            } else {
+                tracing::debug!(type_name = type_name::<R>(), "no response info of type");
+            }
+        });
+
+        self
+    }
+
+    /// Add a response to the operation with the given [`http::StatusCode`],
+    /// e.g. `response_status::<Json<Todo>>(StatusCode::CREATED)`.
+    ///
+    /// See [`WithStatus`](crate::helpers::status::WithStatus) to document
+    /// the status code with the return type of the handler instead.
+    #[cfg(feature = "http")]
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn response_status<R>(self, status: http::StatusCode) -> Self
+    where
+        R: OperationOutput,
+    {
+        self.response_status_with::<R, _>(status, |res| res)
+    }
+
+    /// Add a response to the operation with the given [`http::StatusCode`].
+    ///
+    /// This method additionally accepts a transform function
+    /// to modify the generated documentation.
+    #[cfg(feature = "http")]
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn response_status_with<R, F>(self, status: http::StatusCode, transform: F) -> Self
+    where
+        R: OperationOutput,
+        F: FnOnce(TransformResponse<R::Inner>) -> TransformResponse<R::Inner>,
+    {
+        let status = StatusCode::Code(status.as_u16());
+
+        in_context(|ctx| {
+            if let Some(mut res) = R::operation_response(ctx, self.operation) {
+                let _ = transform(TransformResponse::new(&mut res));
+
+                let responses = self
+                    .operation
+                    .responses
+                    .get_or_insert_with(Default::default);
+                insert_response(ctx, responses, status, res);
             } else {
                 tracing::debug!(type_name = type_name::<R>(), "no response info of type");
             }
@@ -733,12 +1391,27 @@ impl<'t> TransformOperation<'t> {
     /// Add a response to the operation with the given status code range (e.g. 2xx).
     ///
     /// Note that the range is `100`-based, so for the range `2xx`, `2` must be provided.
+    /// The range is checked at compile time, it must be in the range `1..=5`:
+    ///
+    /// ```
+    /// # use aide::{openapi::Operation, transform::TransformOperation};
+    /// # let mut operation = Operation::default();
+    /// TransformOperation::new(&mut operation).response_range::<4, String>();
+    /// ```
+    ///
+    /// ```compile_fail
+    /// # use aide::{openapi::Operation, transform::TransformOperation};
+    /// # let mut operation = Operation::default();
+    /// TransformOperation::new(&mut operation).response_range::<400, String>();
+    /// ```
     #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
     #[allow(clippy::missing_panics_doc)]
     pub fn response_range<const N: u16, R>(self) -> Self
     where
         R: OperationOutput,
     {
+        let status = StatusCode::Range(StatusConst::<N>::RANGE);
+
         if self.operation.responses.is_none() {
             self.operation.responses = Some(Default::default());
         }
@@ -746,13 +1419,7 @@ impl<'t> TransformOperation<'t> {
         in_context(|ctx| {
             if let Some(res) = R::operation_response(ctx, self.operation) {
                 let responses = self.operation.responses.as_mut().unwrap();
-                if responses
-                    .responses
-                    .insert(StatusCode::Range(N), ReferenceOr::Item(res))
-                    .is_some()
-                {
-                    ctx.error(Error::ResponseExists(StatusCode::Range(N)));
-                };
+                insert_response(ctx, responses, status, res);
             } else {
                 tracing::debug!(type_name = type_name::<R>(), "no response info of type");
             }
@@ -764,6 +1431,7 @@ impl<'t> TransformOperation<'t> {
     /// Add a response to the operation with the given status code range (e.g. 2xx).
     ///
     /// Note that the range is `100`-based, so for the range `2xx`, `2` must be provided.
+    /// The range is checked at compile time, it must be in the range `1..=5`.
     ///
     /// This method additionally accepts a transform function
     /// to modify the generated documentation.
@@ -774,24 +1442,18 @@ impl<'t> TransformOperation<'t> {
         R: OperationOutput,
         F: FnOnce(TransformResponse<R::Inner>) -> TransformResponse<R::Inner>,
     {
+        let status = StatusCode::Range(StatusConst::<N>::RANGE);
+
         if self.operation.responses.is_none() {
             self.operation.responses = Some(Default::default());
         }
 
         in_context(|ctx| {
             if let Some(mut res) = R::operation_response(ctx, self.operation) {
-                let t = transform(TransformResponse::new(&mut res));
+                let _ = transform(TransformResponse::new(&mut res));
 
                 let responses = self.operation.responses.as_mut().unwrap();
-                if !t.hidden {
-                    let existing = responses
-                        .responses
-                        .insert(StatusCode::Range(N), ReferenceOr::Item(res))
-                        .is_some();
-                    if existing {
-                        ctx.error(Error::ResponseExists(StatusCode::Range(N)));
-                    };
-                }
+                insert_response(ctx, responses, status, res);
             } else {
                 tracing::debug!(type_name = type_name::<R>(), "no response info of type");
             }
@@ -800,6 +1462,96 @@ impl<'t> TransformOperation<'t> {
         self
     }
 
+    /// Wrap the successful JSON responses of the operation
+    /// in an [`Envelope`](crate::helpers::Envelope) with the metadata type `M`.
+    ///
+    /// Use `()` as `M` for envelopes without metadata.
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn envelope<M>(self) -> Self
+    where
+        M: JsonSchema,
+    {
+        if let Some(responses) = &mut self.operation.responses {
+            in_context(|ctx| {
+                // Only successful responses are wrapped.
+                for (status, res) in &mut responses.responses {
+                    if !is_success(status) {
+                        continue;
+                    }
+
+                    match res {
+                        ReferenceOr::Item(res) => envelope::wrap_response::<M>(ctx, res),
+                        ReferenceOr::Reference { .. } => ctx.error(Error::UnexpectedReference),
+                    }
+                }
+            });
+        }
+
+        self
+    }
+
+    /// Add a code sample in the given language to the operation,
+    /// see [`helpers::code_samples`](crate::helpers::code_samples).
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn code_sample(self, lang: &str, source: &str) -> Self {
+        add_code_sample(self.operation, lang, source);
+        self
+    }
+
+    /// Document the timeout of the operation, e.g. of a timeout layer.
+    ///
+    /// The timeout is stored in the [`TIMEOUT_MS`] extension in
+    /// milliseconds, and a `408 Request Timeout` response is added
+    /// unless one is already documented.
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn timeout(self, timeout: Duration) -> Self {
+        let millis = u64::try_from(timeout.as_millis()).unwrap_or(u64::MAX);
+        self.operation
+            .extensions
+            .insert(TIMEOUT_MS.into(), millis.into());
+
+        in_context(|ctx| {
+            add_response_if_missing(self.operation, 408, timeout_response(ctx, 408));
+        });
+
+        self
+    }
+
+    /// Document the maximum size of the request body in bytes,
+    /// e.g. of a body limit layer.
+    ///
+    /// The size is stored in the [`MAX_BODY_SIZE`] extension of the
+    /// operation and its request body, bodies documented with a string
+    /// schema get a `maxLength` as well. A `413 Payload Too Large`
+    /// response is added unless one is already documented.
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn max_body_size(self, bytes: u64) -> Self {
+        self.operation
+            .extensions
+            .insert(MAX_BODY_SIZE.into(), bytes.into());
+
+        if let Some(ReferenceOr::Item(body)) = &mut self.operation.request_body {
+            body.extensions.insert(MAX_BODY_SIZE.into(), bytes.into());
+
+            let max_length = u32::try_from(bytes).ok();
+            for media in body.content.values_mut() {
+                let (Some(schema), Some(max)) = (&mut media.schema, max_length) else {
+                    continue;
+                };
+                if let Schema::Object(schema) = &mut schema.json_schema {
+                    if schema.has_type(InstanceType::String) {
+                        let string = schema.string();
+                        string.max_length = Some(string.max_length.map_or(max, |m| m.min(max)));
+                    }
+                }
+            }
+        }
+
+        in_context(|ctx| add_response_if_missing(self.operation, 413, body_limit_response(ctx)));
+
+        self
+    }
+
     /// Add a callback to the operation.
     #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
     #[allow(clippy::missing_panics_doc)]
@@ -835,22 +1587,7 @@ impl<'t> TransformOperation<'t> {
             ReferenceOr::Item(p) => p,
         };
 
-        let t = callback_transform(TransformCallback::new(p));
-
-        if t.hidden {
-            callbacks.swap_remove(callback_url);
-            if self
-                .operation
-                .callbacks
-                .get(callback_name)
-                .unwrap()
-                .as_item()
-                .unwrap()
-                .is_empty()
-            {
-                self.operation.callbacks.swap_remove(callback_name);
-            }
-        }
+        let _ = callback_transform(TransformCallback::new(p));
 
         self
     }
@@ -941,6 +1678,44 @@ impl<'t> TransformOperation<'t> {
         self
     }
 
+    /// Allow anonymous access to the operation by adding an empty
+    /// security requirement next to the existing ones, i.e.
+    /// authentication is optional.
+    ///
+    /// Security requirements of the router, see
+    /// [`ApiRouter::require_security`](crate::axum::ApiRouter::require_security),
+    /// are not added to operations that allow anonymous access.
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn allow_anonymous(self) -> Self {
+        if !allows_anonymous(self.operation) {
+            self.operation.security.push(SecurityRequirement::new());
+        }
+        self
+    }
+
+    /// Make the security requirements of the operation optional by
+    /// appending the empty `{}` requirement, e.g.
+    /// `security_requirement("token").security_optional()`.
+    ///
+    /// This is the same as [`allow_anonymous`](Self::allow_anonymous).
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn security_optional(self) -> Self {
+        self.allow_anonymous()
+    }
+
+    /// Make the operation public by replacing its security requirements,
+    /// and the global ones that it would inherit, with an empty security
+    /// requirement.
+    ///
+    /// Unlike [`allow_anonymous`](Self::allow_anonymous) no authentication
+    /// is documented for the operation, and security requirements of the
+    /// router are not added either.
+    #[tracing::instrument(skip_all, fields(operation_id = ?self.operation.operation_id))]
+    pub fn no_security(self) -> Self {
+        self.operation.security = vec![SecurityRequirement::new()];
+        self
+    }
+
     /// Apply an another transform function.
     pub fn with(self, transform: impl FnOnce(Self) -> Self) -> Self {
         transform(self)
@@ -959,7 +1734,6 @@ impl<'t> TransformOperation<'t> {
 /// examples.
 #[must_use]
 pub struct TransformParameter<'t, T> {
-    pub(crate) hidden: bool,
     pub(crate) param: &'t mut Parameter,
     _t: PhantomData<T>,
 }
@@ -968,7 +1742,6 @@ impl<'t, T> TransformParameter<'t, T> {
     /// Create a new transform helper.
     pub fn new(param: &'t mut Parameter) -> Self {
         Self {
-            hidden: false,
             param,
             _t: PhantomData,
         }
@@ -976,14 +1749,32 @@ impl<'t, T> TransformParameter<'t, T> {
 
     /// Hide the parameter from the documentation.
     ///
-    /// This is taken into account by generators provided
-    /// by this library.
-    ///
-    /// Hiding an item causes it to be ignored
-    /// completely, there is no way to restore or "unhide" it afterwards.
+    /// Hidden items are only marked with the [`HIDDEN`](crate::visibility::HIDDEN)
+    /// extension, and can be shown again with `hidden(false)` until they are
+    /// removed with [`prune_hidden`](crate::visibility::prune_hidden), which
+    /// [`ApiRouter`](crate::axum::ApiRouter) does when the documentation is
+    /// finished. Documentation that is built in other ways still contains the
+    /// hidden items until [`TransformOpenApi::prune_hidden`] is called.
+    #[tracing::instrument(skip_all)]
+    pub fn hidden(self, hidden: bool) -> Self {
+        set_hidden(&mut self.param.parameter_data_mut().extensions, hidden);
+        self
+    }
+
+    /// Whether the parameter is hidden from the documentation.
+    #[must_use]
+    pub fn is_hidden(&self) -> bool {
+        is_hidden(&self.param.parameter_data_ref().extensions)
+    }
+
+    /// Set the visibility of the parameter, see [`visibility`](crate::visibility).
     #[tracing::instrument(skip_all)]
-    pub fn hidden(mut self, hidden: bool) -> Self {
-        self.hidden = hidden;
+    pub fn visibility(self, visibility: ApiVisibility) -> Self {
+        let (key, value) = visibility.extension();
+        self.param
+            .parameter_data_mut()
+            .extensions
+            .insert(key, value);
         self
     }
 
@@ -1000,6 +1791,62 @@ impl<'t, T> TransformParameter<'t, T> {
         self
     }
 
+    /// Set whether the parameter is required,
+    /// path parameters are always required.
+    #[tracing::instrument(skip_all)]
+    pub fn required(self, required: bool) -> Self {
+        self.param.parameter_data_mut().required = required;
+        self
+    }
+
+    /// Set the serialization style of the parameter.
+    ///
+    /// The `deepObject` style is only defined for exploded
+    /// parameters, so it also enables [`explode`](Self::explode)
+    /// unless it was set explicitly.
+    #[tracing::instrument(skip_all)]
+    pub fn style(self, style: ParameterStyle) -> Self {
+        if !self.param.set_style(style) {
+            in_context(|ctx| {
+                ctx.error(Error::UnsupportedParameterOption(
+                    self.param.parameter_data_ref().name.clone(),
+                    format!(r#"the "{style}" style"#),
+                ));
+            });
+            return self;
+        }
+
+        let data = self.param.parameter_data_mut();
+        if style == ParameterStyle::DeepObject && data.explode.is_none() {
+            data.explode = Some(true);
+        }
+        self
+    }
+
+    /// Set whether array and object values generate separate
+    /// parameters, the default depends on the style of the parameter.
+    #[tracing::instrument(skip_all)]
+    pub fn explode(self, explode: bool) -> Self {
+        self.param.parameter_data_mut().explode = Some(explode);
+        self
+    }
+
+    /// Allow reserved characters in the value
+    /// without percent-encoding, only for query parameters.
+    #[tracing::instrument(skip_all)]
+    pub fn allow_reserved(mut self, allow: bool) -> Self {
+        match &mut self.param {
+            Parameter::Query { allow_reserved, .. } => *allow_reserved = allow,
+            _ => in_context(|ctx| {
+                ctx.error(Error::UnsupportedParameterOption(
+                    self.param.parameter_data_ref().name.clone(),
+                    "reserved characters".into(),
+                ));
+            }),
+        }
+        self
+    }
+
     /// Apply an another transform function.
     pub fn with(self, transform: impl FnOnce(Self) -> Self) -> Self {
         transform(self)
@@ -1018,7 +1865,6 @@ impl<'t, T> TransformParameter<'t, T> {
 /// examples.
 #[must_use]
 pub struct TransformResponse<'t, T> {
-    pub(crate) hidden: bool,
     pub(crate) response: &'t mut Response,
     _t: PhantomData<T>,
 }
@@ -1027,7 +1873,6 @@ impl<'t, T> TransformResponse<'t, T> {
     /// Create a new transform helper.
     pub fn new(response: &'t mut Response) -> Self {
         Self {
-            hidden: false,
             response,
             _t: PhantomData,
         }
@@ -1035,17 +1880,27 @@ impl<'t, T> TransformResponse<'t, T> {
 
     /// Hide the response from the documentation.
     ///
-    /// This is taken into account by generators provided
-    /// by this library.
+    /// Hidden items are only marked with the [`HIDDEN`](crate::visibility::HIDDEN)
+    /// extension, and can be shown again with `hidden(false)` until they are
+    /// removed with [`prune_hidden`](crate::visibility::prune_hidden), which
+    /// [`ApiRouter`](crate::axum::ApiRouter) does when the documentation is
+    /// finished. Documentation that is built in other ways still contains the
+    /// hidden items until [`TransformOpenApi::prune_hidden`] is called.
     ///
-    /// Hiding an item causes it to be ignored
-    /// completely, there is no way to restore or "unhide" it afterwards.
+    /// A hidden response does not replace a visible
+    /// response with the same status code.
     #[tracing::instrument(skip_all)]
-    pub fn hidden(mut self, hidden: bool) -> Self {
-        self.hidden = hidden;
+    pub fn hidden(self, hidden: bool) -> Self {
+        set_hidden(&mut self.response.extensions, hidden);
         self
     }
 
+    /// Whether the response is hidden from the documentation.
+    #[must_use]
+    pub fn is_hidden(&self) -> bool {
+        is_hidden(&self.response.extensions)
+    }
+
     /// Provide or override the description of the response.
     #[tracing::instrument(skip_all)]
     pub fn description(self, desc: &str) -> Self {
@@ -1069,6 +1924,17 @@ impl<'t, T> TransformResponse<'t, T> {
         self
     }
 
+    /// Wrap the JSON content of the response
+    /// in an [`Envelope`](crate::helpers::Envelope) with the metadata type `M`.
+    #[tracing::instrument(skip_all)]
+    pub fn envelope<M>(self) -> Self
+    where
+        M: JsonSchema,
+    {
+        in_context(|ctx| envelope::wrap_response::<M>(ctx, self.response));
+        self
+    }
+
     /// Apply an another transform function.
     pub fn with(self, transform: impl FnOnce(Self) -> Self) -> Self {
         transform(self)
@@ -1083,31 +1949,34 @@ impl<'t, T> TransformResponse<'t, T> {
 /// A transform helper that wraps a callback [`PathItem`].
 #[must_use]
 pub struct TransformCallback<'t> {
-    hidden: bool,
     path: &'t mut PathItem,
 }
 
 impl<'t> TransformCallback<'t> {
     /// Create a new transform helper.
     pub fn new(path: &'t mut PathItem) -> Self {
-        Self {
-            hidden: false,
-            path,
-        }
+        Self { path }
     }
 
     /// Hide the callback from the documentation.
     ///
-    /// This is taken into account by generators provided
-    /// by this library.
-    ///
-    /// Hiding an item causes it to be ignored
-    /// completely, there is no way to restore or "unhide" it afterwards.
-    pub fn hidden(mut self, hidden: bool) -> Self {
-        self.hidden = hidden;
+    /// Hidden items are only marked with the [`HIDDEN`](crate::visibility::HIDDEN)
+    /// extension, and can be shown again with `hidden(false)` until they are
+    /// removed with [`prune_hidden`](crate::visibility::prune_hidden), which
+    /// [`ApiRouter`](crate::axum::ApiRouter) does when the documentation is
+    /// finished. Documentation that is built in other ways still contains the
+    /// hidden items until [`TransformOpenApi::prune_hidden`] is called.
+    pub fn hidden(self, hidden: bool) -> Self {
+        set_hidden(&mut self.path.extensions, hidden);
         self
     }
 
+    /// Whether the callback is hidden from the documentation.
+    #[must_use]
+    pub fn is_hidden(&self) -> bool {
+        is_hidden(&self.path.extensions)
+    }
+
     /// Add a "delete" callback operation.
     #[allow(clippy::missing_panics_doc)]
     pub fn delete(self, operation: impl FnOnce(TransformOperation) -> TransformOperation) -> Self {
@@ -1119,11 +1988,7 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
-
-        if t.hidden {
-            self.path.delete = None;
-        }
+        let _ = operation(TransformOperation::new(op));
 
         self
     }
@@ -1139,11 +2004,7 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
-
-        if t.hidden {
-            self.path.get = None;
-        }
+        let _ = operation(TransformOperation::new(op));
 
         self
     }
@@ -1159,11 +2020,7 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
-
-        if t.hidden {
-            self.path.head = None;
-        }
+        let _ = operation(TransformOperation::new(op));
 
         self
     }
@@ -1179,11 +2036,7 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
-
-        if t.hidden {
-            self.path.options = None;
-        }
+        let _ = operation(TransformOperation::new(op));
 
         self
     }
@@ -1199,11 +2052,7 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
-
-        if t.hidden {
-            self.path.patch = None;
-        }
+        let _ = operation(TransformOperation::new(op));
 
         self
     }
@@ -1219,11 +2068,7 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
-
-        if t.hidden {
-            self.path.post = None;
-        }
+        let _ = operation(TransformOperation::new(op));
 
         self
     }
@@ -1239,11 +2084,7 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
-
-        if t.hidden {
-            self.path.put = None;
-        }
+        let _ = operation(TransformOperation::new(op));
 
         self
     }
@@ -1259,21 +2100,238 @@ impl<'t> TransformCallback<'t> {
             }
         };
 
-        let t = operation(TransformOperation::new(op));
+        let _ = operation(TransformOperation::new(op));
 
-        if t.hidden {
-            self.path.trace = None;
-        }
+        self
+    }
+
+    /// Apply an another transform function.
+    pub fn path(self, transform: impl FnOnce(TransformPathItem) -> TransformPathItem) -> Self {
+        let _ = transform(TransformPathItem::new(self.path));
 
         self
     }
 
     /// Apply an another transform function.
-    pub fn path(mut self, transform: impl FnOnce(TransformPathItem) -> TransformPathItem) -> Self {
-        let t = transform(TransformPathItem::new(self.path));
+    pub fn with(self, transform: impl FnOnce(Self) -> Self) -> Self {
+        transform(self)
+    }
+}
+
+/// A transform helper that wraps a JSON schema.
+#[must_use]
+pub struct TransformSchema<'t> {
+    schema: &'t mut Schema,
+}
+
+impl<'t> TransformSchema<'t> {
+    /// Create a new transform helper.
+    pub fn new(schema: &'t mut Schema) -> Self {
+        Self { schema }
+    }
+
+    /// Provide or override the description of the schema.
+    #[tracing::instrument(skip_all)]
+    pub fn description(self, desc: &str) -> Self {
+        if let Schema::Object(schema) = &mut *self.schema {
+            schema.metadata().description = Some(desc.into());
+        }
+        self
+    }
+
+    /// Provide or override the description of a value of an enum,
+    /// `value` is the serialized value, e.g. `active` for the
+    /// variant `Active` with `#[serde(rename_all = "snake_case")]`.
+    ///
+    /// See [`schema`](crate::schema#enum-descriptions) for how
+    /// the descriptions are documented.
+    #[tracing::instrument(skip_all)]
+    pub fn variant_description(self, value: &str, desc: &str) -> Self {
+        let found = match serde_json::to_value(&*self.schema) {
+            Ok(serde_json::Value::Object(mut schema)) => {
+                let found = crate::schema::set_variant_description(&mut schema, value, desc);
+                if let Ok(schema) = serde_json::from_value(schema.into()) {
+                    *self.schema = schema;
+                }
+                found
+            }
+            _ => false,
+        };
+
+        if !found {
+            in_context(|ctx| ctx.error(Error::EnumValueNotExists(value.into())));
+        }
+
+        self
+    }
+
+    /// Provide or override the description of a property.
+    #[tracing::instrument(skip_all)]
+    pub fn property_description(self, property: &str, desc: &str) -> Self {
+        self.property_schema(property, |schema| {
+            schema.metadata().description = Some(desc.into());
+        })
+    }
+
+    /// Mark a property as read-only, i.e. only documented in responses
+    /// if the schemas are split, see [`schema`](crate::schema#read-and-write-schemas).
+    #[tracing::instrument(skip_all)]
+    pub fn read_only(self, property: &str) -> Self {
+        self.property_schema(property, |schema| schema.metadata().read_only = true)
+    }
+
+    /// Mark a property as write-only, i.e. only documented in requests
+    /// if the schemas are split, see [`schema`](crate::schema#read-and-write-schemas).
+    #[tracing::instrument(skip_all)]
+    pub fn write_only(self, property: &str) -> Self {
+        self.property_schema(property, |schema| schema.metadata().write_only = true)
+    }
+
+    /// Set the visibility of the schema, see [`visibility`](crate::visibility),
+    /// use [`field`](Self::field) for the visibility of properties.
+    #[tracing::instrument(skip_all)]
+    pub fn visibility(self, visibility: ApiVisibility) -> Self {
+        if let Schema::Object(schema) = &mut *self.schema {
+            let (key, value) = visibility.extension();
+            schema.extensions.insert(key, value);
+        }
+        self
+    }
 
-        if t.hidden {
-            self.hidden = true;
+    /// Mark a property as sensitive, see [`redaction`](crate::redaction).
+    #[tracing::instrument(skip_all)]
+    pub fn sensitive(self, property: &str) -> Self {
+        self.property_schema(property, |schema| {
+            schema
+                .extensions
+                .insert(crate::redaction::SENSITIVE.into(), true.into());
+        })
+    }
+
+    /// Document the keys of a map property with a pattern,
+    /// as `propertyNames`, e.g. `^[a-z][a-z0-9_]*$` for identifiers.
+    #[tracing::instrument(skip_all)]
+    pub fn property_names(self, property: &str, pattern: &str) -> Self {
+        self.property_schema(property, |schema| {
+            let mut names = SchemaObject {
+                instance_type: Some(InstanceType::String.into()),
+                ..Default::default()
+            };
+            names.string().pattern = Some(pattern.into());
+            schema.object().property_names = Some(Box::new(names.into()));
+        })
+    }
+
+    /// Document the maximum number of entries of a map property,
+    /// as `maxProperties`.
+    #[tracing::instrument(skip_all)]
+    pub fn max_properties(self, property: &str, max: u32) -> Self {
+        self.property_schema(property, |schema| {
+            schema.object().max_properties = Some(max);
+        })
+    }
+
+    /// Transform the schema of a property, e.g. to add an example
+    /// that UIs use to fill in request forms:
+    ///
+    /// ```
+    /// # use aide::transform::TransformSchema;
+    /// # use schemars::{schema::Schema, schema_for, JsonSchema};
+    /// #[derive(JsonSchema)]
+    /// struct User {
+    ///     email: String,
+    /// }
+    ///
+    /// let mut schema = Schema::Object(schema_for!(User).schema);
+    ///
+    /// TransformSchema::new(&mut schema).field("email", |f| {
+    ///     f.example("alice@example.com")
+    ///         .description("The address for notifications.")
+    /// });
+    ///
+    /// let schema = serde_json::to_value(&schema).unwrap();
+    /// assert_eq!(
+    ///     schema["properties"]["email"]["examples"],
+    ///     serde_json::json!(["alice@example.com"])
+    /// );
+    /// ```
+    #[tracing::instrument(skip_all)]
+    pub fn field(
+        self,
+        property: &str,
+        transform: impl FnOnce(TransformSchema) -> TransformSchema,
+    ) -> Self {
+        match property_mut(self.schema, property) {
+            Some(schema) => {
+                let _ = transform(TransformSchema::new(schema));
+            }
+            None => in_context(|ctx| ctx.error(Error::PropertyNotExists(property.into()))),
+        }
+
+        self
+    }
+
+    /// Add an example value of the schema, documented in `examples`.
+    ///
+    /// An error is reported if the example cannot be serialized to JSON.
+    #[tracing::instrument(skip_all)]
+    pub fn example(self, example: impl Serialize) -> Self {
+        if let Schema::Object(schema) = &mut *self.schema {
+            match serde_json::to_value(example) {
+                Ok(example) => schema.metadata().examples.push(example),
+                Err(error) => in_context(|ctx| ctx.error(Error::InvalidExample(error))),
+            }
+        }
+        self
+    }
+
+    /// Add the component schemas with the given names to the `oneOf`
+    /// of the schema, i.e. a value matches exactly one of them.
+    #[tracing::instrument(skip_all)]
+    pub fn one_of<I, S>(self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if let Schema::Object(schema) = &mut *self.schema {
+            add_component_refs(&mut schema.subschemas().one_of, names);
+        }
+        self
+    }
+
+    /// Add the component schemas with the given names to the `allOf`
+    /// of the schema, i.e. a value matches all of them, e.g. to extend
+    /// a base schema.
+    #[tracing::instrument(skip_all)]
+    pub fn all_of<I, S>(self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        if let Schema::Object(schema) = &mut *self.schema {
+            add_component_refs(&mut schema.subschemas().all_of, names);
+        }
+        self
+    }
+
+    /// Document the property that tells the schemas of the `oneOf`
+    /// apart as the `discriminator` of the schema, its values are
+    /// the names of the component schemas.
+    #[tracing::instrument(skip_all)]
+    pub fn discriminator(self, property: &str) -> Self {
+        if let Schema::Object(schema) = &mut *self.schema {
+            schema.extensions.insert(
+                "discriminator".into(),
+                serde_json::json!({ "propertyName": property }),
+            );
+        }
+        self
+    }
+
+    fn property_schema(self, property: &str, set: impl FnOnce(&mut SchemaObject)) -> Self {
+        match property_mut(self.schema, property) {
+            Some(Schema::Object(schema)) => set(schema),
+            _ => in_context(|ctx| ctx.error(Error::PropertyNotExists(property.into()))),
         }
 
         self
@@ -1283,6 +2341,71 @@ impl<'t> TransformCallback<'t> {
     pub fn with(self, transform: impl FnOnce(Self) -> Self) -> Self {
         transform(self)
     }
+
+    /// Access the inner schema.
+    #[inline]
+    pub fn inner_mut(&mut self) -> &mut Schema {
+        self.schema
+    }
+}
+
+/// Add references to the component schemas with the given names
+/// to a list of schemas, skipping the ones that are already in it.
+fn add_component_refs<I, S>(schemas: &mut Option<Vec<Schema>>, names: I)
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let schemas = schemas.get_or_insert_with(Vec::new);
+    for name in names {
+        let reference = Schema::new_ref(format!("#/components/schemas/{}", name.as_ref()));
+        if !schemas.contains(&reference) {
+            schemas.push(reference);
+        }
+    }
+}
+
+fn property_mut<'s>(schema: &'s mut Schema, property: &str) -> Option<&'s mut Schema> {
+    match schema {
+        Schema::Object(schema) => schema.object.as_mut()?.properties.get_mut(property),
+        Schema::Bool(_) => None,
+    }
+}
+
+/// Add a response with the given status code.
+///
+/// A hidden response does not replace a visible one, and a
+/// hidden response is replaced without reporting an error.
+fn insert_response(
+    ctx: &mut GenContext,
+    responses: &mut Responses,
+    status: StatusCode,
+    res: Response,
+) {
+    match responses.responses.get(&status) {
+        Some(ReferenceOr::Item(existing)) if is_hidden(&existing.extensions) => {}
+        Some(_) if is_hidden(&res.extensions) => return,
+        Some(_) => ctx.error(Error::ResponseExists(status.clone())),
+        None => {}
+    }
+
+    responses.responses.insert(status, ReferenceOr::Item(res));
+}
+
+/// Set the default response if there is none, or
+/// replace it in the same way as [`insert_response`].
+fn insert_default_response(ctx: &mut GenContext, responses: &mut Responses, res: Response) {
+    match &responses.default {
+        Some(ReferenceOr::Item(existing)) if is_hidden(&existing.extensions) => {}
+        Some(_) if is_hidden(&res.extensions) => return,
+        Some(_) => {
+            ctx.error(Error::DefaultResponseExists);
+            return;
+        }
+        None => {}
+    }
+
+    responses.default = Some(ReferenceOr::Item(res));
 }
 
 fn filter_no_duplicate_response(err: &Error) -> bool {
@@ -5,67 +5,219 @@ use std::borrow::Cow;
 
 use crate::{
     gen::GenContext,
-    openapi::{Operation, PathItem},
+    helpers::{add_missing_parameters, add_response_if_missing, path_parameter, response_header},
+    openapi::{
+        ApiKeyLocation, OpenApi, Operation, Parameter, PathItem, ReferenceOr, Response,
+        SecurityScheme, StatusCode,
+    },
+    operation::PATH_POSITION,
+    visibility::is_hidden,
     Error,
 };
 
+/// The extension on path items of wildcard routes
+/// with the name of the wildcard parameter.
+///
+/// OpenAPI path parameters cannot span multiple segments,
+/// so the wildcard of `/files/*path` is documented as the regular
+/// parameter of `/files/{path}` and the path item is marked
+/// with `x-wildcard: "path"` instead.
+pub const WILDCARD: &str = "x-wildcard";
+
+/// A segment of a route.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RouteSegment<'a> {
+    Static(&'a str),
+    Param { name: &'a str, optional: bool },
+    Wildcard(&'a str),
+}
+
+impl<'a> RouteSegment<'a> {
+    /// Parse a route segment in either the axum (`:id`, `*rest`)
+    /// or the template (`{id}`, `{*rest}`, `{rest+}`) notation,
+    /// parameters ending with `?` are optional.
+    pub(crate) fn parse(segment: &'a str) -> Self {
+        let param = segment.strip_prefix(':').or_else(|| {
+            segment
+                .strip_prefix('{')
+                .and_then(|s| s.strip_suffix('}'))
+                .filter(|s| !s.starts_with('*') && !s.ends_with('+'))
+        });
+        if let Some(name) = param {
+            return match name.strip_suffix('?') {
+                Some(name) => Self::Param {
+                    name,
+                    optional: true,
+                },
+                None => Self::Param {
+                    name,
+                    optional: false,
+                },
+            };
+        }
+
+        let wildcard = segment.strip_prefix('*').or_else(|| {
+            let name = segment.strip_prefix('{')?.strip_suffix('}')?;
+            name.strip_prefix('*').or_else(|| name.strip_suffix('+'))
+        });
+        match wildcard {
+            Some(name) => Self::Wildcard(name),
+            None => Self::Static(segment),
+        }
+    }
+
+    pub(crate) fn name(&self) -> Option<&'a str> {
+        match *self {
+            Self::Static(_) => None,
+            Self::Param { name, .. } | Self::Wildcard(name) => Some(name),
+        }
+    }
+}
+
 /// Transform colon path params to the notation
 /// used in `OpenApi`.
 ///
-/// Axum wildcard routes are not supported by OpenAPI 3 but will be indicated as a param with a trailing `+` 
-/// 
+/// Axum wildcard routes are not supported by OpenAPI 3,
+/// they are turned into regular parameters, see [`WILDCARD`].
+///
 /// # Examples
 ///
 /// The path `/users/:id` is turned into `/users/{id}`.
-/// The path `/:id/:repo/*tree` is turned into `/{id}/{repo}/{tree+}`.
-
+/// The path `/:id/:repo/*tree` is turned into `/{id}/{repo}/{tree}`.
 #[must_use]
 pub fn path_colon_params(s: &str) -> Cow<str> {
-    if !s.contains(':') {
-        return s.into();
+    let rewritten = s
+        .split('/')
+        .map(|segment| match RouteSegment::parse(segment) {
+            RouteSegment::Static(segment) => segment.into(),
+            param => Cow::Owned(format!("{{{}}}", param.name().unwrap_or_default())),
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+
+    if rewritten == s {
+        s.into()
+    } else {
+        rewritten.into()
     }
+}
 
-    let mut rewritten = String::with_capacity(s.len());
+/// The name of the wildcard parameter of a route, if any.
+pub(crate) fn path_wildcard(route: &str) -> Option<&str> {
+    route
+        .split('/')
+        .find_map(|segment| match RouteSegment::parse(segment) {
+            RouteSegment::Wildcard(name) => Some(name),
+            _ => None,
+        })
+}
 
-    #[derive(Clone, Copy)]
-    enum State {
-        None,
-        WasParam,
-        WasWildcard
-    }
-    let mut state = State::None;
-    for c in s.chars() {
-        match (state, c) {
-            (State::None, ':') => {
-                rewritten.push('{');
-                state = State::WasParam;
+/// Report the parts of a route that cannot be
+/// represented by an OpenAPI path template.
+pub(crate) fn check_path_template(ctx: &mut GenContext, route: &str) {
+    let segments: Vec<_> = route.split('/').map(RouteSegment::parse).collect();
+    let mut names = Vec::new();
+
+    for (i, segment) in segments.iter().enumerate() {
+        let reason = match *segment {
+            RouteSegment::Static(s) if s.contains(['{', '}']) => {
+                Some("path templates cannot contain braces outside of parameters".to_string())
             }
-            (State::WasParam, '/') => {
-                rewritten.push('}');
-                rewritten.push(c);
-                state = State::None;
+            RouteSegment::Static(_) => None,
+            RouteSegment::Param { name, optional } => {
+                if optional {
+                    Some(format!(
+                        r#"the parameter "{name}" is optional, but path parameters are always required, document a route for each variant instead"#
+                    ))
+                } else {
+                    None
+                }
             }
-            (_, '*') => {
-                rewritten.push('{');
-                state = State::WasWildcard;
-            },
-            (_, _) => {
-                rewritten.push(c);
+            RouteSegment::Wildcard(name) => {
+                if i + 1 == segments.len() {
+                    None
+                } else {
+                    Some(format!(r#"the wildcard "{name}" must be the last segment"#))
+                }
             }
+        };
+        if let Some(reason) = reason {
+            ctx.error(Error::InvalidPathTemplate(route.into(), reason));
         }
+
+        let Some(name) = segment.name() else {
+            continue;
+        };
+        if name.is_empty() || name.contains(['{', '}', '?', '*']) {
+            ctx.error(Error::InvalidPathTemplate(
+                route.into(),
+                format!(r#"the parameter name "{name}" is not valid"#),
+            ));
+        } else if names.contains(&name) {
+            ctx.error(Error::InvalidPathTemplate(
+                route.into(),
+                format!(r#"the parameter "{name}" appears more than once"#),
+            ));
+        }
+        names.push(name);
     }
+}
+
+/// Split an identifier into lowercase words for case conversions,
+/// e.g. `fooBar`, `foo_bar` and `Foo Bar` all become `["foo", "bar"]`.
+///
+/// Acronyms are words of their own, `HTTPServer` becomes `["http", "server"]`.
+pub(crate) fn case_words(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut prev_lower = false;
+    let mut prev_upper = false;
 
-    match state {
-        State::WasParam => {
-            rewritten += "}"
-        },
-        State::WasWildcard => {
-            rewritten += "+}"
+    for c in s.chars() {
+        if !c.is_ascii_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            prev_lower = false;
+            prev_upper = false;
+            continue;
         }
-        _=> {}
+
+        if c.is_ascii_uppercase() && prev_lower && !word.is_empty() {
+            words.push(std::mem::take(&mut word));
+        }
+
+        // The last uppercase letter of an acronym starts the next word.
+        if c.is_ascii_lowercase() && prev_upper && word.len() > 1 {
+            let first = word.pop();
+            words.push(std::mem::take(&mut word));
+            word.extend(first);
+        }
+
+        prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        prev_upper = c.is_ascii_uppercase();
+        word.push(c.to_ascii_lowercase());
+    }
+
+    if !word.is_empty() {
+        words.push(word);
     }
 
-    rewritten.into()
+    words
+}
+
+/// Convert an identifier to `PascalCase`.
+pub(crate) fn pascal_case(s: &str) -> String {
+    case_words(s)
+        .into_iter()
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        })
+        .collect()
 }
 
 /// Iterate over all operations in a path item.
@@ -102,64 +254,29 @@ pub fn iter_operations_mut(
     vec.into_iter()
 }
 
-pub(crate) fn merge_paths(ctx: &mut GenContext, path: &str, target: &mut PathItem, from: PathItem) {
-    if let Some(op) = from.get {
-        if target.get.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "get"));
-        } else {
-            target.get = Some(op);
-        }
-    }
-    if let Some(op) = from.put {
-        if target.put.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "put"));
-        } else {
-            target.put = Some(op);
-        }
-    }
-    if let Some(op) = from.post {
-        if target.post.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "post"));
-        } else {
-            target.post = Some(op);
-        }
-    }
-    if let Some(op) = from.delete {
-        if target.delete.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "delete"));
-        } else {
-            target.delete = Some(op);
-        }
-    }
-    if let Some(op) = from.options {
-        if target.options.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "options"));
-        } else {
-            target.options = Some(op);
-        }
-    }
-    if let Some(op) = from.head {
-        if target.head.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "head"));
-        } else {
-            target.head = Some(op);
-        }
-    }
-    if let Some(op) = from.patch {
-        if target.patch.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "patch"));
-        } else {
-            target.patch = Some(op);
-        }
-    }
-    if let Some(op) = from.trace {
-        if target.trace.is_some() {
-            ctx.error(Error::OperationExists(path.to_string(), "trace"));
-        } else {
-            target.trace = Some(op);
-        }
+/// The lowercase methods of the operations of a path item.
+pub(crate) const METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// The operation of the path item for the lowercase method.
+pub(crate) fn operation_mut<'a>(path: &'a mut PathItem, method: &str) -> &'a mut Option<Operation> {
+    match method {
+        "delete" => &mut path.delete,
+        "get" => &mut path.get,
+        "head" => &mut path.head,
+        "options" => &mut path.options,
+        "patch" => &mut path.patch,
+        "post" => &mut path.post,
+        "put" => &mut path.put,
+        "trace" => &mut path.trace,
+        _ => unreachable!(),
     }
+}
 
+/// Merge the path-level documentation of `from` into `target`,
+/// the operations are merged separately.
+pub(crate) fn merge_paths(target: &mut PathItem, from: PathItem) {
     if let Some(new_desc) = from.description {
         match &mut target.description {
             Some(desc) => {
@@ -183,6 +300,302 @@ pub(crate) fn merge_paths(ctx: &mut GenContext, path: &str, target: &mut PathIte
     target.extensions.extend(from.extensions);
 }
 
+/// Add the `HEAD` and `OPTIONS` operations derived from
+/// the existing ones if enabled in the context.
+pub(crate) fn derive_implicit_operations(ctx: &mut GenContext, path: &mut PathItem) {
+    if ctx.derive_head_operations && path.head.is_none() {
+        path.head = path.get.as_ref().map(|get| {
+            let mut head = get.clone();
+            head.operation_id = head.operation_id.map(|id| id + "_head");
+            head.request_body = None;
+
+            if let Some(responses) = &mut head.responses {
+                for res in responses
+                    .default
+                    .iter_mut()
+                    .chain(responses.responses.values_mut())
+                    .filter_map(ReferenceOr::as_item_mut)
+                {
+                    res.content.clear();
+                    res.links.clear();
+                }
+            }
+
+            head
+        });
+    }
+
+    if ctx.derive_options_operations && path.options.is_none() {
+        let methods = visible_methods(path);
+        if methods.is_empty() {
+            return;
+        }
+        let methods = methods
+            .into_iter()
+            .chain(["OPTIONS".to_string()])
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let mut allow = response_header::<String>(ctx, "The methods supported by the resource.");
+        allow.example = Some(methods.into());
+
+        let mut res = Response {
+            description: "the supported methods".into(),
+            ..Default::default()
+        };
+        res.headers.insert("Allow".into(), ReferenceOr::Item(allow));
+
+        let mut options = Operation {
+            summary: Some("Supported methods".into()),
+            ..Default::default()
+        };
+        options
+            .responses
+            .get_or_insert_with(Default::default)
+            .responses
+            .insert(StatusCode::Code(204), ReferenceOr::Item(res));
+
+        path.options = Some(options);
+    }
+}
+
+/// Add the `405 Method Not Allowed` response to the operations
+/// of a path without all methods if enabled in the context.
+pub(crate) fn add_method_not_allowed_responses(ctx: &mut GenContext, path: &mut PathItem) {
+    if !ctx.document_method_not_allowed {
+        return;
+    }
+
+    if path.iter().count() == METHODS.len() {
+        return;
+    }
+
+    let methods = visible_methods(path);
+    if methods.is_empty() {
+        return;
+    }
+
+    let mut allow = response_header::<String>(ctx, "The methods supported by the resource.");
+    allow.example = Some(methods.join(", ").into());
+
+    let mut res = Response {
+        description: "the method is not supported by the resource".into(),
+        ..Default::default()
+    };
+    res.headers.insert("Allow".into(), ReferenceOr::Item(allow));
+
+    for (_, operation) in iter_operations_mut(path) {
+        add_response_if_missing(operation, 405, res.clone());
+    }
+}
+
+/// The methods of the operations of a path that are not hidden.
+fn visible_methods(path: &PathItem) -> Vec<String> {
+    path.iter()
+        .filter(|(_, op)| !is_hidden(&op.extensions))
+        .map(|(method, _)| method.to_uppercase())
+        .collect()
+}
+
+/// The names of the parameters of a route, e.g. `/:id/*rest` or `/{id}/{rest+}`.
+pub(crate) fn path_param_names(path: &str) -> Vec<&str> {
+    path.split('/')
+        .filter_map(|segment| RouteSegment::parse(segment).name())
+        .collect()
+}
+
+/// Name the path parameters that are marked with their position,
+/// e.g. of `Path<(A, B)>`, after the parameters of the route.
+pub(crate) fn name_path_parameters(ctx: &mut GenContext, route: &str, path: &mut PathItem) {
+    let names = path_param_names(route);
+
+    for (_, op) in iter_operations_mut(path) {
+        let position = |p: &ReferenceOr<Parameter>| {
+            p.as_item()
+                .and_then(|p| p.parameter_data_ref().extensions.get(PATH_POSITION))
+                .and_then(|position| position.as_u64())
+        };
+
+        let count = op
+            .parameters
+            .iter()
+            .filter(|p| position(p).is_some())
+            .count();
+        if count == 0 {
+            continue;
+        }
+        if count != names.len() {
+            ctx.error(Error::PathParameterCount(route.into(), names.len(), count));
+        }
+
+        op.parameters.retain_mut(|p| {
+            let Some(position) = position(p) else {
+                return true;
+            };
+            let Some(name) = names.get(position as usize) else {
+                return false;
+            };
+
+            let data = p.as_item_mut().unwrap().parameter_data_mut();
+            data.name = (*name).into();
+            data.extensions.shift_remove(PATH_POSITION);
+            true
+        });
+    }
+}
+
+/// Document the parameters of the prefix of a nested route, e.g.
+/// `/tenants/:tenant_id`, for the operations that do not document them.
+///
+/// Operations with positional parameters, e.g. of `Path<(A, B)>`,
+/// already document all parameters of the route.
+pub(crate) fn add_prefix_parameters(ctx: &mut GenContext, prefix: &str, path: &mut PathItem) {
+    let names = path_param_names(prefix);
+    if names.is_empty() {
+        return;
+    }
+
+    let documented = path
+        .parameters
+        .iter()
+        .filter_map(ReferenceOr::as_item)
+        .map(|p| p.parameter_data_ref().name.clone())
+        .collect::<Vec<_>>();
+
+    for (_, op) in iter_operations_mut(path) {
+        let positional = op
+            .parameters
+            .iter()
+            .filter_map(ReferenceOr::as_item)
+            .any(|p| {
+                p.parameter_data_ref()
+                    .extensions
+                    .contains_key(PATH_POSITION)
+            });
+        if positional {
+            continue;
+        }
+
+        let params = names
+            .iter()
+            .filter(|name| !documented.iter().any(|d| d == *name))
+            .map(|name| path_parameter::<String>(ctx, name))
+            .collect::<Vec<_>>();
+        add_missing_parameters(ctx, op, params);
+    }
+}
+
+/// The route of a route nested under the prefix, the root
+/// route of the nested router is served at the prefix itself.
+pub(crate) fn nested_route(prefix: &str, route: &str) -> String {
+    let prefix = prefix.trim_end_matches('/');
+
+    if route == "/" && !prefix.is_empty() {
+        prefix.into()
+    } else {
+        format!("{prefix}{route}")
+    }
+}
+
+/// Require the header parameters that are the header of an API key
+/// security scheme that all security requirements of the operation include.
+pub(crate) fn require_security_headers(api: &mut OpenApi) {
+    let Some(components) = &api.components else {
+        return;
+    };
+
+    let headers = components
+        .security_schemes
+        .iter()
+        .filter_map(|(scheme, s)| match s {
+            ReferenceOr::Item(SecurityScheme::ApiKey {
+                location: ApiKeyLocation::Header,
+                name,
+                ..
+            }) => Some((scheme.as_str(), name.as_str())),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+
+    let Some(paths) = &mut api.paths else {
+        return;
+    };
+
+    for path in paths
+        .paths
+        .values_mut()
+        .filter_map(ReferenceOr::as_item_mut)
+    {
+        for (_, op) in iter_operations_mut(path) {
+            let security = if op.security.is_empty() {
+                &api.security
+            } else {
+                &op.security
+            };
+
+            let requires = |header: &str| {
+                !security.is_empty()
+                    && security.iter().all(|requirement| {
+                        requirement.keys().any(|scheme| {
+                            headers
+                                .iter()
+                                .any(|(s, h)| s == scheme && h.eq_ignore_ascii_case(header))
+                        })
+                    })
+            };
+
+            for param in op
+                .parameters
+                .iter_mut()
+                .filter_map(ReferenceOr::as_item_mut)
+            {
+                if let Parameter::Header { parameter_data, .. } = param {
+                    if requires(&parameter_data.name) {
+                        parameter_data.required = true;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Visit all objects of the serialized document, e.g. to rewrite
+/// strings wherever they appear, and deserialize the result.
+///
+/// The document is left unchanged if the result is not a valid document.
+pub(crate) fn transform_objects(
+    api: &mut OpenApi,
+    mut visit: impl FnMut(&mut serde_json::Map<String, serde_json::Value>),
+) {
+    fn visit_objects(
+        value: &mut serde_json::Value,
+        visit: &mut impl FnMut(&mut serde_json::Map<String, serde_json::Value>),
+    ) {
+        match value {
+            serde_json::Value::Object(object) => {
+                visit(object);
+                for value in object.values_mut() {
+                    visit_objects(value, visit);
+                }
+            }
+            serde_json::Value::Array(values) => {
+                for value in values {
+                    visit_objects(value, visit);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let Ok(mut document) = serde_json::to_value(&*api) else {
+        return;
+    };
+    visit_objects(&mut document, &mut visit);
+    if let Ok(transformed) = serde_json::from_value(document) {
+        *api = transformed;
+    }
+}
+
 // FIXME: remove the code below when the upstream openapiv3 3.1 is available.
 pub(crate) use spec::*;
 mod spec {
@@ -261,6 +674,55 @@ mod tests {
     fn test_path_colon_params() {
         assert_eq!(path_colon_params("/users/:id"), "/users/{id}");
         assert_eq!(path_colon_params("/users/:id/addresses/:address-id"), "/users/{id}/addresses/{address-id}");        
-        assert_eq!(path_colon_params("/:id/:repo/*tree"), "/{id}/{repo}/{tree+}");
+        assert_eq!(path_colon_params("/:id/:repo/*tree"), "/{id}/{repo}/{tree}");
+        assert_eq!(path_colon_params("/files/*path"), "/files/{path}");
+        assert_eq!(path_colon_params("/files/{*path}"), "/files/{path}");
+        assert!(matches!(path_colon_params("/users/{id}"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn test_path_wildcard() {
+        assert_eq!(path_wildcard("/files/*path"), Some("path"));
+        assert_eq!(path_wildcard("/files/{path+}"), Some("path"));
+        assert_eq!(path_wildcard("/files/:path"), None);
+        assert_eq!(
+            path_param_names("/:id/{repo}/*tree"),
+            ["id", "repo", "tree"]
+        );
+    }
+
+    #[test]
+    fn test_case_words() {
+        assert_eq!(case_words("fooBar"), ["foo", "bar"]);
+        assert_eq!(case_words("Foo Bar_baz"), ["foo", "bar", "baz"]);
+        assert_eq!(case_words("HTTPServer"), ["http", "server"]);
+        assert_eq!(case_words("getHTTPServer2"), ["get", "http", "server2"]);
+        assert_eq!(case_words("ID"), ["id"]);
+        assert_eq!(pascal_case("HTTPServer"), "HttpServer");
+    }
+
+    #[test]
+    fn test_check_path_template() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_ = errors.clone();
+        crate::gen::on_error(move |err| errors_.borrow_mut().push(err.to_string()));
+
+        crate::gen::in_context(|ctx| {
+            check_path_template(ctx, "/users/:id/files/*path");
+            check_path_template(ctx, "/users/:id?");
+            check_path_template(ctx, "/files/*path/raw");
+            check_path_template(ctx, "/users/:id/friends/:id");
+            check_path_template(ctx, "/users/:");
+        });
+        crate::gen::reset_context();
+
+        let errors = errors.borrow();
+        assert_eq!(errors.len(), 4);
+        assert!(errors[0].contains(r#""id" is optional"#));
+        assert!(errors[1].contains("must be the last segment"));
+        assert!(errors[2].contains("more than once"));
+        assert!(errors[3].contains(r#"name "" is not valid"#));
     }
 }
@@ -0,0 +1,170 @@
+use std::{
+    future::poll_fn,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::{Body, HttpBody},
+    http::{header::CONTENT_LENGTH, HeaderMap},
+};
+use bytes::{Bytes, BytesMut};
+use http_body::{Frame, SizeHint};
+
+/// A body that was buffered with [`buffer_body`].
+pub(crate) enum Buffered {
+    /// The complete body.
+    Complete(Bytes),
+    /// The body is larger than the limit, it is returned
+    /// unchanged including the part that was already read.
+    TooLarge(Body),
+}
+
+/// Buffer a body of at most `limit` bytes.
+///
+/// Unlike [`axum::body::to_bytes`], a body that turns out to be
+/// too large is not lost, so it can still be passed on as-is.
+/// Bodies that are known to be too large from their `Content-Length`
+/// header or their size hint are not read at all.
+pub(crate) async fn buffer_body(
+    headers: &HeaderMap,
+    mut body: Body,
+    limit: usize,
+) -> Result<Buffered, axum::Error> {
+    if exceeds_limit(headers, &body, limit) {
+        return Ok(Buffered::TooLarge(body));
+    }
+
+    let mut buffer = BytesMut::new();
+
+    while let Some(frame) = poll_fn(|cx| Pin::new(&mut body).poll_frame(cx)).await {
+        // Trailers are not kept, the same as with `to_bytes`.
+        let Ok(data) = frame?.into_data() else {
+            continue;
+        };
+
+        buffer.extend_from_slice(&data);
+
+        if buffer.len() > limit {
+            return Ok(Buffered::TooLarge(Body::new(Prefixed {
+                prefix: Some(buffer.freeze()),
+                rest: body,
+            })));
+        }
+    }
+
+    Ok(Buffered::Complete(buffer.freeze()))
+}
+
+/// Whether the body is known to be larger than `limit`
+/// from its `Content-Length` header or size hint.
+fn exceeds_limit(headers: &HeaderMap, body: &Body, limit: usize) -> bool {
+    let limit = limit as u64;
+
+    let content_length = headers
+        .get(CONTENT_LENGTH)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.parse::<u64>().ok());
+
+    content_length.is_some_and(|length| length > limit) || body.size_hint().lower() > limit
+}
+
+/// The part of a body that was already read, followed by the rest.
+struct Prefixed {
+    prefix: Option<Bytes>,
+    rest: Body,
+}
+
+impl HttpBody for Prefixed {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        if let Some(prefix) = self.prefix.take() {
+            return Poll::Ready(Some(Ok(Frame::data(prefix))));
+        }
+
+        Pin::new(&mut self.rest).poll_frame(cx)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.prefix.is_none() && self.rest.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        let prefix = self.prefix.as_ref().map_or(0, |p| p.len() as u64);
+        let rest = self.rest.size_hint();
+
+        let mut hint = SizeHint::new();
+        hint.set_lower(rest.lower() + prefix);
+        if let Some(upper) = rest.upper() {
+            hint.set_upper(upper + prefix);
+        }
+        hint
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::VecDeque,
+        convert::Infallible,
+        pin::Pin,
+        task::{Context, Poll},
+    };
+
+    use axum::body::{to_bytes, Body, HttpBody};
+    use bytes::Bytes;
+    use http::HeaderMap;
+    use http_body::Frame;
+
+    use super::{buffer_body, Buffered};
+
+    /// A body of chunks without a size hint.
+    struct Chunks(VecDeque<&'static str>);
+
+    impl HttpBody for Chunks {
+        type Data = Bytes;
+        type Error = Infallible;
+
+        fn poll_frame(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+        ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+            Poll::Ready(
+                self.0
+                    .pop_front()
+                    .map(|chunk| Ok(Frame::data(chunk.into()))),
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_buffer_body() {
+        let body = buffer_body(&HeaderMap::new(), Body::from("small"), 8)
+            .await
+            .unwrap();
+        assert!(matches!(body, Buffered::Complete(bytes) if bytes == "small"));
+
+        // Known to be too large from the size hint.
+        let body = buffer_body(&HeaderMap::new(), Body::from("too large"), 8)
+            .await
+            .unwrap();
+        assert!(matches!(body, Buffered::TooLarge(_)));
+
+        // Too large after reading some of the chunks.
+        let chunks = Body::new(Chunks(VecDeque::from(["too ", "large ", "body"])));
+        let Buffered::TooLarge(body) = buffer_body(&HeaderMap::new(), chunks, 8).await.unwrap()
+        else {
+            panic!("the body is larger than the limit");
+        };
+        assert_eq!(to_bytes(body, usize::MAX).await.unwrap(), "too large body");
+
+        let chunks = Body::new(Chunks(VecDeque::from(["fits ", "in"])));
+        let body = buffer_body(&HeaderMap::new(), chunks, 8).await.unwrap();
+        assert!(matches!(body, Buffered::Complete(bytes) if bytes == "fits in"));
+    }
+}
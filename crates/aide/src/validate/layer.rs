@@ -0,0 +1,231 @@
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use axum::{
+    body::Body,
+    http::{header::CONTENT_TYPE, HeaderMap, Request, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use tower_layer::Layer;
+use tower_service::Service;
+
+use super::{
+    body::{buffer_body, Buffered},
+    SpecValidator, Violation,
+};
+use crate::{helpers::is_json_media_type, openapi::OpenApi};
+
+/// A layer that validates requests and responses
+/// against the documentation, see [`SpecValidator`].
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct SpecValidationLayer {
+    validator: Arc<SpecValidator>,
+    validate_responses: bool,
+    body_limit: usize,
+}
+
+impl SpecValidationLayer {
+    /// Create a layer from the finished documentation.
+    ///
+    /// Responses are only validated in debug builds by default.
+    pub fn new(api: &OpenApi) -> Self {
+        Self::from_validator(Arc::new(SpecValidator::new(api)))
+    }
+
+    /// Create a layer from an existing validator.
+    pub fn from_validator(validator: Arc<SpecValidator>) -> Self {
+        Self {
+            validator,
+            validate_responses: cfg!(debug_assertions),
+            body_limit: 2 * 1024 * 1024,
+        }
+    }
+
+    /// Whether responses are validated, violations are logged
+    /// as warnings and the responses are returned as-is.
+    pub fn validate_responses(mut self, validate: bool) -> Self {
+        self.validate_responses = validate;
+        self
+    }
+
+    /// The maximum size of bodies that are validated in bytes,
+    /// 2 MB by default.
+    ///
+    /// Larger requests are rejected, larger responses
+    /// are returned without being validated.
+    pub fn body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = limit;
+        self
+    }
+}
+
+impl<S> Layer<S> for SpecValidationLayer {
+    type Service = SpecValidation<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        SpecValidation {
+            inner,
+            config: self.clone(),
+        }
+    }
+}
+
+/// The service created by [`SpecValidationLayer`].
+#[derive(Debug, Clone)]
+pub struct SpecValidation<S> {
+    inner: S,
+    config: SpecValidationLayer,
+}
+
+impl<S> Service<Request<Body>> for SpecValidation<S>
+where
+    S: Service<Request<Body>, Response = Response> + Clone + Send + 'static,
+    S::Future: Send,
+{
+    type Response = Response;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        // The clone might not be ready, use the one that was polled.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let config = self.config.clone();
+
+        Box::pin(async move {
+            let (parts, body) = req.into_parts();
+            let method = parts.method.as_str().to_ascii_lowercase();
+            let path = parts.uri.path().to_string();
+
+            let Some((operation, path_params)) = config.validator.operation(&method, &path) else {
+                return inner.call(Request::from_parts(parts, body)).await;
+            };
+
+            let mut violations =
+                operation.validate_parameters(&path_params, parts.uri.query(), &parts.headers);
+
+            let body = if operation.has_body() {
+                match buffer_body(&parts.headers, body, config.body_limit).await {
+                    Ok(Buffered::Complete(bytes)) => {
+                        violations
+                            .extend(operation.validate_body(content_type(&parts.headers), &bytes));
+                        Body::from(bytes)
+                    }
+                    Ok(Buffered::TooLarge(_)) => {
+                        return Ok(body_rejection(format!(
+                            "the request body is larger than {} bytes",
+                            config.body_limit
+                        )));
+                    }
+                    Err(error) => {
+                        return Ok(body_rejection(format!(
+                            "failed to read the request body: {error}"
+                        )));
+                    }
+                }
+            } else {
+                body
+            };
+
+            if !violations.is_empty() {
+                return Ok(ValidationRejection(violations).into_response());
+            }
+
+            let res = inner.call(Request::from_parts(parts, body)).await?;
+
+            if !config.validate_responses
+                || !content_type(res.headers()).is_some_and(is_json_media_type)
+            {
+                return Ok(res);
+            }
+
+            let (parts, body) = res.into_parts();
+
+            let bytes = match buffer_body(&parts.headers, body, config.body_limit).await {
+                Ok(Buffered::Complete(bytes)) => bytes,
+                Ok(Buffered::TooLarge(body)) => {
+                    tracing::debug!(
+                        method,
+                        path = operation.path(),
+                        "the response body is too large to be validated"
+                    );
+                    return Ok(Response::from_parts(parts, body));
+                }
+                Err(error) => {
+                    // The body is partially consumed, so it cannot be returned.
+                    tracing::warn!(%error, "failed to read the response body for validation");
+                    return Ok((
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        "failed to read the response body",
+                    )
+                        .into_response());
+                }
+            };
+
+            let violations = operation.validate_response(
+                parts.status.as_u16(),
+                content_type(&parts.headers),
+                &bytes,
+            );
+
+            for violation in violations {
+                tracing::warn!(
+                    method,
+                    path = operation.path(),
+                    status = parts.status.as_u16(),
+                    %violation,
+                    "response does not match the documentation"
+                );
+            }
+
+            Ok(Response::from_parts(parts, Body::from(bytes)))
+        })
+    }
+}
+
+fn body_rejection(message: String) -> Response {
+    ValidationRejection(vec![Violation::new(
+        super::ViolationLocation::Body,
+        message,
+    )])
+    .into_response()
+}
+
+fn content_type(headers: &HeaderMap) -> Option<&str> {
+    headers.get(CONTENT_TYPE).and_then(|h| h.to_str().ok())
+}
+
+/// The rejection of requests that do not match
+/// the documentation, responds with `400 Bad Request`.
+#[derive(Debug, Clone)]
+pub struct ValidationRejection(pub Vec<Violation>);
+
+#[derive(Serialize)]
+struct ValidationErrorResponse {
+    error: &'static str,
+    violations: Vec<Violation>,
+}
+
+impl IntoResponse for ValidationRejection {
+    fn into_response(self) -> Response {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ValidationErrorResponse {
+                error: "request validation failed",
+                violations: self.0,
+            }),
+        )
+            .into_response()
+    }
+}
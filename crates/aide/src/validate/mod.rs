@@ -0,0 +1,933 @@
+//! Request and response validation against the generated documentation.
+//!
+//! [`SpecValidator`] compiles the schemas of all documented operations
+//! and validates parameters, request bodies and responses against them.
+//!
+//! [`SpecValidationLayer`] uses it as a [`tower`](https://docs.rs/tower)
+//! layer: invalid requests are rejected with `400 Bad Request` and a
+//! list of [`Violation`]s, invalid responses are logged (by default
+//! only in debug builds). This turns the documentation into a contract
+//! that is enforced at runtime.
+//!
+//! Only JSON bodies are validated, other media types are passed through.
+//! Requests that do not match any documented operation are not validated.
+//!
+//! The documentation itself can be checked against the `OpenAPI`
+//! specification with [`OpenApi::validate`].
+//!
+//! # Examples
+//!
+//! ```ignore
+//! let mut api = OpenApi::default();
+//!
+//! let app = ApiRouter::new()
+//!     .api_route("/todos", post(create_todo))
+//!     .finish_api(&mut api);
+//!
+//! let app = app.layer(SpecValidationLayer::new(&api));
+//! ```
+
+use std::fmt;
+
+use indexmap::IndexMap;
+use jsonschema::{Draft, JSONSchema};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    helpers::{is_json_media_type, is_same_parameter},
+    openapi::{
+        MediaType, OpenApi, Operation, Parameter, ParameterSchemaOrContent, PathItem, ReferenceOr,
+        RequestBody, Response, StatusCode,
+    },
+    util::WILDCARD,
+};
+
+mod body;
+mod layer;
+mod spec;
+
+pub(crate) use body::{buffer_body, Buffered};
+pub use layer::{SpecValidation, SpecValidationLayer, ValidationRejection};
+pub use spec::{SpecViolation, SpecViolationKind};
+
+/// A violation of the documented contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// The part of the request or response that is invalid.
+    pub location: ViolationLocation,
+    /// The name of the invalid parameter.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// A JSON pointer to the invalid value.
+    pub pointer: String,
+    /// A description of the violation.
+    pub message: String,
+}
+
+impl Violation {
+    pub(crate) fn new(location: ViolationLocation, message: impl Into<String>) -> Self {
+        Self {
+            location,
+            name: None,
+            pointer: String::new(),
+            message: message.into(),
+        }
+    }
+
+    fn named(mut self, name: &str) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.location)?;
+        if let Some(name) = &self.name {
+            write!(f, " \"{name}\"")?;
+        }
+        if !self.pointer.is_empty() {
+            write!(f, " at {}", self.pointer)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// The location of a [`Violation`].
+#[allow(missing_docs)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ViolationLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+    Body,
+    Response,
+}
+
+impl fmt::Display for ViolationLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ViolationLocation::Path => "path parameter",
+            ViolationLocation::Query => "query parameter",
+            ViolationLocation::Header => "header",
+            ViolationLocation::Cookie => "cookie",
+            ViolationLocation::Body => "request body",
+            ViolationLocation::Response => "response",
+        })
+    }
+}
+
+/// Validates requests and responses against the documented operations.
+pub struct SpecValidator {
+    operations: Vec<OperationValidator>,
+}
+
+impl fmt::Debug for SpecValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpecValidator")
+            .field("operations", &self.operations)
+            .finish()
+    }
+}
+
+impl SpecValidator {
+    /// Compile the schemas of all operations in the documentation.
+    ///
+    /// Schemas that cannot be compiled are logged and ignored.
+    #[must_use]
+    pub fn new(api: &OpenApi) -> Self {
+        let components = Components::new(api);
+        let mut operations = Vec::new();
+
+        for (path, item) in api.paths.iter().flat_map(|paths| paths.iter()) {
+            let Some(item) = item.as_item() else {
+                continue;
+            };
+
+            for (method, operation) in item.iter() {
+                operations.push(OperationValidator::new(
+                    &components,
+                    method,
+                    path,
+                    item,
+                    operation,
+                ));
+            }
+        }
+
+        Self { operations }
+    }
+
+    /// All documented operations.
+    pub fn operations(&self) -> impl Iterator<Item = &OperationValidator> {
+        self.operations.iter()
+    }
+
+    /// Find the operation for the given method and request path,
+    /// along with the values of the path parameters.
+    ///
+    /// Literal path segments take precedence over parameters.
+    #[must_use]
+    pub fn operation(
+        &self,
+        method: &str,
+        path: &str,
+    ) -> Option<(&OperationValidator, IndexMap<String, String>)> {
+        self.operations
+            .iter()
+            .filter(|op| op.method.eq_ignore_ascii_case(method))
+            .filter_map(|op| op.template.matches(path).map(|params| (op, params)))
+            .max_by_key(|(op, _)| op.template.literals())
+    }
+}
+
+/// The compiled schemas of a single operation.
+pub struct OperationValidator {
+    method: String,
+    path: String,
+    operation_id: Option<String>,
+    template: PathTemplate,
+    parameters: Vec<ParameterValidator>,
+    body: Option<BodyValidator>,
+    responses: Vec<(Option<StatusCode>, ResponseValidator)>,
+}
+
+impl fmt::Debug for OperationValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OperationValidator")
+            .field("method", &self.method)
+            .field("path", &self.path)
+            .field("operation_id", &self.operation_id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl OperationValidator {
+    fn new(
+        components: &Components,
+        method: &str,
+        path: &str,
+        item: &PathItem,
+        operation: &Operation,
+    ) -> Self {
+        let mut parameters: Vec<ParameterValidator> = Vec::new();
+        let mut seen: Vec<&Parameter> = Vec::new();
+
+        for param in operation.parameters.iter().chain(&item.parameters) {
+            let Some(param) = components.parameter(param) else {
+                continue;
+            };
+            let data = param.parameter_data_ref();
+
+            // Operation parameters override the parameters of the path item
+            // with the same location and name.
+            if seen.iter().any(|p| is_same_parameter(p, param)) {
+                continue;
+            }
+            seen.push(param);
+
+            let schema = match &data.format {
+                ParameterSchemaOrContent::Schema(schema) => components
+                    .compile(&serde_json::to_value(&schema.json_schema).unwrap_or_default()),
+                ParameterSchemaOrContent::Content(content) => content
+                    .values()
+                    .next()
+                    .and_then(|media| components.media_schema(media)),
+            };
+
+            parameters.push(ParameterValidator {
+                location: match param {
+                    Parameter::Query { .. } => ViolationLocation::Query,
+                    Parameter::Header { .. } => ViolationLocation::Header,
+                    Parameter::Path { .. } => ViolationLocation::Path,
+                    Parameter::Cookie { .. } => ViolationLocation::Cookie,
+                },
+                name: data.name.clone(),
+                required: data.required,
+                schema,
+            });
+        }
+
+        let body = operation
+            .request_body
+            .as_ref()
+            .and_then(|body| components.request_body(body))
+            .map(|body| BodyValidator {
+                required: body.required,
+                content: compile_content(components, &body.content),
+            });
+
+        let responses = operation
+            .responses
+            .iter()
+            .flat_map(|responses| {
+                responses.default.iter().map(|res| (None, res)).chain(
+                    responses
+                        .responses
+                        .iter()
+                        .map(|(status, res)| (Some(status.clone()), res)),
+                )
+            })
+            .filter_map(|(status, res)| {
+                let res = components.response(res)?;
+                Some((
+                    status,
+                    ResponseValidator {
+                        content: compile_content(components, &res.content),
+                    },
+                ))
+            })
+            .collect();
+
+        Self {
+            method: method.into(),
+            path: path.into(),
+            operation_id: operation.operation_id.clone(),
+            template: PathTemplate::new(
+                path,
+                item.extensions.get(WILDCARD).and_then(Value::as_str),
+            ),
+            parameters,
+            body,
+            responses,
+        }
+    }
+
+    /// The lowercase method of the operation.
+    #[must_use]
+    pub fn method(&self) -> &str {
+        &self.method
+    }
+
+    /// The documented path of the operation.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// The ID of the operation.
+    #[must_use]
+    pub fn operation_id(&self) -> Option<&str> {
+        self.operation_id.as_deref()
+    }
+
+    /// Whether the operation has a documented request body.
+    #[must_use]
+    pub fn has_body(&self) -> bool {
+        self.body.is_some()
+    }
+
+    /// Validate the parameters of a request.
+    #[must_use]
+    pub fn validate_parameters(
+        &self,
+        path_params: &IndexMap<String, String>,
+        query: Option<&str>,
+        headers: &http::HeaderMap,
+    ) -> Vec<Violation> {
+        let query: Vec<(String, String)> = query
+            .and_then(|q| serde_urlencoded::from_str(q).ok())
+            .unwrap_or_default();
+
+        let cookies: Vec<(String, String)> = headers
+            .get_all(http::header::COOKIE)
+            .iter()
+            .filter_map(|h| h.to_str().ok())
+            .flat_map(|h| h.split(';'))
+            .filter_map(|c| c.trim().split_once('='))
+            .map(|(name, value)| (name.into(), value.into()))
+            .collect();
+
+        let mut violations = Vec::new();
+
+        for param in &self.parameters {
+            let values: Vec<String> = match param.location {
+                ViolationLocation::Path => {
+                    path_params.get(&param.name).cloned().into_iter().collect()
+                }
+                ViolationLocation::Query => query
+                    .iter()
+                    .filter(|(name, _)| *name == param.name)
+                    .map(|(_, value)| value.clone())
+                    .collect(),
+                ViolationLocation::Header => headers
+                    .get_all(param.name.as_str())
+                    .iter()
+                    .filter_map(|h| h.to_str().ok())
+                    .map(Into::into)
+                    .collect(),
+                ViolationLocation::Cookie => cookies
+                    .iter()
+                    .filter(|(name, _)| *name == param.name)
+                    .map(|(_, value)| value.clone())
+                    .collect(),
+                ViolationLocation::Body | ViolationLocation::Response => Vec::new(),
+            };
+
+            if values.is_empty() {
+                if param.required {
+                    violations.push(
+                        Violation::new(param.location, "the parameter is required")
+                            .named(&param.name),
+                    );
+                }
+                continue;
+            }
+
+            let Some(schema) = &param.schema else {
+                continue;
+            };
+
+            let candidates = parameter_candidates(&values);
+            if candidates.iter().any(|value| schema.is_valid(value)) {
+                continue;
+            }
+
+            violations.extend(
+                validate_value(schema, &candidates[0], param.location)
+                    .into_iter()
+                    .map(|v| v.named(&param.name)),
+            );
+        }
+
+        violations
+    }
+
+    /// Validate the body of a request.
+    #[must_use]
+    pub fn validate_body(&self, content_type: Option<&str>, body: &[u8]) -> Vec<Violation> {
+        let Some(validator) = &self.body else {
+            return Vec::new();
+        };
+
+        if body.is_empty() {
+            if validator.required {
+                return vec![Violation::new(
+                    ViolationLocation::Body,
+                    "the request body is required",
+                )];
+            }
+            return Vec::new();
+        }
+
+        validate_content(
+            &validator.content,
+            content_type,
+            body,
+            ViolationLocation::Body,
+        )
+    }
+
+    /// Validate a response with the given status code.
+    #[must_use]
+    pub fn validate_response(
+        &self,
+        status: u16,
+        content_type: Option<&str>,
+        body: &[u8],
+    ) -> Vec<Violation> {
+        let Some(validator) = self.response(status) else {
+            return vec![Violation::new(
+                ViolationLocation::Response,
+                format!("the status code {status} is not documented"),
+            )];
+        };
+
+        if body.is_empty() {
+            return Vec::new();
+        }
+
+        validate_content(
+            &validator.content,
+            content_type,
+            body,
+            ViolationLocation::Response,
+        )
+    }
+
+    /// The documented status codes of the responses,
+    /// `None` is the default response.
+    pub fn response_statuses(&self) -> impl Iterator<Item = Option<&StatusCode>> {
+        self.responses.iter().map(|(status, _)| status.as_ref())
+    }
+
+    fn response(&self, status: u16) -> Option<&ResponseValidator> {
+        let find = |expected: Option<StatusCode>| {
+            self.responses
+                .iter()
+                .find(|(s, _)| *s == expected)
+                .map(|(_, res)| res)
+        };
+
+        find(Some(StatusCode::Code(status)))
+            .or_else(|| find(Some(StatusCode::Range(status / 100))))
+            .or_else(|| find(None))
+    }
+}
+
+struct ParameterValidator {
+    location: ViolationLocation,
+    name: String,
+    required: bool,
+    schema: Option<JSONSchema>,
+}
+
+struct BodyValidator {
+    required: bool,
+    content: Vec<(String, Option<JSONSchema>)>,
+}
+
+struct ResponseValidator {
+    content: Vec<(String, Option<JSONSchema>)>,
+}
+
+fn compile_content(
+    components: &Components,
+    content: &IndexMap<String, MediaType>,
+) -> Vec<(String, Option<JSONSchema>)> {
+    content
+        .iter()
+        .map(|(media_type, media)| (media_type.clone(), components.media_schema(media)))
+        .collect()
+}
+
+fn validate_content(
+    content: &[(String, Option<JSONSchema>)],
+    content_type: Option<&str>,
+    body: &[u8],
+    location: ViolationLocation,
+) -> Vec<Violation> {
+    let Some(content_type) = content_type else {
+        return Vec::new();
+    };
+
+    if !is_json_media_type(content_type) {
+        return Vec::new();
+    }
+
+    let Some((_, schema)) = content
+        .iter()
+        .find(|(media_type, _)| is_json_media_type(media_type))
+    else {
+        if content.is_empty() {
+            return Vec::new();
+        }
+        return vec![Violation::new(
+            location,
+            format!(r#"the media type "{content_type}" is not documented"#),
+        )];
+    };
+
+    let value: Value = match serde_json::from_slice(body) {
+        Ok(value) => value,
+        Err(error) => return vec![Violation::new(location, format!("invalid JSON: {error}"))],
+    };
+
+    match schema {
+        Some(schema) => validate_value(schema, &value, location),
+        None => Vec::new(),
+    }
+}
+
+fn validate_value(
+    schema: &JSONSchema,
+    value: &Value,
+    location: ViolationLocation,
+) -> Vec<Violation> {
+    match schema.validate(value) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|error| Violation {
+                location,
+                name: None,
+                pointer: error.instance_path.to_string(),
+                message: error.to_string(),
+            })
+            .collect(),
+    }
+}
+
+/// The possible JSON values of the raw parameter values,
+/// the most likely interpretation comes first.
+fn parameter_candidates(values: &[String]) -> Vec<Value> {
+    let coerce = |v: &String| {
+        serde_json::from_str::<Value>(v)
+            .ok()
+            .filter(|v| !v.is_string())
+            .unwrap_or_else(|| Value::String(v.clone()))
+    };
+
+    match values {
+        [value] => vec![
+            coerce(value),
+            Value::String(value.clone()),
+            Value::Array(value.split(',').map(|v| coerce(&v.into())).collect()),
+        ],
+        values => vec![
+            Value::Array(values.iter().map(coerce).collect()),
+            Value::Array(values.iter().cloned().map(Value::String).collect()),
+        ],
+    }
+}
+
+/// Schemas and other components that are referenced by operations.
+struct Components<'a> {
+    api: &'a OpenApi,
+    schemas: Value,
+}
+
+impl<'a> Components<'a> {
+    fn new(api: &'a OpenApi) -> Self {
+        Self {
+            api,
+            schemas: api
+                .components
+                .as_ref()
+                .and_then(|c| serde_json::to_value(&c.schemas).ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Compile a schema that can reference the component schemas.
+    fn compile(&self, schema: &Value) -> Option<JSONSchema> {
+        let root = serde_json::json!({
+            "allOf": [schema],
+            "components": { "schemas": self.schemas },
+        });
+
+        match JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .compile(&root)
+        {
+            Ok(schema) => Some(schema),
+            Err(error) => {
+                tracing::warn!(%error, "failed to compile schema for validation");
+                None
+            }
+        }
+    }
+
+    fn media_schema(&self, media: &MediaType) -> Option<JSONSchema> {
+        media
+            .schema
+            .as_ref()
+            .and_then(|schema| serde_json::to_value(&schema.json_schema).ok())
+            .and_then(|schema| self.compile(&schema))
+    }
+
+    fn parameter<'p>(&'p self, param: &'p ReferenceOr<Parameter>) -> Option<&'p Parameter> {
+        self.resolve(param, "parameters", |c, name| c.parameters.get(name))
+    }
+
+    fn request_body<'p>(&'p self, body: &'p ReferenceOr<RequestBody>) -> Option<&'p RequestBody> {
+        self.resolve(body, "requestBodies", |c, name| c.request_bodies.get(name))
+    }
+
+    fn response<'p>(&'p self, res: &'p ReferenceOr<Response>) -> Option<&'p Response> {
+        self.resolve(res, "responses", |c, name| c.responses.get(name))
+    }
+
+    fn resolve<'p, T: 'a>(
+        &'p self,
+        item: &'p ReferenceOr<T>,
+        kind: &str,
+        get: impl Fn(&'a crate::openapi::Components, &str) -> Option<&'a ReferenceOr<T>>,
+    ) -> Option<&'p T> {
+        match item {
+            ReferenceOr::Item(item) => Some(item),
+            ReferenceOr::Reference { reference, .. } => {
+                let name = reference.strip_prefix(&format!("#/components/{kind}/"))?;
+                get(self.api.components.as_ref()?, name)?.as_item()
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TemplateSegment {
+    Literal(String),
+    Param(String),
+    Wildcard(String),
+}
+
+/// A documented path, e.g. `/users/{id}`.
+#[derive(Debug)]
+pub(crate) struct PathTemplate(Vec<TemplateSegment>);
+
+impl PathTemplate {
+    /// The `wildcard` parameter and `{param+}` match the rest of the path.
+    pub(crate) fn new(path: &str, wildcard: Option<&str>) -> Self {
+        Self(
+            path.trim_matches('/')
+                .split('/')
+                .filter(|s| !s.is_empty())
+                .map(
+                    |segment| match segment.strip_prefix('{').and_then(|s| s.strip_suffix('}')) {
+                        Some(name) => match name.strip_suffix('+') {
+                            Some(name) => TemplateSegment::Wildcard(name.into()),
+                            None if Some(name) == wildcard => {
+                                TemplateSegment::Wildcard(name.into())
+                            }
+                            None => TemplateSegment::Param(name.into()),
+                        },
+                        None => TemplateSegment::Literal(segment.into()),
+                    },
+                )
+                .collect(),
+        )
+    }
+
+    /// The amount of literal segments.
+    pub(crate) fn literals(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|s| matches!(s, TemplateSegment::Literal(_)))
+            .count()
+    }
+
+    /// Match a request path, returning the values of the parameters.
+    pub(crate) fn matches(&self, path: &str) -> Option<IndexMap<String, String>> {
+        let segments: Vec<&str> = path
+            .trim_matches('/')
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        let mut params = IndexMap::new();
+
+        for (i, template) in self.0.iter().enumerate() {
+            match template {
+                TemplateSegment::Literal(literal) => {
+                    if segments.get(i) != Some(&literal.as_str()) {
+                        return None;
+                    }
+                }
+                TemplateSegment::Param(name) => {
+                    params.insert(name.clone(), percent_decode(segments.get(i)?));
+                }
+                TemplateSegment::Wildcard(name) => {
+                    params.insert(name.clone(), percent_decode(&segments[i..].join("/")));
+                    return Some(params);
+                }
+            }
+        }
+
+        (segments.len() == self.0.len()).then_some(params)
+    }
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{
+        body::{to_bytes, Body},
+        extract::Path,
+        http::{HeaderMap, Request, StatusCode},
+        Json,
+    };
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+    use serde_json::json;
+    use tower_service::Service;
+
+    use super::{PathTemplate, SpecValidationLayer, SpecValidator, ViolationLocation};
+    use crate::{
+        axum::{routing::post, ApiRouter},
+        openapi::OpenApi,
+    };
+
+    #[derive(Deserialize, JsonSchema)]
+    struct NewTodo {
+        #[allow(dead_code)]
+        title: String,
+    }
+
+    #[derive(Deserialize, JsonSchema)]
+    struct ListPath {
+        #[allow(dead_code)]
+        list: u32,
+    }
+
+    async fn create_todo(Path(_list): Path<ListPath>, Json(_todo): Json<NewTodo>) -> Json<String> {
+        Json(String::new())
+    }
+
+    #[test]
+    fn test_validate_request() {
+        let mut api = OpenApi::default();
+        let app: axum::Router = ApiRouter::new()
+            .api_route("/lists/:list/todos", post(create_todo))
+            .finish_api(&mut api);
+        let _app = app.layer(SpecValidationLayer::new(&api));
+
+        let validator = SpecValidator::new(&api);
+
+        assert!(validator.operation("get", "/lists/1/todos").is_none());
+
+        let (operation, params) = validator.operation("post", "/lists/1/todos").unwrap();
+        assert!(operation
+            .validate_parameters(&params, None, &HeaderMap::new())
+            .is_empty());
+        assert!(operation
+            .validate_body(Some("application/json"), br#"{"title": "Test"}"#)
+            .is_empty());
+        assert!(operation
+            .validate_response(200, Some("application/json"), br#""ok""#)
+            .is_empty());
+
+        let violations = operation.validate_body(Some("application/json"), br#"{"title": 1}"#);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].location, ViolationLocation::Body);
+        assert_eq!(violations[0].pointer, "/title");
+
+        let (operation, params) = validator.operation("post", "/lists/first/todos").unwrap();
+        let violations = operation.validate_parameters(&params, None, &HeaderMap::new());
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].name.as_deref(), Some("list"));
+    }
+
+    async fn list_todos() -> Json<Vec<u32>> {
+        Json((0..100).collect())
+    }
+
+    #[tokio::test]
+    async fn test_validation_layer() {
+        let mut api = OpenApi::default();
+        let app: axum::Router = ApiRouter::new()
+            .api_route("/lists/:list/todos", post(create_todo).get(list_todos))
+            .finish_api(&mut api);
+        let mut app = app.layer(
+            SpecValidationLayer::new(&api)
+                .validate_responses(true)
+                .body_limit(64),
+        );
+
+        let create = |body: &'static str| {
+            Request::post("/lists/1/todos")
+                .header("content-type", "application/json")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let res = app.call(create(r#"{"title": "Test"}"#)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let res = app.call(create(r#"{"title": 1}"#)).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["violations"][0]["location"], "body");
+        assert_eq!(body["violations"][0]["pointer"], "/title");
+
+        // Responses larger than the limit are returned without validation.
+        let req = Request::get("/lists/1/todos").body(Body::empty()).unwrap();
+        let res = app.call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(
+            serde_json::from_slice::<Vec<u32>>(&body).unwrap().len(),
+            100
+        );
+    }
+
+    #[test]
+    fn test_parameter_override() {
+        let api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Items", "version": "1" },
+            "paths": {
+                "/items/{id}": {
+                    "parameters": [
+                        {
+                            "name": "id",
+                            "in": "path",
+                            "required": true,
+                            "schema": { "type": "integer" }
+                        },
+                        {
+                            "name": "X-Version",
+                            "in": "header",
+                            "required": true,
+                            "schema": { "type": "string" }
+                        }
+                    ],
+                    "get": {
+                        "parameters": [
+                            {
+                                "name": "id",
+                                "in": "query",
+                                "schema": { "type": "boolean" }
+                            },
+                            {
+                                "name": "x-version",
+                                "in": "header",
+                                "schema": { "type": "string" }
+                            }
+                        ],
+                        "responses": {}
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let validator = SpecValidator::new(&api);
+
+        // The query parameter does not replace the path parameter of the same name.
+        let (operation, params) = validator.operation("get", "/items/first").unwrap();
+        let violations = operation.validate_parameters(&params, Some("id=1"), &HeaderMap::new());
+        assert_eq!(violations.len(), 2);
+        assert!(violations.iter().all(|v| v.name.as_deref() == Some("id")));
+        assert!(violations
+            .iter()
+            .any(|v| v.location == ViolationLocation::Path));
+        assert!(violations
+            .iter()
+            .any(|v| v.location == ViolationLocation::Query));
+
+        // Header names are case-insensitive, the optional operation
+        // header replaces the required one of the path item.
+        let (operation, params) = validator.operation("get", "/items/1").unwrap();
+        assert!(operation
+            .validate_parameters(&params, Some("id=true"), &HeaderMap::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_path_template_wildcard() {
+        let template = PathTemplate::new("/files/{path}", Some("path"));
+        let params = template.matches("/files/docs/readme.md").unwrap();
+        assert_eq!(params["path"], "docs/readme.md");
+
+        let template = PathTemplate::new("/files/{path}", None);
+        assert!(template.matches("/files/docs/readme.md").is_none());
+    }
+}
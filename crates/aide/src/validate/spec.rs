@@ -0,0 +1,455 @@
+//! Validation of the documentation itself.
+
+use std::fmt;
+
+use jsonschema::{Draft, JSONSchema};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    helpers::oidc::SCOPES_EXTENSION,
+    openapi::{OAuth2Flow, OpenApi, Parameter, PathItem, ReferenceOr, SecurityScheme},
+    refs,
+};
+
+/// A violation of the `OpenAPI` specification by the documentation,
+/// see [`OpenApi::validate`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SpecViolation {
+    /// The rule that is violated.
+    pub kind: SpecViolationKind,
+    /// A JSON pointer to the invalid part of the documentation.
+    pub pointer: String,
+    /// A description of the violation.
+    pub message: String,
+}
+
+impl SpecViolation {
+    fn new(kind: SpecViolationKind, pointer: String, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            pointer,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for SpecViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}: {}", self.kind, self.pointer, self.message)
+    }
+}
+
+/// The rule violated by a [`SpecViolation`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+#[non_exhaustive]
+pub enum SpecViolationKind {
+    /// The document does not match the `OpenAPI` meta-schema.
+    Schema,
+    /// An operation ID is used by multiple operations.
+    DuplicateOperationId,
+    /// A local `$ref` does not point to anything in the document.
+    UnresolvedReference,
+    /// The parameters of an operation do not match its path template.
+    PathParameter,
+    /// A parameter is defined multiple times for an operation.
+    DuplicateParameter,
+    /// A security requirement references a scheme that is not declared.
+    UndeclaredSecurityScheme,
+    /// A security requirement requests a scope
+    /// that the `OAuth2` flows of the scheme do not define.
+    UndeclaredScope,
+}
+
+impl fmt::Display for SpecViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SpecViolationKind::Schema => "schema violation",
+            SpecViolationKind::DuplicateOperationId => "duplicate operation ID",
+            SpecViolationKind::UnresolvedReference => "unresolved reference",
+            SpecViolationKind::PathParameter => "path parameter mismatch",
+            SpecViolationKind::DuplicateParameter => "duplicate parameter",
+            SpecViolationKind::UndeclaredSecurityScheme => "undeclared security scheme",
+            SpecViolationKind::UndeclaredScope => "undeclared scope",
+        })
+    }
+}
+
+impl OpenApi {
+    /// Validate the finished documentation against the official
+    /// `OpenAPI` 3.1 JSON Schema and the rules of the specification
+    /// that the schema cannot express:
+    ///
+    /// - operation IDs are unique,
+    /// - local `$ref`s can be resolved,
+    /// - every parameter of a path template is documented for each of
+    ///   its operations and every path parameter appears in the template,
+    /// - parameters are unique by name and location within an operation,
+    /// - security requirements reference declared security schemes, and
+    ///   the scopes they request are defined by the `OAuth2` flows of the
+    ///   scheme, or the [scopes](crate::helpers::oidc::SCOPES_EXTENSION)
+    ///   of an `OpenID` Connect scheme if they are documented.
+    ///
+    /// Only `OpenAPI` 3.1 is checked, as that is the only
+    /// version the documentation is serialized as.
+    ///
+    /// This is meant to catch mistakes in transforms and
+    /// hand-written parts of the documentation before it is
+    /// published, e.g. in a test, an empty list means that
+    /// the documentation is valid.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut api = OpenApi::default();
+    /// let _app = app().finish_api(&mut api);
+    ///
+    /// let violations = api.validate();
+    /// assert!(violations.is_empty(), "{violations:#?}");
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> Vec<SpecViolation> {
+        let doc = match serde_json::to_value(self) {
+            Ok(doc) => doc,
+            Err(error) => {
+                return vec![SpecViolation::new(
+                    SpecViolationKind::Schema,
+                    String::new(),
+                    format!("the documentation cannot be serialized: {error}"),
+                )]
+            }
+        };
+
+        let mut violations = validate_schema(&doc);
+        violations.extend(refs::dangling_refs(self).into_iter().map(|dangling| {
+            SpecViolation::new(
+                SpecViolationKind::UnresolvedReference,
+                dangling.pointer,
+                format!(r#"the reference "{}" does not exist"#, dangling.reference),
+            )
+        }));
+        validate_operations(self, &mut violations);
+        validate_security(self, &mut violations);
+        violations
+    }
+}
+
+fn validate_schema(doc: &Value) -> Vec<SpecViolation> {
+    let meta_schema: Value = serde_json::from_str(include_str!("../../res/openapi/schema.json"))
+        .expect("the OpenAPI meta-schema is valid JSON");
+
+    let schema = JSONSchema::options()
+        .with_draft(Draft::Draft202012)
+        .compile(&meta_schema)
+        .expect("the OpenAPI meta-schema compiles");
+
+    let violations = match schema.validate(doc) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|error| {
+                SpecViolation::new(
+                    SpecViolationKind::Schema,
+                    error.instance_path.to_string(),
+                    error.to_string(),
+                )
+            })
+            .collect(),
+    };
+    violations
+}
+
+fn validate_operations(api: &OpenApi, violations: &mut Vec<SpecViolation>) {
+    let mut operation_ids: Vec<(&str, String)> = Vec::new();
+
+    for (path, item) in api.paths.iter().flat_map(|paths| paths.iter()) {
+        let Some(item) = item.as_item() else {
+            continue;
+        };
+        let template: Vec<&str> = path
+            .split('/')
+            .filter_map(|s| s.strip_prefix('{')?.strip_suffix('}'))
+            .collect();
+
+        for (method, operation) in item.iter() {
+            let pointer = format!("/paths/{}/{method}", escape_pointer(path));
+
+            if let Some(id) = &operation.operation_id {
+                match operation_ids.iter().find(|(other, _)| other == id) {
+                    Some((_, other)) => violations.push(SpecViolation::new(
+                        SpecViolationKind::DuplicateOperationId,
+                        format!("{pointer}/operationId"),
+                        format!(r#"the operation ID "{id}" is also used at {other}"#),
+                    )),
+                    None => operation_ids.push((id, pointer.clone())),
+                }
+            }
+
+            let mut parameters: Vec<&Parameter> = Vec::new();
+            for param in &operation.parameters {
+                let Some(param) = resolve_parameter(api, param) else {
+                    continue;
+                };
+                if parameters.iter().any(|p| same_parameter(p, param)) {
+                    violations.push(SpecViolation::new(
+                        SpecViolationKind::DuplicateParameter,
+                        format!("{pointer}/parameters"),
+                        format!(
+                            r#"the parameter "{}" is defined more than once"#,
+                            param.parameter_data_ref().name
+                        ),
+                    ));
+                    continue;
+                }
+                parameters.push(param);
+            }
+            parameters.extend(path_parameters(api, item).filter(|param| {
+                !operation
+                    .parameters
+                    .iter()
+                    .filter_map(|p| resolve_parameter(api, p))
+                    .any(|p| same_parameter(p, param))
+            }));
+
+            let path_params: Vec<&str> = parameters
+                .iter()
+                .filter(|p| matches!(p, Parameter::Path { .. }))
+                .map(|p| p.parameter_data_ref().name.as_str())
+                .collect();
+
+            for name in template.iter().filter(|name| !path_params.contains(name)) {
+                violations.push(SpecViolation::new(
+                    SpecViolationKind::PathParameter,
+                    format!("{pointer}/parameters"),
+                    format!(r#"the path parameter "{name}" is not documented"#),
+                ));
+            }
+            for name in path_params.iter().filter(|name| !template.contains(name)) {
+                violations.push(SpecViolation::new(
+                    SpecViolationKind::PathParameter,
+                    format!("{pointer}/parameters"),
+                    format!(r#"the path parameter "{name}" is not part of the path"#),
+                ));
+            }
+        }
+    }
+}
+
+fn validate_security(api: &OpenApi, violations: &mut Vec<SpecViolation>) {
+    let global = api
+        .security
+        .iter()
+        .enumerate()
+        .map(|(i, requirement)| (format!("/security/{i}"), requirement));
+
+    let operations = api
+        .paths
+        .iter()
+        .flat_map(|paths| paths.iter())
+        .filter_map(|(path, item)| Some((path, item.as_item()?)))
+        .flat_map(|(path, item)| {
+            item.iter().flat_map(move |(method, operation)| {
+                operation
+                    .security
+                    .iter()
+                    .enumerate()
+                    .map(move |(i, requirement)| {
+                        (
+                            format!("/paths/{}/{method}/security/{i}", escape_pointer(path)),
+                            requirement,
+                        )
+                    })
+            })
+        });
+
+    for (pointer, requirement) in global.chain(operations) {
+        for (name, scopes) in requirement {
+            let scheme = api
+                .components
+                .as_ref()
+                .and_then(|components| components.security_schemes.get(name));
+            let Some(scheme) = scheme else {
+                violations.push(SpecViolation::new(
+                    SpecViolationKind::UndeclaredSecurityScheme,
+                    pointer.clone(),
+                    format!(r#"the security scheme "{name}" is not declared"#),
+                ));
+                continue;
+            };
+
+            let Some(declared) = refs::resolve(api, scheme).and_then(declared_scopes) else {
+                continue;
+            };
+            for scope in scopes.iter().filter(|scope| !declared.contains(scope)) {
+                violations.push(SpecViolation::new(
+                    SpecViolationKind::UndeclaredScope,
+                    format!("{pointer}/{}", escape_pointer(name)),
+                    format!(
+                        r#"the scope "{scope}" is not defined by the security scheme "{name}""#
+                    ),
+                ));
+            }
+        }
+    }
+}
+
+/// The scopes of a security scheme, if the scheme defines them.
+fn declared_scopes(scheme: &SecurityScheme) -> Option<Vec<&String>> {
+    match scheme {
+        SecurityScheme::OAuth2 { flows, .. } => Some(
+            [
+                &flows.implicit,
+                &flows.password,
+                &flows.client_credentials,
+                &flows.authorization_code,
+            ]
+            .into_iter()
+            .flatten()
+            .flat_map(|flow| match flow {
+                OAuth2Flow::Implicit { scopes, .. }
+                | OAuth2Flow::Password { scopes, .. }
+                | OAuth2Flow::ClientCredentials { scopes, .. }
+                | OAuth2Flow::AuthorizationCode { scopes, .. } => scopes.keys(),
+            })
+            .collect(),
+        ),
+        SecurityScheme::OpenIdConnect { extensions, .. } => extensions
+            .get(SCOPES_EXTENSION)
+            .and_then(Value::as_object)
+            .map(|scopes| scopes.keys().collect()),
+        _ => None,
+    }
+}
+
+fn path_parameters<'a>(
+    api: &'a OpenApi,
+    item: &'a PathItem,
+) -> impl Iterator<Item = &'a Parameter> {
+    item.parameters
+        .iter()
+        .filter_map(|param| resolve_parameter(api, param))
+}
+
+fn resolve_parameter<'a>(
+    api: &'a OpenApi,
+    param: &'a ReferenceOr<Parameter>,
+) -> Option<&'a Parameter> {
+    match param {
+        ReferenceOr::Item(param) => Some(param),
+        ReferenceOr::Reference { reference, .. } => {
+            let name = reference.strip_prefix("#/components/parameters/")?;
+            api.components.as_ref()?.parameters.get(name)?.as_item()
+        }
+    }
+}
+
+fn same_parameter(a: &Parameter, b: &Parameter) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+        && a.parameter_data_ref().name == b.parameter_data_ref().name
+}
+
+fn escape_pointer(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::Path;
+
+    use super::SpecViolationKind;
+    use crate::{
+        axum::{routing::get, ApiRouter},
+        openapi::OpenApi,
+        transform::TransformOperation,
+    };
+
+    async fn get_user(Path(_id): Path<u64>) {}
+
+    #[test]
+    fn test_validate() {
+        let mut api = OpenApi::default();
+        let _app: axum::Router = ApiRouter::new()
+            .api_route(
+                "/users/:id",
+                get(get_user).post_with(get_user, |op: TransformOperation| op.id("user")),
+            )
+            .api_route(
+                "/teams/:team_id",
+                get(get_user).put_with(get_user, |op: TransformOperation| op.id("user")),
+            )
+            .finish_api(&mut api);
+
+        let violations = api.validate();
+        assert_eq!(violations.len(), 1, "{violations:#?}");
+        assert_eq!(violations[0].kind, SpecViolationKind::DuplicateOperationId);
+        assert_eq!(
+            violations[0].pointer,
+            "/paths/~1teams~1{team_id}/put/operationId"
+        );
+
+        let paths = api.paths.as_mut().unwrap();
+        let item = paths.paths["/teams/{team_id}"].as_item_mut().unwrap();
+        item.put = None;
+        let get = item.get.as_mut().unwrap();
+        get.parameters.clear();
+        get.parameters.push(crate::openapi::ReferenceOr::ref_(
+            "#/components/parameters/Missing",
+        ));
+        get.description = Some("docs".into());
+
+        let kinds: Vec<_> = api.validate().into_iter().map(|v| v.kind).collect();
+        assert!(kinds.contains(&SpecViolationKind::UnresolvedReference));
+        assert!(kinds.contains(&SpecViolationKind::PathParameter));
+
+        let mut doc = serde_json::to_value(&api).unwrap();
+        doc["info"] = serde_json::json!({});
+        assert!(super::validate_schema(&doc)
+            .iter()
+            .any(|v| v.kind == SpecViolationKind::Schema && v.pointer == "/info"));
+    }
+
+    #[test]
+    fn test_validate_security() {
+        let api: OpenApi = serde_json::from_value(serde_json::json!({
+            "openapi": "3.1.0",
+            "info": { "title": "", "version": "" },
+            "security": [{ "missing": [] }],
+            "paths": {
+                "/todos": {
+                    "get": {
+                        "security": [{ "oauth": ["todos:read", "todos:delete"] }],
+                        "responses": { "200": { "description": "" } }
+                    }
+                }
+            },
+            "components": {
+                "securitySchemes": {
+                    "oauth": {
+                        "type": "oauth2",
+                        "flows": {
+                            "clientCredentials": {
+                                "tokenUrl": "https://auth.example.com/token",
+                                "scopes": { "todos:read": "" }
+                            }
+                        }
+                    }
+                }
+            }
+        }))
+        .unwrap();
+
+        let violations: Vec<_> = api
+            .validate()
+            .into_iter()
+            .filter(|v| v.kind != SpecViolationKind::Schema)
+            .collect();
+        assert_eq!(violations.len(), 2, "{violations:#?}");
+        assert_eq!(
+            violations[0].kind,
+            SpecViolationKind::UndeclaredSecurityScheme
+        );
+        assert_eq!(violations[0].pointer, "/security/0");
+        assert_eq!(violations[1].kind, SpecViolationKind::UndeclaredScope);
+        assert_eq!(violations[1].pointer, "/paths/~1todos/get/security/0/oauth");
+    }
+}
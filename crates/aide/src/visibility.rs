@@ -0,0 +1,647 @@
+//! Documentation for different audiences.
+//!
+//! Paths, operations, parameters and schemas can be marked as internal
+//! with their transforms, e.g. [`TransformOperation::visibility`], which
+//! stores the [`ApiVisibility`] in the [`VISIBILITY`] extension.
+//! [`OpenApi::filtered`] then produces the documentation for an
+//! [`Audience`], so that a single API can publish both its complete
+//! internal documentation and the documentation for external clients.
+//!
+//! # Examples
+//!
+//! ```
+//! use aide::{
+//!     openapi::{OpenApi, Operation},
+//!     transform::TransformOperation,
+//!     visibility::{ApiVisibility, Audience},
+//! };
+//!
+//! let mut operation = Operation::default();
+//! let _ = TransformOperation::new(&mut operation).visibility(ApiVisibility::Internal);
+//!
+//! let mut api = OpenApi::default();
+//! // ...
+//!
+//! let external_api = api.filtered(Audience::External);
+//! ```
+//!
+//! Schemas that are only referenced by internal items are kept in the
+//! external documentation, mark them as internal as well to remove them.
+//! Internal schemas that are still referenced by external items are kept
+//! as well, so that the references remain valid, and an
+//! [`Error::InternalSchemaReferenced`](crate::Error::InternalSchemaReferenced)
+//! is reported for them.
+//!
+//! # Hidden Items
+//!
+//! Items hidden with their transforms, e.g. [`TransformOperation::hidden`],
+//! are marked with the [`HIDDEN`] extension and are removed from the
+//! documentation by [`prune_hidden`] only when it is finished, so they can
+//! be shown again by later transforms:
+//!
+//! ```
+//! use aide::transform::TransformOpenApi;
+//!
+//! fn show_debug_routes(api: TransformOpenApi) -> TransformOpenApi {
+//!     api.all_operations(|op| op.hidden(!cfg!(debug_assertions)))
+//! }
+//! ```
+//!
+//! [`TransformOperation::visibility`]: crate::transform::TransformOperation::visibility
+//! [`TransformOperation::hidden`]: crate::transform::TransformOperation::hidden
+
+use std::mem;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    gen::in_context,
+    openapi::{
+        Callback, OpenApi, Operation, Parameter, PathItem, ReferenceOr, Response, SchemaObject,
+    },
+    schema::visit_schemas_mut,
+    util::iter_operations_mut,
+    Error,
+};
+
+/// The extension with the visibility of an item.
+pub const VISIBILITY: &str = "x-visibility";
+
+/// The extension that marks an item as hidden.
+pub const HIDDEN: &str = "x-aide-hidden";
+
+/// The visibility of an item of the documentation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiVisibility {
+    /// Documented for all audiences.
+    #[default]
+    External,
+    /// Only documented for internal audiences.
+    Internal,
+}
+
+impl ApiVisibility {
+    pub(crate) fn extension(self) -> (String, Value) {
+        let value = match self {
+            Self::External => "external",
+            Self::Internal => "internal",
+        };
+        (VISIBILITY.into(), value.into())
+    }
+}
+
+/// The audience of the documentation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Audience {
+    /// Only the external items are documented.
+    External,
+    /// All items are documented.
+    Internal,
+}
+
+impl Audience {
+    /// Whether items with the visibility are documented for the audience.
+    #[must_use]
+    pub fn can_see(self, visibility: ApiVisibility) -> bool {
+        match self {
+            Self::External => visibility == ApiVisibility::External,
+            Self::Internal => true,
+        }
+    }
+
+    fn can_see_extensions(self, extensions: &IndexMap<String, Value>) -> bool {
+        self.can_see(visibility(extensions.get(VISIBILITY)))
+    }
+}
+
+fn visibility(extension: Option<&Value>) -> ApiVisibility {
+    extension
+        .and_then(|value| ApiVisibility::deserialize(value).ok())
+        .unwrap_or_default()
+}
+
+impl OpenApi {
+    /// The documentation for the audience, without the items
+    /// the audience cannot see, see [`visibility`](crate::visibility).
+    ///
+    /// The [`VISIBILITY`] extensions are removed from
+    /// the documentation for external audiences.
+    #[must_use]
+    pub fn filtered(&self, audience: Audience) -> OpenApi {
+        let mut api = self.clone();
+        if audience == Audience::Internal {
+            return api;
+        }
+
+        let can_see_param = |param: &ReferenceOr<Parameter>| match param {
+            ReferenceOr::Item(param) => {
+                audience.can_see_extensions(&param.parameter_data_ref().extensions)
+            }
+            ReferenceOr::Reference { .. } => true,
+        };
+
+        if let Some(paths) = &mut api.paths {
+            paths.paths.retain(|_, path| match path {
+                ReferenceOr::Item(path) => audience.can_see_extensions(&path.extensions),
+                ReferenceOr::Reference { .. } => true,
+            });
+
+            for (_, path) in &mut paths.paths {
+                let ReferenceOr::Item(path) = path else {
+                    continue;
+                };
+
+                path.extensions.shift_remove(VISIBILITY);
+                path.parameters.retain(can_see_param);
+
+                for operation in operations_mut(path) {
+                    if operation
+                        .as_ref()
+                        .is_some_and(|op| !audience.can_see_extensions(&op.extensions))
+                    {
+                        *operation = None;
+                    }
+                }
+
+                for (_, operation) in iter_operations_mut(path) {
+                    operation.extensions.shift_remove(VISIBILITY);
+                    operation.parameters.retain(can_see_param);
+                    remove_visibility(&mut operation.parameters);
+                }
+                remove_visibility(&mut path.parameters);
+            }
+        }
+
+        let internal_schemas = api.components.as_ref().map_or_else(Vec::new, |components| {
+            components
+                .schemas
+                .iter()
+                .filter(|(_, schema)| match &schema.json_schema {
+                    schemars::schema::Schema::Object(schema) => {
+                        !audience.can_see(visibility(schema.extensions.get(VISIBILITY)))
+                    }
+                    schemars::schema::Schema::Bool(_) => false,
+                })
+                .map(|(name, _)| name.clone())
+                .collect()
+        });
+
+        visit_schemas_mut(&mut api, &mut |schema, _| {
+            schema.remove(VISIBILITY);
+
+            let Some(Value::Object(properties)) = schema.get_mut("properties") else {
+                return;
+            };
+
+            let mut hidden = Vec::new();
+            properties.retain(|name, property| {
+                let visible = audience.can_see(visibility(property.get(VISIBILITY)));
+                if !visible {
+                    hidden.push(name.clone());
+                }
+                visible
+            });
+
+            if let Some(Value::Array(required)) = schema.get_mut("required") {
+                required.retain(|name| !hidden.iter().any(|h| name == h));
+            }
+        });
+
+        if let Some(components) = &mut api.components {
+            let mut schemas = mem::take(&mut components.schemas);
+            let referenced = referenced_schemas(&api, &schemas, &internal_schemas);
+
+            schemas.retain(|name, _| {
+                if !internal_schemas.contains(name) {
+                    return true;
+                }
+                if !referenced.contains(name) {
+                    return false;
+                }
+                in_context(|ctx| ctx.error(Error::InternalSchemaReferenced(name.clone())));
+                true
+            });
+
+            if let Some(components) = &mut api.components {
+                components.schemas = schemas;
+            }
+        }
+
+        api
+    }
+}
+
+/// The names of the schemas that are referenced by the document without
+/// its component schemas, or by the schemas that are not internal,
+/// directly or through other schemas.
+fn referenced_schemas(
+    api: &OpenApi,
+    schemas: &IndexMap<String, SchemaObject>,
+    internal_schemas: &[String],
+) -> Vec<String> {
+    let mut pending = Vec::new();
+    collect_schema_refs(&serde_json::to_value(api).unwrap_or_default(), &mut pending);
+    pending.extend(
+        schemas
+            .keys()
+            .filter(|name| !internal_schemas.contains(name))
+            .cloned(),
+    );
+
+    let mut referenced = Vec::new();
+    while let Some(name) = pending.pop() {
+        if referenced.contains(&name) {
+            continue;
+        }
+        if let Some(schema) = schemas.get(&name) {
+            collect_schema_refs(
+                &serde_json::to_value(schema).unwrap_or_default(),
+                &mut pending,
+            );
+        }
+        referenced.push(name);
+    }
+
+    referenced
+}
+
+fn collect_schema_refs(value: &Value, refs: &mut Vec<String>) {
+    match value {
+        Value::Object(object) => {
+            if let Some(name) = object
+                .get("$ref")
+                .and_then(Value::as_str)
+                .and_then(|r| r.strip_prefix("#/components/schemas/"))
+            {
+                refs.push(name.replace("~1", "/").replace("~0", "~"));
+            }
+            for value in object.values() {
+                collect_schema_refs(value, refs);
+            }
+        }
+        Value::Array(items) => {
+            for value in items {
+                collect_schema_refs(value, refs);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Remove the hidden paths, operations, parameters, responses
+/// and callbacks from the documentation.
+pub fn prune_hidden(api: &mut OpenApi) {
+    if let Some(paths) = &mut api.paths {
+        paths.paths.retain(|_, path| retain_path(path));
+    }
+}
+
+/// Prune the path item, paths that only have
+/// hidden operations are removed as well.
+fn retain_path(path: &mut ReferenceOr<PathItem>) -> bool {
+    let ReferenceOr::Item(path) = path else {
+        return true;
+    };
+
+    if is_hidden(&path.extensions) {
+        return false;
+    }
+
+    let documented = path.iter().next().is_some();
+    prune_path_item(path);
+    !documented || path.iter().next().is_some()
+}
+
+fn prune_path_item(path: &mut PathItem) {
+    path.parameters.retain(|param| !is_hidden_param(param));
+
+    for operation in operations_mut(path) {
+        if operation
+            .as_ref()
+            .is_some_and(|op| is_hidden(&op.extensions))
+        {
+            *operation = None;
+        }
+    }
+
+    for (_, operation) in iter_operations_mut(path) {
+        operation.parameters.retain(|param| !is_hidden_param(param));
+
+        if let Some(responses) = &mut operation.responses {
+            let is_hidden_response = |res: &ReferenceOr<Response>| match res {
+                ReferenceOr::Item(res) => is_hidden(&res.extensions),
+                ReferenceOr::Reference { .. } => false,
+            };
+
+            if responses.default.as_ref().is_some_and(is_hidden_response) {
+                responses.default = None;
+            }
+            responses
+                .responses
+                .retain(|_, res| !is_hidden_response(res));
+        }
+
+        for callback in operation
+            .callbacks
+            .values_mut()
+            .filter_map(ReferenceOr::as_item_mut)
+        {
+            callback.retain(|_, path| retain_path(path));
+        }
+        operation
+            .callbacks
+            .retain(|_, callback| !callback.as_item().is_some_and(Callback::is_empty));
+    }
+}
+
+/// Move the hidden mark of the path to its operations.
+#[cfg_attr(any(not(feature = "axum"), aide_docs_disabled), allow(dead_code))]
+pub(crate) fn hide_operations(path: &mut PathItem) {
+    if path.extensions.shift_remove(HIDDEN).is_none() {
+        return;
+    }
+
+    for (_, operation) in iter_operations_mut(path) {
+        set_hidden(&mut operation.extensions, true);
+    }
+}
+
+pub(crate) fn set_hidden(extensions: &mut IndexMap<String, Value>, hidden: bool) {
+    if hidden {
+        extensions.insert(HIDDEN.into(), true.into());
+    } else {
+        extensions.shift_remove(HIDDEN);
+    }
+}
+
+pub(crate) fn is_hidden(extensions: &IndexMap<String, Value>) -> bool {
+    extensions.get(HIDDEN) == Some(&Value::Bool(true))
+}
+
+fn is_hidden_param(param: &ReferenceOr<Parameter>) -> bool {
+    match param {
+        ReferenceOr::Item(param) => is_hidden(&param.parameter_data_ref().extensions),
+        ReferenceOr::Reference { .. } => false,
+    }
+}
+
+fn operations_mut(path: &mut PathItem) -> [&mut Option<Operation>; 8] {
+    [
+        &mut path.get,
+        &mut path.put,
+        &mut path.post,
+        &mut path.delete,
+        &mut path.options,
+        &mut path.head,
+        &mut path.patch,
+        &mut path.trace,
+    ]
+}
+
+fn remove_visibility(params: &mut [ReferenceOr<Parameter>]) {
+    for param in params.iter_mut().filter_map(ReferenceOr::as_item_mut) {
+        param
+            .parameter_data_mut()
+            .extensions
+            .shift_remove(VISIBILITY);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_filtered() {
+        let api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Users", "version": "1" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "parameters": [
+                            { "name": "name", "in": "query", "schema": { "type": "string" } },
+                            {
+                                "name": "debug",
+                                "in": "query",
+                                "schema": { "type": "boolean" },
+                                "x-visibility": "internal"
+                            }
+                        ],
+                        "responses": {}
+                    },
+                    "delete": { "x-visibility": "internal", "responses": {} }
+                },
+                "/admin": {
+                    "x-visibility": "internal",
+                    "get": { "responses": {} }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "required": ["name", "risk_score"],
+                        "properties": {
+                            "name": { "type": "string" },
+                            "risk_score": { "type": "number", "x-visibility": "internal" }
+                        }
+                    },
+                    "AuditLog": { "type": "object", "x-visibility": "internal" }
+                }
+            }
+        }))
+        .unwrap();
+
+        assert_eq!(api.filtered(Audience::Internal), api);
+
+        let api = serde_json::to_value(api.filtered(Audience::External)).unwrap();
+
+        let paths = api["paths"].as_object().unwrap();
+        assert_eq!(paths.keys().collect::<Vec<_>>(), ["/users"]);
+
+        let users = paths["/users"].as_object().unwrap();
+        assert_eq!(users.keys().collect::<Vec<_>>(), ["get"]);
+        assert_eq!(users["get"]["parameters"].as_array().unwrap().len(), 1);
+        assert_eq!(users["get"]["parameters"][0]["name"], "name");
+        assert!(users["get"]["parameters"][0].get(VISIBILITY).is_none());
+
+        assert_eq!(
+            api["components"]["schemas"],
+            json!({
+                "User": {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": { "name": { "type": "string" } }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_filtered_referenced_schemas() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Users", "version": "1" },
+            "paths": {
+                "/users": {
+                    "get": {
+                        "responses": {
+                            "200": {
+                                "description": "the users",
+                                "content": {
+                                    "application/json": {
+                                        "schema": { "$ref": "#/components/schemas/User" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            },
+            "components": {
+                "schemas": {
+                    "User": {
+                        "type": "object",
+                        "properties": {
+                            "role": { "$ref": "#/components/schemas/Role" },
+                            "audit": {
+                                "$ref": "#/components/schemas/AuditLog",
+                                "x-visibility": "internal"
+                            }
+                        }
+                    },
+                    "Role": {
+                        "x-visibility": "internal",
+                        "type": "object",
+                        "properties": {
+                            "permissions": { "$ref": "#/components/schemas/Permissions" }
+                        }
+                    },
+                    "Permissions": { "type": "array", "x-visibility": "internal" },
+                    "AuditLog": { "type": "object", "x-visibility": "internal" }
+                }
+            }
+        }))
+        .unwrap();
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        crate::gen::on_error({
+            let errors = errors.clone();
+            move |e| errors.borrow_mut().push(e.to_string())
+        });
+
+        let external = api.filtered(Audience::External);
+        crate::gen::reset_context();
+
+        // Only the schema of the internal property is removed.
+        let schemas = &external.components.unwrap().schemas;
+        assert_eq!(
+            schemas.keys().collect::<Vec<_>>(),
+            ["Permissions", "Role", "User"]
+        );
+        assert_eq!(
+            *errors.borrow(),
+            [
+                r#"the internal schema "Permissions" is referenced by external items"#,
+                r#"the internal schema "Role" is referenced by external items"#,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_prune_hidden() {
+        let mut api: OpenApi = serde_json::from_value(json!({
+            "openapi": "3.1.0",
+            "info": { "title": "Users", "version": "1" },
+            "paths": {
+                "/users": {
+                    "post": {
+                        "parameters": [
+                            { "name": "trace", "in": "header", "schema": {}, "x-aide-hidden": true }
+                        ],
+                        "responses": {
+                            "201": { "description": "Created" },
+                            "418": { "description": "Teapot", "x-aide-hidden": true }
+                        },
+                        "callbacks": {
+                            "onCreated": {
+                                "{$request.body#/url}": {
+                                    "post": { "responses": {}, "x-aide-hidden": true }
+                                }
+                            }
+                        }
+                    },
+                    "delete": { "responses": {}, "x-aide-hidden": true }
+                },
+                "/debug": {
+                    "get": { "responses": {}, "x-aide-hidden": true }
+                }
+            }
+        }))
+        .unwrap();
+
+        prune_hidden(&mut api);
+        let api = serde_json::to_value(api).unwrap();
+
+        assert_eq!(
+            api["paths"],
+            json!({
+                "/users": {
+                    "post": {
+                        "responses": {
+                            "201": { "description": "Created" }
+                        }
+                    }
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn test_hidden_response_keeps_visible() {
+        use std::{cell::RefCell, rc::Rc};
+
+        use crate::{
+            gen,
+            transform::{TransformOpenApi, TransformOperation},
+        };
+
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        gen::on_error({
+            let errors = errors.clone();
+            move |e| errors.borrow_mut().push(e.to_string())
+        });
+
+        let mut op = Operation::default();
+        let _ = TransformOperation::new(&mut op)
+            .response_with::<200, String, _>(|res| res.description("the user"))
+            .response_with::<200, String, _>(|res| res.description("debug").hidden(true))
+            .response_with::<418, String, _>(|res| res.description("debug").hidden(true))
+            .response_with::<418, String, _>(|res| res.description("teapot"));
+        assert!(errors.borrow().is_empty(), "{:?}", errors.borrow());
+
+        let mut api = OpenApi::default();
+        api.paths.get_or_insert_with(Default::default).paths.insert(
+            "/users".into(),
+            ReferenceOr::Item(PathItem {
+                get: Some(op),
+                ..Default::default()
+            }),
+        );
+        let _ = TransformOpenApi::new(&mut api).prune_hidden();
+
+        let responses =
+            serde_json::to_value(&api).unwrap()["paths"]["/users"]["get"]["responses"].clone();
+        assert_eq!(responses["200"]["description"], "the user");
+        assert_eq!(responses["418"]["description"], "teapot");
+        assert!(responses["418"].get("x-aide-hidden").is_none());
+    }
+}
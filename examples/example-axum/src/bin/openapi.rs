@@ -0,0 +1,23 @@
+//! Write the documentation without starting the server:
+//!
+//! ```text
+//! cargo run --bin openapi -- --out openapi.json --format yaml
+//! ```
+
+use aide::{emit::EmitError, openapi::OpenApi};
+use example_axum::{api_docs, app, state::AppState};
+
+fn main() -> Result<(), EmitError> {
+    // The document can be written to the standard output.
+    aide::gen::on_error(|error| {
+        eprintln!("{error}");
+    });
+
+    aide::gen::extract_schemas(true);
+
+    aide::emit::run(|| {
+        let mut api = OpenApi::default();
+        let _ = app(AppState::default()).finish_api_with(&mut api, api_docs);
+        api
+    })
+}
@@ -0,0 +1,51 @@
+use aide::{axum::ApiRouter, openapi::Tag, transform::TransformOpenApi};
+use axum::http::StatusCode;
+use docs::docs_routes;
+use errors::AppError;
+use extractors::Json;
+use state::AppState;
+use todos::routes::todo_routes;
+use uuid::Uuid;
+
+pub mod docs;
+pub mod errors;
+pub mod extractors;
+pub mod state;
+pub mod todos;
+
+/// The documented routes of the application,
+/// shared by the server and the `openapi` binary.
+pub fn app(state: AppState) -> ApiRouter<AppState> {
+    ApiRouter::new()
+        .nest_api_service("/todo", todo_routes(state.clone()))
+        .nest_api_service("/docs", docs_routes(state))
+}
+
+pub fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
+    api.title("Aide axum Open API")
+        .summary("An example Todo application")
+        .description(include_str!("README.md"))
+        .tag(Tag {
+            name: "todo".into(),
+            description: Some("Todo Management".into()),
+            ..Default::default()
+        })
+        .security_scheme(
+            "ApiKey",
+            aide::openapi::SecurityScheme::ApiKey {
+                location: aide::openapi::ApiKeyLocation::Header,
+                name: "X-Auth-Key".into(),
+                description: Some("A key that is ignored.".into()),
+                extensions: Default::default(),
+            },
+        )
+        .default_response_with::<Json<AppError>, _>(|res| {
+            res.example(AppError {
+                error: "some error happened".to_string(),
+                error_details: None,
+                error_id: Uuid::nil(),
+                // This is not visible.
+                status: StatusCode::IM_A_TEAPOT,
+            })
+        })
+}
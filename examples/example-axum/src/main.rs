@@ -1,24 +1,9 @@
 use std::sync::Arc;
 
-use aide::{
-    axum::ApiRouter,
-    openapi::{OpenApi, Tag},
-    transform::TransformOpenApi,
-};
-use axum::{http::StatusCode, Extension};
-use docs::docs_routes;
-use errors::AppError;
-use extractors::Json;
-use state::AppState;
-use todos::routes::todo_routes;
+use aide::openapi::OpenApi;
+use axum::Extension;
+use example_axum::{api_docs, app, state::AppState};
 use tokio::net::TcpListener;
-use uuid::Uuid;
-
-pub mod docs;
-pub mod errors;
-pub mod extractors;
-pub mod state;
-pub mod todos;
 
 #[tokio::main]
 async fn main() {
@@ -32,9 +17,7 @@ async fn main() {
 
     let mut api = OpenApi::default();
 
-    let app = ApiRouter::new()
-        .nest_api_service("/todo", todo_routes(state.clone()))
-        .nest_api_service("/docs", docs_routes(state.clone()))
+    let app = app(state.clone())
         .finish_api_with(&mut api, api_docs)
         .layer(Extension(Arc::new(api))) // Arc is very important here or you will face massive memory and performance issues
         .with_state(state);
@@ -45,32 +28,3 @@ async fn main() {
 
     axum::serve(listener, app).await.unwrap();
 }
-
-fn api_docs(api: TransformOpenApi) -> TransformOpenApi {
-    api.title("Aide axum Open API")
-        .summary("An example Todo application")
-        .description(include_str!("README.md"))
-        .tag(Tag {
-            name: "todo".into(),
-            description: Some("Todo Management".into()),
-            ..Default::default()
-        })
-        .security_scheme(
-            "ApiKey",
-            aide::openapi::SecurityScheme::ApiKey {
-                location: aide::openapi::ApiKeyLocation::Header,
-                name: "X-Auth-Key".into(),
-                description: Some("A key that is ignored.".into()),
-                extensions: Default::default(),
-            },
-        )
-        .default_response_with::<Json<AppError>, _>(|res| {
-            res.example(AppError {
-                error: "some error happened".to_string(),
-                error_details: None,
-                error_id: Uuid::nil(),
-                // This is not visible.
-                status: StatusCode::IM_A_TEAPOT,
-            })
-        })
-}